@@ -0,0 +1,149 @@
+//! The `bootstrap` subcommand, which derives a trusted height and hash from several
+//! independent nodes, to use with `--trusted-height` and `--trusted-hash`.
+//!
+//! The header is only trusted if all the nodes agree on it, so that a single lying node
+//! cannot make the light client bootstrap from a header of its choosing.
+
+use color_eyre::{eyre::eyre, Result};
+use futures::future::join_all;
+use serde::Serialize;
+use tendermint::Time;
+use tendermint_light_client::types::{Hash, Height};
+use tendermint_rpc::{Client, HttpClientUrl};
+use tracing::{info, warn};
+
+use crate::{connection::ConnectionArgs, output::OutputFormat, List};
+
+#[derive(clap::Args, Debug)]
+pub struct BootstrapArgs {
+    /// Identifier of the chain
+    #[clap(long)]
+    chain_id: String,
+
+    /// Comma-separated list of the RPC addresses of at least two independent nodes
+    #[clap(long, env = "TM_LIGHT_CLIENT_RPC_URLS", hide_env_values = true)]
+    rpc_urls: List<HttpClientUrl>,
+
+    /// Height of the header to trust (default: latest height known to all the nodes)
+    #[clap(long)]
+    height: Option<Height>,
+
+    #[clap(flatten)]
+    connection: ConnectionArgs,
+}
+
+/// The trusted state agreed upon by all the nodes.
+#[derive(Clone, Debug, Serialize)]
+pub struct BootstrapOutput {
+    /// Height of the trusted header
+    pub height: Height,
+    /// Hash of the trusted header
+    pub hash: Hash,
+    /// Time of the trusted header
+    pub time: Time,
+}
+
+impl BootstrapOutput {
+    /// Print the output to stdout in the given format.
+    pub fn print(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Text => {
+                println!("{} {}", self.height, self.hash);
+                Ok(())
+            },
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(self)?);
+                Ok(())
+            },
+        }
+    }
+}
+
+pub async fn run(args: BootstrapArgs, output: OutputFormat) -> Result<()> {
+    let rpc_urls = args.rpc_urls.0;
+    if rpc_urls.len() < 2 {
+        return Err(eyre!(
+            "at least two RPC addresses are needed to cross-check the trusted header"
+        ));
+    }
+
+    let connection = &args.connection;
+    let nodes = join_all(rpc_urls.into_iter().map(|addr| async move {
+        let url = addr.redacted();
+        connection
+            .connect(addr)
+            .await
+            .map(|(client, status)| (url, client, status))
+    }))
+    .await
+    .into_iter()
+    .collect::<Result<Vec<_>>>()?;
+
+    for (url, _, status) in &nodes {
+        if status.node_info.network.as_str() != args.chain_id {
+            return Err(eyre!(
+                "node at {url} is on chain {}, not on chain {}",
+                status.node_info.network,
+                args.chain_id
+            ));
+        }
+
+        if status.sync_info.catching_up {
+            warn!("Node at {url} is still catching up with the chain");
+        }
+    }
+
+    let height = match args.height {
+        Some(height) => height,
+        None => nodes
+            .iter()
+            .map(|(_, _, status)| status.sync_info.latest_block_height)
+            .min()
+            .ok_or_else(|| eyre!("no node to fetch the header from"))?,
+    };
+
+    info!(
+        "Fetching header at height {} from {} nodes...",
+        height,
+        nodes.len()
+    );
+
+    let headers = join_all(nodes.iter().map(|(_, client, _)| client.commit(height)))
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (first_url, _, _) = &nodes[0];
+    let header = &headers[0].signed_header.header;
+    let hash = header.hash();
+
+    let disagreeing = nodes
+        .iter()
+        .zip(&headers)
+        .filter(|(_, commit)| commit.signed_header.header.hash() != hash)
+        .map(|((url, _, _), commit)| {
+            format!("{url} has hash {}", commit.signed_header.header.hash())
+        })
+        .collect::<Vec<_>>();
+
+    if !disagreeing.is_empty() {
+        return Err(eyre!(
+            "nodes disagree on the header at height {height}: {first_url} has hash {hash}, {}",
+            disagreeing.join(", ")
+        ));
+    }
+
+    info!(
+        "All {} nodes agree on header at height {} with hash {}",
+        nodes.len(),
+        height,
+        hash
+    );
+
+    BootstrapOutput {
+        height: header.height,
+        hash,
+        time: header.time,
+    }
+    .print(output)
+}
@@ -0,0 +1,240 @@
+//! Checkpoints of long verifications, from which an interrupted run can resume.
+//!
+//! A checkpoint file records the trusted state a verification started from, along with every
+//! light block trusted or verified since, and is rewritten each time a new light block gets
+//! verified. Resuming from a checkpoint puts those light blocks back into the light store,
+//! so that verification carries on from the highest of them.
+
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{
+    eyre::{eyre, Context},
+    Result,
+};
+use serde::{Deserialize, Serialize};
+use tendermint_light_client::{
+    store::LightStore,
+    types::{Hash, Height, LightBlock, Status},
+};
+use tracing::{debug, info, warn};
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct CheckpointArgs {
+    /// Path of a checkpoint file to record the progress of verification to, after each
+    /// verified light block (default: the file given with `--resume`, if any)
+    #[clap(long)]
+    checkpoint: Option<PathBuf>,
+
+    /// Path of a checkpoint file written by an interrupted run, to resume verification from
+    #[clap(long)]
+    resume: Option<PathBuf>,
+}
+
+/// The progress of a verification, as recorded in a checkpoint file.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Height of the trusted header the verification started from
+    pub trusted_height: Height,
+    /// Hash of the trusted header the verification started from
+    pub trusted_hash: Hash,
+    /// The light blocks trusted or verified so far, sorted by height
+    pub light_blocks: Vec<LightBlock>,
+}
+
+/// The checkpoint a verification resumes from, loaded once from the [`CheckpointArgs`], along
+/// with the checkpoint file to record the progress of verification to, if any.
+#[derive(Clone, Debug)]
+pub struct Checkpointing {
+    path: Option<PathBuf>,
+    /// The checkpoint resumed from, or an empty one starting from the trusted state
+    checkpoint: Checkpoint,
+}
+
+impl CheckpointArgs {
+    /// Load the checkpoint to resume from, if any, checking that it starts from the given
+    /// trusted state.
+    pub fn open(&self, trusted_height: Height, trusted_hash: Hash) -> Result<Checkpointing> {
+        let checkpoint = match self.load(trusted_height, trusted_hash)? {
+            Some(checkpoint) => {
+                info!(
+                    "Resuming from checkpoint with {} light blocks, up to height {}",
+                    checkpoint.light_blocks.len(),
+                    checkpoint
+                        .light_blocks
+                        .last()
+                        .map_or(trusted_height, |lb| lb.height())
+                );
+
+                checkpoint
+            },
+            None => Checkpoint {
+                trusted_height,
+                trusted_hash,
+                light_blocks: Vec::new(),
+            },
+        };
+
+        Ok(Checkpointing {
+            path: self.checkpoint.clone().or_else(|| self.resume.clone()),
+            checkpoint,
+        })
+    }
+
+    fn load(&self, trusted_height: Height, trusted_hash: Hash) -> Result<Option<Checkpoint>> {
+        let Some(path) = &self.resume else {
+            return Ok(None);
+        };
+
+        let bytes =
+            fs::read(path).wrap_err_with(|| format!("failed to read {}", path.display()))?;
+        let checkpoint: Checkpoint = serde_json::from_slice(&bytes)
+            .wrap_err_with(|| format!("failed to parse checkpoint from {}", path.display()))?;
+
+        if checkpoint.trusted_height != trusted_height || checkpoint.trusted_hash != trusted_hash {
+            return Err(eyre!(
+                "checkpoint in {} starts from header at height {} with hash {}, \
+                not at height {trusted_height} with hash {trusted_hash}",
+                path.display(),
+                checkpoint.trusted_height,
+                checkpoint.trusted_hash
+            ));
+        }
+
+        Ok(Some(checkpoint))
+    }
+}
+
+impl Checkpointing {
+    /// The light blocks of the checkpoint resumed from, sorted by height, if any.
+    pub fn resumed_light_blocks(&self) -> &[LightBlock] {
+        &self.checkpoint.light_blocks
+    }
+
+    /// Wrap the given light store, so that it records the progress of verification to the
+    /// checkpoint file, after filling it with the light blocks of the checkpoint resumed from.
+    pub fn wrap(&self, mut light_store: Box<dyn LightStore>) -> Box<dyn LightStore> {
+        let trusted_height = self.checkpoint.trusted_height;
+
+        // Verification started from the block at the trusted height
+        for lb in &self.checkpoint.light_blocks {
+            let status = if lb.height() == trusted_height {
+                Status::Trusted
+            } else {
+                Status::Verified
+            };
+
+            if light_store.get_trusted_or_verified(lb.height()).is_none() {
+                light_store.insert(lb.clone(), status);
+            }
+        }
+
+        match &self.path {
+            Some(path) => Box::new(CheckpointStore {
+                inner: light_store,
+                path: path.clone(),
+                checkpoint: self.checkpoint.clone(),
+            }),
+            None => light_store,
+        }
+    }
+}
+
+/// A light store which rewrites its checkpoint file each time a light block gets
+/// trusted or verified.
+#[derive(Debug)]
+struct CheckpointStore {
+    inner: Box<dyn LightStore>,
+    path: PathBuf,
+    checkpoint: Checkpoint,
+}
+
+impl CheckpointStore {
+    fn record(&mut self, light_block: &LightBlock, status: Status) {
+        if status < Status::Verified {
+            return;
+        }
+
+        let light_blocks = &mut self.checkpoint.light_blocks;
+        match light_blocks.binary_search_by_key(&light_block.height(), |lb| lb.height()) {
+            Ok(_) => return,
+            Err(index) => light_blocks.insert(index, light_block.clone()),
+        }
+
+        // The light store cannot fail, so a checkpoint which cannot be written only
+        // costs the ability to resume from it.
+        match write_checkpoint(&self.path, &self.checkpoint) {
+            Ok(()) => debug!(
+                "Wrote checkpoint at height {} to {}",
+                light_block.height(),
+                self.path.display()
+            ),
+            Err(e) => warn!("failed to write checkpoint to {}: {e}", self.path.display()),
+        }
+    }
+}
+
+impl LightStore for CheckpointStore {
+    fn get(&self, height: Height, status: Status) -> Option<LightBlock> {
+        self.inner.get(height, status)
+    }
+
+    fn update(&mut self, light_block: &LightBlock, status: Status) {
+        self.inner.update(light_block, status);
+        self.record(light_block, status);
+    }
+
+    fn insert(&mut self, light_block: LightBlock, status: Status) {
+        self.record(&light_block, status);
+        self.inner.insert(light_block, status);
+    }
+
+    fn remove(&mut self, height: Height, status: Status) {
+        self.inner.remove(height, status)
+    }
+
+    fn highest(&self, status: Status) -> Option<LightBlock> {
+        self.inner.highest(status)
+    }
+
+    fn highest_before(&self, height: Height, status: Status) -> Option<LightBlock> {
+        self.inner.highest_before(height, status)
+    }
+
+    fn lowest(&self, status: Status) -> Option<LightBlock> {
+        self.inner.lowest(status)
+    }
+
+    fn all(&self, status: Status) -> Box<dyn Iterator<Item = LightBlock>> {
+        self.inner.all(status)
+    }
+
+    fn range(&self, status: Status, range: Range<Height>) -> Box<dyn Iterator<Item = LightBlock>> {
+        self.inner.range(status, range)
+    }
+
+    fn heights(&self, status: Status) -> Box<dyn Iterator<Item = Height>> {
+        self.inner.heights(status)
+    }
+}
+
+/// Write the checkpoint to a temporary file first, so that an interruption while writing
+/// never leaves a truncated checkpoint behind.
+fn write_checkpoint(path: &Path, checkpoint: &Checkpoint) -> Result<()> {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+
+    let mut writer = BufWriter::new(File::create(&tmp_path)?);
+    serde_json::to_writer(&mut writer, checkpoint)?;
+    writer.flush()?;
+    drop(writer);
+
+    fs::rename(&tmp_path, path)?;
+
+    Ok(())
+}
@@ -0,0 +1,217 @@
+//! Options for connecting to nodes, shared by all the subcommands which talk to them.
+//!
+//! Every connection option can also be set with an environment variable, and the RPC
+//! credentials can be read from a file, so that secrets never have to appear in the
+//! command line, where they would be visible in process lists. The credentials are
+//! redacted from the addresses of the nodes whenever they are displayed.
+
+use std::{fmt, fs, path::PathBuf, str::FromStr, time::Duration};
+
+use color_eyre::{
+    eyre::{eyre, Context},
+    Report, Result,
+};
+use tendermint_rpc::{client::CompatMode, endpoint::status, Client, HttpClient, HttpClientUrl};
+use tracing::debug;
+
+use crate::seconds_or_forever;
+
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct ConnectionArgs {
+    /// RPC compatibility mode of the nodes: v0.34, v0.37 or v0.38 (default: detected from `/status`)
+    #[clap(long, env = "TM_LIGHT_CLIENT_COMPAT_MODE", value_parser = parse_compat_mode)]
+    compat_mode: Option<CompatMode>,
+
+    /// URL of an HTTP proxy to connect to the nodes through
+    #[clap(long, env = "TM_LIGHT_CLIENT_PROXY_URL", hide_env_values = true)]
+    proxy_url: Option<HttpClientUrl>,
+
+    /// Credentials to authenticate to the nodes with, as `username:password`, for the RPC
+    /// addresses which do not contain any
+    #[clap(
+        long,
+        env = "TM_LIGHT_CLIENT_RPC_CREDENTIALS",
+        hide_env_values = true,
+        conflicts_with = "rpc_credentials_file"
+    )]
+    rpc_credentials: Option<Credentials>,
+
+    /// Path of a file containing the credentials to authenticate to the nodes with,
+    /// as `username:password`
+    #[clap(long, env = "TM_LIGHT_CLIENT_RPC_CREDENTIALS_FILE")]
+    rpc_credentials_file: Option<PathBuf>,
+
+    /// Maximum time to wait for each light block requested from the nodes, in seconds
+    /// (0 waits forever)
+    #[clap(long, env = "TM_LIGHT_CLIENT_RPC_TIMEOUT", default_value = "30")]
+    rpc_timeout: u64,
+}
+
+impl ConnectionArgs {
+    /// Connect to the node at the given address, using the given RPC compatibility mode,
+    /// or the one matching the version the node reports in its status otherwise.
+    pub async fn connect(&self, rpc_addr: HttpClientUrl) -> Result<(HttpClient, status::Response)> {
+        let rpc_addr = self.authenticate(rpc_addr)?;
+        let url = rpc_addr.redacted();
+
+        // The `/status` endpoint is the same in all RPC dialects, so it can be queried
+        // before the compatibility mode is known.
+        let mut rpc_client = self.client(rpc_addr)?;
+        let status = rpc_client.status().await?;
+
+        let compat_mode = match self.compat_mode {
+            Some(compat_mode) => compat_mode,
+            None => {
+                CompatMode::from_version(status.node_info.version.clone()).wrap_err_with(|| {
+                    format!(
+                        "failed to detect the RPC compatibility mode of {url}, \
+                        use --compat-mode to set it"
+                    )
+                })?
+            },
+        };
+
+        debug!("Using RPC compatibility mode {compat_mode} for {url}");
+
+        // Keep using the same client, so that its connection to the node is reused
+        rpc_client.set_compat_mode(compat_mode);
+
+        Ok((rpc_client, status))
+    }
+
+    /// The maximum time to wait for each light block requested from the nodes, if any.
+    pub fn timeout(&self) -> Option<Duration> {
+        seconds_or_forever(self.rpc_timeout)
+    }
+
+    fn client(&self, rpc_addr: HttpClientUrl) -> Result<HttpClient> {
+        let mut builder = HttpClient::builder(rpc_addr);
+
+        if let Some(proxy_url) = &self.proxy_url {
+            builder = builder.proxy_url(proxy_url.clone());
+        }
+
+        builder.build().map_err(Into::into)
+    }
+
+    /// Add the credentials to the given address, unless it already contains some.
+    fn authenticate(&self, rpc_addr: HttpClientUrl) -> Result<HttpClientUrl> {
+        let Some(credentials) = self.credentials()? else {
+            return Ok(rpc_addr);
+        };
+
+        let mut url = url::Url::from(rpc_addr.clone());
+        if !url.username().is_empty() {
+            return Ok(rpc_addr);
+        }
+
+        url.set_username(&credentials.username)
+            .and_then(|()| url.set_password(credentials.password.as_deref()))
+            .map_err(|()| eyre!("cannot add credentials to {}", rpc_addr.redacted()))?;
+
+        url.as_str().parse().map_err(Into::into)
+    }
+
+    fn credentials(&self) -> Result<Option<Credentials>> {
+        match (&self.rpc_credentials, &self.rpc_credentials_file) {
+            (Some(credentials), _) => Ok(Some(credentials.clone())),
+            (None, Some(path)) => {
+                let contents = fs::read_to_string(path)
+                    .wrap_err_with(|| format!("failed to read {}", path.display()))?;
+
+                contents
+                    .parse()
+                    .map(Some)
+                    .wrap_err_with(|| format!("invalid credentials in {}", path.display()))
+            },
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+/// Credentials for HTTP Basic authentication, which are never printed.
+#[derive(Clone)]
+pub struct Credentials {
+    username: String,
+    password: Option<String>,
+}
+
+impl FromStr for Credentials {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+
+        let (username, password) = match s.split_once(':') {
+            Some((username, password)) => (username, Some(password.to_string())),
+            None => (s, None),
+        };
+
+        if username.is_empty() {
+            return Err(eyre!(
+                "invalid credentials, format must be username:password"
+            ));
+        }
+
+        Ok(Self {
+            username: username.to_string(),
+            password,
+        })
+    }
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Credentials(<redacted>)")
+    }
+}
+
+/// Parse an RPC compatibility mode, given as the Tendermint version of the nodes.
+pub fn parse_compat_mode(s: &str) -> Result<CompatMode> {
+    match s.trim().trim_start_matches('v') {
+        "0.34" => Ok(CompatMode::V0_34),
+        "0.37" => Ok(CompatMode::V0_37),
+        "0.38" => Ok(CompatMode::V0_38),
+        _ => Err(eyre!(
+            "invalid compatibility mode: {s}, must be one of v0.34, v0.37 or v0.38"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_credentials() {
+        let credentials: Credentials = " alice:secret:more \n".parse().unwrap();
+        assert_eq!(credentials.username, "alice");
+        assert_eq!(credentials.password.as_deref(), Some("secret:more"));
+
+        let credentials: Credentials = "alice".parse().unwrap();
+        assert_eq!(credentials.username, "alice");
+        assert_eq!(credentials.password, None);
+
+        assert!("".parse::<Credentials>().is_err());
+        assert!(":secret".parse::<Credentials>().is_err());
+    }
+
+    #[test]
+    fn never_prints_credentials() {
+        let credentials: Credentials = "alice:secret".parse().unwrap();
+        let debug = format!("{credentials:?}");
+        assert_eq!(debug, "Credentials(<redacted>)");
+        assert!(!debug.contains("alice") && !debug.contains("secret"));
+    }
+
+    #[test]
+    fn parses_compat_modes() {
+        assert_eq!(parse_compat_mode("0.34").unwrap(), CompatMode::V0_34);
+        assert_eq!(parse_compat_mode("v0.37").unwrap(), CompatMode::V0_37);
+        assert_eq!(parse_compat_mode(" v0.38 ").unwrap(), CompatMode::V0_38);
+
+        for s in ["", "v0.35", "0.34.1", "latest"] {
+            assert!(parse_compat_mode(s).is_err(), "{s}");
+        }
+    }
+}
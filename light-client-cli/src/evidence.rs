@@ -0,0 +1,455 @@
+//! A persistent record of the evidence of the attacks detected by misbehavior detection, and of
+//! its submission.
+//!
+//! Every piece of evidence is recorded in the evidence store before it gets submitted, keyed by
+//! the height and hash of its conflicting block, so that a restarted process does not submit the
+//! same evidence again. A failed submission, eg. because the node is restarting or its mempool
+//! is full, is retried with an exponential backoff, and submitted evidence is looked for in the
+//! blocks of the node it was submitted to, until it is committed. The store is a JSON file,
+//! rewritten whenever it changes, from which operators can inspect what was detected, against
+//! which peer, when, and what became of it.
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use color_eyre::{eyre::Context, Result};
+use serde::{Deserialize, Serialize};
+use tendermint::{
+    evidence::{Evidence, LightClientAttackEvidence},
+    Time,
+};
+use tendermint_light_client::types::{Hash, Height, PeerId};
+use tendermint_light_client_detector::Provider;
+use tracing::{debug, error, info, warn};
+
+/// Maximum number of blocks looked for submitted evidence at once, so that catching up with a
+/// long chain does not hold up everything else.
+const MAX_SCANNED_BLOCKS: u64 = 100;
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct EvidenceStoreArgs {
+    /// Path of a JSON file in which to record the evidence of the attacks detected, so that
+    /// the same evidence is never submitted twice (default: in-memory store)
+    #[clap(long)]
+    evidence_store: Option<PathBuf>,
+
+    /// Delay before retrying to submit evidence after a failed attempt, in seconds, doubled
+    /// after every failed attempt
+    #[clap(long, default_value = "10")]
+    evidence_retry_delay: u64,
+
+    /// Maximum delay before retrying to submit evidence after a failed attempt, in seconds
+    #[clap(long, default_value = "3600")]
+    evidence_max_retry_delay: u64,
+
+    /// Number of failed attempts after which the submission of evidence is abandoned
+    /// (0 never abandons it)
+    #[clap(long, default_value = "10")]
+    evidence_max_attempts: u32,
+}
+
+impl EvidenceStoreArgs {
+    /// Open the evidence store given on the command line, if any.
+    pub fn open(&self) -> Result<EvidenceStore> {
+        let store = match &self.evidence_store {
+            Some(path) => EvidenceStore::open(path)?,
+            None => EvidenceStore::default(),
+        };
+
+        Ok(store.with_retry_policy(RetryPolicy {
+            delay: Duration::from_secs(self.evidence_retry_delay),
+            max_delay: Duration::from_secs(self.evidence_max_retry_delay),
+            max_attempts: self.evidence_max_attempts,
+        }))
+    }
+}
+
+/// How the submission of evidence is retried after a failed attempt.
+#[derive(Copy, Clone, Debug)]
+pub struct RetryPolicy {
+    /// Delay before the first retry, doubled after every failed attempt
+    pub delay: Duration,
+    /// Maximum delay between two attempts
+    pub max_delay: Duration,
+    /// Number of failed attempts after which the submission is abandoned, 0 for never
+    pub max_attempts: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_secs(10),
+            max_delay: Duration::from_secs(3600),
+            max_attempts: 10,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The delay before the next attempt, after the given number of failed attempts.
+    fn backoff(&self, failed_attempts: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(failed_attempts.saturating_sub(1));
+        self.delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    fn is_exhausted(&self, failed_attempts: u32) -> bool {
+        self.max_attempts > 0 && failed_attempts >= self.max_attempts
+    }
+}
+
+/// What became of a piece of evidence.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceStatus {
+    /// The evidence was not submitted yet
+    #[default]
+    Pending,
+    /// The evidence was submitted, but not found in a block yet
+    Submitted,
+    /// The evidence was found in a block
+    Committed,
+    /// The submission of the evidence failed too many times
+    Abandoned,
+}
+
+/// A piece of evidence of an attack, as recorded in an evidence store.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EvidenceRecord {
+    /// Height of the conflicting block of the evidence
+    pub height: Height,
+    /// Hash of the conflicting block of the evidence
+    pub conflicting_hash: Hash,
+    /// Peer which provided the conflicting block
+    pub against: PeerId,
+    /// Peer to submit the evidence to
+    pub report_to: PeerId,
+    /// When the evidence was first detected
+    pub detected_at: Time,
+    /// When the evidence was submitted, if it was
+    pub reported_at: Option<Time>,
+    /// What became of the evidence
+    #[serde(default)]
+    pub status: EvidenceStatus,
+    /// Number of failed attempts to submit the evidence
+    #[serde(default)]
+    pub failed_attempts: u32,
+    /// Error of the last failed attempt to submit the evidence
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// When to attempt to submit the evidence again, after a failed attempt
+    #[serde(default)]
+    pub retry_at: Option<Time>,
+    /// Height of the last block of the peer the evidence was submitted to which was looked for
+    /// the evidence
+    #[serde(default)]
+    pub scanned_height: Option<Height>,
+    /// Height of the block in which the evidence was committed, once found
+    #[serde(default)]
+    pub committed_height: Option<Height>,
+    /// The evidence itself
+    pub evidence: LightClientAttackEvidence,
+}
+
+impl EvidenceRecord {
+    pub fn new(
+        evidence: LightClientAttackEvidence,
+        against: PeerId,
+        report_to: PeerId,
+        detected_at: Time,
+    ) -> Self {
+        let header = &evidence.conflicting_block.signed_header.header;
+
+        Self {
+            height: header.height,
+            conflicting_hash: header.hash(),
+            against,
+            report_to,
+            detected_at,
+            reported_at: None,
+            status: EvidenceStatus::Pending,
+            failed_attempts: 0,
+            last_error: None,
+            retry_at: None,
+            scanned_height: None,
+            committed_height: None,
+            evidence,
+        }
+    }
+
+    /// The key of the record in the evidence store.
+    pub fn key(&self) -> (Height, Hash) {
+        (self.height, self.conflicting_hash)
+    }
+
+    /// Whether the given evidence, eg. included in a block, is the evidence of this record.
+    fn is_evidence(&self, evidence: &Evidence) -> bool {
+        match evidence {
+            Evidence::LightClientAttack(evidence) => {
+                evidence.conflicting_block.signed_header.header.hash() == self.conflicting_hash
+            },
+            Evidence::DuplicateVote(_) => false,
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct EvidenceFile {
+    evidence: Vec<EvidenceRecord>,
+}
+
+/// The evidence of the attacks detected so far, keyed by the height and hash of their
+/// conflicting block, and persisted to a file if the store was opened from one.
+#[derive(Debug, Default)]
+pub struct EvidenceStore {
+    path: Option<PathBuf>,
+    records: BTreeMap<(Height, Hash), EvidenceRecord>,
+    retry_policy: RetryPolicy,
+}
+
+impl EvidenceStore {
+    /// Open the evidence store persisted to the given file, which is created on the first
+    /// change if it does not exist yet.
+    pub fn open(path: &Path) -> Result<Self> {
+        let records = if path.exists() {
+            let bytes =
+                fs::read(path).wrap_err_with(|| format!("failed to read {}", path.display()))?;
+
+            let file: EvidenceFile = serde_json::from_slice(&bytes)
+                .wrap_err_with(|| format!("failed to parse evidence from {}", path.display()))?;
+
+            file.evidence
+                .into_iter()
+                .map(|mut record| {
+                    // Stores written before the status was recorded only tell whether the
+                    // evidence was reported
+                    if record.status == EvidenceStatus::Pending && record.reported_at.is_some() {
+                        record.status = EvidenceStatus::Submitted;
+                    }
+
+                    (record.key(), record)
+                })
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            records,
+            retry_policy: RetryPolicy::default(),
+        })
+    }
+
+    /// Retry the submission of evidence with the given policy after a failed attempt.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Record the given evidence, unless it was recorded already.
+    pub fn record(&mut self, record: EvidenceRecord) -> Result<()> {
+        if self.records.contains_key(&record.key()) {
+            return Ok(());
+        }
+
+        self.records.insert(record.key(), record);
+        self.persist()
+    }
+
+    /// Submit the recorded evidence with the given key to the given provider, unless it was
+    /// submitted already, or its next attempt is not due yet, and record the outcome.
+    pub async fn submit(
+        &mut self,
+        key: (Height, Hash),
+        provider: &Provider,
+        now: Time,
+    ) -> Result<()> {
+        let Some(record) = self.records.get(&key) else {
+            return Ok(());
+        };
+
+        match record.status {
+            EvidenceStatus::Pending => {},
+            EvidenceStatus::Submitted | EvidenceStatus::Committed => {
+                info!(
+                    "Evidence against {} was already reported to {}",
+                    record.against, record.report_to
+                );
+                return Ok(());
+            },
+            EvidenceStatus::Abandoned => return Ok(()),
+        }
+
+        if record.retry_at.is_some_and(|retry_at| retry_at > now) {
+            debug!(
+                "Not retrying to submit evidence against {} to {} before {}",
+                record.against,
+                record.report_to,
+                record.retry_at.unwrap()
+            );
+            return Ok(());
+        }
+
+        // The evidence is looked for in the blocks the provider commits after submitting it
+        let latest_height = provider.probe().await.ok().map(|probe| probe.latest_height);
+
+        let result = provider
+            .report_evidence(Evidence::from(record.evidence.clone()))
+            .await;
+
+        let record = self.records.get_mut(&key).unwrap();
+
+        match result {
+            Ok(hash) => {
+                info!(
+                    "Reported evidence {hash} against {} to {}",
+                    record.against, record.report_to
+                );
+
+                record.status = EvidenceStatus::Submitted;
+                record.reported_at = Some(now);
+                record.last_error = None;
+                record.retry_at = None;
+                record.scanned_height = latest_height.or(Some(record.height));
+            },
+            Err(e) => {
+                record.failed_attempts += 1;
+                record.last_error = Some(e.to_string());
+
+                if self.retry_policy.is_exhausted(record.failed_attempts) {
+                    error!(
+                        "Abandoning the submission of evidence against {} to {} after {} failed attempts: {e}",
+                        record.against, record.report_to, record.failed_attempts
+                    );
+
+                    record.status = EvidenceStatus::Abandoned;
+                    record.retry_at = None;
+                } else {
+                    let backoff = self.retry_policy.backoff(record.failed_attempts);
+
+                    warn!(
+                        "Failed to report evidence against {} to {}, retrying in {backoff:?}: {e}",
+                        record.against, record.report_to
+                    );
+
+                    record.retry_at = Some((now + backoff)?);
+                }
+            },
+        }
+
+        self.persist()
+    }
+
+    /// Retry the submission of the evidence whose next attempt is due, and look for the
+    /// submitted evidence in the blocks of the peers it was submitted to, among the given
+    /// providers.
+    pub async fn process(&mut self, providers: &[&Provider], now: Time) -> Result<()> {
+        let keys = self.records.keys().copied().collect::<Vec<_>>();
+
+        for key in keys {
+            let record = &self.records[&key];
+
+            let Some(provider) = providers
+                .iter()
+                .find(|provider| *provider.peer_id() == record.report_to)
+            else {
+                continue;
+            };
+
+            match record.status {
+                EvidenceStatus::Pending => self.submit(key, provider, now).await?,
+                EvidenceStatus::Submitted => self.look_for_commit(key, provider).await?,
+                EvidenceStatus::Committed | EvidenceStatus::Abandoned => {},
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Look for the submitted evidence with the given key in the blocks of the given provider
+    /// which were not looked at yet, up to `MAX_SCANNED_BLOCKS` of them.
+    async fn look_for_commit(&mut self, key: (Height, Hash), provider: &Provider) -> Result<()> {
+        let latest_height = match provider.probe().await {
+            Ok(probe) => probe.latest_height.value(),
+            Err(e) => {
+                debug!("failed to probe {}: {e}", provider.peer_id());
+                return Ok(());
+            },
+        };
+
+        let record = self.records.get_mut(&key).unwrap();
+        let from = record.scanned_height.unwrap_or(record.height).value() + 1;
+        let to = latest_height.min(from + MAX_SCANNED_BLOCKS - 1);
+
+        if from > to {
+            return Ok(());
+        }
+
+        for height in from..=to {
+            let height = Height::try_from(height)?;
+
+            let evidence = match provider.block_evidence(height).await {
+                Ok(evidence) => evidence,
+                Err(e) => {
+                    debug!(
+                        "failed to fetch block {height} from {}: {e}",
+                        provider.peer_id()
+                    );
+                    break;
+                },
+            };
+
+            record.scanned_height = Some(height);
+
+            if evidence.iter().any(|evidence| record.is_evidence(evidence)) {
+                info!(
+                    "Evidence against {} was committed by {} at height {height}",
+                    record.against, record.report_to
+                );
+
+                record.status = EvidenceStatus::Committed;
+                record.committed_height = Some(height);
+                break;
+            }
+        }
+
+        self.persist()
+    }
+
+    /// Write the store to its file, if any, through a temporary file so that an interruption
+    /// while writing never leaves a truncated store behind.
+    fn persist(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+
+        let file = EvidenceFile {
+            evidence: self.records.values().cloned().collect(),
+        };
+
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        serde_json::to_writer_pretty(&mut writer, &file)?;
+        writer.flush()?;
+        drop(writer);
+
+        fs::rename(&tmp_path, path)
+            .wrap_err_with(|| format!("failed to write evidence to {}", path.display()))?;
+
+        debug!(
+            "Recorded {} pieces of evidence to {}",
+            self.records.len(),
+            path.display()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+//! The `fetch` subcommand, which builds a trace file by verifying a header against a node.
+
+use std::path::PathBuf;
+
+use color_eyre::{eyre::eyre, Result};
+use tendermint_light_client::types::{Hash, Height};
+use tendermint_rpc::HttpClientUrl;
+use tracing::info;
+
+use crate::{
+    checkpoint::CheckpointArgs,
+    connection::ConnectionArgs,
+    ibc::IbcArgs,
+    make_provider,
+    output::{OutputFormat, VerificationOutput},
+    parse_hash,
+    trace::{self, TraceFormat},
+    VerificationOptions,
+};
+
+#[derive(clap::Args, Debug)]
+pub struct FetchArgs {
+    /// Identifier of the chain
+    #[clap(long)]
+    chain_id: String,
+
+    /// RPC address of the node to fetch the light blocks from
+    #[clap(long, env = "TM_LIGHT_CLIENT_RPC_URL", hide_env_values = true)]
+    rpc_url: HttpClientUrl,
+
+    /// Height of trusted header
+    #[clap(long)]
+    trusted_height: Height,
+
+    /// Hash of trusted header, in hex or base64
+    #[clap(long, value_parser = parse_hash)]
+    trusted_hash: Hash,
+
+    /// Height of the header to verify (default: latest height)
+    #[clap(long)]
+    target_height: Option<Height>,
+
+    /// Path of the trace file to write, or `-` for stdout
+    #[clap(long)]
+    output_file: PathBuf,
+
+    /// Format of the trace file to write
+    #[clap(long, value_enum, default_value_t = TraceFormat::Json)]
+    output_format: TraceFormat,
+
+    /// Directory in which to persist the light store (default: in-memory store)
+    #[clap(long)]
+    store_path: Option<PathBuf>,
+
+    #[clap(flatten)]
+    connection: ConnectionArgs,
+
+    #[clap(flatten)]
+    verification: VerificationOptions,
+
+    #[clap(flatten)]
+    ibc: IbcArgs,
+
+    #[clap(flatten)]
+    checkpoint: CheckpointArgs,
+}
+
+pub async fn run(args: FetchArgs, output: OutputFormat) -> Result<()> {
+    let trace_to_stdout = trace::is_stdio(&args.output_file);
+    if trace_to_stdout && output == OutputFormat::Json {
+        return Err(eyre!(
+            "cannot print the results as JSON when writing the trace to stdout"
+        ));
+    }
+
+    let options = args.verification.to_options();
+    let checkpointing = args
+        .checkpoint
+        .open(args.trusted_height, args.trusted_hash)?;
+
+    let mut provider = make_provider(
+        &args.chain_id,
+        args.rpc_url,
+        args.trusted_height,
+        args.trusted_hash,
+        options,
+        args.store_path.as_deref(),
+        &args.connection,
+        Some(&checkpointing),
+    )
+    .await?;
+
+    // The block verification starts from, which is not part of the verification trace.
+    let trusted_block = provider
+        .latest_trusted()
+        .ok_or_else(|| eyre!("no trusted state found for {}", provider.peer_id()))?;
+
+    let target_block = if let Some(target_height) = args.target_height {
+        info!(
+            "Verifying to height {} on {}...",
+            target_height,
+            provider.peer_id()
+        );
+        provider.verify_to_height(target_height)
+    } else {
+        info!("Verifying to latest height on {}...", provider.peer_id());
+        provider.verify_to_highest()
+    }?;
+
+    // Include the trusted block, so that the trace can be verified from the same trusted state
+    let mut trace = provider.get_trace(target_block.height());
+    if !trace.iter().any(|lb| lb.height() == trusted_block.height()) {
+        trace.push(trusted_block);
+    }
+
+    // The light blocks verified before resuming are only known from the checkpoint
+    for lb in checkpointing.resumed_light_blocks() {
+        if lb.height() <= target_block.height() && !trace.iter().any(|t| t.height() == lb.height())
+        {
+            trace.push(lb.clone());
+        }
+    }
+
+    trace.sort_by_key(|lb| lb.height());
+
+    info!(
+        "Verified to height {} using {} light blocks, writing trace to {}",
+        target_block.height(),
+        trace.len(),
+        if trace_to_stdout {
+            "stdout".to_string()
+        } else {
+            args.output_file.display().to_string()
+        }
+    );
+
+    let verification_output = VerificationOutput::new(&target_block, trace.clone());
+
+    trace::write_trace(&args.output_file, trace, args.output_format)?;
+    args.ibc.export(&target_block, &options)?;
+
+    // The trace itself is the output when written to stdout
+    if trace_to_stdout {
+        return Ok(());
+    }
+
+    verification_output.print(output)
+}
@@ -0,0 +1,209 @@
+//! Export of a verified header as the client and consensus states of an ICS-07 Tendermint
+//! IBC light client, in the JSON encoding of their Protobuf messages.
+//!
+//! The proof specs and upgrade path of the client state depend on the application of the
+//! chain rather than on its headers, so they are left out.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use color_eyre::{eyre::Context, Result};
+use serde::Serialize;
+use subtle_encoding::base64;
+use tendermint::Time;
+use tendermint_light_client::{light_client::Options, types::LightBlock};
+use tracing::info;
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct IbcArgs {
+    /// Path of a JSON file to write the IBC client and consensus states of the verified header to
+    #[clap(long)]
+    export_ibc: Option<PathBuf>,
+
+    /// Unbonding period of the chain to put in the IBC client state, in seconds
+    /// (default: 3/2 of the trusting period)
+    #[clap(long, requires = "export_ibc")]
+    unbonding_period: Option<u64>,
+}
+
+impl IbcArgs {
+    /// Export the IBC states of the given verified block, if requested.
+    pub fn export(&self, verified: &LightBlock, options: &Options) -> Result<()> {
+        let Some(path) = &self.export_ibc else {
+            return Ok(());
+        };
+
+        let unbonding_period = self
+            .unbonding_period
+            .map(Duration::from_secs)
+            .unwrap_or(options.trusting_period * 3 / 2);
+
+        let states = IbcStates::new(verified, options, unbonding_period);
+
+        info!(
+            "Writing IBC client and consensus states at height {} to {}",
+            verified.height(),
+            path.display()
+        );
+
+        write_json(path, &states)
+    }
+}
+
+/// The states an ICS-07 client must be created with to trust the verified header.
+#[derive(Clone, Debug, Serialize)]
+pub struct IbcStates {
+    pub client_state: ClientState,
+    pub consensus_state: ConsensusState,
+}
+
+/// The JSON encoding of `ibc.lightclients.tendermint.v1.ClientState`.
+#[derive(Clone, Debug, Serialize)]
+pub struct ClientState {
+    #[serde(rename = "@type")]
+    pub type_url: &'static str,
+    pub chain_id: String,
+    pub trust_level: Fraction,
+    pub trusting_period: String,
+    pub unbonding_period: String,
+    pub max_clock_drift: String,
+    pub frozen_height: IbcHeight,
+    pub latest_height: IbcHeight,
+}
+
+/// The JSON encoding of `ibc.lightclients.tendermint.v1.ConsensusState`.
+#[derive(Clone, Debug, Serialize)]
+pub struct ConsensusState {
+    #[serde(rename = "@type")]
+    pub type_url: &'static str,
+    pub timestamp: Time,
+    pub root: MerkleRoot,
+    pub next_validators_hash: String,
+}
+
+/// The JSON encoding of `ibc.lightclients.tendermint.v1.Fraction`.
+#[derive(Clone, Debug, Serialize)]
+pub struct Fraction {
+    pub numerator: String,
+    pub denominator: String,
+}
+
+/// The JSON encoding of `ibc.core.client.v1.Height`.
+#[derive(Clone, Debug, Serialize)]
+pub struct IbcHeight {
+    pub revision_number: String,
+    pub revision_height: String,
+}
+
+/// The JSON encoding of `ibc.core.commitment.v1.MerkleRoot`.
+#[derive(Clone, Debug, Serialize)]
+pub struct MerkleRoot {
+    pub hash: String,
+}
+
+impl IbcStates {
+    pub fn new(verified: &LightBlock, options: &Options, unbonding_period: Duration) -> Self {
+        let header = &verified.signed_header.header;
+        let chain_id = header.chain_id.to_string();
+
+        let latest_height = IbcHeight {
+            revision_number: revision_number(&chain_id).to_string(),
+            revision_height: header.height.to_string(),
+        };
+
+        let client_state = ClientState {
+            type_url: "/ibc.lightclients.tendermint.v1.ClientState",
+            chain_id,
+            trust_level: Fraction {
+                numerator: options.trust_threshold.numerator().to_string(),
+                denominator: options.trust_threshold.denominator().to_string(),
+            },
+            trusting_period: proto_duration(options.trusting_period),
+            unbonding_period: proto_duration(unbonding_period),
+            max_clock_drift: proto_duration(options.clock_drift),
+            frozen_height: IbcHeight {
+                revision_number: "0".to_string(),
+                revision_height: "0".to_string(),
+            },
+            latest_height,
+        };
+
+        let consensus_state = ConsensusState {
+            type_url: "/ibc.lightclients.tendermint.v1.ConsensusState",
+            timestamp: header.time,
+            root: MerkleRoot {
+                hash: base64_string(header.app_hash.as_bytes()),
+            },
+            next_validators_hash: base64_string(header.next_validators_hash.as_bytes()),
+        };
+
+        Self {
+            client_state,
+            consensus_state,
+        }
+    }
+}
+
+/// The revision number of a chain, as encoded in its identifier by IBC: `{name}-{revision}`,
+/// where the revision has no leading zero, or 0 if the identifier does not follow that format.
+fn revision_number(chain_id: &str) -> u64 {
+    match chain_id.rsplit_once('-') {
+        Some((name, revision)) if !name.is_empty() && !revision.starts_with('0') => {
+            revision.parse().unwrap_or(0)
+        },
+        _ => 0,
+    }
+}
+
+/// The JSON encoding of a `google.protobuf.Duration`.
+fn proto_duration(duration: Duration) -> String {
+    match duration.subsec_nanos() {
+        0 => format!("{}s", duration.as_secs()),
+        nanos => format!("{}.{:09}s", duration.as_secs(), nanos),
+    }
+}
+
+fn base64_string(bytes: &[u8]) -> String {
+    String::from_utf8(base64::encode(bytes)).expect("base64 is always valid UTF-8")
+}
+
+fn write_json(path: &Path, states: &IbcStates) -> Result<()> {
+    let file =
+        File::create(path).wrap_err_with(|| format!("failed to create {}", path.display()))?;
+    let mut writer = BufWriter::new(file);
+
+    serde_json::to_writer_pretty(&mut writer, states)?;
+    writeln!(writer)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_revision_numbers() {
+        assert_eq!(revision_number("cosmoshub-4"), 4);
+        assert_eq!(revision_number("osmo-test-5"), 5);
+        assert_eq!(revision_number("evmos_9001-12"), 12);
+        assert_eq!(revision_number("ibc-0"), 0);
+
+        for chain_id in ["testchain", "chain-04", "-4", "chain-", "chain-x"] {
+            assert_eq!(revision_number(chain_id), 0, "{chain_id}");
+        }
+    }
+
+    #[test]
+    fn encodes_proto_durations() {
+        assert_eq!(proto_duration(Duration::ZERO), "0s");
+        assert_eq!(proto_duration(Duration::from_secs(1_209_600)), "1209600s");
+        assert_eq!(proto_duration(Duration::from_millis(1_500)), "1.500000000s");
+        assert_eq!(proto_duration(Duration::from_nanos(1)), "0.000000001s");
+    }
+}
@@ -0,0 +1,10 @@
+//! Stateless verification of headers against traces of light blocks, as performed by the
+//! `verify` subcommand of the `tendermint-light-client-cli` binary, and of the proofs of
+//! ABCI query responses against verified headers, as performed by `verify-query`.
+//!
+//! Without the default `cli` feature, this library only depends on the light client itself,
+//! and can be built for `wasm32-unknown-unknown`, to embed the exact same verification
+//! as the CLI in browsers and smart-contract environments.
+
+pub mod query;
+pub mod stateless;
@@ -1,29 +1,69 @@
 #![allow(unused)]
 
-use std::{convert::Infallible, str::FromStr, time::Duration};
+use std::{
+    convert::Infallible,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    str::FromStr,
+    time::Duration,
+};
 
 use clap::Parser;
 use color_eyre::{
-    eyre::{eyre, Result},
+    eyre::{eyre, Context, Result},
     Report,
 };
 use futures::future::join_all;
-use tendermint::{crypto::default::Sha256, evidence::Evidence, Time};
+use subtle_encoding::base64;
+use tendermint::{
+    crypto::default::Sha256,
+    evidence::Evidence,
+    hash::{Algorithm, SHA256_HASH_SIZE},
+    Time,
+};
 use tendermint_light_client::{
-    builder::LightClientBuilder,
+    builder::{error as builder_error, LightClientBuilder},
+    components::io::{IoError, IoErrorDetail},
+    errors::{Error as LightClientError, ErrorDetail as LightClientErrorDetail},
     instance::Instance,
     light_client::Options,
-    store::memory::MemoryStore,
-    types::{Hash, Height, LightBlock, TrustThreshold},
+    store::{memory::MemoryStore, sled::SledStore, LightStore},
+    types::{Hash, Height, LightBlock, PeerId, TrustThreshold},
+    verifier::{errors::VerificationErrorDetail, options::VerificationMode},
 };
+use tendermint_light_client_cli::{query, stateless};
 use tendermint_light_client_detector::{
-    compare_new_header_with_witness, detect_divergence, gather_evidence_from_conflicting_headers,
-    CompareError, Error, ErrorDetail, Provider, Trace,
+    compare_new_header_with_witness, detect_divergences, gather_evidence_from_conflicting_headers,
+    CompareError, Divergence, Error, ErrorDetail, ForkReport, Provider, QuorumOutcome,
+    QuorumPolicy, QuorumVerdict, Trace,
 };
-use tendermint_rpc::{Client, HttpClient, HttpClientUrl, Url};
+use tendermint_rpc::{client::CompatMode, endpoint::status, Client, HttpClient, HttpClientUrl};
 use tracing::{debug, error, info, metadata::LevelFilter, warn};
 use tracing_subscriber::{util::SubscriberInitExt, EnvFilter};
 
+use crate::{
+    checkpoint::Checkpointing,
+    connection::ConnectionArgs,
+    evidence::{EvidenceRecord, EvidenceStore, EvidenceStoreArgs},
+    output::{OutputFormat, VerificationOutput},
+};
+
+mod bootstrap;
+mod checkpoint;
+mod connection;
+mod evidence;
+mod fetch;
+mod ibc;
+mod metrics;
+mod output;
+mod repl;
+mod signature;
+mod store;
+mod trace;
+mod verify;
+mod verify_query;
+mod watch;
+
 fn parse_trust_threshold(s: &str) -> Result<TrustThreshold> {
     if let Some((l, r)) = s.split_once('/') {
         TrustThreshold::new(l.parse()?, r.parse()?).map_err(Into::into)
@@ -34,6 +74,36 @@ fn parse_trust_threshold(s: &str) -> Result<TrustThreshold> {
     }
 }
 
+/// The given number of seconds, or no time limit if it is 0.
+fn seconds_or_forever(seconds: u64) -> Option<Duration> {
+    (seconds > 0).then(|| Duration::from_secs(seconds))
+}
+
+/// Parse a SHA-256 hash given either in hex, in upper or lower case and optionally prefixed
+/// with `0x`, or in base64, as different explorers and RPC responses present hashes differently.
+fn parse_hash(s: &str) -> Result<Hash> {
+    let s = s.trim();
+    let hex = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s);
+
+    if hex.len() == 2 * SHA256_HASH_SIZE && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Hash::from_hex_upper(Algorithm::Sha256, &hex.to_ascii_uppercase())
+            .map_err(Into::into);
+    }
+
+    match base64::decode(s) {
+        Ok(bytes) if bytes.len() == SHA256_HASH_SIZE => {
+            Hash::from_bytes(Algorithm::Sha256, &bytes).map_err(Into::into)
+        },
+        _ => Err(eyre!(
+            "invalid hash: {s}, must be a SHA-256 hash in hex (optionally prefixed with 0x) \
+            or in base64"
+        )),
+    }
+}
+
 #[derive(Clone, Debug)]
 struct List<T>(Vec<T>);
 
@@ -51,7 +121,7 @@ impl<E, T: FromStr<Err = E>> FromStr for List<T> {
 #[derive(clap::Args, Debug, Clone)]
 struct Verbosity {
     /// Increase verbosity, can be repeated up to 2 times
-    #[arg(long, short, action = clap::ArgAction::Count)]
+    #[arg(long, short, global = true, action = clap::ArgAction::Count)]
     verbose: u8,
 }
 
@@ -65,56 +135,295 @@ impl Verbosity {
     }
 }
 
+#[derive(clap::Args, Debug, Clone)]
+struct VerificationOptions {
+    /// Trust threshold
+    #[clap(long, value_parser = parse_trust_threshold, default_value_t = TrustThreshold::TWO_THIRDS)]
+    trust_threshold: TrustThreshold,
+
+    /// Trusting period, in seconds (default: two weeks)
+    #[clap(long, default_value = "1209600")]
+    trusting_period: u64,
+
+    /// Maximum clock drift, in seconds
+    #[clap(long, default_value = "5")]
+    max_clock_drift: u64,
+
+    /// Verify every header between the trusted and target headers, instead of skipping
+    /// over intermediate headers
+    #[clap(long)]
+    sequential: bool,
+}
+
+impl VerificationOptions {
+    fn to_options(&self) -> Options {
+        Options {
+            trust_threshold: self.trust_threshold,
+            trusting_period: Duration::from_secs(self.trusting_period),
+            clock_drift: Duration::from_secs(self.max_clock_drift),
+            verification_mode: if self.sequential {
+                VerificationMode::Sequential
+            } else {
+                VerificationMode::Skipping
+            },
+            commit_signatures: Default::default(),
+        }
+    }
+}
+
 #[derive(Debug, Parser)]
 #[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[clap(flatten)]
+    detect: Option<DetectArgs>,
+
+    /// Increase verbosity
+    #[clap(flatten)]
+    verbose: Verbosity,
+
+    /// Format in which to print the verification results, `json` prints them to stdout
+    #[clap(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum Command {
+    /// Derive a trusted height and hash from a header that several independent nodes agree on
+    Bootstrap(bootstrap::BootstrapArgs),
+
+    /// Verify a header against a primary and build a trace file from the light blocks used
+    Fetch(fetch::FetchArgs),
+
+    /// Verify a header using the light blocks of a trace file, without talking to any node
+    Verify(verify::VerifyArgs),
+
+    /// Verify the Merkle proof of an ABCI query response against a header verified using a trace
+    VerifyQuery(verify_query::VerifyQueryArgs),
+
+    /// Manipulate trace files
+    #[command(subcommand)]
+    Trace(trace::TraceCommand),
+
+    /// Migrate, export and compress persistent light stores
+    #[command(subcommand)]
+    Store(store::StoreCommand),
+
+    /// Interactively load traces, inspect their light blocks and step through verification
+    Repl(repl::ReplArgs),
+
+    /// Continuously verify new headers produced by a primary, and check them against witnesses
+    Watch(watch::WatchArgs),
+}
+
+/// Verify a header against a primary, and run misbehavior detection against witnesses
+#[derive(clap::Args, Debug)]
+struct DetectArgs {
     /// Identifier of the chain
     #[clap(long)]
     chain_id: String,
 
     /// Primary RPC address
-    #[clap(long)]
+    #[clap(long, env = "TM_LIGHT_CLIENT_PRIMARY", hide_env_values = true)]
     primary: HttpClientUrl,
 
     /// Comma-separated list of witnesses RPC addresses
-    #[clap(long)]
+    #[clap(long, env = "TM_LIGHT_CLIENT_WITNESSES", hide_env_values = true)]
     witnesses: List<HttpClientUrl>,
 
     /// Height of trusted header
     #[clap(long)]
     trusted_height: Height,
 
-    /// Hash of trusted header
-    #[clap(long)]
+    /// Hash of trusted header, in hex or base64
+    #[clap(long, value_parser = parse_hash)]
     trusted_hash: Hash,
 
     /// Height of the header to verify
     #[clap(long)]
     height: Option<Height>,
 
-    /// Trust threshold
-    #[clap(long, value_parser = parse_trust_threshold, default_value_t = TrustThreshold::TWO_THIRDS)]
-    trust_threshold: TrustThreshold,
-
-    /// Trusting period, in seconds (default: two weeks)
-    #[clap(long, default_value = "1209600")]
-    trusting_period: u64,
-
-    /// Maximum clock drift, in seconds
-    #[clap(long, default_value = "5")]
-    max_clock_drift: u64,
+    #[clap(flatten)]
+    verification: VerificationOptions,
 
     /// Maximum block lag, in seconds
     #[clap(long, default_value = "5")]
     max_block_lag: u64,
 
-    /// Increase verbosity
+    /// Maximum time to run misbehavior detection against each witness for, in seconds
+    /// (0 waits forever), after which the witness is counted as failed
+    #[clap(long, default_value = "60")]
+    witness_timeout: u64,
+
+    /// Directory in which to persist the light store of the primary (default: in-memory store)
+    #[clap(long)]
+    store_path: Option<PathBuf>,
+
     #[clap(flatten)]
-    verbose: Verbosity,
+    connection: ConnectionArgs,
+
+    /// Submit the evidence of an attack to the primary and witnesses when one is detected
+    #[clap(long)]
+    report_evidence: bool,
+
+    #[clap(flatten)]
+    evidence_store: EvidenceStoreArgs,
+
+    #[clap(flatten)]
+    quorum: QuorumArgs,
+
+    /// Directory in which to write a JSON report of every divergence found, with both traces,
+    /// the point at which they diverge and the evidence gathered (default: no reports)
+    #[clap(long)]
+    divergence_reports: Option<PathBuf>,
+}
+
+/// How many witnesses must agree with, or diverge from, the primary.
+#[derive(clap::Args, Debug, Clone)]
+struct QuorumArgs {
+    /// Minimum number of witnesses which must return the same header as the primary for it to
+    /// be accepted
+    #[clap(long, default_value = "0")]
+    min_agreeing_witnesses: usize,
+
+    /// Minimum number of witnesses which must return a header conflicting with the one of the
+    /// primary for it to be treated as a fork
+    #[clap(long, default_value = "1")]
+    min_diverging_witnesses: usize,
+}
+
+impl QuorumArgs {
+    fn policy(&self) -> QuorumPolicy {
+        QuorumPolicy::new(self.min_agreeing_witnesses, self.min_diverging_witnesses)
+    }
+}
+
+/// The exit codes of the CLI, which tell apart the possible outcomes of a run.
+///
+/// Exit code 2 is left to clap, which uses it for invalid command-line arguments.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum ExitStatus {
+    /// The header was verified, and no fork was detected
+    Success = 0,
+    /// Any other error, eg. an invalid trace file
+    Failure = 1,
+    /// The header, or the proof of a query response, could not be verified
+    VerificationFailed = 3,
+    /// A witness provided a header conflicting with the one of the primary
+    ForkDetected = 4,
+    /// The trusted state is outside of the trusting period
+    TrustingPeriodExpired = 5,
+    /// A local I/O error occurred, eg. while accessing the light store
+    Io = 6,
+    /// An error occurred while talking to a node
+    Rpc = 7,
+}
+
+impl ExitStatus {
+    /// The exit status for the first error of the chain of causes of the given report
+    /// which has a known outcome.
+    fn of(report: &Report) -> Self {
+        report
+            .chain()
+            .find_map(Self::of_cause)
+            .unwrap_or(Self::Failure)
+    }
+
+    fn of_cause(cause: &(dyn std::error::Error + 'static)) -> Option<Self> {
+        if let Some(e) = cause.downcast_ref::<LightClientError>() {
+            Some(Self::of_light_client_error(e.detail()))
+        } else if let Some(e) = cause.downcast_ref::<builder_error::Error>() {
+            Self::of_builder_error(e.detail())
+        } else if let Some(e) = cause.downcast_ref::<stateless::Error>() {
+            match e.detail() {
+                stateless::ErrorDetail::Builder(e) => Self::of_builder_error(&e.source),
+                stateless::ErrorDetail::Verification(e) => {
+                    Some(Self::of_light_client_error(&e.source))
+                },
+                stateless::ErrorDetail::EmptyTrace(_) => None,
+            }
+        } else if let Some(e) = cause.downcast_ref::<Error>() {
+            match e.detail() {
+                ErrorDetail::Io(e) => Some(Self::of_io_error(&e.source)),
+                ErrorDetail::LightClient(e) => Some(Self::of_light_client_error(&e.source)),
+                ErrorDetail::Divergence(_) => Some(Self::ForkDetected),
+                ErrorDetail::NoQuorum(_) => Some(Self::VerificationFailed),
+                _ => None,
+            }
+        } else if let Some(e) = cause.downcast_ref::<IoError>() {
+            Some(Self::of_io_error(e.detail()))
+        } else if cause.is::<query::Error>() {
+            Some(Self::VerificationFailed)
+        } else if cause.is::<tendermint_rpc::Error>() {
+            Some(Self::Rpc)
+        } else if cause.is::<std::io::Error>() {
+            Some(Self::Io)
+        } else {
+            None
+        }
+    }
+
+    fn of_builder_error(detail: &builder_error::ErrorDetail) -> Option<Self> {
+        match detail {
+            builder_error::ErrorDetail::Io(e) => Some(Self::of_io_error(&e.source)),
+            builder_error::ErrorDetail::InvalidLightBlock(e) => {
+                Some(Self::of_verification_error(&e.source))
+            },
+            builder_error::ErrorDetail::NoTrustedStateInStore(_)
+            | builder_error::ErrorDetail::EmptyWitnessList(_) => None,
+            _ => Some(Self::VerificationFailed),
+        }
+    }
+
+    fn of_light_client_error(detail: &LightClientErrorDetail) -> Self {
+        match detail {
+            LightClientErrorDetail::Io(e) => Self::of_io_error(&e.source),
+            LightClientErrorDetail::ForkDetected(_) => Self::ForkDetected,
+            LightClientErrorDetail::TrustedStateOutsideTrustingPeriod(_) => {
+                Self::TrustingPeriodExpired
+            },
+            LightClientErrorDetail::InvalidLightBlock(e) => Self::of_verification_error(&e.source),
+            LightClientErrorDetail::Sled(_)
+            | LightClientErrorDetail::SerdeCbor(_)
+            | LightClientErrorDetail::Snappy(_)
+            | LightClientErrorDetail::UnknownStorageFormat(_) => Self::Io,
+            LightClientErrorDetail::NoPrimary(_)
+            | LightClientErrorDetail::NoWitnesses(_)
+            | LightClientErrorDetail::NoWitnessesLeft(_)
+            | LightClientErrorDetail::ChannelDisconnected(_) => Self::Failure,
+            _ => Self::VerificationFailed,
+        }
+    }
+
+    fn of_verification_error(detail: &VerificationErrorDetail) -> Self {
+        match detail {
+            VerificationErrorDetail::NotWithinTrustPeriod(_) => Self::TrustingPeriodExpired,
+            _ => Self::VerificationFailed,
+        }
+    }
+
+    fn of_io_error(detail: &IoErrorDetail) -> Self {
+        match detail {
+            IoErrorDetail::Rpc(_) | IoErrorDetail::Timeout(_) => Self::Rpc,
+            IoErrorDetail::HeightTooHigh(_)
+            | IoErrorDetail::InvalidHeight(_)
+            | IoErrorDetail::InvalidValidatorSet(_) => Self::VerificationFailed,
+            IoErrorDetail::Runtime(_) => Self::Io,
+        }
+    }
+}
+
+impl From<ExitStatus> for ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        ExitCode::from(status as u8)
+    }
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     color_eyre::install()?;
 
     let args = Cli::parse();
@@ -125,22 +434,50 @@ async fn main() -> Result<()> {
 
     tracing_subscriber::fmt()
         .with_target(false)
+        .with_writer(std::io::stderr)
         .with_env_filter(env_filter)
         .finish()
         .init();
 
-    let options = Options {
-        trust_threshold: args.trust_threshold,
-        trusting_period: Duration::from_secs(args.trusting_period),
-        clock_drift: Duration::from_secs(args.max_clock_drift),
+    let result = match (args.command, args.detect) {
+        (Some(Command::Bootstrap(bootstrap_args)), _) => {
+            bootstrap::run(bootstrap_args, args.output).await
+        },
+        (Some(Command::Fetch(fetch_args)), _) => fetch::run(fetch_args, args.output).await,
+        (Some(Command::Verify(verify_args)), _) => verify::run(verify_args, args.output).await,
+        (Some(Command::VerifyQuery(verify_query_args)), _) => {
+            verify_query::run(verify_query_args, args.output)
+        },
+        (Some(Command::Trace(trace_command)), _) => trace::run(trace_command),
+        (Some(Command::Store(store_command)), _) => store::run(store_command),
+        (Some(Command::Repl(repl_args)), _) => repl::run(repl_args, args.output),
+        (Some(Command::Watch(watch_args)), _) => watch::run(watch_args, args.output).await,
+        (None, Some(detect_args)) => detect(detect_args, args.output).await,
+        (None, None) => unreachable!("clap requires either a subcommand or the detector arguments"),
     };
 
+    match result {
+        Ok(()) => Ok(ExitStatus::Success.into()),
+        Err(report) => {
+            eprintln!("Error: {report:?}");
+            Ok(ExitStatus::of(&report).into())
+        },
+    }
+}
+
+async fn detect(args: DetectArgs, output: OutputFormat) -> Result<()> {
+    let options = args.verification.to_options();
+    let mut evidence_store = args.evidence_store.open()?;
+
     let mut primary = make_provider(
         &args.chain_id,
         args.primary,
         args.trusted_height,
         args.trusted_hash,
         options,
+        args.store_path.as_deref(),
+        &args.connection,
+        None,
     )
     .await?;
 
@@ -158,45 +495,111 @@ async fn main() -> Result<()> {
 
     info!("Verified to height {} on primary", primary_block.height());
     let primary_trace = primary.get_trace(primary_block.height());
+    let verification_output = VerificationOutput::new(&primary_block, primary_trace.clone());
 
     let witnesses = join_all(args.witnesses.0.into_iter().map(|addr| {
         make_provider(
-            &args.chain_id,
+            primary.chain_id(),
             addr,
             trusted_block.height(),
             trusted_block.signed_header.header.hash(),
             options,
+            None,
+            &args.connection,
+            None,
         )
     }))
     .await;
 
     let mut witnesses = witnesses.into_iter().collect::<Result<Vec<_>>>()?;
 
-    let max_clock_drift = Duration::from_secs(args.max_clock_drift);
+    let max_clock_drift = options.clock_drift;
     let max_block_lag = Duration::from_secs(args.max_block_lag);
+    let witness_timeout = seconds_or_forever(args.witness_timeout);
     let now = Time::now();
 
-    run_detector(
+    let outcome = run_detector(
         &mut primary,
         witnesses.as_mut_slice(),
         primary_trace,
         max_clock_drift,
         max_block_lag,
+        witness_timeout,
         now,
+        &args.quorum.policy(),
+        args.report_evidence,
+        &mut evidence_store,
     )
     .await?;
 
+    if let Some(dir) = &args.divergence_reports {
+        write_divergence_reports(dir, &outcome.divergences)?;
+    }
+
+    match outcome.verdict {
+        QuorumVerdict::Accepted => {},
+        QuorumVerdict::Diverged => {
+            return Err(LightClientError::fork_detected(outcome.diverging()).into());
+        },
+        QuorumVerdict::NoQuorum => {
+            return Err(Error::no_quorum(
+                outcome.agreeing.len(),
+                args.quorum.min_agreeing_witnesses,
+            )
+            .into());
+        },
+    }
+
+    verification_output.print(output)
+}
+
+/// Write the versioned JSON report of each of the given divergences to its own file in the
+/// given directory, named after the height of the conflicting headers and the witness.
+fn write_divergence_reports(dir: &Path, divergences: &[Divergence]) -> Result<()> {
+    if divergences.is_empty() {
+        return Ok(());
+    }
+
+    std::fs::create_dir_all(dir)
+        .wrap_err_with(|| format!("failed to create directory {}", dir.display()))?;
+
+    for divergence in divergences {
+        let report = divergence.divergence_report();
+        let path = dir.join(format!(
+            "divergence-{}-{}.json",
+            report.divergence.conflicting_height, report.witness
+        ));
+
+        std::fs::write(&path, report.to_json())
+            .wrap_err_with(|| format!("failed to write divergence report to {}", path.display()))?;
+
+        info!("Wrote divergence report to {}", path.display());
+    }
+
     Ok(())
 }
 
+/// Run misbehavior detection against all witnesses, and return its outcome, as decided by the
+/// given quorum policy from the witnesses which agree with the primary and those which reported
+/// a header conflicting with it.
+///
+/// The evidence gathered is recorded in the given evidence store. If `report_evidence` is set,
+/// the evidence gathered against the primary is submitted to the witness which found it, and
+/// the evidence gathered against the witness to the primary, unless it was submitted already.
+/// A failed submission is recorded in the evidence store, to be retried later on.
+#[allow(clippy::too_many_arguments)]
 async fn run_detector(
     primary: &mut Provider,
     witnesses: &mut [Provider],
     primary_trace: Vec<LightBlock>,
     max_clock_drift: Duration,
     max_block_lag: Duration,
+    witness_timeout: Option<Duration>,
     now: Time,
-) -> Result<(), Report> {
+    quorum: &QuorumPolicy,
+    report_evidence: bool,
+    evidence_store: &mut EvidenceStore,
+) -> Result<QuorumOutcome, Report> {
     if witnesses.is_empty() {
         return Err(Error::no_witnesses().into());
     }
@@ -211,16 +614,39 @@ async fn run_detector(
     let last_verified_block = primary_trace.last();
     let last_verified_header = &last_verified_block.signed_header;
 
-    for witness in witnesses {
-        let divergence = detect_divergence::<Sha256>(
-            Some(primary),
-            witness,
-            primary_trace.clone().into_vec(),
-            max_clock_drift,
-            max_block_lag,
-        )
-        .await;
+    // The witnesses are queried concurrently, each for at most `--witness-timeout`
+    let results = detect_divergences::<Sha256, _>(
+        Some(&*primary),
+        witnesses,
+        primary_trace.into_vec(),
+        max_clock_drift,
+        max_block_lag,
+        witness_timeout,
+    )
+    .await;
 
+    let outcome = quorum.evaluate(witnesses, &results);
+
+    // Report the accountability of the forks found, once per conflicting header
+    for report in ForkReport::from_divergences(*primary.peer_id(), &outcome.divergences) {
+        warn!("{report}");
+    }
+
+    match outcome.verdict {
+        QuorumVerdict::Accepted if !outcome.divergences.is_empty() => warn!(
+            "{} witnesses diverge from the primary, fewer than the {} required for an incident",
+            outcome.divergences.len(),
+            quorum.min_diverging
+        ),
+        QuorumVerdict::NoQuorum => warn!(
+            "{} witnesses agree with the primary, fewer than the {} required to accept its header",
+            outcome.agreeing.len(),
+            quorum.min_agreeing
+        ),
+        _ => {},
+    }
+
+    for (witness, divergence) in witnesses.iter().zip(results) {
         let evidence = match divergence {
             Ok(Some(divergence)) => divergence.evidence,
             Ok(None) => {
@@ -241,44 +667,231 @@ async fn run_detector(
             },
         };
 
-        // Report the evidence to the witness
-        witness
-            .report_evidence(Evidence::from(evidence.against_primary))
-            .await
-            .map_err(|e| eyre!("failed to report evidence to witness: {}", e))?;
-
-        if let Some(against_witness) = evidence.against_witness {
-            // Report the evidence to the primary
-            primary
-                .report_evidence(Evidence::from(against_witness))
-                .await
-                .map_err(|e| eyre!("failed to report evidence to primary: {}", e))?;
+        // Record the evidence before submitting it, so that it is never submitted twice,
+        // even across restarts
+        let against_primary = EvidenceRecord::new(
+            evidence.against_primary,
+            *primary.peer_id(),
+            *witness.peer_id(),
+            now,
+        );
+        let against_primary_key = against_primary.key();
+        evidence_store.record(against_primary)?;
+
+        let against_witness_key = match evidence.against_witness {
+            Some(against_witness) => {
+                let against_witness = EvidenceRecord::new(
+                    against_witness,
+                    *witness.peer_id(),
+                    *primary.peer_id(),
+                    now,
+                );
+                let key = against_witness.key();
+                evidence_store.record(against_witness)?;
+                Some(key)
+            },
+            None => None,
+        };
+
+        if !report_evidence {
+            warn!(
+                "not reporting the evidence of the attack detected by witness {}, \
+                use --report-evidence to submit it",
+                witness.peer_id()
+            );
+
+            continue;
+        }
+
+        // Report the evidence against the primary to the witness, and the evidence against the
+        // witness to the primary. A failed submission is retried later on, see `EvidenceStore`.
+        evidence_store
+            .submit(against_primary_key, witness, Time::now())
+            .await?;
+
+        if let Some(key) = against_witness_key {
+            evidence_store.submit(key, primary, Time::now()).await?;
         }
     }
 
-    Ok(())
+    Ok(outcome)
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn make_provider(
     chain_id: &str,
     rpc_addr: HttpClientUrl,
     trusted_height: Height,
     trusted_hash: Hash,
     options: Options,
+    store_path: Option<&Path>,
+    connection: &ConnectionArgs,
+    checkpoint: Option<&Checkpointing>,
 ) -> Result<Provider> {
-    use tendermint_rpc::client::CompatMode;
+    let (rpc_client, status) = connection.connect(rpc_addr).await?;
+    let node_id = status.node_info.id;
+
+    // Fail fast on a node of another chain or network, rather than on the mismatching hashes
+    // of its headers once they are compared with those of the other providers
+    let network = status.node_info.network.as_str();
+    if network != chain_id {
+        return Err(
+            Error::chain_id_mismatch(node_id, chain_id.to_string(), network.to_string()).into(),
+        );
+    }
+
+    let light_store: Box<dyn LightStore> = match store_path {
+        Some(path) => Box::new(open_light_store(path, &node_id)?),
+        None => Box::new(MemoryStore::new()),
+    };
+
+    let light_store = match checkpoint {
+        Some(checkpoint) => checkpoint.wrap(light_store),
+        None => light_store,
+    };
+
+    let stored_block = resumable_block(light_store.as_ref(), trusted_height, trusted_hash)?;
 
-    let rpc_client = HttpClient::builder(rpc_addr)
-        .compat_mode(CompatMode::V0_34)
-        .build()?;
+    let builder = LightClientBuilder::prod(
+        node_id,
+        rpc_client.clone(),
+        light_store,
+        options,
+        connection.timeout(),
+    );
 
-    let node_id = rpc_client.status().await?.node_info.id;
-    let light_store = Box::new(MemoryStore::new());
+    let instance = match stored_block {
+        Some(stored_block) => {
+            info!(
+                "Resuming from light block at height {} found in light store",
+                stored_block.height()
+            );
 
-    let instance =
-        LightClientBuilder::prod(node_id, rpc_client.clone(), light_store, options, None)
+            builder.trust_from_store()?.build()
+        },
+        None => builder
             .trust_primary_at(trusted_height, trusted_hash)?
-            .build();
+            .build(),
+    };
 
     Ok(Provider::new(chain_id.to_string(), instance, rpc_client))
 }
+
+/// The light block to resume from, ie. the highest trusted or verified light block of the given
+/// light store if it is at or above the trusted height, or `None` to trust the light block of the
+/// primary at the trusted height instead.
+///
+/// Resuming requires the light store to hold the light block at the trusted height, so that its
+/// hash can be checked against the trusted hash. Otherwise, as when it has since been pruned,
+/// the light block of the primary is trusted instead, which checks it, and the light blocks of
+/// the light store above it are kept.
+fn resumable_block(
+    light_store: &dyn LightStore,
+    trusted_height: Height,
+    trusted_hash: Hash,
+) -> Result<Option<LightBlock>> {
+    let stored_block = match light_store.highest_trusted_or_verified() {
+        Some(stored_block) if stored_block.height() >= trusted_height => stored_block,
+        _ => return Ok(None),
+    };
+
+    let Some(trusted_block) = light_store.get_trusted_or_verified(trusted_height) else {
+        warn!(
+            "Light store does not contain the light block at trusted height {trusted_height}, \
+            trusting the primary at that height instead"
+        );
+        return Ok(None);
+    };
+
+    let stored_hash = trusted_block.signed_header.header.hash();
+    if stored_hash != trusted_hash {
+        return Err(eyre!(
+            "light store contains a different header at trusted height {trusted_height}: \
+            expected hash {trusted_hash}, found {stored_hash}"
+        ));
+    }
+
+    Ok(Some(stored_block))
+}
+
+/// Open the persistent light store for the given node, within the given directory.
+///
+/// Each node gets its own store, as the light client only accepts light blocks
+/// which were provided by the node it is connected to.
+fn open_light_store(path: &Path, node_id: &tendermint::node::Id) -> Result<SledStore> {
+    let path = path.join(node_id.to_string());
+
+    SledStore::open(&path)
+        .wrap_err_with(|| format!("failed to open light store at {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use tendermint_light_client::{
+        store::memory::MemoryStore, tests::light_blocks, verifier::types::Status,
+    };
+
+    use super::*;
+
+    /// A light store holding the light blocks of the default test chain at the given heights.
+    fn light_store(heights: &[usize]) -> (MemoryStore, Vec<LightBlock>) {
+        let light_blocks = light_blocks(8);
+        let mut light_store = MemoryStore::new();
+        for &height in heights {
+            light_store.insert(light_blocks[height - 1].clone(), Status::Verified);
+        }
+
+        (light_store, light_blocks)
+    }
+
+    fn resumable_height(
+        light_store: &MemoryStore,
+        trusted_block: &LightBlock,
+    ) -> Result<Option<u64>> {
+        let trusted_hash = trusted_block.signed_header.header.hash();
+        let stored_block = resumable_block(light_store, trusted_block.height(), trusted_hash)?;
+
+        Ok(stored_block.map(|lb| lb.height().value()))
+    }
+
+    #[test]
+    fn resumes_from_store_holding_trusted_block() {
+        let (light_store, light_blocks) = light_store(&[3, 6]);
+
+        assert_eq!(
+            resumable_height(&light_store, &light_blocks[2]).unwrap(),
+            Some(6)
+        );
+    }
+
+    #[test]
+    fn trusts_primary_below_trusted_height() {
+        let (light_store, light_blocks) = light_store(&[3]);
+
+        assert_eq!(
+            resumable_height(&light_store, &light_blocks[4]).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn trusts_primary_if_store_lacks_trusted_block() {
+        let (light_store, light_blocks) = light_store(&[2, 6]);
+
+        assert_eq!(
+            resumable_height(&light_store, &light_blocks[3]).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn rejects_store_holding_another_trusted_block() {
+        let (light_store, light_blocks) = light_store(&[3, 6]);
+        let trusted_hash = light_blocks[1].signed_header.header.hash();
+
+        let err = resumable_block(&light_store, 3_u32.into(), trusted_hash).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("different header at trusted height 3"));
+    }
+}
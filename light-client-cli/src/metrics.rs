@@ -0,0 +1,213 @@
+//! Prometheus metrics of the `watch` subcommand, served over HTTP at `/metrics`.
+
+use std::net::SocketAddr;
+
+use color_eyre::{eyre::Context, Result};
+use prometheus::{
+    Encoder, GaugeVec, Histogram, HistogramOpts, IntCounter, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+use tendermint_light_client_detector::PeerScore;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+use tracing::{debug, info};
+
+/// The metrics recorded while watching a chain.
+#[derive(Clone, Debug)]
+pub struct Metrics {
+    registry: Registry,
+
+    /// Height of the latest verified header
+    pub verified_height: IntGauge,
+    /// Number of headers verified so far
+    pub verified_headers: IntCounter,
+    /// Number of light blocks needed to verify each header
+    pub bisection_steps: Histogram,
+    /// Time taken to verify each header, in seconds
+    pub verification_duration: Histogram,
+    /// Number of failed verifications of the latest header, other than RPC errors
+    pub verification_failures: IntCounter,
+    /// Number of times a witness reported a header conflicting with the primary
+    pub witness_disagreements: IntCounter,
+    /// Number of errors raised while talking to the primary
+    pub rpc_errors: IntCounter,
+    /// Number of witnesses currently used for misbehavior detection
+    pub active_witnesses: IntGauge,
+    /// Number of witnesses demoted to standby after failing their health checks
+    pub witness_demotions: IntCounter,
+    /// Number of times the primary was replaced by a witness after failing too many requests
+    pub primary_failovers: IntCounter,
+    /// Average latency of every peer, in seconds
+    pub peer_latency: GaugeVec,
+    /// Average error rate of every peer
+    pub peer_error_rate: GaugeVec,
+    /// Number of blocks every peer is behind the primary
+    pub peer_height_lag: IntGaugeVec,
+}
+
+impl Metrics {
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new_custom(Some("light_client".to_string()), None)?;
+
+        let verified_height =
+            IntGauge::new("verified_height", "Height of the latest verified header")?;
+        let verified_headers =
+            IntCounter::new("verified_headers_total", "Number of headers verified")?;
+        let bisection_steps = Histogram::with_opts(
+            HistogramOpts::new(
+                "bisection_steps",
+                "Number of light blocks needed to verify a header",
+            )
+            .buckets(vec![1.0, 2.0, 3.0, 5.0, 8.0, 13.0, 21.0, 34.0]),
+        )?;
+        let verification_duration = Histogram::with_opts(HistogramOpts::new(
+            "verification_duration_seconds",
+            "Time taken to verify a header",
+        ))?;
+        let verification_failures = IntCounter::new(
+            "verification_failures_total",
+            "Number of failed verifications, other than RPC errors",
+        )?;
+        let witness_disagreements = IntCounter::new(
+            "witness_disagreements_total",
+            "Number of headers from witnesses conflicting with the primary",
+        )?;
+        let rpc_errors = IntCounter::new(
+            "rpc_errors_total",
+            "Number of errors raised while talking to the primary",
+        )?;
+        let active_witnesses = IntGauge::new(
+            "active_witnesses",
+            "Number of witnesses used for misbehavior detection",
+        )?;
+        let witness_demotions = IntCounter::new(
+            "witness_demotions_total",
+            "Number of witnesses demoted after failing their health checks",
+        )?;
+        let primary_failovers = IntCounter::new(
+            "primary_failovers_total",
+            "Number of times the primary was replaced by a witness",
+        )?;
+        let peer_latency = GaugeVec::new(
+            Opts::new("peer_latency_seconds", "Average latency of a peer"),
+            &["peer"],
+        )?;
+        let peer_error_rate = GaugeVec::new(
+            Opts::new(
+                "peer_error_rate",
+                "Average ratio of failed requests to a peer",
+            ),
+            &["peer"],
+        )?;
+        let peer_height_lag = IntGaugeVec::new(
+            Opts::new(
+                "peer_height_lag",
+                "Number of blocks a peer is behind the primary",
+            ),
+            &["peer"],
+        )?;
+
+        registry.register(Box::new(verified_height.clone()))?;
+        registry.register(Box::new(verified_headers.clone()))?;
+        registry.register(Box::new(bisection_steps.clone()))?;
+        registry.register(Box::new(verification_duration.clone()))?;
+        registry.register(Box::new(verification_failures.clone()))?;
+        registry.register(Box::new(witness_disagreements.clone()))?;
+        registry.register(Box::new(rpc_errors.clone()))?;
+        registry.register(Box::new(active_witnesses.clone()))?;
+        registry.register(Box::new(witness_demotions.clone()))?;
+        registry.register(Box::new(primary_failovers.clone()))?;
+        registry.register(Box::new(peer_latency.clone()))?;
+        registry.register(Box::new(peer_error_rate.clone()))?;
+        registry.register(Box::new(peer_height_lag.clone()))?;
+
+        Ok(Self {
+            registry,
+            verified_height,
+            verified_headers,
+            bisection_steps,
+            verification_duration,
+            verification_failures,
+            witness_disagreements,
+            rpc_errors,
+            active_witnesses,
+            witness_demotions,
+            primary_failovers,
+            peer_latency,
+            peer_error_rate,
+            peer_height_lag,
+        })
+    }
+
+    /// Record the score of the given peer.
+    pub fn record_peer_score(&self, peer: &str, score: &PeerScore) {
+        if let Some(latency) = score.latency {
+            self.peer_latency
+                .with_label_values(&[peer])
+                .set(latency.as_secs_f64());
+        }
+
+        self.peer_error_rate
+            .with_label_values(&[peer])
+            .set(score.error_rate);
+
+        if let Some(height_lag) = score.height_lag {
+            self.peer_height_lag
+                .with_label_values(&[peer])
+                .set(height_lag as i64);
+        }
+    }
+
+    /// Encode the metrics in the Prometheus text format.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Serve the metrics over HTTP at `/metrics` on the given address, until the process exits.
+    pub async fn serve(self, addr: SocketAddr) -> Result<()> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .wrap_err_with(|| format!("failed to bind metrics server to {addr}"))?;
+
+        info!("Serving metrics at http://{addr}/metrics");
+
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            let metrics = self.clone();
+
+            tokio::spawn(async move {
+                if let Err(e) = metrics.respond(stream).await {
+                    debug!("failed to serve metrics to {peer}: {e}");
+                }
+            });
+        }
+    }
+
+    async fn respond(&self, mut stream: TcpStream) -> Result<()> {
+        let mut request = [0; 1024];
+        let len = stream.read(&mut request).await?;
+
+        let response = if request[..len].starts_with(b"GET /metrics ") {
+            let body = self.encode()?;
+            let mut response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                TextEncoder::new().format_type(),
+                body.len()
+            )
+            .into_bytes();
+            response.extend(body);
+            response
+        } else {
+            b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_vec()
+        };
+
+        stream.write_all(&response).await?;
+        stream.shutdown().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+//! Machine-readable output of the verification results.
+
+use color_eyre::Result;
+use serde::Serialize;
+use tendermint::block::Header;
+use tendermint_light_client::types::{Hash, Height, LightBlock};
+
+/// The format in which to print the verification results.
+///
+/// With `Text`, only the height and hash of the verified header are printed to stdout,
+/// on a single line, while with `Json` the full results are.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// The outcome of verifying a header, as printed with `--output json`.
+#[derive(Clone, Debug, Serialize)]
+pub struct VerificationOutput {
+    /// Height of the verified header
+    pub height: Height,
+    /// Hash of the verified header
+    pub hash: Hash,
+    /// Hash of the validator set at the verified height
+    pub validators_hash: Hash,
+    /// Hash of the validator set at the next height
+    pub next_validators_hash: Hash,
+    /// The verified header
+    pub header: Header,
+    /// The light blocks used to verify the header, sorted by height
+    pub trace: Vec<LightBlock>,
+}
+
+impl VerificationOutput {
+    pub fn new(verified: &LightBlock, mut trace: Vec<LightBlock>) -> Self {
+        trace.sort_by_key(|lb| lb.height());
+
+        let header = verified.signed_header.header.clone();
+
+        Self {
+            height: header.height,
+            hash: header.hash(),
+            validators_hash: header.validators_hash,
+            next_validators_hash: header.next_validators_hash,
+            header,
+            trace,
+        }
+    }
+
+    /// Print the output to stdout in the given format.
+    pub fn print(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Text => {
+                println!("{} {}", self.height, self.hash);
+                Ok(())
+            },
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(self)?);
+                Ok(())
+            },
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! Verification of the Merkle proofs of ABCI query responses against the app hash
+//! of a verified header.
+//!
+//! The proofs are expected in the format used by the Cosmos SDK: a chain of ICS-23 commitment
+//! proofs, where the first operation proves the queried key and value against the root of
+//! a store, and each subsequent operation proves the root computed by the previous one
+//! against the root above it, up to the app hash.
+//!
+//! Each operation is verified with the ICS-23 proof spec registered for its type in the given
+//! [`ProofSpecs`], which default to the ones of the Cosmos SDK, so that the proofs of chains
+//! committing to their state with other Merkle trees can be verified as well.
+
+use std::collections::BTreeMap;
+
+use flex_error::{define_error, DisplayOnly};
+use ics23::{
+    calculate_existence_root, commitment_proof::Proof, iavl_spec, tendermint_spec,
+    verify_membership, verify_non_membership, CommitmentProof, HostFunctionsManager, ProofSpec,
+};
+use prost::Message;
+use tendermint::merkle::proof::{ProofOp, ProofOps};
+
+define_error! {
+    Error {
+        EmptyProof
+            | _ | { "proof does not contain any operation" },
+
+        UnsupportedProofOp
+            { field_type: String, supported: Vec<String> }
+            | e | {
+                format_args!("unsupported proof operation type: {}, expected one of {}",
+                    e.field_type, e.supported.join(", "))
+            },
+
+        InvalidProofOp
+            { index: usize }
+            [ DisplayOnly<prost::DecodeError> ]
+            | e | { format_args!("failed to decode proof operation #{}", e.index) },
+
+        KeyMismatch
+            | _ | { "first proof operation is for another key than the queried one" },
+
+        InvalidRoot
+            { index: usize, reason: String }
+            | e | {
+                format_args!("failed to compute the root of proof operation #{}: {}",
+                    e.index, e.reason)
+            },
+
+        InvalidProof
+            { index: usize }
+            | e | { format_args!("proof operation #{} does not verify", e.index) },
+
+        AppHashMismatch
+            | _ | { "proof does not verify against the app hash of the header" },
+    }
+}
+
+/// The ICS-23 proof specs to verify the operations of a proof with, by operation type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProofSpecs {
+    specs: BTreeMap<String, ProofSpec>,
+}
+
+impl ProofSpecs {
+    /// The proof specs of the Cosmos SDK: `ics23:iavl` for the IAVL trees of the stores,
+    /// and `ics23:simple` for the simple Merkle tree committing to the roots of the stores.
+    pub fn cosmos() -> Self {
+        Self::empty()
+            .with_spec("ics23:iavl", iavl_spec())
+            .with_spec("ics23:simple", tendermint_spec())
+    }
+
+    /// No proof spec at all, to register the ones of a chain with [`ProofSpecs::with_spec`].
+    pub fn empty() -> Self {
+        Self {
+            specs: BTreeMap::new(),
+        }
+    }
+
+    /// Verify the operations of the given type with the given proof spec, instead of the one
+    /// registered for it already, if any.
+    pub fn with_spec(mut self, op_type: impl Into<String>, spec: ProofSpec) -> Self {
+        self.specs.insert(op_type.into(), spec);
+        self
+    }
+
+    /// The proof spec to verify the operations of the given type with, if any.
+    pub fn get(&self, op_type: &str) -> Option<&ProofSpec> {
+        self.specs.get(op_type)
+    }
+
+    /// The types of operations which have a proof spec, in lexicographic order.
+    pub fn op_types(&self) -> impl Iterator<Item = &str> {
+        self.specs.keys().map(String::as_str)
+    }
+}
+
+impl Default for ProofSpecs {
+    fn default() -> Self {
+        Self::cosmos()
+    }
+}
+
+/// Verify that the given value is stored under the given key, or that no value is stored
+/// under it if the value is empty, in the state committed to by the given app hash, using
+/// the proof specs of the Cosmos SDK.
+pub fn verify_query_proof(
+    proof: &ProofOps,
+    key: &[u8],
+    value: &[u8],
+    app_hash: &[u8],
+) -> Result<(), Error> {
+    verify_query_proof_with_specs(proof, key, value, app_hash, &ProofSpecs::default())
+}
+
+/// Verify the given proof like [`verify_query_proof`] does, using the given proof specs.
+pub fn verify_query_proof_with_specs(
+    proof: &ProofOps,
+    key: &[u8],
+    value: &[u8],
+    app_hash: &[u8],
+    specs: &ProofSpecs,
+) -> Result<(), Error> {
+    let first = proof.ops.first().ok_or_else(Error::empty_proof)?;
+    if first.key != key {
+        return Err(Error::key_mismatch());
+    }
+
+    let mut value = value.to_vec();
+
+    for (index, op) in proof.ops.iter().enumerate() {
+        let (spec, commitment_proof) = decode_op(index, op, specs)?;
+        let root = calculate_root(index, &commitment_proof)?;
+
+        // Only the queried key can be absent, the roots of the stores above it always exist
+        let verified = if index == 0 && value.is_empty() {
+            verify_non_membership::<HostFunctionsManager>(&commitment_proof, spec, &root, &op.key)
+        } else {
+            verify_membership::<HostFunctionsManager>(
+                &commitment_proof,
+                spec,
+                &root,
+                &op.key,
+                &value,
+            )
+        };
+
+        if !verified {
+            return Err(Error::invalid_proof(index));
+        }
+
+        value = root;
+    }
+
+    if value != app_hash {
+        return Err(Error::app_hash_mismatch());
+    }
+
+    Ok(())
+}
+
+fn decode_op<'a>(
+    index: usize,
+    op: &ProofOp,
+    specs: &'a ProofSpecs,
+) -> Result<(&'a ProofSpec, CommitmentProof), Error> {
+    let spec = specs.get(&op.field_type).ok_or_else(|| {
+        Error::unsupported_proof_op(
+            op.field_type.clone(),
+            specs.op_types().map(String::from).collect(),
+        )
+    })?;
+
+    let commitment_proof = CommitmentProof::decode(op.data.as_slice())
+        .map_err(|e| Error::invalid_proof_op(index, e))?;
+
+    Ok((spec, commitment_proof))
+}
+
+fn calculate_root(index: usize, proof: &CommitmentProof) -> Result<Vec<u8>, Error> {
+    let existence_proof = match &proof.proof {
+        Some(Proof::Exist(existence_proof)) => Some(existence_proof),
+        Some(Proof::Nonexist(non_existence_proof)) => non_existence_proof
+            .left
+            .as_ref()
+            .or(non_existence_proof.right.as_ref()),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        Error::invalid_root(
+            index,
+            "expected an existence or non-existence proof".to_string(),
+        )
+    })?;
+
+    calculate_existence_root::<HostFunctionsManager>(existence_proof)
+        .map_err(|e| Error::invalid_root(index, e.to_string()))
+}
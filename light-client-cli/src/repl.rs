@@ -0,0 +1,419 @@
+//! The `repl` subcommand, an interactive session to load traces, inspect their light blocks,
+//! and step through the verification of a header one bisection step at a time.
+//!
+//! Each step mirrors an iteration of the forward verification of the light client:
+//! the light block at the current height is verified against the highest trusted or verified
+//! one below the target height, and the scheduler then picks the height to try next.
+
+use std::{
+    io::{self, BufRead, IsTerminal, Write},
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{eyre::eyre, Result};
+use tendermint::Time;
+use tendermint_light_client::{
+    components::{
+        io::{AtHeight, Io},
+        scheduler::Scheduler,
+    },
+    light_client::Options,
+    store::{memory::MemoryStore, LightStore},
+    types::{Hash, Height, LightBlock, Status},
+    verifier::{ProdVerifier, Verdict, Verifier},
+};
+use tendermint_light_client_cli::stateless::{trace_scheduler, TraceIo};
+
+use crate::{
+    output::OutputFormat,
+    parse_hash,
+    trace::{self, TraceFormat},
+    VerificationOptions,
+};
+
+const HELP: &str = "\
+Commands:
+  load <file>            Load a trace file, replacing the current one and clearing the light store
+  trust <height> <hash>  Trust the light block of the trace at the given height, if it has the given hash
+  blocks                 List the light blocks of the trace
+  show <height>          Show the light block of the trace at the given height
+  store                  List the light blocks of the light store, with their status
+  target <height>        Start verifying the header at the given height, one step at a time
+  step                   Perform the next verification step
+  verify <height>        Verify the header at the given height, printing every step
+  help                   Print this message
+  quit                   Exit the REPL";
+
+#[derive(clap::Args, Debug)]
+pub struct ReplArgs {
+    /// Path of a trace file to load on startup
+    #[clap(long)]
+    input_file: Option<PathBuf>,
+
+    /// Format of the trace files (default: auto-detected)
+    #[clap(long, value_enum)]
+    input_format: Option<TraceFormat>,
+
+    /// Height of the header to trust on startup
+    #[clap(long, requires_all = ["input_file", "trusted_hash"])]
+    trusted_height: Option<Height>,
+
+    /// Hash of the header to trust on startup, in hex or base64
+    #[clap(long, value_parser = parse_hash, requires = "trusted_height")]
+    trusted_hash: Option<Hash>,
+
+    /// Time at which to verify the headers, in RFC 3339 format (default: current time)
+    #[clap(long)]
+    now: Option<Time>,
+
+    #[clap(flatten)]
+    verification: VerificationOptions,
+}
+
+/// A verification of a header in progress.
+#[derive(Copy, Clone, Debug)]
+struct Bisection {
+    target_height: Height,
+    current_height: Height,
+}
+
+/// The state of a REPL session.
+struct Session {
+    options: Options,
+    now: Option<Time>,
+    input_format: Option<TraceFormat>,
+    output: OutputFormat,
+    io: Option<TraceIo>,
+    scheduler: Box<dyn Scheduler>,
+    light_store: MemoryStore,
+    bisection: Option<Bisection>,
+}
+
+impl Session {
+    fn new(
+        options: Options,
+        now: Option<Time>,
+        input_format: Option<TraceFormat>,
+        output: OutputFormat,
+    ) -> Self {
+        Self {
+            options,
+            now,
+            input_format,
+            output,
+            io: None,
+            scheduler: Box::new(trace_scheduler(Vec::new())),
+            light_store: MemoryStore::new(),
+            bisection: None,
+        }
+    }
+
+    /// Run the given command line, and return whether the session should end.
+    fn execute(&mut self, line: &str) -> Result<bool> {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+
+        match words.as_slice() {
+            [] => {},
+            ["load", path] => self.load(Path::new(path))?,
+            ["trust", height, hash] => self.trust(height.parse()?, parse_hash(hash)?)?,
+            ["blocks"] => self.blocks()?,
+            ["show", height] => self.show(height.parse()?)?,
+            ["store"] => self.store(),
+            ["target", height] => self.target(height.parse()?)?,
+            ["step"] => {
+                self.step()?;
+            },
+            ["verify", height] => {
+                self.target(height.parse()?)?;
+                while !self.step()? {}
+            },
+            ["help"] => println!("{HELP}"),
+            ["quit"] | ["exit"] => return Ok(true),
+            _ => {
+                return Err(eyre!(
+                    "invalid command: {line}, type `help` for a list of commands"
+                ))
+            },
+        }
+
+        Ok(false)
+    }
+
+    fn io(&self) -> Result<&TraceIo> {
+        self.io
+            .as_ref()
+            .ok_or_else(|| eyre!("no trace loaded, use `load <file>` first"))
+    }
+
+    fn light_block(&self, height: Height) -> Result<LightBlock> {
+        self.io()?
+            .fetch_light_block(AtHeight::At(height))
+            .map_err(Into::into)
+    }
+
+    fn status(&self, height: Height) -> Option<Status> {
+        Status::iter()
+            .iter()
+            .copied()
+            .filter(|&status| self.light_store.get(height, status).is_some())
+            .max()
+    }
+
+    fn load(&mut self, path: &Path) -> Result<()> {
+        // Commands are read from stdin, so traces cannot be read from it too
+        if trace::is_stdio(path) {
+            return Err(eyre!("cannot load a trace from stdin in the REPL"));
+        }
+
+        let trace = trace::read_trace(path, self.input_format)?;
+        let peer_id = trace[0].provider;
+        let io = TraceIo::new(peer_id, trace);
+
+        println!(
+            "Loaded {} light blocks from {}",
+            io.heights().len(),
+            trace::display_input(path)
+        );
+
+        self.scheduler = Box::new(trace_scheduler(io.heights()));
+        self.io = Some(io);
+        self.light_store = MemoryStore::new();
+        self.bisection = None;
+
+        Ok(())
+    }
+
+    fn trust(&mut self, height: Height, hash: Hash) -> Result<()> {
+        let light_block = self.light_block(height)?;
+        let found = light_block.signed_header.header.hash();
+
+        if found != hash {
+            return Err(eyre!(
+                "light block at height {height} has hash {found}, not {hash}"
+            ));
+        }
+
+        self.light_store.update(&light_block, Status::Trusted);
+        println!("Trusted light block at height {height} with hash {hash}");
+
+        Ok(())
+    }
+
+    fn blocks(&self) -> Result<()> {
+        let io = self.io()?;
+
+        for height in io.heights() {
+            let light_block = io.fetch_light_block(AtHeight::At(height))?;
+            let header = &light_block.signed_header.header;
+
+            println!("{} {} {}", height, header.hash(), header.time);
+        }
+
+        Ok(())
+    }
+
+    fn show(&self, height: Height) -> Result<()> {
+        let light_block = self.light_block(height)?;
+
+        if self.output == OutputFormat::Json {
+            println!("{}", serde_json::to_string_pretty(&light_block)?);
+            return Ok(());
+        }
+
+        let header = &light_block.signed_header.header;
+        let commit = &light_block.signed_header.commit;
+        let status = self
+            .status(height)
+            .map(|status| format!("{status:?}"))
+            .unwrap_or_else(|| "not in store".to_string());
+
+        println!("Height:               {}", header.height);
+        println!("Hash:                 {}", header.hash());
+        println!("Chain:                {}", header.chain_id);
+        println!("Time:                 {}", header.time);
+        println!(
+            "Validators:           {} ({})",
+            light_block.validators.validators().len(),
+            header.validators_hash
+        );
+        println!(
+            "Next validators:      {} ({})",
+            light_block.next_validators.validators().len(),
+            header.next_validators_hash
+        );
+        println!(
+            "Commit:               {} signatures at round {}",
+            commit.signatures.len(),
+            commit.round
+        );
+        println!("Status:               {status}");
+
+        Ok(())
+    }
+
+    fn store(&self) {
+        let mut light_blocks = Status::iter()
+            .iter()
+            .flat_map(|&status| {
+                self.light_store
+                    .all(status)
+                    .map(move |light_block| (light_block, status))
+            })
+            .collect::<Vec<_>>();
+
+        light_blocks.sort_by_key(|(light_block, _)| light_block.height());
+
+        for (light_block, status) in light_blocks {
+            println!(
+                "{} {} {:?}",
+                light_block.height(),
+                light_block.signed_header.header.hash(),
+                status
+            );
+        }
+    }
+
+    fn target(&mut self, target_height: Height) -> Result<()> {
+        self.io()?;
+
+        let trusted_block = self
+            .light_store
+            .highest_trusted_or_verified_before(target_height)
+            .ok_or_else(|| {
+                eyre!("no trusted state below height {target_height}, use `trust` first")
+            })?;
+
+        println!(
+            "Verifying header at height {} from trusted height {}",
+            target_height,
+            trusted_block.height()
+        );
+
+        self.bisection = Some(Bisection {
+            target_height,
+            current_height: target_height,
+        });
+
+        Ok(())
+    }
+
+    /// Perform the next verification step, and return whether the verification is over.
+    fn step(&mut self) -> Result<bool> {
+        let Bisection {
+            target_height,
+            current_height,
+        } = self
+            .bisection
+            .ok_or_else(|| eyre!("no verification in progress, use `target <height>` first"))?;
+
+        let trusted_block = self
+            .light_store
+            .highest_trusted_or_verified_before(target_height)
+            .ok_or_else(|| eyre!("no trusted state below height {target_height}"))?;
+
+        if trusted_block.height() == target_height {
+            println!("Header at height {target_height} is verified");
+            self.bisection = None;
+            return Ok(true);
+        }
+
+        let current_block = self.light_block(current_height)?;
+
+        let verdict = ProdVerifier::default().verify_update_header(
+            current_block.as_untrusted_state(),
+            trusted_block.as_trusted_state(),
+            &self.options,
+            self.now.unwrap_or_else(Time::now),
+        );
+
+        match verdict {
+            Verdict::Success => {
+                self.light_store.update(&current_block, Status::Verified);
+                println!(
+                    "Verified header at height {} from trusted height {}",
+                    current_height,
+                    trusted_block.height()
+                );
+            },
+            Verdict::NotEnoughTrust(tally) => {
+                self.light_store.update(&current_block, Status::Unverified);
+                println!(
+                    "Not enough trust in header at height {} from trusted height {}: {}",
+                    current_height,
+                    trusted_block.height(),
+                    tally
+                );
+            },
+            Verdict::Invalid(e) => {
+                self.light_store.update(&current_block, Status::Failed);
+                self.bisection = None;
+                return Err(eyre!(
+                    "header at height {} is invalid against trusted height {}: {}",
+                    current_height,
+                    trusted_block.height(),
+                    e
+                ));
+            },
+        }
+
+        if self
+            .light_store
+            .get_trusted_or_verified(target_height)
+            .is_some()
+        {
+            println!("Header at height {target_height} is verified");
+            self.bisection = None;
+            return Ok(true);
+        }
+
+        let next_height = self
+            .scheduler
+            .schedule(&self.light_store, current_height, target_height);
+
+        println!("Next height to verify: {next_height}");
+
+        self.bisection = Some(Bisection {
+            target_height,
+            current_height: next_height,
+        });
+
+        Ok(false)
+    }
+}
+
+pub fn run(args: ReplArgs, output: OutputFormat) -> Result<()> {
+    let mut session = Session::new(
+        args.verification.to_options(),
+        args.now,
+        args.input_format,
+        output,
+    );
+
+    if let Some(input_file) = &args.input_file {
+        session.load(input_file)?;
+    }
+
+    if let (Some(height), Some(hash)) = (args.trusted_height, args.trusted_hash) {
+        session.trust(height, hash)?;
+    }
+
+    let interactive = io::stdin().is_terminal();
+    let mut lines = io::stdin().lock().lines();
+
+    loop {
+        if interactive {
+            print!("> ");
+            io::stdout().flush()?;
+        }
+
+        let Some(line) = lines.next().transpose()? else {
+            return Ok(());
+        };
+
+        match session.execute(&line) {
+            Ok(true) => return Ok(()),
+            Ok(false) => {},
+            Err(e) => eprintln!("Error: {e}"),
+        }
+    }
+}
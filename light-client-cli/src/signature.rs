@@ -0,0 +1,201 @@
+//! Verification of detached ed25519 signatures over trace files, for traces distributed by
+//! a service which signs the traces it produces.
+//!
+//! The signature covers the exact bytes of the file, whatever its format, and is read from
+//! its own file, either as 64 raw bytes or in hex or base64.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{
+    eyre::{eyre, Context},
+    Result,
+};
+use subtle_encoding::{base64, hex};
+use tendermint::{
+    crypto::{default::signature::Verifier as DefaultVerifier, signature::Verifier},
+    PublicKey, Signature,
+};
+use tracing::debug;
+
+use crate::trace::display_input;
+
+const ED25519_PUBLIC_KEY_SIZE: usize = 32;
+const ED25519_SIGNATURE_SIZE: usize = 64;
+
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct SignatureArgs {
+    /// Path of a file containing the ed25519 signature of an input file, as raw bytes, in hex
+    /// or in base64; given once per input file, in the same order
+    #[clap(long, value_name = "SIGNATURE_FILE", requires = "trace_signer_pubkey")]
+    trace_signature: Vec<PathBuf>,
+
+    /// Ed25519 public key the input files must be signed with, in hex or base64
+    #[clap(long, value_parser = parse_public_key, requires = "trace_signature")]
+    trace_signer_pubkey: Option<PublicKey>,
+}
+
+impl SignatureArgs {
+    /// Check the number of signatures against the number of input files, if signatures
+    /// are required.
+    pub fn check_count(&self, input_files: usize) -> Result<()> {
+        if self.trace_signer_pubkey.is_some() && self.trace_signature.len() != input_files {
+            return Err(eyre!(
+                "expected one --trace-signature per input file, got {} signatures for {} input files",
+                self.trace_signature.len(),
+                input_files
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Verify the contents of the input file at the given index against its signature,
+    /// if signatures are required.
+    pub fn verify(&self, index: usize, path: &Path, bytes: &[u8]) -> Result<()> {
+        let Some(pubkey) = self.trace_signer_pubkey else {
+            return Ok(());
+        };
+
+        let signature_path = self.trace_signature.get(index).ok_or_else(|| {
+            eyre!(
+                "no --trace-signature given for input file {}",
+                display_input(path)
+            )
+        })?;
+
+        let signature = read_signature(signature_path)?;
+
+        DefaultVerifier::verify(pubkey, bytes, &signature).map_err(|e| {
+            eyre!(
+                "signature in {} does not verify over {} with key {}: {e}",
+                signature_path.display(),
+                display_input(path),
+                pubkey.to_hex()
+            )
+        })?;
+
+        debug!(
+            "Verified signature of {} with key {}",
+            display_input(path),
+            pubkey.to_hex()
+        );
+
+        Ok(())
+    }
+}
+
+fn read_signature(path: &Path) -> Result<Signature> {
+    let contents = fs::read(path).wrap_err_with(|| format!("failed to read {}", path.display()))?;
+
+    let bytes = if contents.len() == ED25519_SIGNATURE_SIZE {
+        Some(contents)
+    } else {
+        std::str::from_utf8(&contents)
+            .ok()
+            .and_then(|s| decode_hex_or_base64(s, ED25519_SIGNATURE_SIZE))
+    }
+    .ok_or_else(|| {
+        eyre!(
+            "invalid signature in {}, must be an ed25519 signature as raw bytes, in hex or in base64",
+            path.display()
+        )
+    })?;
+
+    Signature::try_from(bytes.as_slice())
+        .wrap_err_with(|| format!("invalid signature in {}", path.display()))
+}
+
+/// Parse an ed25519 public key, given either in hex, optionally prefixed with `0x`,
+/// or in base64.
+fn parse_public_key(s: &str) -> Result<PublicKey> {
+    decode_hex_or_base64(s, ED25519_PUBLIC_KEY_SIZE)
+        .and_then(|bytes| PublicKey::from_raw_ed25519(&bytes))
+        .ok_or_else(|| {
+            eyre!("invalid public key: {s}, must be an ed25519 public key in hex or base64")
+        })
+}
+
+/// Decode the given string, either in hex or in base64, if it holds exactly `len` bytes.
+fn decode_hex_or_base64(s: &str, len: usize) -> Option<Vec<u8>> {
+    let s = s.trim();
+    let digits = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s);
+
+    let bytes = if digits.len() == 2 * len && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        hex::decode(digits.to_ascii_lowercase()).ok()?
+    } else {
+        base64::decode(s).ok()?
+    };
+
+    (bytes.len() == len).then_some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write the given signature file to a temporary path, unique to the given test.
+    fn signature_file(test: &str, contents: &[u8]) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("signature-{test}-{}.sig", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn decodes_hex_or_base64() {
+        let bytes = (0..32).collect::<Vec<u8>>();
+        let hex = String::from_utf8(hex::encode(&bytes)).unwrap();
+        let base64 = String::from_utf8(base64::encode(&bytes)).unwrap();
+
+        assert_eq!(decode_hex_or_base64(&hex, 32), Some(bytes.clone()));
+        assert_eq!(
+            decode_hex_or_base64(&format!("0x{}", hex.to_uppercase()), 32),
+            Some(bytes.clone())
+        );
+        assert_eq!(
+            decode_hex_or_base64(&format!(" {base64}\n"), 32),
+            Some(bytes.clone())
+        );
+
+        assert_eq!(decode_hex_or_base64(&hex, 31), None);
+        assert_eq!(decode_hex_or_base64(&base64, 33), None);
+        assert_eq!(decode_hex_or_base64("not a key", 32), None);
+    }
+
+    #[test]
+    fn reads_signatures_in_any_encoding() {
+        let bytes = [7; ED25519_SIGNATURE_SIZE];
+        let encodings = [
+            ("raw", bytes.to_vec()),
+            ("hex", hex::encode(bytes)),
+            ("base64", base64::encode(bytes)),
+        ];
+
+        for (encoding, contents) in encodings {
+            let path = signature_file(encoding, &contents);
+            let signature = read_signature(&path).unwrap();
+            fs::remove_file(&path).unwrap();
+            assert_eq!(signature.as_bytes(), bytes, "{encoding}");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_signatures() {
+        let contents = [b"not a signature".to_vec(), vec![7; 32], vec![7; 65]];
+
+        for (i, contents) in contents.iter().enumerate() {
+            let path = signature_file(&format!("invalid-{i}"), contents);
+            let result = read_signature(&path);
+            fs::remove_file(&path).unwrap();
+            assert!(result.is_err(), "{contents:?}");
+        }
+
+        assert!(read_signature(Path::new("/nonexistent/trace.sig")).is_err());
+    }
+}
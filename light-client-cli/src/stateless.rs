@@ -0,0 +1,267 @@
+//! Verification of headers using only the light blocks of a trace, without talking to any node.
+
+use std::collections::BTreeMap;
+
+use flex_error::define_error;
+use tendermint::Time;
+use tendermint_light_client::{
+    builder::{error::Error as BuilderError, LightClientBuilder},
+    components::clock::{Clock, FixedClock},
+    errors::Error as LightClientError,
+    instance::Instance,
+    light_client::Options,
+    predicates::ProdPredicates,
+    store::memory::MemoryStore,
+    types::{Hash, Height, LightBlock, PeerId},
+    verifier::{self, errors::VerificationError, ProdVerifier, Verdict},
+};
+// Re-export for backward compatibility
+pub use tendermint_light_client::components::{io::TraceIo, scheduler::trace_scheduler};
+
+define_error! {
+    Error {
+        EmptyTrace
+            | _ | { "trace is empty" },
+
+        Builder
+            [ BuilderError ]
+            | _ | { "failed to trust the header at the trusted height" },
+
+        Verification
+            [ LightClientError ]
+            | _ | { "failed to verify the header" },
+    }
+}
+
+/// Build a light client instance for the given peer, which trusts the block at the given
+/// height and hash, and serves all other light blocks from the given trace.
+pub fn make_instance(
+    peer_id: PeerId,
+    trace: Vec<LightBlock>,
+    trusted_height: Height,
+    trusted_hash: Hash,
+    options: Options,
+    clock: Box<dyn Clock>,
+) -> Result<Instance, BuilderError> {
+    let io = TraceIo::new(peer_id, trace);
+    let scheduler = trace_scheduler(io.heights());
+
+    #[allow(clippy::box_default)]
+    let instance = LightClientBuilder::custom(
+        peer_id,
+        options,
+        Box::new(MemoryStore::new()),
+        Box::new(io),
+        clock,
+        Box::new(ProdVerifier::default()),
+        Box::new(scheduler),
+        Box::new(ProdPredicates),
+    )
+    .trust_primary_at(trusted_height, trusted_hash)?
+    .build();
+
+    Ok(instance)
+}
+
+/// Verify the header at the given height, or at the highest height of the trace if none
+/// is given, using only the light blocks of the trace and taking `now` as the current time.
+///
+/// Returns the verified light block.
+pub fn verify_trace(
+    trace: Vec<LightBlock>,
+    trusted_height: Height,
+    trusted_hash: Hash,
+    target_height: Option<Height>,
+    options: Options,
+    now: Time,
+) -> Result<LightBlock, Error> {
+    verify(
+        trace,
+        trusted_height,
+        trusted_hash,
+        target_height,
+        options,
+        now,
+    )
+    .map(|(verified, _)| verified)
+}
+
+/// Prune the trace down to the trusted block and the light blocks the header at the given
+/// height, or at the highest height of the trace if none is given, is verified with.
+///
+/// Those are the light blocks of the path [`verify_trace`] walks through the trace, skipping
+/// from each verified light block to the highest one it can verify. The walk is greedy, so
+/// that the pruned trace is not always the smallest one the header can be verified with, as
+/// skipping less far at some step can let the next one skip further, but it verifies along
+/// the same path. Nothing is pruned up to the header with sequential verification.
+pub fn prune_trace(
+    trace: Vec<LightBlock>,
+    trusted_height: Height,
+    trusted_hash: Hash,
+    target_height: Option<Height>,
+    options: Options,
+    now: Time,
+) -> Result<Vec<LightBlock>, Error> {
+    let (_, pruned) = verify(
+        trace,
+        trusted_height,
+        trusted_hash,
+        target_height,
+        options,
+        now,
+    )?;
+
+    Ok(pruned)
+}
+
+/// Verify the header at the given height, returning it along with the light blocks it was
+/// verified with, from the trusted block up, in ascending order of height.
+fn verify(
+    trace: Vec<LightBlock>,
+    trusted_height: Height,
+    trusted_hash: Hash,
+    target_height: Option<Height>,
+    options: Options,
+    now: Time,
+) -> Result<(LightBlock, Vec<LightBlock>), Error> {
+    let peer_id = trace
+        .first()
+        .map(|lb| lb.provider)
+        .ok_or_else(Error::empty_trace)?;
+
+    let target_height = target_height
+        .or_else(|| trace.iter().map(|lb| lb.height()).max())
+        .ok_or_else(Error::empty_trace)?;
+
+    // The light blocks up to the target height, without those at the same height as a later
+    // one, like the light client sees them through a `TraceIo`
+    let light_blocks = trace
+        .iter()
+        .filter(|lb| lb.height() <= target_height)
+        .map(|lb| (lb.height(), lb.clone()))
+        .collect::<BTreeMap<_, _>>()
+        .into_values()
+        .collect::<Vec<_>>();
+
+    // The light client checks the trusted block against its hash and the options
+    let mut instance = make_instance(
+        peer_id,
+        trace,
+        trusted_height,
+        trusted_hash,
+        options,
+        Box::new(FixedClock::new(now)),
+    )
+    .map_err(Error::builder)?;
+
+    let trusted = instance
+        .latest_trusted()
+        .expect("the trusted block is in the light store of the instance");
+
+    let has_target = light_blocks.last().map(|lb| lb.height()) == Some(target_height);
+    if target_height <= trusted_height || !has_target {
+        // Leave the verification backward to the light client, as well as the failure to
+        // find the target in the trace
+        let verified = instance
+            .light_client
+            .verify_to_target(target_height, &mut instance.state)
+            .map_err(Error::verification)?;
+
+        let mut pruned = instance.state.get_trace(verified.height());
+        if !pruned.iter().any(|lb| lb.height() == trusted_height) {
+            pruned.push(trusted);
+        }
+        pruned.sort_by_key(|lb| lb.height());
+
+        return Ok((verified, pruned));
+    }
+
+    let verdict = verifier::verify_trace(&trusted, &light_blocks, &options, now);
+    let error = match verdict.verdict {
+        Verdict::Success => None,
+        Verdict::Invalid(e) => Some(e),
+        Verdict::NotEnoughTrust(tally) => Some(VerificationError::not_enough_trust(tally).0),
+    };
+    if let Some(e) = error {
+        return Err(Error::verification(LightClientError::invalid_light_block(
+            e,
+        )));
+    }
+
+    let verified = light_blocks
+        .into_iter()
+        .filter(|lb| verdict.verified_heights.contains(&lb.height()));
+    let pruned = core::iter::once(trusted)
+        .chain(verified)
+        .collect::<Vec<_>>();
+    let target = pruned
+        .last()
+        .cloned()
+        .expect("the trace verified up to its target");
+
+    Ok((target, pruned))
+}
+
+#[cfg(test)]
+mod tests {
+    use tendermint_light_client::tests::{light_blocks, now, options, testgen_to_lb};
+    use tendermint_testgen::{helpers::get_time, Generator, Header, Validator};
+
+    use super::*;
+
+    /// The light blocks of a chain of the given length, whose single validator changes at
+    /// every height, so that no light block can be verified from another but the one below.
+    fn rotating_chain(length: u64) -> Vec<LightBlock> {
+        (1..=length)
+            .map(|height| {
+                let validators = [Validator::new(&height.to_string()).voting_power(50)];
+                let next_validators = [Validator::new(&(height + 1).to_string()).voting_power(50)];
+                let header = Header::new(&validators)
+                    .height(height)
+                    .chain_id("test-chain")
+                    .next_validators(&next_validators)
+                    .time(get_time(height).unwrap());
+
+                let light_block = tendermint_testgen::LightBlock::new_default_with_header(header)
+                    .next_validators(&next_validators)
+                    .generate()
+                    .unwrap();
+
+                testgen_to_lb(light_block)
+            })
+            .collect()
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn prune(trace: &[LightBlock], target_height: Option<u32>) -> Result<Vec<u64>, Error> {
+        let trusted = &trace[0];
+        let pruned = prune_trace(
+            trace.to_vec(),
+            trusted.height(),
+            trusted.signed_header.header.hash(),
+            target_height.map(Height::from),
+            options(),
+            now(trace.len() as u64),
+        )?;
+
+        Ok(pruned.iter().map(|lb| lb.height().value()).collect())
+    }
+
+    #[test]
+    fn prunes_skippable_trace() {
+        let trace = light_blocks(5);
+
+        assert_eq!(prune(&trace, None).unwrap(), [1, 5]);
+        assert_eq!(prune(&trace, Some(3)).unwrap(), [1, 3]);
+    }
+
+    #[test]
+    fn keeps_trace_which_cannot_be_pruned() {
+        let trace = rotating_chain(4);
+        assert_eq!(prune(&trace, None).unwrap(), [1, 2, 3, 4]);
+
+        // Without the light blocks in between, the header cannot be verified at all
+        let gapped = [trace[0].clone(), trace[1].clone(), trace[3].clone()];
+        assert!(prune(&gapped, None).is_err());
+    }
+}
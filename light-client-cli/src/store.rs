@@ -0,0 +1,199 @@
+//! The `store` subcommands, which operate on the persistent light stores written
+//! by the `--store-path` option of the other subcommands.
+//!
+//! Within the directory given with `--store-path`, each node gets its own store, in a
+//! sub-directory named after its node id: that sub-directory is the path to give here.
+
+use std::path::{Path, PathBuf};
+
+use color_eyre::{
+    eyre::{eyre, Context},
+    Result,
+};
+use tendermint_light_client::{
+    store::{migrate, sled::SledStore, sqlite::SqliteStore, LightStore},
+    types::{LightBlock, Status},
+};
+use tracing::info;
+
+use crate::trace::{self, TraceFormat};
+
+#[derive(clap::Subcommand, Debug)]
+pub enum StoreCommand {
+    /// Copy all the light blocks of a light store into another one, preserving their status
+    Migrate(MigrateArgs),
+
+    /// Export the trusted and verified light blocks of a light store to a trace file
+    Export(ExportArgs),
+
+    /// Compress the light blocks of a sled light store written by a previous version,
+    /// which stored them uncompressed
+    Compress(CompressArgs),
+}
+
+/// The implementation of a persistent light store: a sled database, as written by
+/// `--store-path`, an SQLite database file, or a RocksDB database.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum StoreBackend {
+    #[default]
+    Sled,
+    Sqlite,
+    #[cfg(feature = "rocksdb")]
+    Rocksdb,
+}
+
+impl StoreBackend {
+    /// Open the light store at the given path, creating it if needed.
+    pub fn open(self, path: &Path) -> Result<Box<dyn LightStore>> {
+        let light_store: Box<dyn LightStore> = match self {
+            Self::Sled => Box::new(SledStore::open(path)?),
+            Self::Sqlite => Box::new(SqliteStore::open(path)?),
+            #[cfg(feature = "rocksdb")]
+            Self::Rocksdb => Box::new(tendermint_light_client::store::rocksdb::RocksDbStore::open(
+                path,
+            )?),
+        };
+
+        Ok(light_store)
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct MigrateArgs {
+    /// Path of the light store to copy the light blocks from
+    #[clap(long)]
+    from: PathBuf,
+
+    /// Implementation of the light store to copy the light blocks from
+    #[clap(long, value_enum, default_value_t)]
+    from_backend: StoreBackend,
+
+    /// Path of the light store to copy the light blocks to, created if needed
+    #[clap(long)]
+    to: PathBuf,
+
+    /// Implementation of the light store to copy the light blocks to
+    #[clap(long, value_enum, default_value_t)]
+    to_backend: StoreBackend,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ExportArgs {
+    /// Path of the light store to export
+    #[clap(long)]
+    store_path: PathBuf,
+
+    /// Implementation of the light store to export
+    #[clap(long, value_enum, default_value_t)]
+    backend: StoreBackend,
+
+    /// Path of the trace file to write, or `-` for stdout
+    #[clap(long)]
+    output_file: PathBuf,
+
+    /// Format of the trace file to write
+    #[clap(long, value_enum, default_value_t)]
+    output_format: TraceFormat,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct CompressArgs {
+    /// Path of the sled light store to compress
+    #[clap(long)]
+    store_path: PathBuf,
+}
+
+pub fn run(command: StoreCommand) -> Result<()> {
+    match command {
+        StoreCommand::Migrate(args) => run_migrate(args),
+        StoreCommand::Export(args) => run_export(args),
+        StoreCommand::Compress(args) => run_compress(args),
+    }
+}
+
+fn run_migrate(args: MigrateArgs) -> Result<()> {
+    if args.from == args.to {
+        return Err(eyre!(
+            "cannot migrate light store at {} to itself",
+            args.from.display()
+        ));
+    }
+
+    let from = open_existing(&args.from, args.from_backend)?;
+    let mut to = args
+        .to_backend
+        .open(&args.to)
+        .wrap_err_with(|| format!("failed to open light store at {}", args.to.display()))?;
+
+    let count = migrate(from.as_ref(), to.as_mut());
+
+    info!(
+        "Copied {count} light blocks from {} to {}",
+        args.from.display(),
+        args.to.display()
+    );
+
+    Ok(())
+}
+
+fn run_export(args: ExportArgs) -> Result<()> {
+    let light_store = open_existing(&args.store_path, args.backend)?;
+
+    // The trusted and verified statuses cannot both hold a light block at the same height
+    let trace: Vec<LightBlock> = light_store
+        .all(Status::Trusted)
+        .chain(light_store.all(Status::Verified))
+        .collect();
+
+    if trace.is_empty() {
+        return Err(eyre!(
+            "light store at {} contains no trusted or verified light block",
+            args.store_path.display()
+        ));
+    }
+
+    info!(
+        "Exporting {} light blocks from {}",
+        trace.len(),
+        args.store_path.display()
+    );
+
+    trace::write_trace(&args.output_file, trace, args.output_format)
+}
+
+fn run_compress(args: CompressArgs) -> Result<()> {
+    if !args.store_path.exists() {
+        return Err(eyre!(
+            "no light store found at {}",
+            args.store_path.display()
+        ));
+    }
+
+    let light_store = SledStore::open(&args.store_path).wrap_err_with(|| {
+        format!(
+            "failed to open light store at {}",
+            args.store_path.display()
+        )
+    })?;
+
+    let count = light_store.compress_all()?;
+
+    info!(
+        "Compressed {count} light blocks in {}",
+        args.store_path.display()
+    );
+
+    Ok(())
+}
+
+/// Open the light store at the given path, which must already exist, so that a typo in the
+/// path does not silently create, and read from, an empty store.
+fn open_existing(path: &Path, backend: StoreBackend) -> Result<Box<dyn LightStore>> {
+    if !path.exists() {
+        return Err(eyre!("no light store found at {}", path.display()));
+    }
+
+    backend
+        .open(path)
+        .wrap_err_with(|| format!("failed to open light store at {}", path.display()))
+}
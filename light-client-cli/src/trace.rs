@@ -0,0 +1,288 @@
+//! Reading, writing and serving verification traces.
+//!
+//! A trace file contains the [`LightBlock`]s used to verify a header, as produced by the
+//! `fetch` subcommand and consumed by the `verify` subcommand, either as a JSON array or
+//! as a sequence of length-delimited Protobuf messages (see [`proto`]).
+//! The path `-` stands for stdin when reading a trace, and for stdout when writing one.
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{
+    eyre::{eyre, Context},
+    Result,
+};
+use tendermint::Time;
+use tendermint_light_client::types::{Hash, Height, LightBlock};
+use tendermint_light_client_cli::stateless;
+use tracing::info;
+
+use crate::{parse_hash, signature::SignatureArgs, VerificationOptions};
+
+pub mod proto;
+
+/// The encoding of a trace file: either a JSON array of light blocks, or a sequence
+/// of length-delimited Protobuf light blocks.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum TraceFormat {
+    #[default]
+    Json,
+    Proto,
+}
+
+impl TraceFormat {
+    /// Guess the format of a trace from its first bytes.
+    ///
+    /// A JSON trace starts with an array, while the first byte of a Protobuf trace
+    /// is the length prefix of a light block, which never looks like whitespace or `[`
+    /// since light blocks are much larger than such lengths.
+    pub fn detect(bytes: &[u8]) -> Self {
+        match bytes.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'[') | None => Self::Json,
+            Some(_) => Self::Proto,
+        }
+    }
+}
+
+/// Whether the given path stands for stdin or stdout.
+pub fn is_stdio(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+/// Read a trace file in the given format, or in the detected one if none is given.
+pub fn read_trace(path: &Path, format: Option<TraceFormat>) -> Result<Vec<LightBlock>> {
+    let bytes = read_input(path)?;
+    parse_trace(path, &bytes, format)
+}
+
+/// Read the whole contents of an input file, or of stdin.
+fn read_input(path: &Path) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+
+    if is_stdio(path) {
+        io::stdin().lock().read_to_end(&mut bytes)
+    } else {
+        File::open(path)
+            .wrap_err_with(|| format!("failed to open {}", path.display()))?
+            .read_to_end(&mut bytes)
+    }
+    .wrap_err_with(|| format!("failed to read {}", display_input(path)))?;
+
+    Ok(bytes)
+}
+
+fn parse_trace(path: &Path, bytes: &[u8], format: Option<TraceFormat>) -> Result<Vec<LightBlock>> {
+    let trace = match format.unwrap_or_else(|| TraceFormat::detect(bytes)) {
+        TraceFormat::Json => serde_json::from_slice(bytes).map_err(Into::into),
+        TraceFormat::Proto => proto::decode_trace(bytes),
+    }
+    .wrap_err_with(|| format!("failed to parse trace from {}", display_input(path)))?;
+
+    if trace.is_empty() {
+        return Err(eyre!("trace in {} is empty", display_input(path)));
+    }
+
+    Ok(trace)
+}
+
+/// Read several trace files, and merge their light blocks into a single trace.
+///
+/// A light block found in more than one file is only kept once, while different light blocks
+/// at the same height are rejected, as at most one of them can be part of the chain.
+/// The signature of each file, if required, is verified before parsing it.
+pub fn read_traces(
+    paths: &[PathBuf],
+    format: Option<TraceFormat>,
+    signatures: &SignatureArgs,
+) -> Result<Vec<LightBlock>> {
+    signatures.check_count(paths.len())?;
+
+    let mut light_blocks = BTreeMap::<Height, (LightBlock, &Path)>::new();
+
+    for (index, path) in paths.iter().enumerate() {
+        let bytes = read_input(path)?;
+        signatures.verify(index, path, &bytes)?;
+
+        for lb in parse_trace(path, &bytes, format)? {
+            match light_blocks.get(&lb.height()) {
+                Some((existing, _)) if same_block(existing, &lb) => {},
+                Some((existing, existing_path)) => {
+                    return Err(eyre!(
+                        "conflicting light blocks at height {}: {} has hash {}, {} has hash {}",
+                        lb.height(),
+                        display_input(existing_path),
+                        existing.signed_header.header.hash(),
+                        display_input(path),
+                        lb.signed_header.header.hash(),
+                    ));
+                },
+                None => {
+                    light_blocks.insert(lb.height(), (lb, path));
+                },
+            }
+        }
+    }
+
+    Ok(light_blocks.into_values().map(|(lb, _)| lb).collect())
+}
+
+/// Whether two light blocks are the same, regardless of the peer which provided them.
+fn same_block(a: &LightBlock, b: &LightBlock) -> bool {
+    a.signed_header == b.signed_header
+        && a.validators == b.validators
+        && a.next_validators == b.next_validators
+}
+
+/// Write the given trace to a file in the given format, with its light blocks sorted by height.
+pub fn write_trace(path: &Path, mut trace: Vec<LightBlock>, format: TraceFormat) -> Result<()> {
+    trace.sort_by_key(|lb| lb.height());
+
+    let writer: Box<dyn Write> = if is_stdio(path) {
+        Box::new(io::stdout().lock())
+    } else {
+        let file =
+            File::create(path).wrap_err_with(|| format!("failed to create {}", path.display()))?;
+        Box::new(file)
+    };
+
+    let mut writer = BufWriter::new(writer);
+
+    match format {
+        TraceFormat::Json => {
+            serde_json::to_writer_pretty(&mut writer, &trace)?;
+            writeln!(writer)?;
+        },
+        TraceFormat::Proto => writer.write_all(&proto::encode_trace(trace))?,
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// A human-readable name for the given input trace path, for use in messages.
+pub fn display_input(path: &Path) -> String {
+    if is_stdio(path) {
+        "stdin".to_string()
+    } else {
+        path.display().to_string()
+    }
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum TraceCommand {
+    /// Convert a trace file between the JSON and Protobuf formats
+    Convert(ConvertArgs),
+
+    /// Keep only the light blocks of a trace file needed to verify a header by bisection
+    Prune(PruneArgs),
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ConvertArgs {
+    /// Path of a trace file to convert, or `-` for stdin (can be repeated, to merge several files)
+    #[clap(long = "input-file", value_name = "INPUT_FILE", required = true)]
+    input_files: Vec<PathBuf>,
+
+    /// Format of the trace files to convert (default: auto-detected)
+    #[clap(long, value_enum)]
+    input_format: Option<TraceFormat>,
+
+    /// Path of the converted trace file to write, or `-` for stdout
+    #[clap(long)]
+    output_file: PathBuf,
+
+    /// Format of the converted trace file
+    #[clap(long, value_enum)]
+    output_format: TraceFormat,
+
+    #[clap(flatten)]
+    signatures: SignatureArgs,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct PruneArgs {
+    /// Path of a trace file to prune, or `-` for stdin (can be repeated, to merge several files)
+    #[clap(long = "input-file", value_name = "INPUT_FILE", required = true)]
+    input_files: Vec<PathBuf>,
+
+    /// Format of the trace files to prune (default: auto-detected)
+    #[clap(long, value_enum)]
+    input_format: Option<TraceFormat>,
+
+    #[clap(flatten)]
+    signatures: SignatureArgs,
+
+    /// Height of trusted header
+    #[clap(long)]
+    trusted_height: Height,
+
+    /// Hash of trusted header, in hex or base64
+    #[clap(long, value_parser = parse_hash)]
+    trusted_hash: Hash,
+
+    /// Height of the header to verify (default: highest height in the trace)
+    #[clap(long)]
+    target_height: Option<Height>,
+
+    /// Path of the pruned trace file to write, or `-` for stdout
+    #[clap(long)]
+    output_file: PathBuf,
+
+    /// Format of the pruned trace file
+    #[clap(long, value_enum, default_value_t = TraceFormat::Json)]
+    output_format: TraceFormat,
+
+    /// Time at which to verify the headers, in RFC 3339 format (default: current time)
+    #[clap(long)]
+    now: Option<Time>,
+
+    #[clap(flatten)]
+    verification: VerificationOptions,
+}
+
+pub fn run(command: TraceCommand) -> Result<()> {
+    match command {
+        TraceCommand::Convert(args) => {
+            let trace = read_traces(&args.input_files, args.input_format, &args.signatures)?;
+
+            info!(
+                "Converting trace of {} light blocks from {}",
+                trace.len(),
+                args.input_files
+                    .iter()
+                    .map(|path| display_input(path))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+
+            write_trace(&args.output_file, trace, args.output_format)
+        },
+        TraceCommand::Prune(args) => {
+            let trace = read_traces(&args.input_files, args.input_format, &args.signatures)?;
+            let len = trace.len();
+
+            let pruned = stateless::prune_trace(
+                trace,
+                args.trusted_height,
+                args.trusted_hash,
+                args.target_height,
+                args.verification.to_options(),
+                args.now.unwrap_or_else(Time::now),
+            )?;
+
+            info!(
+                "Pruned trace from {} to {} light blocks, up to height {}",
+                len,
+                pruned.len(),
+                pruned.last().map_or(args.trusted_height, |lb| lb.height())
+            );
+
+            write_trace(&args.output_file, pruned, args.output_format)
+        },
+    }
+}
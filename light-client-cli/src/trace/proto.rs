@@ -0,0 +1,96 @@
+//! Protobuf encoding of traces, as a sequence of length-delimited light blocks.
+
+use color_eyre::{
+    eyre::{eyre, Context},
+    Result,
+};
+use prost::Message;
+use tendermint::{block::signed_header::SignedHeader, node, validator};
+use tendermint_light_client::types::LightBlock;
+use tendermint_proto::types::{SignedHeader as RawSignedHeader, ValidatorSet as RawValidatorSet};
+
+/// A light block, as encoded in a Protobuf trace.
+///
+/// The first two fields are the ones of `tendermint.types.LightBlock`,
+/// so that each light block of a trace can also be decoded as such.
+#[derive(Clone, PartialEq, Message)]
+pub struct RawLightBlock {
+    #[prost(message, optional, tag = "1")]
+    pub signed_header: Option<RawSignedHeader>,
+    #[prost(message, optional, tag = "2")]
+    pub validator_set: Option<RawValidatorSet>,
+    #[prost(message, optional, tag = "3")]
+    pub next_validator_set: Option<RawValidatorSet>,
+    #[prost(bytes = "vec", tag = "4")]
+    pub provider: Vec<u8>,
+}
+
+impl TryFrom<RawLightBlock> for LightBlock {
+    type Error = color_eyre::Report;
+
+    fn try_from(raw: RawLightBlock) -> Result<Self> {
+        let signed_header = raw
+            .signed_header
+            .ok_or_else(|| eyre!("missing signed header"))?;
+        let validators = raw
+            .validator_set
+            .ok_or_else(|| eyre!("missing validator set"))?;
+        let next_validators = raw
+            .next_validator_set
+            .ok_or_else(|| eyre!("missing next validator set"))?;
+        let provider: [u8; 20] = raw.provider.as_slice().try_into().map_err(|_| {
+            eyre!(
+                "invalid provider id length: expected 20 bytes, got {}",
+                raw.provider.len()
+            )
+        })?;
+
+        Ok(LightBlock::new(
+            SignedHeader::try_from(signed_header)?,
+            validator::Set::try_from(validators)?,
+            validator::Set::try_from(next_validators)?,
+            node::Id::new(provider),
+        ))
+    }
+}
+
+impl From<LightBlock> for RawLightBlock {
+    fn from(lb: LightBlock) -> Self {
+        Self {
+            signed_header: Some(lb.signed_header.into()),
+            validator_set: Some(lb.validators.into()),
+            next_validator_set: Some(lb.next_validators.into()),
+            provider: lb.provider.as_bytes().to_vec(),
+        }
+    }
+}
+
+/// Encode a trace as a sequence of length-delimited light blocks.
+pub fn encode_trace(trace: Vec<LightBlock>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+
+    for lb in trace {
+        RawLightBlock::from(lb)
+            .encode_length_delimited(&mut bytes)
+            .expect("encoding to a Vec never fails");
+    }
+
+    bytes
+}
+
+/// Decode a trace encoded as a sequence of length-delimited light blocks.
+pub fn decode_trace(mut bytes: &[u8]) -> Result<Vec<LightBlock>> {
+    let mut trace = Vec::new();
+
+    while !bytes.is_empty() {
+        let raw = RawLightBlock::decode_length_delimited(&mut bytes)
+            .wrap_err_with(|| format!("failed to decode light block #{}", trace.len()))?;
+
+        let lb = LightBlock::try_from(raw)
+            .wrap_err_with(|| format!("invalid light block #{}", trace.len()))?;
+
+        trace.push(lb);
+    }
+
+    Ok(trace)
+}
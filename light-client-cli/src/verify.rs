@@ -0,0 +1,343 @@
+//! The `verify` subcommand, which verifies a header using the light blocks of a trace file,
+//! without talking to any node.
+//!
+//! When witness traces are given, the verified header is also checked against each of them
+//! with the attack detector.
+
+use std::{path::PathBuf, time::Duration};
+
+use color_eyre::{eyre::eyre, Report, Result};
+use tendermint::{crypto::default::Sha256, Time};
+use tendermint_light_client::{
+    components::clock::{Clock, FixedClock, SystemClock},
+    errors::Error as LightClientError,
+    instance::Instance,
+    light_client::Options,
+    types::{Hash, Height, LightBlock, PeerId},
+};
+use tendermint_light_client_cli::stateless;
+use tendermint_light_client_detector::{detect_divergences, Provider, Trace};
+use tracing::{error, info, warn};
+
+use crate::{
+    ibc::IbcArgs,
+    output::{OutputFormat, VerificationOutput},
+    parse_hash,
+    signature::SignatureArgs,
+    trace::{self, TraceFormat},
+    VerificationOptions,
+};
+
+#[derive(clap::Args, Debug)]
+pub struct VerifyArgs {
+    /// Path of a trace file containing light blocks to verify with, or `-` for stdin
+    /// (can be repeated, to merge the light blocks of several files)
+    #[clap(long = "input-file", value_name = "INPUT_FILE", required = true)]
+    input_files: Vec<PathBuf>,
+
+    /// Format of the trace files (default: auto-detected)
+    #[clap(long, value_enum)]
+    input_format: Option<TraceFormat>,
+
+    #[clap(flatten)]
+    signatures: SignatureArgs,
+
+    /// Height of trusted header
+    #[clap(long)]
+    trusted_height: Height,
+
+    /// Hash of trusted header, in hex or base64
+    #[clap(long, value_parser = parse_hash)]
+    trusted_hash: Hash,
+
+    /// Height of the header to verify (default: highest height in the trace)
+    #[clap(long, conflicts_with_all = ["from_height", "to_height", "step"])]
+    height: Option<Height>,
+
+    /// Verify every header of the trace from this height on (default: trusted height)
+    #[clap(long)]
+    from_height: Option<Height>,
+
+    /// Verify every header of the trace up to this height (default: highest height in the trace)
+    #[clap(long)]
+    to_height: Option<Height>,
+
+    /// Only verify every Nth header of the trace within the range, and the last one
+    #[clap(long)]
+    step: Option<usize>,
+
+    /// Path of a trace file served by a witness, to detect divergences against (can be repeated)
+    #[clap(long = "witness-trace")]
+    witness_traces: Vec<PathBuf>,
+
+    /// Time at which to verify the headers, in RFC 3339 format, eg. to re-verify a trace
+    /// captured in the past (default: current time)
+    #[clap(long)]
+    now: Option<Time>,
+
+    #[clap(flatten)]
+    verification: VerificationOptions,
+
+    #[clap(flatten)]
+    ibc: IbcArgs,
+}
+
+pub async fn run(args: VerifyArgs, output: OutputFormat) -> Result<()> {
+    let trace = trace::read_traces(&args.input_files, args.input_format, &args.signatures)?;
+    let target_heights = target_heights(&args, &trace)?;
+
+    let options = args.verification.to_options();
+
+    let mut primary = make_provider(
+        trace,
+        args.trusted_height,
+        args.trusted_hash,
+        options,
+        args.now,
+    )?;
+
+    // Verifying the heights in ascending order lets each verification start from
+    // the header verified by the previous one.
+    let mut verification_outputs = Vec::new();
+    let mut last_verified = None;
+    let mut failures = 0;
+
+    for &target_height in &target_heights {
+        info!("Verifying to height {} using trace...", target_height);
+
+        match primary.verify_to_height(target_height) {
+            Ok(verified) => {
+                info!(
+                    "Verified header at height {} with hash {}",
+                    verified.height(),
+                    verified.signed_header.header.hash()
+                );
+
+                let trace = primary.get_trace(verified.height());
+                verification_outputs.push(VerificationOutput::new(&verified, trace));
+                last_verified = Some(verified);
+            },
+            Err(e) if target_heights.len() > 1 => {
+                error!("Failed to verify header at height {target_height}: {e}");
+                failures += 1;
+            },
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    if failures > 0 {
+        print_all(&verification_outputs, output)?;
+
+        return Err(eyre!(
+            "failed to verify {failures} out of {} headers",
+            target_heights.len()
+        ));
+    }
+
+    // Detect divergences against, and export, the highest verified header only
+    let verified = last_verified.ok_or_else(|| eyre!("no header was verified"))?;
+
+    if args.witness_traces.is_empty() {
+        args.ibc.export(&verified, &options)?;
+        return print_all(&verification_outputs, output);
+    }
+    let primary_trace = Trace::new(primary.get_trace(verified.height()))?;
+
+    let mut witnesses = args
+        .witness_traces
+        .iter()
+        .map(|path| {
+            let witness_trace = trace::read_trace(path, args.input_format)?;
+            make_provider(
+                witness_trace,
+                args.trusted_height,
+                args.trusted_hash,
+                options,
+                args.now,
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // A trace never grows, so there is no point in waiting for a lagging witness to catch up.
+    let divergences = detect_divergences::<Sha256, _>(
+        Some(&primary),
+        &mut witnesses,
+        primary_trace.into_vec(),
+        Duration::ZERO,
+        Duration::ZERO,
+        None,
+    )
+    .await;
+
+    let mut conflicts = Vec::new();
+
+    for ((path, witness), divergence) in args.witness_traces.iter().zip(&witnesses).zip(divergences)
+    {
+        match divergence {
+            Ok(Some(divergence)) => {
+                conflicts.push(*witness.peer_id());
+
+                let challenging_block = &divergence.challenging_block;
+                warn!(
+                    "Conflicting header found in witness trace {}: \
+                    primary has hash {} at height {}, witness has hash {} at height {}",
+                    trace::display_input(path),
+                    verified.signed_header.header.hash(),
+                    verified.height(),
+                    challenging_block.signed_header.header.hash(),
+                    challenging_block.height(),
+                );
+            },
+            Ok(None) => {
+                info!(
+                    "No divergence found with witness trace {}",
+                    trace::display_input(path)
+                );
+            },
+            Err(e) => {
+                error!(
+                    "Failed to run attack detector against witness trace {}: {e}",
+                    trace::display_input(path)
+                );
+            },
+        }
+    }
+
+    if !conflicts.is_empty() {
+        let conflicts_count = conflicts.len();
+
+        return Err(
+            Report::new(LightClientError::fork_detected(conflicts)).wrap_err(format!(
+                "found conflicting headers in {conflicts_count} out of {} witness traces",
+                args.witness_traces.len()
+            )),
+        );
+    }
+
+    args.ibc.export(&verified, &options)?;
+    print_all(&verification_outputs, output)
+}
+
+fn print_all(verification_outputs: &[VerificationOutput], output: OutputFormat) -> Result<()> {
+    for verification_output in verification_outputs {
+        verification_output.print(output)?;
+    }
+
+    Ok(())
+}
+
+/// The heights of the headers to verify, in ascending order.
+///
+/// Either the single height given with `--height`, the highest height of the trace,
+/// or the heights of the trace within the range given with `--from-height` and `--to-height`.
+fn target_heights(args: &VerifyArgs, trace: &[LightBlock]) -> Result<Vec<Height>> {
+    let highest = trace
+        .iter()
+        .map(|lb| lb.height())
+        .max()
+        .ok_or_else(|| eyre!("trace is empty"))?;
+
+    if let Some(height) = args.height {
+        return Ok(vec![height]);
+    }
+
+    if args.from_height.is_none() && args.to_height.is_none() && args.step.is_none() {
+        return Ok(vec![highest]);
+    }
+
+    let from_height = args.from_height.unwrap_or(args.trusted_height);
+    let to_height = args.to_height.unwrap_or(highest);
+    let step = args.step.unwrap_or(1);
+
+    if step == 0 {
+        return Err(eyre!("step must be greater than 0"));
+    }
+
+    let mut heights = trace
+        .iter()
+        .map(|lb| lb.height())
+        .filter(|&h| h > args.trusted_height && from_height <= h && h <= to_height)
+        .collect::<Vec<_>>();
+
+    heights.sort();
+    heights.dedup();
+
+    let last = heights.last().copied();
+    let mut heights = heights.into_iter().step_by(step).collect::<Vec<_>>();
+
+    if let Some(last) = last {
+        if heights.last() != Some(&last) {
+            heights.push(last);
+        }
+    }
+
+    if heights.is_empty() {
+        return Err(eyre!(
+            "trace does not contain any header from height {from_height} to height {to_height}"
+        ));
+    }
+
+    Ok(heights)
+}
+
+/// Build a light client instance which trusts the block at the given height and hash,
+/// and serves all other light blocks from the given trace.
+///
+/// The instance verifies light blocks at the given time if any, and at the current time otherwise.
+pub fn make_instance(
+    trace: Vec<LightBlock>,
+    trusted_height: Height,
+    trusted_hash: Hash,
+    options: Options,
+    now: Option<Time>,
+) -> Result<Instance> {
+    let peer_id = trace_peer_id(&trace)?;
+
+    let instance = stateless::make_instance(
+        peer_id,
+        trace,
+        trusted_height,
+        trusted_hash,
+        options,
+        make_clock(now),
+    )?;
+
+    Ok(instance)
+}
+
+/// Build a provider for the attack detector which trusts the block at the given height
+/// and hash, and serves all other light blocks from the given trace, like [`make_instance`].
+fn make_provider(
+    trace: Vec<LightBlock>,
+    trusted_height: Height,
+    trusted_hash: Hash,
+    options: Options,
+    now: Option<Time>,
+) -> Result<Provider> {
+    let peer_id = trace_peer_id(&trace)?;
+
+    let provider = Provider::from_trace(
+        peer_id,
+        trace,
+        trusted_height,
+        trusted_hash,
+        options,
+        make_clock(now),
+    )?;
+
+    Ok(provider)
+}
+
+fn trace_peer_id(trace: &[LightBlock]) -> Result<PeerId> {
+    trace
+        .first()
+        .map(|lb| lb.provider)
+        .ok_or_else(|| eyre!("trace is empty"))
+}
+
+fn make_clock(now: Option<Time>) -> Box<dyn Clock> {
+    match now {
+        Some(now) => Box::new(FixedClock::new(now)),
+        None => Box::new(SystemClock),
+    }
+}
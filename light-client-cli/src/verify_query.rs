@@ -0,0 +1,228 @@
+//! The `verify-query` subcommand, which verifies the Merkle proof of an ABCI query response
+//! against the app hash of a header verified using the light blocks of a trace file.
+//!
+//! The app hash committing to the state at height `H` is the one of the header at height `H + 1`,
+//! so that is the header which gets verified.
+
+use std::{
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+};
+
+use color_eyre::{
+    eyre::{eyre, Context},
+    Result,
+};
+use ics23::{iavl_spec, smt_spec, tendermint_spec, ProofSpec};
+use prost::Message;
+use serde::Serialize;
+use subtle_encoding::hex;
+use tendermint::{hash::AppHash, serializers, Time};
+use tendermint_light_client::types::{Hash, Height};
+use tendermint_light_client_cli::query::{verify_query_proof_with_specs, ProofSpecs};
+use tendermint_rpc::endpoint::abci_query::AbciQuery;
+use tracing::info;
+
+use crate::{
+    output::OutputFormat,
+    parse_hash,
+    signature::SignatureArgs,
+    trace::{self, TraceFormat},
+    verify::make_instance,
+    VerificationOptions,
+};
+
+#[derive(clap::Args, Debug)]
+pub struct VerifyQueryArgs {
+    /// Path of the `/abci_query` response to verify, as returned by the RPC, or `-` for stdin
+    #[clap(long)]
+    query_file: PathBuf,
+
+    /// Path of a trace file containing light blocks to verify with (can be repeated)
+    #[clap(long = "input-file", value_name = "INPUT_FILE", required = true)]
+    input_files: Vec<PathBuf>,
+
+    /// Format of the trace files (default: auto-detected)
+    #[clap(long, value_enum)]
+    input_format: Option<TraceFormat>,
+
+    #[clap(flatten)]
+    signatures: SignatureArgs,
+
+    /// Height of trusted header
+    #[clap(long)]
+    trusted_height: Height,
+
+    /// Hash of trusted header, in hex or base64
+    #[clap(long, value_parser = parse_hash)]
+    trusted_hash: Hash,
+
+    /// Time at which to verify the header, in RFC 3339 format (default: current time)
+    #[clap(long)]
+    now: Option<Time>,
+
+    /// ICS-23 proof spec to verify the proof operations of the given type with, as
+    /// `TYPE=SPEC`, where `SPEC` is one of `iavl`, `tendermint` and `smt`, or the path of
+    /// a file containing a Protobuf-encoded proof spec (can be repeated, in addition to
+    /// the specs of the Cosmos SDK for `ics23:iavl` and `ics23:simple`)
+    #[clap(long = "proof-spec", value_name = "TYPE=SPEC", value_parser = parse_proof_spec)]
+    proof_specs: Vec<(String, ProofSpec)>,
+
+    #[clap(flatten)]
+    verification: VerificationOptions,
+}
+
+/// The outcome of verifying a query response, as printed with `--output json`.
+#[derive(Clone, Debug, Serialize)]
+pub struct QueryOutput {
+    /// Height of the state the query was performed against
+    pub height: Height,
+    /// Height of the verified header, whose app hash commits to that state
+    pub header_height: Height,
+    /// App hash of the verified header
+    #[serde(with = "serializers::apphash")]
+    pub app_hash: AppHash,
+    /// The queried key
+    #[serde(with = "serializers::bytes::hexstring")]
+    pub key: Vec<u8>,
+    /// The value stored under the key, empty if there is none
+    #[serde(with = "serializers::bytes::hexstring")]
+    pub value: Vec<u8>,
+}
+
+impl QueryOutput {
+    /// Print the output to stdout in the given format.
+    pub fn print(&self, format: OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Text => {
+                println!(
+                    "{} {} {}",
+                    self.height,
+                    String::from_utf8(hex::encode_upper(&self.key))?,
+                    String::from_utf8(hex::encode_upper(&self.value))?
+                );
+                Ok(())
+            },
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(self)?);
+                Ok(())
+            },
+        }
+    }
+}
+
+pub fn run(args: VerifyQueryArgs, output: OutputFormat) -> Result<()> {
+    let query = read_query(&args)?;
+
+    if query.code.is_err() {
+        return Err(eyre!(
+            "query failed with code {}: {}",
+            query.code.value(),
+            query.log
+        ));
+    }
+
+    let proof = query
+        .proof
+        .as_ref()
+        .ok_or_else(|| eyre!("query response has no proof, query with `prove` set to true"))?;
+
+    let header_height = query.height.increment();
+
+    let trace = trace::read_traces(&args.input_files, args.input_format, &args.signatures)?;
+    let mut instance = make_instance(
+        trace,
+        args.trusted_height,
+        args.trusted_hash,
+        args.verification.to_options(),
+        args.now,
+    )?;
+
+    info!(
+        "Verifying header at height {} using trace...",
+        header_height
+    );
+
+    let verified = instance
+        .light_client
+        .verify_to_target(header_height, &mut instance.state)?;
+
+    let app_hash = verified.signed_header.header.app_hash;
+
+    let specs = args
+        .proof_specs
+        .iter()
+        .cloned()
+        .fold(ProofSpecs::default(), |specs, (op_type, spec)| {
+            specs.with_spec(op_type, spec)
+        });
+
+    verify_query_proof_with_specs(proof, &query.key, &query.value, app_hash.as_bytes(), &specs)
+        .wrap_err("failed to verify the proof of the query response")?;
+
+    info!(
+        "Verified query response at height {} against app hash {} of header at height {}",
+        query.height, app_hash, header_height
+    );
+
+    QueryOutput {
+        height: query.height,
+        header_height,
+        app_hash,
+        key: query.key,
+        value: query.value,
+    }
+    .print(output)
+}
+
+/// Parse a proof spec given as `TYPE=SPEC` on the command line.
+fn parse_proof_spec(s: &str) -> Result<(String, ProofSpec)> {
+    let (op_type, spec) = s
+        .split_once('=')
+        .ok_or_else(|| eyre!("invalid proof spec: {s}, expected TYPE=SPEC"))?;
+
+    let spec = match spec {
+        "iavl" => iavl_spec(),
+        "tendermint" => tendermint_spec(),
+        "smt" => smt_spec(),
+        path => {
+            let bytes = fs::read(path).wrap_err_with(|| format!("failed to read {path}"))?;
+            ProofSpec::decode(bytes.as_slice())
+                .wrap_err_with(|| format!("invalid proof spec in {path}"))?
+        },
+    };
+
+    Ok((op_type.to_string(), spec))
+}
+
+/// Read an `/abci_query` response, either as the full JSON-RPC response or as its result only.
+fn read_query(args: &VerifyQueryArgs) -> Result<AbciQuery> {
+    let path = &args.query_file;
+
+    if trace::is_stdio(path) && args.input_files.iter().any(|p| trace::is_stdio(p)) {
+        return Err(eyre!(
+            "cannot read both the query response and a trace file from stdin"
+        ));
+    }
+
+    let bytes = if trace::is_stdio(path) {
+        let mut bytes = Vec::new();
+        io::stdin().lock().read_to_end(&mut bytes)?;
+        bytes
+    } else {
+        fs::read(path).wrap_err_with(|| format!("failed to read {}", path.display()))?
+    };
+
+    let mut json: serde_json::Value = serde_json::from_slice(&bytes)
+        .wrap_err_with(|| format!("failed to parse {}", trace::display_input(path)))?;
+
+    for field in ["result", "response"] {
+        if let Some(inner) = json.get_mut(field) {
+            json = inner.take();
+        }
+    }
+
+    serde_json::from_value(json)
+        .wrap_err_with(|| format!("invalid query response in {}", trace::display_input(path)))
+}
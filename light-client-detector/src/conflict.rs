@@ -1,10 +1,12 @@
 use tendermint::{crypto::Sha256, evidence::LightClientAttackEvidence, merkle::MerkleHash};
-use tendermint_light_client::verifier::types::LightBlock;
-use tracing::{error, error_span, warn};
+use tendermint_light_client::{evidence::make_evidence, verifier::types::LightBlock};
+use tracing::{error, error_span, warn, Instrument};
 
 use super::{
-    error::Error, evidence::make_evidence, examine::examine_conflicting_header_against_trace,
-    provider::Provider, trace::Trace,
+    error::Error,
+    examine::examine_conflicting_header_against_trace,
+    provider::{AsyncProvider, Provider},
+    trace::Trace,
 };
 
 #[derive(Clone, Debug)]
@@ -29,12 +31,48 @@ pub async fn gather_evidence_from_conflicting_headers<H>(
 where
     H: Sha256 + MerkleHash + Default,
 {
-    let _span =
-        error_span!("gather_evidence_from_conflicting_headers", witness = %witness.peer_id())
-            .entered();
+    gather_evidence_from_conflicting_headers_async::<H, Provider>(
+        primary,
+        witness,
+        primary_trace,
+        challenging_block,
+    )
+    .await
+}
+
+/// Gather the evidence from conflicting headers like [`gather_evidence_from_conflicting_headers`]
+/// does, from any [`AsyncProvider`].
+pub async fn gather_evidence_from_conflicting_headers_async<H, P>(
+    primary: Option<&P>,
+    witness: &P,
+    primary_trace: &Trace,
+    challenging_block: &LightBlock,
+) -> Result<GatheredEvidence, Error>
+where
+    H: Sha256 + MerkleHash + Default,
+    P: AsyncProvider + ?Sized,
+{
+    let span =
+        error_span!("gather_evidence_from_conflicting_headers", witness = %witness.peer_id());
 
+    gather_evidence::<H, P>(primary, witness, primary_trace, challenging_block)
+        .instrument(span)
+        .await
+}
+
+async fn gather_evidence<H, P>(
+    primary: Option<&P>,
+    witness: &P,
+    primary_trace: &Trace,
+    challenging_block: &LightBlock,
+) -> Result<GatheredEvidence, Error>
+where
+    H: Sha256 + MerkleHash + Default,
+    P: AsyncProvider + ?Sized,
+{
     let (witness_trace, primary_block) =
-        examine_conflicting_header_against_trace::<H>(primary_trace, challenging_block, witness)
+        examine_conflicting_header_against_trace::<H, P>(primary_trace, challenging_block, witness)
+            .await
             .map_err(|e| {
                 error!("Error validating witness's divergent header: {e}");
                 e
@@ -74,7 +112,8 @@ where
     // trace provided by the witness and holding the primary as the source of truth. Note: primary may not
     // respond but this is okay as we will halt anyway.
     let (primary_trace, witness_block) =
-        examine_conflicting_header_against_trace::<H>(&witness_trace, &primary_block, primary)
+        examine_conflicting_header_against_trace::<H, P>(&witness_trace, &primary_block, primary)
+            .await
             .map_err(|e| {
                 error!("Error validating primary's divergent header: {e}");
                 e
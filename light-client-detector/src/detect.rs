@@ -1,16 +1,29 @@
-use std::{thread, time::Duration};
+use std::time::Duration;
 
-use tracing::{debug, warn};
+use futures::{executor::block_on, future::join_all};
+use tracing::{debug, warn, Instrument};
 
-use tendermint::{block::signed_header::SignedHeader, crypto::Sha256, merkle::MerkleHash};
+use tendermint::{
+    block::{signed_header::SignedHeader, Header},
+    crypto::Sha256,
+    merkle::MerkleHash,
+};
 use tendermint_light_client::light_client::TargetOrLatest;
 use tendermint_light_client::verifier::errors::ErrorExt;
-use tendermint_light_client::verifier::types::LightBlock;
+use tendermint_light_client::verifier::types::{LightBlock, PeerId};
 
-use crate::conflict::GatheredEvidence;
+use crate::{
+    conflict::GatheredEvidence,
+    freeze::FrozenHeight,
+    quorum::{QuorumOutcome, QuorumPolicy},
+    report::{DivergenceReport, ForkReport},
+};
 
 use super::{
-    error::Error, gather_evidence_from_conflicting_headers, provider::Provider, trace::Trace,
+    conflict::gather_evidence_from_conflicting_headers_async,
+    error::Error,
+    provider::{AsyncProvider, Provider},
+    trace::Trace,
 };
 
 /// A divergence between the primary and a witness that has been detected in [`detect_divergence`].
@@ -20,6 +33,31 @@ pub struct Divergence {
     pub evidence: GatheredEvidence,
     /// The conflicting light block that was returned by the witness
     pub challenging_block: LightBlock,
+    /// The trace of the primary the conflicting light block was found against
+    pub primary_trace: Trace,
+}
+
+impl Divergence {
+    /// The height to freeze the light client at, along with the conflicting headers.
+    ///
+    /// See [`FrozenHeight::from_divergence`] for details.
+    pub fn frozen_height(&self) -> FrozenHeight {
+        FrozenHeight::from_divergence(self)
+    }
+
+    /// The accountability report of the fork found against the given primary.
+    ///
+    /// See [`ForkReport::new`] for details.
+    pub fn report(&self, primary: PeerId) -> ForkReport {
+        ForkReport::new(primary, self)
+    }
+
+    /// The serializable report of this divergence, for consumers which do not link this crate.
+    ///
+    /// See [`DivergenceReport::new`] for details.
+    pub fn divergence_report(&self) -> DivergenceReport {
+        DivergenceReport::new(self)
+    }
 }
 
 /// Given a primary trace and a witness, detect any divergence between the two,
@@ -43,6 +81,35 @@ pub async fn detect_divergence<H>(
 ) -> Result<Option<Divergence>, Error>
 where
     H: Sha256 + MerkleHash + Default,
+{
+    detect_divergence_async::<H, Provider>(
+        primary,
+        witness,
+        primary_trace,
+        max_clock_drift,
+        max_block_lag,
+    )
+    .await
+}
+
+/// Detect any divergence between the given primary trace and the given witness like
+/// [`detect_divergence`] does, from any [`AsyncProvider`].
+///
+/// When the providers do not block, eg. with [`AsyncInstance`]s, the detection can run within
+/// an async task, and be bounded by a timeout, or cancelled by dropping it, without tying up a
+/// thread.
+///
+/// [`AsyncInstance`]: tendermint_light_client::instance::AsyncInstance
+pub async fn detect_divergence_async<H, P>(
+    primary: Option<&P>,
+    witness: &mut P,
+    primary_trace: Vec<LightBlock>,
+    max_clock_drift: Duration,
+    max_block_lag: Duration,
+) -> Result<Option<Divergence>, Error>
+where
+    H: Sha256 + MerkleHash + Default,
+    P: AsyncProvider,
 {
     let primary_trace = Trace::new(primary_trace)?;
 
@@ -56,12 +123,14 @@ where
         "Running detector against primary trace"
     );
 
-    let result = compare_new_header_with_witness(
-        last_verified_header,
-        witness,
-        max_clock_drift,
-        max_block_lag,
-    );
+    let result = HeaderComparison::default()
+        .compare_async(
+            last_verified_header,
+            witness,
+            max_clock_drift,
+            max_block_lag,
+        )
+        .await;
 
     match result {
         // No divergence found
@@ -80,8 +149,16 @@ where
                 "Found conflicting headers between primary and witness"
             );
 
+            if let Some(primary) = primary {
+                primary.notify_fork_detected(
+                    last_verified_block,
+                    *witness.peer_id(),
+                    &challenging_block,
+                );
+            }
+
             // Gather the evidence to report from the conflicting headers
-            let evidence = gather_evidence_from_conflicting_headers::<H>(
+            let evidence = gather_evidence_from_conflicting_headers_async::<H, P>(
                 primary,
                 witness,
                 &primary_trace,
@@ -92,6 +169,7 @@ where
             Ok(Some(Divergence {
                 evidence,
                 challenging_block: *challenging_block,
+                primary_trace,
             }))
         },
 
@@ -111,6 +189,85 @@ where
     }
 }
 
+/// Run [`detect_divergence_async`] against all the given witnesses concurrently, and return the
+/// result for each of them, in the same order as the witnesses.
+///
+/// The detection against every witness is bounded by the given timeout, if any, after which it
+/// fails with a `WitnessTimeout` error for that witness, without delaying the detection against
+/// the other witnesses.
+///
+/// The detection runs within the calling task, so the witnesses must not block it for the
+/// detection to be concurrent and for the timeout to take effect. [`AsyncInstance`]s never do,
+/// and neither do [`Provider`]s within a Tokio runtime, whose blocking calls run on its blocking
+/// thread pool. Such a call keeps running once the detection against its witness times out,
+/// until the I/O of the light client of the witness gives up, and the next calls to the
+/// witness that need its light client wait for it.
+///
+/// [`AsyncInstance`]: tendermint_light_client::instance::AsyncInstance
+pub async fn detect_divergences<H, P>(
+    primary: Option<&P>,
+    witnesses: &mut [P],
+    primary_trace: Vec<LightBlock>,
+    max_clock_drift: Duration,
+    max_block_lag: Duration,
+    timeout: Option<Duration>,
+) -> Vec<Result<Option<Divergence>, Error>>
+where
+    H: Sha256 + MerkleHash + Default,
+    P: AsyncProvider,
+{
+    let primary_trace = &primary_trace;
+
+    join_all(witnesses.iter_mut().map(|witness| async move {
+        let peer_id = *witness.peer_id();
+        let detection = detect_divergence_async::<H, P>(
+            primary,
+            witness,
+            primary_trace.clone(),
+            max_clock_drift,
+            max_block_lag,
+        );
+
+        match timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, detection).await {
+                Ok(result) => result,
+                Err(_) => Err(Error::witness_timeout(peer_id, timeout)),
+            },
+            None => detection.await,
+        }
+    }))
+    .await
+}
+
+/// Run [`detect_divergences`] against all the given witnesses, and decide with the given policy
+/// whether the header verified on the primary is accepted, given how many witnesses agree with
+/// it, and whether the divergences found are an incident, given how many witnesses diverge.
+pub async fn detect_divergences_with_quorum<H, P>(
+    primary: Option<&P>,
+    witnesses: &mut [P],
+    primary_trace: Vec<LightBlock>,
+    max_clock_drift: Duration,
+    max_block_lag: Duration,
+    timeout: Option<Duration>,
+    policy: &QuorumPolicy,
+) -> QuorumOutcome
+where
+    H: Sha256 + MerkleHash + Default,
+    P: AsyncProvider,
+{
+    let results = detect_divergences::<H, P>(
+        primary,
+        witnesses,
+        primary_trace,
+        max_clock_drift,
+        max_block_lag,
+        timeout,
+    )
+    .await;
+
+    policy.evaluate(witnesses, &results)
+}
+
 /// An error that arised when comparing a header from the primary with a header from a witness
 /// with [`compare_new_header_with_witness`].
 #[derive(Debug)]
@@ -139,25 +296,169 @@ pub fn compare_new_header_with_witness(
     max_clock_drift: Duration,
     max_block_lag: Duration,
 ) -> Result<(), CompareError> {
-    let light_block = check_against_witness(new_header, witness, max_clock_drift, max_block_lag)?;
+    HeaderComparison::default().compare(new_header, witness, max_clock_drift, max_block_lag)
+}
+
+/// The fields of the headers compared by [`HeaderComparison::compare`] to tell whether the
+/// witness returned a header conflicting with the one of the primary.
+///
+/// By default, only the hashes of the headers are compared, like
+/// [`compare_new_header_with_witness`] does. Comparing other fields instead, or in addition,
+/// catches subtler misbehaviour when the hashes of the headers legitimately differ, eg. across
+/// RPC versions:
+///
+/// ```ignore
+/// let comparison = HeaderComparison::new()
+///     .with_app_hash()
+///     .with_next_validators_hash()
+///     .with_time_within(Duration::from_secs(1));
+///
+/// comparison.compare(new_header, witness, max_clock_drift, max_block_lag)?;
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HeaderComparison {
+    hash: bool,
+    app_hash: bool,
+    next_validators_hash: bool,
+    max_time_difference: Option<Duration>,
+}
+
+impl Default for HeaderComparison {
+    fn default() -> Self {
+        Self::new().with_hash()
+    }
+}
+
+impl HeaderComparison {
+    /// A comparison which does not compare any field, and never finds conflicting headers
+    /// until fields to compare are added to it.
+    pub fn new() -> Self {
+        Self {
+            hash: false,
+            app_hash: false,
+            next_validators_hash: false,
+            max_time_difference: None,
+        }
+    }
+
+    /// Compare the hashes of the headers.
+    pub fn with_hash(mut self) -> Self {
+        self.hash = true;
+        self
+    }
+
+    /// Compare the application hashes of the headers.
+    pub fn with_app_hash(mut self) -> Self {
+        self.app_hash = true;
+        self
+    }
+
+    /// Compare the hashes of the next validator sets of the headers.
+    pub fn with_next_validators_hash(mut self) -> Self {
+        self.next_validators_hash = true;
+        self
+    }
 
-    if light_block.signed_header.header.hash() != new_header.header.hash() {
-        return Err(CompareError::ConflictingHeaders(Box::new(light_block)));
+    /// Check that the times of the headers are at most the given duration apart.
+    pub fn with_time_within(mut self, max_difference: Duration) -> Self {
+        self.max_time_difference = Some(max_difference);
+        self
     }
 
-    Ok(())
+    /// The name of the first compared field which differs between the given headers, if any.
+    pub fn mismatch(&self, header: &Header, other: &Header) -> Option<&'static str> {
+        if self.hash && header.hash() != other.hash() {
+            return Some("hash");
+        }
+
+        if self.app_hash && header.app_hash != other.app_hash {
+            return Some("app_hash");
+        }
+
+        if self.next_validators_hash && header.next_validators_hash != other.next_validators_hash {
+            return Some("next_validators_hash");
+        }
+
+        if let Some(max_difference) = self.max_time_difference {
+            let difference = header
+                .time
+                .duration_since(other.time)
+                .or_else(|_| other.time.duration_since(header.time))
+                .unwrap_or(Duration::MAX);
+
+            if difference > max_difference {
+                return Some("time");
+            }
+        }
+
+        None
+    }
+
+    /// Compare the verified header from the primary with the header at the same height from
+    /// the given witness, like [`compare_new_header_with_witness`] does, but reporting
+    /// conflicting headers as soon as any of the compared fields differs between them.
+    pub fn compare(
+        &self,
+        new_header: &SignedHeader,
+        witness: &mut Provider,
+        max_clock_drift: Duration,
+        max_block_lag: Duration,
+    ) -> Result<(), CompareError> {
+        // The calling thread waits for the light client of the witness either way
+        block_on(self.compare_async(new_header, witness, max_clock_drift, max_block_lag))
+    }
+
+    /// Compare the verified header from the primary with the header at the same height from
+    /// the given witness like [`HeaderComparison::compare`] does, with any [`AsyncProvider`].
+    pub async fn compare_async<P>(
+        &self,
+        new_header: &SignedHeader,
+        witness: &mut P,
+        max_clock_drift: Duration,
+        max_block_lag: Duration,
+    ) -> Result<(), CompareError>
+    where
+        P: AsyncProvider + ?Sized,
+    {
+        let light_block =
+            check_against_witness(new_header, witness, max_clock_drift, max_block_lag).await?;
+
+        if let Some(field) = self.mismatch(&new_header.header, &light_block.signed_header.header) {
+            debug!(witness = %witness.peer_id(), field, "Headers of primary and witness differ");
+
+            return Err(CompareError::ConflictingHeaders(Box::new(light_block)));
+        }
+
+        Ok(())
+    }
 }
 
-fn check_against_witness(
+async fn check_against_witness<P>(
     sh: &SignedHeader,
-    witness: &mut Provider,
+    witness: &mut P,
     max_clock_drift: Duration,
     max_block_lag: Duration,
-) -> Result<LightBlock, CompareError> {
-    let _span =
-        tracing::debug_span!("check_against_witness", witness = %witness.peer_id()).entered();
+) -> Result<LightBlock, CompareError>
+where
+    P: AsyncProvider + ?Sized,
+{
+    let span = tracing::debug_span!("check_against_witness", witness = %witness.peer_id());
 
-    let light_block = witness.fetch_light_block(sh.header.height);
+    fetch_from_witness(sh, witness, max_clock_drift, max_block_lag)
+        .instrument(span)
+        .await
+}
+
+async fn fetch_from_witness<P>(
+    sh: &SignedHeader,
+    witness: &mut P,
+    max_clock_drift: Duration,
+    max_block_lag: Duration,
+) -> Result<LightBlock, CompareError>
+where
+    P: AsyncProvider + ?Sized,
+{
+    let light_block = witness.fetch_light_block(sh.header.height).await;
 
     match light_block {
         // No error means we move on to checking the hash of the two headers
@@ -180,6 +481,7 @@ fn check_against_witness(
 
             let light_block = witness
                 .get_target_block_or_latest(sh.header.height)
+                .await
                 .map_err(|_| CompareError::BadWitness)?;
 
             let light_block = match light_block {
@@ -206,10 +508,11 @@ fn check_against_witness(
             let wait_time = 2 * max_clock_drift + max_block_lag;
             debug!("The witness is behind. We wait for {wait_time:?}");
 
-            thread::sleep(wait_time);
+            witness.wait(wait_time).await;
 
             let light_block = witness
                 .get_target_block_or_latest(sh.header.height)
+                .await
                 .map_err(|_| CompareError::BadWitness)?;
 
             let light_block = match light_block {
@@ -240,3 +543,143 @@ fn check_against_witness(
         Err(other) => Err(CompareError::Other(other)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tendermint::{crypto::default::Sha256 as DefaultSha256, Hash};
+    use tendermint_light_client::tests::light_blocks;
+    use tokio::time::Instant;
+
+    use super::*;
+    use crate::{
+        error::ErrorDetail,
+        tests::{blocking_witness, SlowWitness},
+    };
+
+    #[test]
+    fn finds_mismatches_of_compared_fields() {
+        let header = light_blocks(1)[0].signed_header.header.clone();
+        let all_fields = HeaderComparison::new()
+            .with_hash()
+            .with_app_hash()
+            .with_next_validators_hash()
+            .with_time_within(Duration::from_secs(1));
+
+        assert_eq!(all_fields.mismatch(&header, &header), None);
+
+        let mut other_app_hash = header.clone();
+        other_app_hash.app_hash = vec![1; 32].try_into().unwrap();
+        let mut other_next_validators = header.clone();
+        other_next_validators.next_validators_hash = Hash::Sha256([1; 32]);
+        let mut later = header.clone();
+        later.time = (header.time + Duration::from_secs(2)).unwrap();
+
+        let app_hash = HeaderComparison::new().with_app_hash();
+        assert_eq!(
+            app_hash.mismatch(&header, &other_app_hash),
+            Some("app_hash")
+        );
+        assert_eq!(app_hash.mismatch(&header, &other_next_validators), None);
+
+        let next_validators = HeaderComparison::new().with_next_validators_hash();
+        assert_eq!(
+            next_validators.mismatch(&header, &other_next_validators),
+            Some("next_validators_hash")
+        );
+
+        let time = HeaderComparison::new().with_time_within(Duration::from_secs(1));
+        assert_eq!(time.mismatch(&header, &later), Some("time"));
+        assert_eq!(time.mismatch(&later, &header), Some("time"));
+        let time = HeaderComparison::new().with_time_within(Duration::from_secs(2));
+        assert_eq!(time.mismatch(&header, &later), None);
+
+        // Every field changes the hash, which is compared first
+        assert_eq!(all_fields.mismatch(&header, &other_app_hash), Some("hash"));
+        assert_eq!(
+            HeaderComparison::default().mismatch(&header, &later),
+            Some("hash")
+        );
+    }
+
+    #[test]
+    fn compares_no_field_by_default_with_new() {
+        let blocks = light_blocks(2);
+        let (header, other) = (
+            &blocks[0].signed_header.header,
+            &blocks[1].signed_header.header,
+        );
+
+        assert_eq!(HeaderComparison::new().mismatch(header, other), None);
+        assert_eq!(
+            HeaderComparison::default().mismatch(header, other),
+            Some("hash")
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn times_out_slow_witnesses() {
+        let primary_trace = light_blocks(2);
+        let light_block = &primary_trace[1];
+        let timeout = Duration::from_secs(10);
+        let mut witnesses = vec![
+            SlowWitness::new(1, light_block, Duration::ZERO),
+            SlowWitness::new(2, light_block, timeout * 2),
+            SlowWitness::new(3, light_block, timeout / 2),
+        ];
+
+        let start = Instant::now();
+        let results = detect_divergences::<DefaultSha256, _>(
+            None,
+            &mut witnesses,
+            primary_trace.clone(),
+            Duration::ZERO,
+            Duration::ZERO,
+            Some(timeout),
+        )
+        .await;
+
+        // The witnesses are queried concurrently, and the slow one does not delay the others
+        assert_eq!(Instant::now() - start, timeout);
+        assert!(matches!(results[0], Ok(None)));
+        assert!(matches!(results[2], Ok(None)));
+        match results[1].as_ref().map_err(Error::detail) {
+            Err(ErrorDetail::WitnessTimeout(e)) => {
+                assert_eq!(e.witness, witnesses[1].peer_id);
+                assert_eq!(e.timeout, timeout);
+            },
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn times_out_blocking_witnesses() {
+        let primary_trace = light_blocks(2);
+        let timeout = Duration::from_millis(200);
+        let mut witnesses = vec![
+            blocking_witness(1, &primary_trace, Duration::ZERO),
+            blocking_witness(2, &primary_trace, timeout * 5),
+            blocking_witness(3, &primary_trace, Duration::ZERO),
+        ];
+
+        let start = std::time::Instant::now();
+        let results = detect_divergences::<DefaultSha256, _>(
+            None,
+            &mut witnesses,
+            primary_trace.clone(),
+            Duration::ZERO,
+            Duration::ZERO,
+            Some(timeout),
+        )
+        .await;
+
+        // The blocking witnesses are queried concurrently, and the slow one does not delay the
+        // others past the timeout
+        assert!(start.elapsed() < timeout * 3);
+        assert!(matches!(results[0], Ok(None)));
+        assert!(matches!(results[2], Ok(None)));
+        assert!(matches!(
+            results[1].as_ref().map_err(Error::detail),
+            Err(ErrorDetail::WitnessTimeout(_))
+        ));
+    }
+}
@@ -1,7 +1,9 @@
+use core::time::Duration;
+
 use tendermint::{block::Height, Hash, Time};
 use tendermint_light_client::components::io::IoError;
 use tendermint_light_client::errors::Error as LightClientError;
-use tendermint_light_client::verifier::types::LightBlock;
+use tendermint_light_client::verifier::types::{LightBlock, PeerId};
 
 use crate::conflict::GatheredEvidence;
 
@@ -35,6 +37,43 @@ flex_error::define_error! {
         BadWitness
             |_| { "bad witness" },
 
+        ChainIdMismatch
+            {
+                provider: PeerId,
+                expected: String,
+                found: String,
+            }
+            |e| {
+                format_args!(
+                    "provider {} is on chain {}, not on chain {} of the primary",
+                    e.provider, e.found, e.expected
+                )
+            },
+
+        NoQuorum
+            {
+                agreeing: usize,
+                min_agreeing: usize,
+            }
+            |e| {
+                format_args!(
+                    "only {} witnesses agree with the primary, fewer than the {} required",
+                    e.agreeing, e.min_agreeing
+                )
+            },
+
+        WitnessTimeout
+            {
+                witness: PeerId,
+                timeout: Duration,
+            }
+            |e| {
+                format_args!(
+                    "misbehavior detection against witness {} timed out after {:?}",
+                    e.witness, e.timeout
+                )
+            },
+
         TargetBlockLowerThanTrusted
             {
                 target_height: Height,
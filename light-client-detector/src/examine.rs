@@ -5,7 +5,7 @@ use tendermint_light_client::{
     verifier::types::{LightBlock, Status},
 };
 
-use super::{error::Error, provider::Provider, trace::Trace};
+use super::{error::Error, provider::AsyncProvider, trace::Trace};
 
 // examineConflictingHeaderAgainstTrace takes a trace from one provider and a divergent header that
 // it has received from another and preforms verifySkipping at the heights of each of the intermediate
@@ -21,13 +21,14 @@ use super::{error::Error, provider::Provider, trace::Trace};
 //  2. The last block in the trace can not be of a lower height than the target block
 //     trace[len(trace)-1].Height >= targetBlock.Height
 //  3. The last block in the trace is conflicting with the target block
-pub fn examine_conflicting_header_against_trace<H>(
+pub async fn examine_conflicting_header_against_trace<H, P>(
     trace: &Trace,
     target_block: &LightBlock,
-    source: &Provider,
+    source: &P,
 ) -> Result<(Trace, LightBlock), Error>
 where
     H: Sha256 + MerkleHash + Default,
+    P: AsyncProvider + ?Sized,
 {
     let trusted_block = trace.first();
 
@@ -39,15 +40,16 @@ where
     };
 
     let mut previously_verified_block =
-        check_trusted_block::<H>(source, trusted_block, target_block)?;
+        check_trusted_block::<H, P>(source, trusted_block, target_block).await?;
 
     for trace_block in trace.iter().skip(1) {
-        let result = examine_conflicting_header_against_trace_block::<H>(
+        let result = examine_conflicting_header_against_trace_block::<H, P>(
             source,
             trace_block,
             target_block,
             previously_verified_block,
-        )?;
+        )
+        .await?;
 
         match result {
             ExaminationResult::Continue(prev_verified_block) => {
@@ -73,13 +75,14 @@ pub enum ExaminationResult {
     Divergence(Trace, LightBlock),
 }
 
-fn check_trusted_block<H>(
-    source: &Provider,
+async fn check_trusted_block<H, P>(
+    source: &P,
     trusted_block: &LightBlock,
     target_block: &LightBlock,
 ) -> Result<LightBlock, Error>
 where
     H: Sha256 + MerkleHash + Default,
+    P: AsyncProvider + ?Sized,
 {
     // This case only happens in a forward lunatic attack. We treat the block with the
     // height directly after the targetBlock as the divergent block
@@ -102,6 +105,7 @@ where
     } else {
         source
             .fetch_light_block(trusted_block.height())
+            .await
             .map_err(Error::light_client)?
     };
 
@@ -122,14 +126,15 @@ where
 
 // check of primary is same as witness block at that height
 
-fn examine_conflicting_header_against_trace_block<H>(
-    source: &Provider,
+async fn examine_conflicting_header_against_trace_block<H, P>(
+    source: &P,
     trace_block: &LightBlock,
     target_block: &LightBlock,
     prev_verified_block: LightBlock,
 ) -> Result<ExaminationResult, Error>
 where
     H: Sha256 + MerkleHash + Default,
+    P: AsyncProvider + ?Sized,
 {
     // This case only happens in a forward lunatic attack. We treat the block with the
     // height directly after the targetBlock as the divergent block
@@ -148,7 +153,8 @@ where
         // Before sending back the divergent block and trace we need to ensure we have verified
         // the final gap between the previouslyVerifiedBlock and the targetBlock
         if prev_verified_block.height() != target_block.height() {
-            let source_trace = verify_skipping(source, prev_verified_block, target_block.clone())?;
+            let source_trace =
+                verify_skipping(source, prev_verified_block, target_block.clone()).await?;
 
             return Ok(ExaminationResult::Divergence(
                 source_trace,
@@ -163,6 +169,7 @@ where
     } else {
         source
             .fetch_light_block(trace_block.height())
+            .await
             .map_err(Error::light_client)?
     };
 
@@ -171,7 +178,7 @@ where
 
     // we check that the source provider can verify a block at the same height of the
     // intermediate height
-    let source_trace = verify_skipping(source, prev_verified_block, source_block.clone())?;
+    let source_trace = verify_skipping(source, prev_verified_block, source_block.clone()).await?;
 
     // check if the headers verified by the source has diverged from the trace
     if source_block_hash != trace_block_hash {
@@ -186,11 +193,14 @@ where
     Ok(ExaminationResult::Continue(source_block))
 }
 
-fn verify_skipping(
-    source: &Provider,
+async fn verify_skipping<P>(
+    source: &P,
     trusted: LightBlock,
     target: LightBlock,
-) -> Result<Trace, Error> {
+) -> Result<Trace, Error>
+where
+    P: AsyncProvider + ?Sized,
+{
     let target_height = target.height();
 
     let mut store = MemoryStore::new();
@@ -201,6 +211,7 @@ fn verify_skipping(
 
     let _ = source
         .verify_to_height_with_state(target_height, &mut state)
+        .await
         .map_err(Error::light_client)?;
 
     let blocks = state.get_trace(target_height);
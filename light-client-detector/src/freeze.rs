@@ -0,0 +1,131 @@
+use tendermint::{
+    block::{signed_header::SignedHeader, Height},
+    validator::Set as ValidatorSet,
+};
+use tendermint_light_client::verifier::{misbehaviour::AttackType, types::LightBlock};
+
+use crate::detect::Divergence;
+
+/// One of the conflicting headers of a [`FrozenHeight`], along with the validator sets needed
+/// to verify it from the last light block both providers agree on, as in the `Header` of an
+/// IBC Tendermint client update or misbehaviour.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MisbehaviourHeader {
+    /// The conflicting header, and its commit
+    pub signed_header: SignedHeader,
+    /// The validator set which signed the commit
+    pub validator_set: ValidatorSet,
+    /// Height of the light block the header was verified from
+    pub trusted_height: Height,
+    /// Next validator set of the light block the header was verified from
+    pub trusted_next_validator_set: ValidatorSet,
+}
+
+impl MisbehaviourHeader {
+    fn new(
+        signed_header: SignedHeader,
+        validator_set: ValidatorSet,
+        common_block: &LightBlock,
+    ) -> Self {
+        Self {
+            signed_header,
+            validator_set,
+            trusted_height: common_block.height(),
+            trusted_next_validator_set: common_block.next_validators.clone(),
+        }
+    }
+}
+
+/// The height from which a light client must stop trusting the chain once a divergence between
+/// the primary and a witness has been confirmed, along with the conflicting headers, in the
+/// form needed to freeze an IBC light client with a `Misbehaviour` message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrozenHeight {
+    /// Height at which the light client must be frozen, ie. the lowest height
+    /// of the conflicting headers
+    pub height: Height,
+    /// The kind of attack which produced the conflicting headers
+    pub attack_type: AttackType,
+    /// The conflicting header from the primary
+    pub header1: MisbehaviourHeader,
+    /// The conflicting header from the witness
+    pub header2: MisbehaviourHeader,
+}
+
+impl FrozenHeight {
+    /// Identify the height to freeze the light client at, and the conflicting headers,
+    /// from the given divergence.
+    ///
+    /// Both headers are verified from the light block the witness trace starts from, at which
+    /// the primary and the witness still agree.
+    pub fn from_divergence(divergence: &Divergence) -> Self {
+        let common_block = divergence.evidence.witness_trace.first();
+        let witness_block = divergence.evidence.witness_trace.last();
+        let primary_block = &divergence.evidence.against_primary.conflicting_block;
+
+        let attack_type =
+            AttackType::classify(&witness_block.signed_header, &primary_block.signed_header);
+
+        let header1 = MisbehaviourHeader::new(
+            primary_block.signed_header.clone(),
+            primary_block.validator_set.clone(),
+            common_block,
+        );
+
+        let header2 = MisbehaviourHeader::new(
+            witness_block.signed_header.clone(),
+            witness_block.validators.clone(),
+            common_block,
+        );
+
+        Self {
+            height: header1
+                .signed_header
+                .header
+                .height
+                .min(header2.signed_header.header.height),
+            attack_type,
+            header1,
+            header2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{divergence, witness};
+
+    #[test]
+    fn freezes_at_conflicting_headers() {
+        for attack_type in [AttackType::Lunatic, AttackType::Equivocation] {
+            let divergence = divergence(attack_type, witness(1));
+            let common_block = divergence.evidence.witness_trace.first();
+            let primary_block = divergence.primary_trace.last();
+
+            let frozen = FrozenHeight::from_divergence(&divergence);
+
+            assert_eq!(frozen.height, primary_block.height());
+            assert_eq!(frozen.attack_type, attack_type);
+
+            assert_eq!(frozen.header1.signed_header, primary_block.signed_header);
+            assert_eq!(frozen.header1.validator_set, primary_block.validators);
+            assert_eq!(
+                frozen.header2.signed_header,
+                divergence.challenging_block.signed_header
+            );
+            assert_eq!(
+                frozen.header2.validator_set,
+                divergence.challenging_block.validators
+            );
+
+            for header in [&frozen.header1, &frozen.header2] {
+                assert_eq!(header.trusted_height, common_block.height());
+                assert_eq!(
+                    header.trusted_next_validator_set,
+                    common_block.next_validators
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use core::{cmp::Ordering, time::Duration};
+
+use derive_more::Display;
+use tendermint::block::Height;
+
+/// The result of probing a provider with its `/status` endpoint.
+#[derive(Clone, Debug)]
+pub struct Probe {
+    /// Time the provider took to answer
+    pub latency: Duration,
+
+    /// Latest height of the provider
+    pub latest_height: Height,
+
+    /// Chain the provider is on
+    pub chain_id: String,
+}
+
+/// The thresholds a provider must stay within to be considered healthy.
+#[derive(Copy, Clone, Debug)]
+pub struct HealthThresholds {
+    /// Maximum time the provider may take to answer a probe
+    pub max_latency: Duration,
+
+    /// Maximum number of blocks the provider may be behind the reference height,
+    /// usually the latest height of the primary
+    pub max_height_lag: u64,
+}
+
+impl Default for HealthThresholds {
+    fn default() -> Self {
+        Self {
+            max_latency: Duration::from_secs(5),
+            max_height_lag: 10,
+        }
+    }
+}
+
+/// The reason why a provider is not healthy.
+#[derive(Clone, Debug, Display, PartialEq, Eq)]
+pub enum Unhealthy {
+    /// The provider could not be probed at all
+    #[display(fmt = "unreachable: {_0}")]
+    Unreachable(String),
+
+    /// The provider is on another chain
+    #[display(fmt = "on chain {found} instead of {expected}")]
+    WrongChain { expected: String, found: String },
+
+    /// The provider took too long to answer
+    #[display(fmt = "answered in {latency:?}, more than {max_latency:?}")]
+    TooSlow {
+        latency: Duration,
+        max_latency: Duration,
+    },
+
+    /// The provider is too far behind
+    #[display(
+        fmt = "at height {latest_height}, more than {max_height_lag} blocks behind {reference_height}"
+    )]
+    Lagging {
+        latest_height: Height,
+        reference_height: Height,
+        max_height_lag: u64,
+    },
+}
+
+impl HealthThresholds {
+    /// Check that the given probe of a provider, expected to be on the given chain,
+    /// is within these thresholds, comparing its latest height with the given reference height.
+    pub fn check(
+        &self,
+        probe: &Probe,
+        chain_id: &str,
+        reference_height: Height,
+    ) -> Result<(), Unhealthy> {
+        if probe.chain_id != chain_id {
+            return Err(Unhealthy::WrongChain {
+                expected: chain_id.to_string(),
+                found: probe.chain_id.clone(),
+            });
+        }
+
+        if probe.latency > self.max_latency {
+            return Err(Unhealthy::TooSlow {
+                latency: probe.latency,
+                max_latency: self.max_latency,
+            });
+        }
+
+        let lag = reference_height
+            .value()
+            .saturating_sub(probe.latest_height.value());
+
+        if lag > self.max_height_lag {
+            return Err(Unhealthy::Lagging {
+                latest_height: probe.latest_height,
+                reference_height,
+                max_height_lag: self.max_height_lag,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Running statistics of the requests made to a provider, from which providers are ranked
+/// when one has to be picked over the others, eg. when failing over to another primary.
+///
+/// The latency and error rate are exponentially weighted moving averages, so that the latest
+/// requests weigh more than older ones.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PeerScore {
+    /// Average time the provider took to answer its probes, unknown until it answered one
+    pub latency: Option<Duration>,
+
+    /// Average ratio of the requests to the provider which failed, between 0 and 1
+    pub error_rate: f64,
+
+    /// Number of blocks the provider was behind the reference height at its latest probe
+    pub height_lag: Option<u64>,
+
+    /// Number of requests to the provider recorded so far
+    pub requests: u64,
+}
+
+impl PeerScore {
+    /// Weight of the latest request in the moving averages.
+    pub const SMOOTHING: f64 = 0.2;
+
+    /// Record a probe which the provider answered, comparing its latest height with the given
+    /// reference height, usually the latest height of the primary.
+    pub fn record_probe(&mut self, probe: &Probe, reference_height: Height) {
+        self.latency = Some(match self.latency {
+            Some(latency) => {
+                latency.mul_f64(1.0 - Self::SMOOTHING) + probe.latency.mul_f64(Self::SMOOTHING)
+            },
+            None => probe.latency,
+        });
+
+        self.height_lag = Some(
+            reference_height
+                .value()
+                .saturating_sub(probe.latest_height.value()),
+        );
+
+        self.record(false);
+    }
+
+    /// Record another request which the provider answered.
+    pub fn record_success(&mut self) {
+        self.record(false);
+    }
+
+    /// Record a request which failed, eg. because the provider could not be reached.
+    pub fn record_failure(&mut self) {
+        self.record(true);
+    }
+
+    fn record(&mut self, failed: bool) {
+        let sample = if failed { 1.0 } else { 0.0 };
+
+        self.error_rate = if self.requests == 0 {
+            sample
+        } else {
+            self.error_rate * (1.0 - Self::SMOOTHING) + sample * Self::SMOOTHING
+        };
+
+        self.requests += 1;
+    }
+
+    /// The average time it takes to get an answer from the provider, if it is retried
+    /// whenever a request fails, or `None` if it never answered a probe.
+    pub fn effective_latency(&self) -> Option<Duration> {
+        let success_rate = 1.0 - self.error_rate;
+
+        // A provider which always fails never answers, which is the worst possible latency
+        self.latency.map(|latency| {
+            Duration::try_from_secs_f64(latency.as_secs_f64() / success_rate)
+                .unwrap_or(Duration::MAX)
+        })
+    }
+
+    /// Compare the preference for this provider with the one for another provider, the
+    /// preferred one being the lowest, ie. the one with the lowest effective latency, and
+    /// then the least lagging, the providers which never answered a probe coming last.
+    pub fn cmp_preference(&self, other: &Self) -> Ordering {
+        let key = |score: &Self| {
+            (
+                score.effective_latency().is_none(),
+                score.effective_latency(),
+                score.height_lag,
+            )
+        };
+
+        key(self).cmp(&key(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(latency: Duration, latest_height: u32, chain_id: &str) -> Probe {
+        Probe {
+            latency,
+            latest_height: Height::from(latest_height),
+            chain_id: chain_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn checks_probes_against_thresholds() {
+        let thresholds = HealthThresholds::default();
+        let reference_height = Height::from(100_u32);
+        let check = |probe: Probe| thresholds.check(&probe, "test-chain", reference_height);
+
+        assert_eq!(
+            check(probe(Duration::from_secs(5), 90, "test-chain")),
+            Ok(())
+        );
+        // Providers ahead of the reference height are not lagging
+        assert_eq!(check(probe(Duration::ZERO, 110, "test-chain")), Ok(()));
+
+        assert_eq!(
+            check(probe(Duration::ZERO, 100, "other-chain")),
+            Err(Unhealthy::WrongChain {
+                expected: "test-chain".to_string(),
+                found: "other-chain".to_string(),
+            })
+        );
+        assert_eq!(
+            check(probe(Duration::from_secs(6), 100, "test-chain")),
+            Err(Unhealthy::TooSlow {
+                latency: Duration::from_secs(6),
+                max_latency: Duration::from_secs(5),
+            })
+        );
+        assert_eq!(
+            check(probe(Duration::ZERO, 89, "test-chain")),
+            Err(Unhealthy::Lagging {
+                latest_height: Height::from(89_u32),
+                reference_height,
+                max_height_lag: 10,
+            })
+        );
+    }
+
+    #[test]
+    fn checks_chain_before_latency_and_lag() {
+        let thresholds = HealthThresholds::default();
+        let result = thresholds.check(
+            &probe(Duration::from_secs(60), 1, "other-chain"),
+            "test-chain",
+            Height::from(100_u32),
+        );
+
+        assert!(matches!(result, Err(Unhealthy::WrongChain { .. })));
+    }
+}
@@ -5,14 +5,33 @@
 mod conflict;
 mod detect;
 mod error;
-mod evidence;
 mod examine;
+mod freeze;
+mod health;
+mod peers;
 mod provider;
+mod quorum;
+mod report;
 mod trace;
 
-pub use conflict::gather_evidence_from_conflicting_headers;
-pub use detect::{compare_new_header_with_witness, detect_divergence, CompareError, Divergence};
+#[cfg(test)]
+mod tests;
+
+pub use conflict::{
+    gather_evidence_from_conflicting_headers, gather_evidence_from_conflicting_headers_async,
+};
+pub use detect::{
+    compare_new_header_with_witness, detect_divergence, detect_divergence_async,
+    detect_divergences, detect_divergences_with_quorum, CompareError, Divergence, HeaderComparison,
+};
 pub use error::{Error, ErrorDetail};
-pub use provider::Provider;
+pub use freeze::{FrozenHeight, MisbehaviourHeader};
+pub use health::{HealthThresholds, PeerScore, Probe, Unhealthy};
+pub use peers::{PeerChanges, Peers};
+pub use provider::{AsyncProvider, Provider};
+pub use quorum::{QuorumOutcome, QuorumPolicy, QuorumVerdict};
+pub use report::{
+    Culprit, DivergencePoint, DivergenceReport, ForkReport, DIVERGENCE_REPORT_VERSION,
+};
 pub use tendermint::evidence::{Evidence, LightClientAttackEvidence};
 pub use trace::Trace;
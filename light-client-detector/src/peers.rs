@@ -0,0 +1,485 @@
+use std::{collections::HashMap, hash::Hash};
+
+use crate::PeerScore;
+
+/// The peers a long-running detector works with: a primary, the witnesses it is checked
+/// against, and the standby witnesses which can take the place of witnesses which are gone.
+///
+/// Every peer is known by its address `A`, eg. its RPC address, and the primary and the
+/// witnesses are connected to with a provider `P`. Connecting to new peers is left to the
+/// caller, which then adds them with [`Peers::add_witness`] or [`Peers::replace_primary`],
+/// eg. after finding out which peers changed with [`Peers::changes_to`].
+#[derive(Debug)]
+pub struct Peers<A, P> {
+    primary_addr: A,
+    primary: P,
+    /// The address and health of each of the witnesses, in the same order as `witnesses`
+    witness_peers: Vec<WitnessPeer<A>>,
+    witnesses: Vec<P>,
+    /// The addresses of the standby witnesses, in order of preference
+    standby: Vec<A>,
+    /// The standby witnesses which were demoted at the latest health check
+    demoted: Vec<A>,
+    /// Number of witnesses to keep, by promoting standby witnesses when some are gone
+    target_witnesses: usize,
+    /// The score of every peer requested so far, by address
+    scores: HashMap<A, PeerScore>,
+    /// Number of consecutive requests the primary failed
+    primary_failures: u32,
+}
+
+#[derive(Debug)]
+struct WitnessPeer<A> {
+    addr: A,
+    /// Number of consecutive health checks the witness failed
+    failures: u32,
+}
+
+impl<A> WitnessPeer<A> {
+    fn new(addr: A) -> Self {
+        Self { addr, failures: 0 }
+    }
+}
+
+/// The changes which turn the peers into the ones at other addresses, see
+/// [`Peers::changes_to`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PeerChanges<A> {
+    /// The address of the new primary, if it is not the current one
+    pub primary: Option<A>,
+
+    /// The addresses of the witnesses which are gone, in their current order
+    pub removed_witnesses: Vec<A>,
+
+    /// The addresses of the new witnesses, in their given order
+    pub added_witnesses: Vec<A>,
+}
+
+impl<A> PeerChanges<A> {
+    /// Whether the peers stay the same.
+    pub fn is_empty(&self) -> bool {
+        self.primary.is_none()
+            && self.removed_witnesses.is_empty()
+            && self.added_witnesses.is_empty()
+    }
+}
+
+impl<A, P> Peers<A, P>
+where
+    A: Clone + Eq + Hash,
+{
+    /// Start with the given primary and witnesses, keeping as many witnesses as given.
+    pub fn new(primary_addr: A, primary: P, witnesses: Vec<(A, P)>, standby: Vec<A>) -> Self {
+        let (witness_peers, witnesses): (Vec<_>, Vec<_>) = witnesses
+            .into_iter()
+            .map(|(addr, witness)| (WitnessPeer::new(addr), witness))
+            .unzip();
+
+        Self {
+            primary_addr,
+            primary,
+            target_witnesses: witnesses.len(),
+            witness_peers,
+            witnesses,
+            standby,
+            demoted: Vec::new(),
+            scores: HashMap::new(),
+            primary_failures: 0,
+        }
+    }
+
+    pub fn primary_addr(&self) -> &A {
+        &self.primary_addr
+    }
+
+    pub fn primary(&self) -> &P {
+        &self.primary
+    }
+
+    pub fn primary_mut(&mut self) -> &mut P {
+        &mut self.primary
+    }
+
+    /// The addresses of the witnesses, in the same order as [`Peers::witnesses`].
+    pub fn witness_addrs(&self) -> impl ExactSizeIterator<Item = &A> {
+        self.witness_peers.iter().map(|peer| &peer.addr)
+    }
+
+    pub fn witnesses(&self) -> &[P] {
+        &self.witnesses
+    }
+
+    pub fn witnesses_mut(&mut self) -> &mut [P] {
+        &mut self.witnesses
+    }
+
+    /// The primary along with the witnesses, to check the primary against the witnesses.
+    pub fn primary_and_witnesses_mut(&mut self) -> (&mut P, &mut [P]) {
+        (&mut self.primary, &mut self.witnesses)
+    }
+
+    /// The addresses of the standby witnesses, in order of preference.
+    pub fn standby(&self) -> &[A] {
+        &self.standby
+    }
+
+    /// Number of witnesses to keep, by promoting standby witnesses when some are gone.
+    pub fn target_witnesses(&self) -> usize {
+        self.target_witnesses
+    }
+
+    /// Number of consecutive health checks the witness at the given address failed, if there
+    /// is such a witness.
+    pub fn witness_failures(&self, addr: &A) -> Option<u32> {
+        self.witness_peer(addr).map(|peer| peer.failures)
+    }
+
+    /// Record whether the witness at the given address passed its latest health check,
+    /// returning the number of consecutive health checks it failed, if there is such a witness.
+    pub fn record_health_check(&mut self, addr: &A, healthy: bool) -> Option<u32> {
+        let peer = self
+            .witness_peers
+            .iter_mut()
+            .find(|peer| peer.addr == *addr)?;
+
+        peer.failures = if healthy { 0 } else { peer.failures + 1 };
+        Some(peer.failures)
+    }
+
+    fn witness_peer(&self, addr: &A) -> Option<&WitnessPeer<A>> {
+        self.witness_peers.iter().find(|peer| peer.addr == *addr)
+    }
+
+    /// Number of consecutive requests the primary failed.
+    pub fn primary_failures(&self) -> u32 {
+        self.primary_failures
+    }
+
+    /// The score of every peer requested so far, by address.
+    pub fn scores(&self) -> &HashMap<A, PeerScore> {
+        &self.scores
+    }
+
+    /// The score of the peer at the given address, which is the default one if it was never
+    /// requested.
+    pub fn score(&self, addr: &A) -> PeerScore {
+        self.scores.get(addr).copied().unwrap_or_default()
+    }
+
+    /// The score of the peer at the given address, to record a new request in.
+    pub fn score_mut(&mut self, addr: &A) -> &mut PeerScore {
+        self.scores.entry(addr.clone()).or_default()
+    }
+
+    /// Record whether the primary answered the latest request made to it.
+    pub fn record_primary_request(&mut self, answered: bool) {
+        let score = self.scores.entry(self.primary_addr.clone()).or_default();
+
+        if answered {
+            score.record_success();
+            self.primary_failures = 0;
+        } else {
+            score.record_failure();
+            self.primary_failures += 1;
+        }
+    }
+
+    /// The changes which turn these peers into the ones at the given addresses.
+    ///
+    /// The witnesses given more than once are only added once, and the primary is neither
+    /// added nor removed as a witness: both stay up to the caller.
+    pub fn changes_to(&self, primary_addr: &A, witness_addrs: &[A]) -> PeerChanges<A> {
+        let primary = (*primary_addr != self.primary_addr).then(|| primary_addr.clone());
+
+        let removed_witnesses = self
+            .witness_addrs()
+            .filter(|addr| !witness_addrs.contains(addr))
+            .cloned()
+            .collect();
+
+        let mut added_witnesses: Vec<A> = Vec::new();
+        for addr in witness_addrs {
+            if self.witness_peer(addr).is_none() && !added_witnesses.contains(addr) {
+                added_witnesses.push(addr.clone());
+            }
+        }
+
+        PeerChanges {
+            primary,
+            removed_witnesses,
+            added_witnesses,
+        }
+    }
+
+    /// Replace the primary with the given one, returning the previous one along with its
+    /// address.
+    pub fn replace_primary(&mut self, addr: A, primary: P) -> (A, P) {
+        self.primary_failures = 0;
+
+        let primary = core::mem::replace(&mut self.primary, primary);
+        let addr = core::mem::replace(&mut self.primary_addr, addr);
+        (addr, primary)
+    }
+
+    /// Add the given witness after the current ones, unless there already is a witness at
+    /// its address, in which case it is returned back.
+    pub fn add_witness(&mut self, addr: A, witness: P) -> Result<(), P> {
+        if self.witness_peer(&addr).is_some() {
+            return Err(witness);
+        }
+
+        self.witness_peers.push(WitnessPeer::new(addr));
+        self.witnesses.push(witness);
+        Ok(())
+    }
+
+    /// Remove the witness at the given address, returning it if there was one.
+    pub fn remove_witness(&mut self, addr: &A) -> Option<P> {
+        let index = self
+            .witness_peers
+            .iter()
+            .position(|peer| peer.addr == *addr)?;
+
+        self.witness_peers.remove(index);
+        Some(self.witnesses.remove(index))
+    }
+
+    /// Replace the standby witnesses with the given ones, in order of preference.
+    pub fn set_standby(&mut self, standby: Vec<A>) {
+        self.standby = standby;
+        self.demoted.clear();
+    }
+
+    /// Set the number of witnesses to keep, by promoting standby witnesses when some are gone.
+    pub fn set_target_witnesses(&mut self, target_witnesses: usize) {
+        self.target_witnesses = target_witnesses;
+    }
+
+    /// Number of standby witnesses to promote to get back to the target number of witnesses.
+    pub fn missing_witnesses(&self) -> usize {
+        self.target_witnesses.saturating_sub(self.witnesses.len())
+    }
+
+    /// Demote to standby the witnesses which failed at least the given number of consecutive
+    /// health checks, returning their addresses.
+    ///
+    /// The demoted witnesses come after the other standby witnesses, and are left out of the
+    /// [`Peers::promotion_candidates`] until the next call, so that they are only tried again
+    /// after the next health check.
+    pub fn demote_failing(&mut self, max_failures: u32) -> Vec<A> {
+        let mut demoted = Vec::new();
+
+        let mut i = 0;
+        while i < self.witness_peers.len() {
+            if self.witness_peers[i].failures >= max_failures {
+                let peer = self.witness_peers.remove(i);
+                self.witnesses.remove(i);
+                demoted.push(peer.addr);
+            } else {
+                i += 1;
+            }
+        }
+
+        self.standby.extend(demoted.iter().cloned());
+        self.demoted.clone_from(&demoted);
+        demoted
+    }
+
+    /// The standby witnesses to try to promote, in order of their scores and then of
+    /// preference, leaving out the ones which were demoted at the latest health check.
+    pub fn promotion_candidates(&mut self) -> Vec<A> {
+        let scores = &self.scores;
+        let demoted = &self.demoted;
+        let score = |addr: &A| scores.get(addr).copied().unwrap_or_default();
+
+        // Sorting is stable, so that standby witnesses with the same score, eg. those which
+        // were never probed, stay in order of preference
+        self.standby.sort_by(|a, b| {
+            demoted
+                .contains(a)
+                .cmp(&demoted.contains(b))
+                .then_with(|| score(a).cmp_preference(&score(b)))
+        });
+
+        self.standby
+            .iter()
+            .filter(|addr| !demoted.contains(addr))
+            .cloned()
+            .collect()
+    }
+
+    /// Promote the given standby witness after the current witnesses, unless there already
+    /// is a witness at its address, in which case it is returned back.
+    pub fn promote(&mut self, addr: A, witness: P) -> Result<(), P> {
+        self.standby.retain(|a| *a != addr);
+        self.add_witness(addr, witness)
+    }
+
+    /// The address of the witness to replace a failing primary with, which is the one with
+    /// the best score among those which passed their latest health check.
+    pub fn fail_over_candidate(&self) -> Option<&A> {
+        self.witness_peers
+            .iter()
+            .filter(|peer| peer.failures == 0)
+            .map(|peer| &peer.addr)
+            .min_by(|a, b| self.score(a).cmp_preference(&self.score(b)))
+    }
+
+    /// Replace the primary with the given one, connected to the witness at the given address,
+    /// which stops being a witness, and demote the primary to standby, returning its address.
+    pub fn fail_over(&mut self, addr: A, primary: P) -> A {
+        self.remove_witness(&addr);
+
+        let (demoted, _) = self.replace_primary(addr, primary);
+        self.standby.push(demoted.clone());
+        demoted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use tendermint::block::Height;
+
+    use super::*;
+    use crate::Probe;
+
+    /// A probe of a provider at height 10, which answered in the given number of seconds.
+    fn probe(latency: u64) -> Probe {
+        Probe {
+            latency: Duration::from_secs(latency),
+            latest_height: Height::from(10_u32),
+            chain_id: "test-chain".to_string(),
+        }
+    }
+
+    fn peers() -> Peers<&'static str, u32> {
+        Peers::new("primary", 0, vec![("a", 1), ("b", 2), ("c", 3)], vec!["d"])
+    }
+
+    #[test]
+    fn finds_no_changes_to_same_peers() {
+        let peers = peers();
+        let changes = peers.changes_to(&"primary", &["c", "a", "b"]);
+
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn finds_added_and_removed_witnesses() {
+        let peers = peers();
+        let changes = peers.changes_to(&"other", &["e", "b", "e", "d"]);
+
+        assert_eq!(
+            changes,
+            PeerChanges {
+                primary: Some("other"),
+                removed_witnesses: vec!["a", "c"],
+                added_witnesses: vec!["e", "d"],
+            }
+        );
+    }
+
+    #[test]
+    fn adds_and_removes_witnesses() {
+        let mut peers = peers();
+
+        assert_eq!(peers.remove_witness(&"b"), Some(2));
+        assert_eq!(peers.remove_witness(&"b"), None);
+        assert_eq!(peers.add_witness("e", 5), Ok(()));
+        assert_eq!(peers.add_witness("a", 6), Err(6));
+
+        assert_eq!(
+            peers.witness_addrs().collect::<Vec<_>>(),
+            [&"a", &"c", &"e"]
+        );
+        assert_eq!(peers.witnesses(), [1, 3, 5]);
+        assert_eq!(peers.target_witnesses(), 3);
+    }
+
+    #[test]
+    fn counts_consecutive_failed_health_checks() {
+        let mut peers = peers();
+
+        assert_eq!(peers.record_health_check(&"a", false), Some(1));
+        assert_eq!(peers.record_health_check(&"a", false), Some(2));
+        assert_eq!(peers.record_health_check(&"b", false), Some(1));
+        assert_eq!(peers.record_health_check(&"a", true), Some(0));
+        assert_eq!(peers.record_health_check(&"d", false), None);
+
+        assert_eq!(peers.witness_failures(&"b"), Some(1));
+
+        // Witnesses which come back start over
+        peers.remove_witness(&"b");
+        peers.add_witness("b", 2).unwrap();
+        assert_eq!(peers.witness_failures(&"b"), Some(0));
+    }
+
+    #[test]
+    fn rotates_failing_witnesses_with_standby() {
+        let mut peers = Peers::new("primary", 0, vec![("a", 1), ("b", 2)], vec!["c", "d", "e"]);
+        peers
+            .score_mut(&"e")
+            .record_probe(&probe(1), Height::from(10_u32));
+
+        peers.record_health_check(&"a", false);
+        peers.record_health_check(&"b", false);
+        peers.record_health_check(&"b", false);
+
+        assert_eq!(peers.demote_failing(2), ["b"]);
+        assert_eq!(peers.witness_addrs().collect::<Vec<_>>(), [&"a"]);
+        assert_eq!(peers.missing_witnesses(), 1);
+
+        // Probed witnesses come first, and the ones which were just demoted are left out
+        assert_eq!(peers.promotion_candidates(), ["e", "c", "d"]);
+        assert_eq!(peers.promote("c", 3), Ok(()));
+        assert_eq!(peers.standby(), ["e", "d", "b"]);
+        assert_eq!(peers.missing_witnesses(), 0);
+
+        // The demoted witnesses can be promoted again after the next health check
+        assert!(peers.demote_failing(2).is_empty());
+        assert_eq!(peers.promotion_candidates(), ["e", "d", "b"]);
+    }
+
+    #[test]
+    fn fails_over_to_best_healthy_witness() {
+        let mut peers = Peers::new("primary", 0, vec![("a", 1), ("b", 2), ("c", 3)], vec![]);
+        peers
+            .score_mut(&"a")
+            .record_probe(&probe(1), Height::from(10_u32));
+        peers
+            .score_mut(&"b")
+            .record_probe(&probe(2), Height::from(10_u32));
+        peers
+            .score_mut(&"c")
+            .record_probe(&probe(3), Height::from(10_u32));
+
+        peers.record_health_check(&"a", false);
+        assert_eq!(peers.fail_over_candidate(), Some(&"b"));
+
+        assert_eq!(peers.fail_over("b", 4), "primary");
+        assert_eq!(peers.primary_addr(), &"b");
+        assert_eq!(peers.witness_addrs().collect::<Vec<_>>(), [&"a", &"c"]);
+        assert_eq!(peers.standby(), ["primary"]);
+
+        peers.record_health_check(&"c", false);
+        assert_eq!(peers.fail_over_candidate(), None);
+    }
+
+    #[test]
+    fn replaces_primary() {
+        let mut peers = peers();
+        peers.record_primary_request(false);
+        assert_eq!(peers.primary_failures(), 1);
+
+        assert_eq!(peers.replace_primary("other", 4), ("primary", 0));
+        assert_eq!(peers.primary_addr(), &"other");
+        assert_eq!(peers.primary(), &4);
+        assert_eq!(peers.primary_failures(), 0);
+
+        // The score of the previous primary is kept, in case it comes back
+        assert_eq!(peers.score(&"primary").requests, 1);
+        assert_eq!(peers.score(&"other").requests, 0);
+    }
+}
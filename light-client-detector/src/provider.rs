@@ -1,65 +1,204 @@
+use core::time::Duration;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
 use tendermint::block::Height;
 use tendermint::evidence::Evidence;
 use tendermint::hash::Hash;
+use tendermint_light_client::builder::error::Error as BuilderError;
+use tendermint_light_client::builder::LightClientBuilder;
+use tendermint_light_client::components::clock::Clock;
+use tendermint_light_client::components::io::{Io, TraceIo};
+use tendermint_light_client::components::scheduler::{self, trace_scheduler};
 use tendermint_light_client::errors::Error;
-use tendermint_light_client::instance::Instance;
-use tendermint_light_client::light_client::TargetOrLatest;
+use tendermint_light_client::instance::{AsyncInstance, Instance};
+use tendermint_light_client::light_client::{LightClient, Options, TargetOrLatest};
+use tendermint_light_client::predicates::ProdPredicates;
 use tendermint_light_client::state::State;
 use tendermint_light_client::store::memory::MemoryStore;
-use tendermint_light_client::verifier::types::LightBlock;
+use tendermint_light_client::store::LightStore;
+use tendermint_light_client::verifier::types::{LightBlock, PeerId};
+use tendermint_light_client::verifier::ProdVerifier;
 use tendermint_rpc::{Client, Error as RpcError, HttpClient};
 
+use crate::health::Probe;
+
 /// A interface over a light client instance and its RPC client.
+///
+/// The light client of the provider blocks the calling thread on its I/O. As an
+/// [`AsyncProvider`], its calls run on the blocking thread pool of the current Tokio runtime
+/// instead, if any, so that the detection against several providers runs concurrently.
 #[derive(Debug)]
 pub struct Provider {
     chain_id: String,
-    instance: Instance,
-    rpc_client: HttpClient,
+    peer_id: PeerId,
+    instance: Arc<RwLock<Instance>>,
+    rpc_client: Option<HttpClient>,
 }
 
 impl Provider {
     pub fn new(chain_id: String, instance: Instance, rpc_client: HttpClient) -> Self {
         Self {
             chain_id,
-            instance,
-            rpc_client,
+            peer_id: *instance.peer_id(),
+            instance: Arc::new(RwLock::new(instance)),
+            rpc_client: Some(rpc_client),
+        }
+    }
+
+    /// Create a provider which is not backed by an RPC client, eg. because its light client
+    /// instance gets its light blocks from a file.
+    ///
+    /// Such a provider cannot report evidence.
+    pub fn without_rpc_client(chain_id: String, instance: Instance) -> Self {
+        Self {
+            chain_id,
+            peer_id: *instance.peer_id(),
+            instance: Arc::new(RwLock::new(instance)),
+            rpc_client: None,
         }
     }
 
+    /// Create a provider for the given peer which is not backed by an RPC client, and whose
+    /// light client fetches light blocks with the given `Io` component, eg. from a file or
+    /// a trace, and verifies them from the trusted light blocks of the given light store.
+    ///
+    /// Such a provider cannot report evidence.
+    pub fn from_io(
+        chain_id: String,
+        peer_id: PeerId,
+        options: Options,
+        io: impl Io + 'static,
+        light_store: impl LightStore + 'static,
+        clock: impl Clock + 'static,
+    ) -> Self {
+        let light_client = LightClient::new(
+            peer_id,
+            options,
+            clock,
+            scheduler::basic_bisecting_schedule,
+            ProdVerifier::default(),
+            io,
+        );
+
+        let instance = Instance::new(light_client, State::new(light_store));
+
+        Self::without_rpc_client(chain_id, instance)
+    }
+
+    /// Create a provider for the given peer which serves the light blocks of the given trace
+    /// instead of fetching them from a node, and trusts the one at the given height and hash.
+    ///
+    /// Its light client only verifies the light blocks of the trace, with a
+    /// [`trace_scheduler`], and the chain id of the provider is the one of the trusted
+    /// light block. Such a provider cannot report evidence.
+    pub fn from_trace(
+        peer_id: PeerId,
+        trace: Vec<LightBlock>,
+        trusted_height: Height,
+        trusted_hash: Hash,
+        options: Options,
+        clock: Box<dyn Clock>,
+    ) -> Result<Self, BuilderError> {
+        let io = TraceIo::new(peer_id, trace);
+        let scheduler = trace_scheduler(io.heights());
+
+        #[allow(clippy::box_default)]
+        let instance = LightClientBuilder::custom(
+            peer_id,
+            options,
+            Box::new(MemoryStore::new()),
+            Box::new(io),
+            clock,
+            Box::new(ProdVerifier::default()),
+            Box::new(scheduler),
+            Box::new(ProdPredicates),
+        )
+        .trust_primary_at(trusted_height, trusted_hash)?
+        .build();
+
+        let chain_id = instance
+            .latest_trusted()
+            .map(|lb| lb.signed_header.header.chain_id.to_string())
+            .unwrap_or_default();
+
+        Ok(Self::without_rpc_client(chain_id, instance))
+    }
+
     pub fn chain_id(&self) -> &str {
         &self.chain_id
     }
 
     pub fn peer_id(&self) -> &tendermint::node::Id {
-        self.instance.peer_id()
+        &self.peer_id
     }
 
+    /// Submit the given evidence to the provider, encoded with the protos of the compatibility
+    /// mode of its RPC client, so that nodes of any supported version can decode it.
     pub async fn report_evidence(&self, evidence: Evidence) -> Result<Hash, RpcError> {
-        self.rpc_client
+        let rpc_client = self.rpc_client.as_ref().ok_or_else(|| {
+            RpcError::client_internal(format!(
+                "cannot report evidence to provider {} without an RPC client",
+                self.peer_id()
+            ))
+        })?;
+
+        rpc_client
             .broadcast_evidence(evidence)
             .await
             .map(|response| response.hash)
     }
 
-    pub fn fetch_light_block(&self, height: Height) -> Result<LightBlock, Error> {
-        let mut state = State::new(MemoryStore::new());
+    /// Probe the provider with its `/status` endpoint, to check its health.
+    pub async fn probe(&self) -> Result<Probe, RpcError> {
+        let rpc_client = self.rpc_client.as_ref().ok_or_else(|| {
+            RpcError::client_internal(format!(
+                "cannot probe provider {} without an RPC client",
+                self.peer_id()
+            ))
+        })?;
 
-        self.instance
-            .light_client
-            .get_or_fetch_block(height, &mut state)
-            .map(|(lb, _)| lb)
+        let started_at = std::time::Instant::now();
+        let status = rpc_client.status().await?;
+
+        Ok(Probe {
+            latency: started_at.elapsed(),
+            latest_height: status.sync_info.latest_block_height,
+            chain_id: status.node_info.network.to_string(),
+        })
+    }
+
+    /// Fetch the evidence included in the block at the given height, eg. to check that the
+    /// evidence reported to the provider was committed.
+    pub async fn block_evidence(&self, height: Height) -> Result<Vec<Evidence>, RpcError> {
+        let rpc_client = self.rpc_client.as_ref().ok_or_else(|| {
+            RpcError::client_internal(format!(
+                "cannot fetch blocks from provider {} without an RPC client",
+                self.peer_id()
+            ))
+        })?;
+
+        let response = rpc_client.block(height).await?;
+
+        Ok(response.block.evidence.into_vec())
+    }
+
+    pub fn fetch_light_block(&self, height: Height) -> Result<LightBlock, Error> {
+        fetch_light_block(&self.instance.read().unwrap(), height)
     }
 
     pub fn verify_to_highest(&mut self) -> Result<LightBlock, Error> {
-        self.instance
-            .light_client
-            .verify_to_highest(&mut self.instance.state)
+        let mut instance = self.instance_mut();
+        let instance = &mut *instance;
+        instance.light_client.verify_to_highest(&mut instance.state)
     }
 
     pub fn verify_to_height(&mut self, height: Height) -> Result<LightBlock, Error> {
-        self.instance
+        let mut instance = self.instance_mut();
+        let instance = &mut *instance;
+        instance
             .light_client
-            .verify_to_target(height, &mut self.instance.state)
+            .verify_to_target(height, &mut instance.state)
     }
 
     pub fn verify_to_height_with_state(
@@ -67,20 +206,229 @@ impl Provider {
         height: Height,
         state: &mut State,
     ) -> Result<LightBlock, Error> {
-        self.instance.light_client.verify_to_target(height, state)
+        let instance = self.instance.read().unwrap();
+        instance.light_client.verify_to_target(height, state)
     }
 
     pub fn get_target_block_or_latest(&mut self, height: Height) -> Result<TargetOrLatest, Error> {
-        self.instance
+        let mut instance = self.instance_mut();
+        let instance = &mut *instance;
+        instance
             .light_client
-            .get_target_block_or_latest(height, &mut self.instance.state)
+            .get_target_block_or_latest(height, &mut instance.state)
     }
 
     pub fn get_trace(&self, height: Height) -> Vec<LightBlock> {
-        self.instance.state.get_trace(height)
+        self.instance.read().unwrap().state.get_trace(height)
     }
 
     pub fn latest_trusted(&self) -> Option<LightBlock> {
-        self.instance.latest_trusted()
+        self.instance.read().unwrap().latest_trusted()
+    }
+
+    /// Notify the observers of the light client of this provider that the given witness
+    /// reported a light block conflicting with the given light block, verified by this provider.
+    pub fn notify_fork_detected(
+        &self,
+        verified_block: &LightBlock,
+        witness: PeerId,
+        conflicting_block: &LightBlock,
+    ) {
+        self.instance
+            .read()
+            .unwrap()
+            .light_client
+            .notify_fork_detected(verified_block, witness, conflicting_block);
+    }
+
+    /// The light client instance, which waits for the calls to it still running in the
+    /// background, if any, eg. after the detection against this provider timed out.
+    fn instance_mut(&mut self) -> std::sync::RwLockWriteGuard<'_, Instance> {
+        self.instance.write().unwrap()
+    }
+}
+
+fn fetch_light_block(instance: &Instance, height: Height) -> Result<LightBlock, Error> {
+    let mut state = State::new(MemoryStore::new());
+
+    instance
+        .light_client
+        .get_or_fetch_block(height, &mut state)
+        .map(|(lb, _)| lb)
+}
+
+/// Run the given blocking call on the blocking thread pool of the current Tokio runtime, so
+/// that it does not block the calling task, or on the calling thread outside of a runtime.
+///
+/// The call runs to completion even if the returned future is dropped, eg. once it times out.
+async fn run_blocking<T, F>(f: F) -> T
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => handle
+            .spawn_blocking(f)
+            .await
+            .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic())),
+        Err(_) => f(),
+    }
+}
+
+/// The interface the detector needs from the primary and the witnesses, to run misbehavior
+/// detection and gather evidence with [`detect_divergence_async`] and
+/// [`gather_evidence_from_conflicting_headers_async`].
+///
+/// It is implemented by [`AsyncInstance`], whose light client fetches light blocks without
+/// blocking, so that detection can run inside async tasks, bounded by timeouts and cancelled by
+/// dropping it, without tying up a thread. It is also implemented by [`Provider`], whose light
+/// client blocks the calling thread instead.
+///
+/// [`detect_divergence_async`]: crate::detect_divergence_async
+/// [`gather_evidence_from_conflicting_headers_async`]: crate::gather_evidence_from_conflicting_headers_async
+#[async_trait]
+pub trait AsyncProvider: Send + Sync {
+    /// The peer id of the provider.
+    fn peer_id(&self) -> &PeerId;
+
+    /// Fetch the light block at the given height, without verifying it.
+    async fn fetch_light_block(&self, height: Height) -> Result<LightBlock, Error>;
+
+    /// Get the light block at the given height, or the latest one if the provider does not
+    /// have it yet.
+    async fn get_target_block_or_latest(&mut self, height: Height)
+        -> Result<TargetOrLatest, Error>;
+
+    /// Verify the light block at the given height from the trusted light blocks of the given
+    /// state, recording the verified light blocks in it.
+    async fn verify_to_height_with_state(
+        &self,
+        height: Height,
+        state: &mut State,
+    ) -> Result<LightBlock, Error>;
+
+    /// Wait for the given duration, eg. for the provider to catch up with the primary.
+    async fn wait(&self, duration: Duration);
+
+    /// Notify the observers of the light client of this provider that the given witness
+    /// reported a light block conflicting with the given light block, verified by this provider.
+    fn notify_fork_detected(
+        &self,
+        verified_block: &LightBlock,
+        witness: PeerId,
+        conflicting_block: &LightBlock,
+    );
+}
+
+#[async_trait]
+impl AsyncProvider for Provider {
+    fn peer_id(&self) -> &PeerId {
+        Provider::peer_id(self)
+    }
+
+    #[allow(clippy::result_large_err)]
+    async fn fetch_light_block(&self, height: Height) -> Result<LightBlock, Error> {
+        let instance = self.instance.clone();
+        run_blocking(move || fetch_light_block(&instance.read().unwrap(), height)).await
+    }
+
+    #[allow(clippy::result_large_err)]
+    async fn get_target_block_or_latest(
+        &mut self,
+        height: Height,
+    ) -> Result<TargetOrLatest, Error> {
+        let instance = self.instance.clone();
+        run_blocking(move || {
+            let mut instance = instance.write().unwrap();
+            let instance = &mut *instance;
+            instance
+                .light_client
+                .get_target_block_or_latest(height, &mut instance.state)
+        })
+        .await
+    }
+
+    async fn verify_to_height_with_state(
+        &self,
+        height: Height,
+        state: &mut State,
+    ) -> Result<LightBlock, Error> {
+        // The state is handed back once the call completes, and left empty if it is cancelled
+        let instance = self.instance.clone();
+        let mut owned_state = std::mem::replace(state, State::new(MemoryStore::new()));
+        let (result, owned_state) = run_blocking(move || {
+            let instance = instance.read().unwrap();
+            let result = instance
+                .light_client
+                .verify_to_target(height, &mut owned_state);
+            (result, owned_state)
+        })
+        .await;
+
+        *state = owned_state;
+        result
+    }
+
+    async fn wait(&self, duration: Duration) {
+        match tokio::runtime::Handle::try_current() {
+            Ok(_) => tokio::time::sleep(duration).await,
+            Err(_) => std::thread::sleep(duration),
+        }
+    }
+
+    fn notify_fork_detected(
+        &self,
+        verified_block: &LightBlock,
+        witness: PeerId,
+        conflicting_block: &LightBlock,
+    ) {
+        Provider::notify_fork_detected(self, verified_block, witness, conflicting_block);
+    }
+}
+
+#[async_trait]
+impl AsyncProvider for AsyncInstance {
+    fn peer_id(&self) -> &PeerId {
+        AsyncInstance::peer_id(self)
+    }
+
+    async fn fetch_light_block(&self, height: Height) -> Result<LightBlock, Error> {
+        let mut state = State::new(MemoryStore::new());
+
+        self.light_client
+            .get_or_fetch_block(height, &mut state)
+            .await
+            .map(|(lb, _)| lb)
+    }
+
+    async fn get_target_block_or_latest(
+        &mut self,
+        height: Height,
+    ) -> Result<TargetOrLatest, Error> {
+        self.light_client
+            .get_target_block_or_latest(height, &mut self.state)
+            .await
+    }
+
+    async fn verify_to_height_with_state(
+        &self,
+        height: Height,
+        state: &mut State,
+    ) -> Result<LightBlock, Error> {
+        self.light_client.verify_to_target(height, state).await
+    }
+
+    async fn wait(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+
+    fn notify_fork_detected(
+        &self,
+        verified_block: &LightBlock,
+        witness: PeerId,
+        conflicting_block: &LightBlock,
+    ) {
+        self.light_client
+            .notify_fork_detected(verified_block, witness, conflicting_block);
     }
 }
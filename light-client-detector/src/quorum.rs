@@ -0,0 +1,230 @@
+use tendermint_light_client::verifier::types::PeerId;
+
+use crate::{detect::Divergence, error::Error, provider::AsyncProvider};
+
+/// The policy deciding, from the outcome of misbehavior detection against all the witnesses,
+/// whether the header of the primary is accepted, and whether the divergences found are an
+/// incident.
+///
+/// The default policy accepts the header of the primary unless a single witness diverges from
+/// it, even if no witness could actually confirm it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct QuorumPolicy {
+    /// Minimum number of witnesses which must return the same header as the primary for it to
+    /// be accepted
+    pub min_agreeing: usize,
+
+    /// Minimum number of witnesses which must return a header conflicting with the one of the
+    /// primary for the divergences to be treated as an incident, at least 1
+    pub min_diverging: usize,
+}
+
+impl Default for QuorumPolicy {
+    fn default() -> Self {
+        Self {
+            min_agreeing: 0,
+            min_diverging: 1,
+        }
+    }
+}
+
+impl QuorumPolicy {
+    /// A policy which accepts the header of the primary once at least `min_agreeing` witnesses
+    /// agree with it, and treats `min_diverging` or more witnesses diverging from it as an
+    /// incident.
+    pub fn new(min_agreeing: usize, min_diverging: usize) -> Self {
+        Self {
+            min_agreeing,
+            min_diverging,
+        }
+    }
+
+    /// Apply this policy to the results of [`detect_divergences`] against the given witnesses,
+    /// in the same order as the witnesses.
+    ///
+    /// The divergences make up an incident as soon as enough witnesses diverge, regardless of
+    /// how many witnesses agree with the primary.
+    ///
+    /// [`detect_divergences`]: crate::detect_divergences
+    pub fn evaluate<P>(
+        &self,
+        witnesses: &[P],
+        results: &[Result<Option<Divergence>, Error>],
+    ) -> QuorumOutcome
+    where
+        P: AsyncProvider,
+    {
+        let mut agreeing = Vec::new();
+        let mut divergences = Vec::new();
+        let mut failed = Vec::new();
+
+        for (witness, result) in witnesses.iter().zip(results) {
+            match result {
+                Ok(None) => agreeing.push(*witness.peer_id()),
+                Ok(Some(divergence)) => divergences.push(divergence.clone()),
+                Err(_) => failed.push(*witness.peer_id()),
+            }
+        }
+
+        let verdict = if !divergences.is_empty() && divergences.len() >= self.min_diverging {
+            QuorumVerdict::Diverged
+        } else if agreeing.len() >= self.min_agreeing {
+            QuorumVerdict::Accepted
+        } else {
+            QuorumVerdict::NoQuorum
+        };
+
+        QuorumOutcome {
+            verdict,
+            agreeing,
+            divergences,
+            failed,
+        }
+    }
+}
+
+/// The decision of a [`QuorumPolicy`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum QuorumVerdict {
+    /// Enough witnesses agree with the primary, and too few diverge from it to be an incident
+    Accepted,
+    /// Enough witnesses diverge from the primary for it to be an incident
+    Diverged,
+    /// Too few witnesses agree with the primary, and too few diverge from it to be an incident
+    NoQuorum,
+}
+
+/// The outcome of misbehavior detection against all the witnesses, as decided by a
+/// [`QuorumPolicy`].
+#[derive(Clone, Debug)]
+pub struct QuorumOutcome {
+    /// The decision of the policy
+    pub verdict: QuorumVerdict,
+    /// The witnesses which returned the same header as the primary
+    pub agreeing: Vec<PeerId>,
+    /// The divergences found with the witnesses which returned a conflicting header
+    pub divergences: Vec<Divergence>,
+    /// The witnesses against which misbehavior detection failed
+    pub failed: Vec<PeerId>,
+}
+
+impl QuorumOutcome {
+    /// The witnesses which returned a header conflicting with the one of the primary.
+    pub fn diverging(&self) -> Vec<PeerId> {
+        self.divergences
+            .iter()
+            .map(|divergence| divergence.challenging_block.provider)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use tendermint_light_client::{tests::light_blocks, verifier::misbehaviour::AttackType};
+
+    use super::*;
+    use crate::tests::{divergence, witness, SlowWitness};
+
+    fn witnesses(count: u8) -> Vec<SlowWitness> {
+        let light_block = &light_blocks(1)[0];
+        (1..=count)
+            .map(|id| SlowWitness::new(id, light_block, Duration::ZERO))
+            .collect()
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn agrees() -> Result<Option<Divergence>, Error> {
+        Ok(None)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn diverges(id: u8) -> Result<Option<Divergence>, Error> {
+        Ok(Some(divergence(AttackType::Lunatic, witness(id))))
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn fails(id: u8) -> Result<Option<Divergence>, Error> {
+        Err(Error::witness_timeout(witness(id), Duration::from_secs(1)))
+    }
+
+    #[test]
+    fn accepts_by_default_unless_a_witness_diverges() {
+        let policy = QuorumPolicy::default();
+
+        let outcome = policy.evaluate(&witnesses(2), &[fails(1), fails(2)]);
+        assert_eq!(outcome.verdict, QuorumVerdict::Accepted);
+
+        let outcome = policy.evaluate(&witnesses(2), &[agrees(), diverges(2)]);
+        assert_eq!(outcome.verdict, QuorumVerdict::Diverged);
+    }
+
+    #[test]
+    fn needs_a_divergence_to_diverge_without_min_diverging() {
+        let policy = QuorumPolicy::new(1, 0);
+
+        let outcome = policy.evaluate(&witnesses(1), &[agrees()]);
+        assert_eq!(outcome.verdict, QuorumVerdict::Accepted);
+
+        let outcome = policy.evaluate(&witnesses(1), &[fails(1)]);
+        assert_eq!(outcome.verdict, QuorumVerdict::NoQuorum);
+
+        let outcome = policy.evaluate(&witnesses(2), &[agrees(), diverges(2)]);
+        assert_eq!(outcome.verdict, QuorumVerdict::Diverged);
+    }
+
+    #[test]
+    fn diverges_regardless_of_agreeing_witnesses() {
+        let policy = QuorumPolicy::new(2, 1);
+        let results = [agrees(), agrees(), agrees(), diverges(4)];
+
+        let outcome = policy.evaluate(&witnesses(4), &results);
+        assert_eq!(outcome.verdict, QuorumVerdict::Diverged);
+        assert_eq!(outcome.agreeing.len(), 3);
+    }
+
+    #[test]
+    fn waits_for_enough_diverging_witnesses() {
+        let policy = QuorumPolicy::new(1, 2);
+
+        let outcome = policy.evaluate(&witnesses(2), &[agrees(), diverges(2)]);
+        assert_eq!(outcome.verdict, QuorumVerdict::Accepted);
+
+        let outcome = policy.evaluate(&witnesses(2), &[fails(1), diverges(2)]);
+        assert_eq!(outcome.verdict, QuorumVerdict::NoQuorum);
+
+        let outcome = policy.evaluate(&witnesses(3), &[agrees(), diverges(2), diverges(3)]);
+        assert_eq!(outcome.verdict, QuorumVerdict::Diverged);
+    }
+
+    #[test]
+    fn has_no_quorum_when_witnesses_fail() {
+        let policy = QuorumPolicy::new(2, 1);
+
+        let outcome = policy.evaluate(&witnesses(3), &[agrees(), fails(2), fails(3)]);
+        assert_eq!(outcome.verdict, QuorumVerdict::NoQuorum);
+        assert_eq!(outcome.agreeing, [witness(1)]);
+        assert_eq!(outcome.failed, [witness(2), witness(3)]);
+        assert!(outcome.divergences.is_empty());
+    }
+
+    #[test]
+    fn keeps_witnesses_in_order() {
+        let policy = QuorumPolicy::new(1, 3);
+        let results = [
+            diverges(1),
+            fails(2),
+            agrees(),
+            diverges(4),
+            agrees(),
+            fails(6),
+        ];
+
+        let outcome = policy.evaluate(&witnesses(6), &results);
+        assert_eq!(outcome.verdict, QuorumVerdict::Accepted);
+        assert_eq!(outcome.agreeing, [witness(3), witness(5)]);
+        assert_eq!(outcome.diverging(), [witness(1), witness(4)]);
+        assert_eq!(outcome.failed, [witness(2), witness(6)]);
+    }
+}
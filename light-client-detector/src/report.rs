@@ -0,0 +1,446 @@
+use core::fmt;
+use std::collections::BTreeMap;
+
+use serde_derive::{Deserialize, Serialize};
+use tendermint::{
+    account, block::signed_header::SignedHeader, evidence::LightClientAttackEvidence, validator,
+    Hash,
+};
+use tendermint_light_client::verifier::{
+    misbehaviour::AttackType,
+    types::{Height, LightBlock, PeerId},
+};
+
+use crate::detect::Divergence;
+
+/// A validator held accountable for a fork, along with its voting power.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Culprit {
+    /// Address of the validator
+    pub address: account::Id,
+    /// Voting power of the validator
+    pub voting_power: u64,
+}
+
+impl From<validator::Info> for Culprit {
+    fn from(validator: validator::Info) -> Self {
+        Self {
+            address: validator.address,
+            voting_power: validator.power.value(),
+        }
+    }
+}
+
+/// The accountability report of a fork between the header verified on the primary and the
+/// conflicting header returned by one or more witnesses, for governance and slashing follow-up.
+///
+/// The report is serializable, and its `Display` implementation pretty-prints it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForkReport {
+    /// Chain on which the fork happened
+    pub chain_id: String,
+    /// Height of the conflicting headers
+    pub height: Height,
+    /// The kind of attack which produced the conflicting headers
+    pub attack_type: AttackType,
+    /// Height of the last header the primary and the witnesses agree on
+    pub common_height: Height,
+    /// The primary
+    pub primary: PeerId,
+    /// Hash of the header of the primary
+    pub primary_hash: Hash,
+    /// The witnesses which returned the same conflicting header
+    pub witnesses: Vec<PeerId>,
+    /// Hash of the conflicting header of the witnesses
+    pub witness_hash: Hash,
+    /// Validators which signed both conflicting headers, by decreasing voting power
+    pub double_signers: Vec<Culprit>,
+    /// Validators of the common header which signed the header of the primary, when it is the
+    /// product of a lunatic attack, holding the witnesses as the source of truth, by decreasing
+    /// voting power
+    pub lunatic_signers: Vec<Culprit>,
+    /// Total voting power of the validators held accountable
+    pub culprit_voting_power: u64,
+    /// Total voting power of the validators at the height of the evidence
+    pub total_voting_power: u64,
+}
+
+impl ForkReport {
+    /// Build the report of the fork found by the given divergence between the given primary
+    /// and the witness which returned its challenging block.
+    pub fn new(primary: PeerId, divergence: &Divergence) -> Self {
+        let evidence = &divergence.evidence.against_primary;
+        let primary_header = &evidence.conflicting_block.signed_header;
+        let witness_header = &divergence.challenging_block.signed_header;
+
+        let attack_type = AttackType::classify(witness_header, primary_header);
+
+        let double_signers = find_double_signers(
+            primary_header,
+            &evidence.conflicting_block.validator_set,
+            witness_header,
+        );
+
+        let mut lunatic_signers: Vec<Culprit> = match attack_type {
+            AttackType::Lunatic => evidence
+                .byzantine_validators
+                .iter()
+                .cloned()
+                .map(Culprit::from)
+                .collect(),
+            _ => Vec::new(),
+        };
+        sort_culprits(&mut lunatic_signers);
+
+        let culprits: BTreeMap<_, _> = double_signers
+            .iter()
+            .chain(&lunatic_signers)
+            .map(|culprit| (culprit.address, culprit.voting_power))
+            .collect();
+
+        Self {
+            chain_id: primary_header.header.chain_id.to_string(),
+            height: primary_header.header.height,
+            attack_type,
+            common_height: divergence.evidence.witness_trace.first().height(),
+            primary,
+            primary_hash: primary_header.header.hash(),
+            witnesses: vec![divergence.challenging_block.provider],
+            witness_hash: witness_header.header.hash(),
+            double_signers,
+            lunatic_signers,
+            culprit_voting_power: culprits.values().sum(),
+            total_voting_power: evidence.total_voting_power.value(),
+        }
+    }
+
+    /// Build the reports of the forks found by the given divergences with the given primary,
+    /// one per conflicting header, which the witnesses returning it agree on.
+    pub fn from_divergences(primary: PeerId, divergences: &[Divergence]) -> Vec<Self> {
+        let mut reports: Vec<Self> = Vec::new();
+
+        for divergence in divergences {
+            let report = Self::new(primary, divergence);
+
+            match reports.iter_mut().find(|other| {
+                other.height == report.height && other.witness_hash == report.witness_hash
+            }) {
+                Some(other) => other.witnesses.extend(report.witnesses),
+                None => reports.push(report),
+            }
+        }
+
+        reports
+    }
+
+    /// Ratio of the total voting power held by the validators held accountable.
+    pub fn culprit_voting_power_ratio(&self) -> f64 {
+        if self.total_voting_power == 0 {
+            return 0.0;
+        }
+
+        self.culprit_voting_power as f64 / self.total_voting_power as f64
+    }
+}
+
+impl fmt::Display for ForkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Fork on chain {} at height {} ({:?} attack), diverging after height {}",
+            self.chain_id, self.height, self.attack_type, self.common_height
+        )?;
+
+        writeln!(f, "  Primary {}: {}", self.primary, self.primary_hash)?;
+
+        let witnesses: Vec<_> = self.witnesses.iter().map(ToString::to_string).collect();
+        writeln!(
+            f,
+            "  Witnesses {}: {}",
+            witnesses.join(", "),
+            self.witness_hash
+        )?;
+
+        write_culprits(f, "Double signers", &self.double_signers)?;
+        if self.attack_type == AttackType::Lunatic {
+            write_culprits(f, "Lunatic signers", &self.lunatic_signers)?;
+        }
+
+        write!(
+            f,
+            "  Culprits hold {} out of {} voting power ({:.2}%)",
+            self.culprit_voting_power,
+            self.total_voting_power,
+            self.culprit_voting_power_ratio() * 100.0
+        )
+    }
+}
+
+/// The version of the format of [`DivergenceReport`], bumped whenever it changes in a way
+/// which is not backward compatible.
+pub const DIVERGENCE_REPORT_VERSION: u32 = 1;
+
+/// The point at which the traces of the primary and the witness diverge.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DivergencePoint {
+    /// Height of the last light block the primary and the witness agree on
+    pub common_height: Height,
+    /// Hash of the header of the last light block the primary and the witness agree on
+    pub common_hash: Hash,
+    /// Height of the conflicting headers
+    pub conflicting_height: Height,
+    /// Hash of the conflicting header of the primary
+    pub primary_hash: Hash,
+    /// Hash of the conflicting header of the witness
+    pub witness_hash: Hash,
+}
+
+/// A self-contained and versioned report of a divergence found by the detector, holding both
+/// traces, the point at which they diverge and the evidence gathered, so that it can be
+/// consumed as JSON by monitoring systems and auditors which do not link this crate.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DivergenceReport {
+    /// The version of the format of the report, see [`DIVERGENCE_REPORT_VERSION`]
+    pub version: u32,
+    /// Chain on which the divergence was found
+    pub chain_id: String,
+    /// The primary
+    pub primary: PeerId,
+    /// The witness which returned the conflicting header
+    pub witness: PeerId,
+    /// The point at which the traces of the primary and the witness diverge
+    pub divergence: DivergencePoint,
+    /// The trace verified by the primary, up to its header
+    pub primary_trace: Vec<LightBlock>,
+    /// The trace verified by the witness from the common light block, up to its conflicting
+    /// header
+    pub witness_trace: Vec<LightBlock>,
+    /// The evidence against the primary, holding the witness as the source of truth
+    pub evidence_against_primary: LightClientAttackEvidence,
+    /// The evidence against the witness, holding the primary as the source of truth, if any
+    pub evidence_against_witness: Option<LightClientAttackEvidence>,
+}
+
+impl DivergenceReport {
+    /// Build the report of the given divergence.
+    pub fn new(divergence: &Divergence) -> Self {
+        let evidence = &divergence.evidence;
+        let common_block = evidence.witness_trace.first();
+        let primary_block = &evidence.against_primary.conflicting_block.signed_header;
+        let primary_trace = &divergence.primary_trace;
+
+        Self {
+            version: DIVERGENCE_REPORT_VERSION,
+            chain_id: primary_block.header.chain_id.to_string(),
+            primary: primary_trace.last().provider,
+            witness: divergence.challenging_block.provider,
+            divergence: DivergencePoint {
+                common_height: common_block.height(),
+                common_hash: common_block.signed_header.header.hash(),
+                conflicting_height: primary_block.header.height,
+                primary_hash: primary_block.header.hash(),
+                witness_hash: divergence.challenging_block.signed_header.header.hash(),
+            },
+            primary_trace: primary_trace.iter().cloned().collect(),
+            witness_trace: evidence.witness_trace.iter().cloned().collect(),
+            evidence_against_primary: evidence.against_primary.clone(),
+            evidence_against_witness: evidence.against_witness.clone(),
+        }
+    }
+
+    /// Serialize the report to pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a divergence report is always serializable")
+    }
+
+    /// Deserialize a report from JSON, failing if it is of an unknown version.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        use serde::de::Error;
+
+        let report: Self = serde_json::from_str(json)?;
+
+        if report.version != DIVERGENCE_REPORT_VERSION {
+            return Err(serde_json::Error::custom(format!(
+                "unsupported divergence report version {}, expected {}",
+                report.version, DIVERGENCE_REPORT_VERSION
+            )));
+        }
+
+        Ok(report)
+    }
+}
+
+fn write_culprits(f: &mut fmt::Formatter<'_>, title: &str, culprits: &[Culprit]) -> fmt::Result {
+    writeln!(f, "  {title} ({}):", culprits.len())?;
+
+    for culprit in culprits {
+        writeln!(f, "    {} ({})", culprit.address, culprit.voting_power)?;
+    }
+
+    Ok(())
+}
+
+/// The validators which signed the block of both the given headers, along with their voting
+/// power in the given validator set of the first header.
+fn find_double_signers(
+    header: &SignedHeader,
+    validator_set: &validator::Set,
+    other: &SignedHeader,
+) -> Vec<Culprit> {
+    let signers = |header: &SignedHeader| {
+        header
+            .commit
+            .signatures
+            .iter()
+            .filter(|sig| sig.is_commit())
+            .filter_map(|sig| sig.validator_address())
+            .collect::<Vec<_>>()
+    };
+
+    let other_signers = signers(other);
+
+    let mut double_signers: Vec<Culprit> = signers(header)
+        .into_iter()
+        .filter(|address| other_signers.contains(address))
+        .filter_map(|address| validator_set.validator(address))
+        .map(Culprit::from)
+        .collect();
+
+    sort_culprits(&mut double_signers);
+    double_signers
+}
+
+/// Sort the given culprits by decreasing voting power, and then by address.
+fn sort_culprits(culprits: &mut [Culprit]) {
+    culprits.sort_by(|a, b| {
+        b.voting_power
+            .cmp(&a.voting_power)
+            .then_with(|| a.address.cmp(&b.address))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{divergence, primary, witness};
+
+    /// All the validators of the given light block, as culprits.
+    fn all_validators(light_block: &LightBlock) -> Vec<Culprit> {
+        let mut culprits: Vec<_> = light_block
+            .validators
+            .validators()
+            .iter()
+            .cloned()
+            .map(Culprit::from)
+            .collect();
+        sort_culprits(&mut culprits);
+        culprits
+    }
+
+    #[test]
+    fn reports_lunatic_attacks() {
+        let divergence = divergence(AttackType::Lunatic, witness(1));
+        let report = ForkReport::new(primary(), &divergence);
+        let common_block = divergence.evidence.witness_trace.first();
+        let primary_block = divergence.primary_trace.last();
+
+        assert_eq!(report.attack_type, AttackType::Lunatic);
+        assert_eq!(report.height, primary_block.height());
+        assert_eq!(report.common_height, common_block.height());
+        assert_eq!(report.primary, primary());
+        assert_eq!(
+            report.primary_hash,
+            primary_block.signed_header.header.hash()
+        );
+        assert_eq!(report.witnesses, [witness(1)]);
+        assert_eq!(
+            report.witness_hash,
+            divergence.challenging_block.signed_header.header.hash()
+        );
+
+        // The same validators signed both headers, and are the validators of the common block
+        assert_eq!(report.double_signers, all_validators(primary_block));
+        assert_eq!(report.lunatic_signers, all_validators(common_block));
+        assert_eq!(report.culprit_voting_power, report.total_voting_power);
+        assert_eq!(report.culprit_voting_power_ratio(), 1.0);
+    }
+
+    #[test]
+    fn reports_no_lunatic_signers_of_equivocations() {
+        let divergence = divergence(AttackType::Equivocation, witness(1));
+        let report = ForkReport::new(primary(), &divergence);
+
+        assert_eq!(report.attack_type, AttackType::Equivocation);
+        assert!(report.lunatic_signers.is_empty());
+        assert_eq!(
+            report.double_signers,
+            all_validators(divergence.primary_trace.last())
+        );
+        assert!(!report.to_string().contains("Lunatic signers"));
+    }
+
+    #[test]
+    fn reports_forks_once_per_conflicting_header() {
+        let divergences = [
+            divergence(AttackType::Lunatic, witness(1)),
+            divergence(AttackType::Lunatic, witness(2)),
+        ];
+
+        let reports = ForkReport::from_divergences(primary(), &divergences);
+
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].witnesses, [witness(1), witness(2)]);
+        assert_eq!(reports[0], {
+            let mut report = ForkReport::new(primary(), &divergences[0]);
+            report.witnesses.push(witness(2));
+            report
+        });
+    }
+
+    #[test]
+    fn prints_fork_reports() {
+        let report = ForkReport::new(primary(), &divergence(AttackType::Lunatic, witness(1)));
+        let printed = report.to_string();
+
+        assert!(printed.starts_with(&format!(
+            "Fork on chain {} at height 3 (Lunatic attack), diverging after height 1",
+            report.chain_id
+        )));
+        assert!(printed.contains(&format!("Witnesses {}", witness(1))));
+        assert!(printed.contains("Lunatic signers"));
+        assert!(printed.ends_with("(100.00%)"));
+    }
+
+    #[test]
+    fn reports_divergences() {
+        let divergence = divergence(AttackType::Lunatic, witness(1));
+        let report = DivergenceReport::new(&divergence);
+
+        assert_eq!(report.version, DIVERGENCE_REPORT_VERSION);
+        assert_eq!(report.primary, primary());
+        assert_eq!(report.witness, witness(1));
+        assert_eq!(report.divergence.common_height, Height::from(1_u32));
+        assert_eq!(report.divergence.conflicting_height, Height::from(3_u32));
+        assert_eq!(report.primary_trace.len(), 2);
+        assert_eq!(report.witness_trace.len(), 2);
+        assert!(report.evidence_against_witness.is_some());
+
+        assert_eq!(
+            DivergenceReport::from_json(&report.to_json()).unwrap(),
+            report
+        );
+    }
+
+    #[test]
+    fn rejects_divergence_reports_of_unknown_version() {
+        let report = DivergenceReport {
+            version: DIVERGENCE_REPORT_VERSION + 1,
+            ..DivergenceReport::new(&divergence(AttackType::Lunatic, witness(1)))
+        };
+
+        let err = DivergenceReport::from_json(&report.to_json()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("unsupported divergence report version"));
+    }
+}
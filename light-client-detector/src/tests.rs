@@ -0,0 +1,170 @@
+//! Fixtures shared by the tests of the detector.
+
+use core::time::Duration;
+
+use async_trait::async_trait;
+use tendermint::AppHash;
+use tendermint_light_client::{
+    components::io::Io,
+    errors::Error as LightClientError,
+    evidence::make_evidence,
+    light_client::TargetOrLatest,
+    state::State,
+    store::{memory::MemoryStore, LightStore},
+    tests::{light_blocks, now, options, testgen_to_lb, MockClock, MockIo},
+    verifier::{
+        misbehaviour::AttackType,
+        types::{Height, LightBlock, PeerId, Status},
+    },
+};
+use tendermint_testgen::{helpers::get_time, Generator, LightChain};
+
+use crate::{
+    conflict::GatheredEvidence, detect::Divergence, provider::AsyncProvider, Provider, Trace,
+};
+
+/// The peer ID of the primary of the [`divergence`].
+pub fn primary() -> PeerId {
+    PeerId::new([0; 20])
+}
+
+/// The peer ID of the witness with the given ID.
+pub fn witness(id: u8) -> PeerId {
+    PeerId::new([id; 20])
+}
+
+/// A divergence between the primary, which returned a light block at height 3 forged by the
+/// given attack, either lunatic or equivocation, and the given witness, which returned the
+/// one of the default chain, both verified from the light block at height 1.
+pub fn divergence(attack_type: AttackType, witness: PeerId) -> Divergence {
+    let blocks = light_blocks(3);
+    let with_provider = |light_block: &LightBlock, provider| LightBlock {
+        provider,
+        ..light_block.clone()
+    };
+
+    let header = LightChain::default_with_length(3).light_blocks[2]
+        .header
+        .clone()
+        .unwrap();
+
+    let header = match attack_type {
+        AttackType::Lunatic => header.app_hash(AppHash::try_from(vec![1; 32]).unwrap()),
+        AttackType::Equivocation => header.time(get_time(10).unwrap()),
+        AttackType::Amnesia => unimplemented!("amnesia attacks are not forged"),
+    };
+
+    let forged = testgen_to_lb(
+        tendermint_testgen::LightBlock::new_default_with_header(header)
+            .generate()
+            .unwrap(),
+    );
+
+    let common_block = with_provider(&blocks[0], witness);
+    let challenging_block = with_provider(&blocks[2], witness);
+    let forged = with_provider(&forged, primary());
+
+    Divergence {
+        evidence: GatheredEvidence {
+            witness_trace: Trace::new(vec![common_block.clone(), challenging_block.clone()])
+                .unwrap(),
+            against_primary: make_evidence(
+                forged.clone(),
+                challenging_block.clone(),
+                common_block.clone(),
+            ),
+            against_witness: Some(make_evidence(
+                challenging_block.clone(),
+                forged.clone(),
+                common_block,
+            )),
+        },
+        challenging_block,
+        primary_trace: Trace::new(vec![with_provider(&blocks[0], primary()), forged]).unwrap(),
+    }
+}
+
+/// A witness which serves the given light block after the given delay.
+pub struct SlowWitness {
+    pub peer_id: PeerId,
+    light_block: LightBlock,
+    delay: Duration,
+}
+
+impl SlowWitness {
+    pub fn new(id: u8, light_block: &LightBlock, delay: Duration) -> Self {
+        Self {
+            peer_id: witness(id),
+            light_block: light_block.clone(),
+            delay,
+        }
+    }
+}
+
+#[async_trait]
+impl AsyncProvider for SlowWitness {
+    fn peer_id(&self) -> &PeerId {
+        &self.peer_id
+    }
+
+    async fn fetch_light_block(&self, _height: Height) -> Result<LightBlock, LightClientError> {
+        tokio::time::sleep(self.delay).await;
+        Ok(self.light_block.clone())
+    }
+
+    async fn get_target_block_or_latest(
+        &mut self,
+        height: Height,
+    ) -> Result<TargetOrLatest, LightClientError> {
+        self.fetch_light_block(height)
+            .await
+            .map(TargetOrLatest::Target)
+    }
+
+    async fn verify_to_height_with_state(
+        &self,
+        height: Height,
+        _state: &mut State,
+    ) -> Result<LightBlock, LightClientError> {
+        self.fetch_light_block(height).await
+    }
+
+    async fn wait(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+
+    fn notify_fork_detected(&self, _: &LightBlock, _: PeerId, _: &LightBlock) {}
+}
+
+/// A witness whose light client serves the given light blocks, trusting the first one, and
+/// blocks the calling thread for the given delay before every light block it fetches.
+pub fn blocking_witness(id: u8, light_blocks: &[LightBlock], delay: Duration) -> Provider {
+    let light_blocks = light_blocks
+        .iter()
+        .map(|lb| LightBlock {
+            provider: witness(id),
+            ..lb.clone()
+        })
+        .collect::<Vec<_>>();
+    let mock_io = MockIo::new(light_blocks.clone());
+    let io = move |height| {
+        std::thread::sleep(delay);
+        Io::fetch_light_block(&mock_io, height)
+    };
+
+    let mut light_store = MemoryStore::new();
+    light_store.insert(light_blocks[0].clone(), Status::Trusted);
+
+    let clock = MockClock {
+        now: now(light_blocks.len() as u64),
+    };
+
+    Provider::from_io(
+        light_blocks[0].signed_header.header.chain_id.to_string(),
+        witness(id),
+        options(),
+        io,
+        light_store,
+        clock,
+    )
+}
@@ -99,6 +99,8 @@ impl From<JsOptions> for Options {
             trust_threshold: TrustThreshold::new(num, den).unwrap(),
             trusting_period: Duration::from_secs(o.trusting_period),
             clock_drift: Duration::from_secs(o.clock_drift),
+            verification_mode: Default::default(),
+            commit_signatures: Default::default(),
         }
     }
 }
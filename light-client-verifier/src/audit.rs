@@ -0,0 +1,100 @@
+//! Records of the checks performed while verifying a light block, to be able to tell
+//! afterwards why it was accepted or rejected.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    errors::{VerificationError, VerificationErrorDetail},
+    prelude::*,
+    types::ValidatorAddress,
+};
+
+/// The predicates evaluated by the [`PredicateVerifier`](crate::PredicateVerifier),
+/// named after the methods of [`VerificationPredicates`](crate::predicates::VerificationPredicates).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Predicate {
+    /// The validator set matches the hash in the header
+    ValidatorSetsMatch,
+    /// The next validator set matches the hash in the header
+    NextValidatorsMatch,
+    /// The header matches the block id of the commit
+    HeaderMatchesCommit,
+    /// The commit is valid with respect to the validator set
+    ValidCommit,
+    /// The trusted header is within the trusting period
+    IsWithinTrustPeriod,
+    /// The header is more recent than the trusted header
+    IsMonotonicBftTime,
+    /// The header is on the same chain as the trusted header
+    IsMatchingChainId,
+    /// The validator set matches the next validator set of the adjacent trusted header
+    ValidNextValidatorSet,
+    /// The header is higher than the trusted header
+    IsMonotonicHeight,
+    /// The header is not from the future
+    IsHeaderFromPast,
+    /// Enough of the trusted validators signed the commit
+    HasSufficientValidatorsOverlap,
+    /// The commit does not have too many absent signatures
+    IsWithinAbsentSignaturesCap,
+    /// Enough of the validators of the header signed the commit
+    HasSufficientSignersOverlap,
+    /// The header passes the additional checks of the predicates
+    AdditionalChecks,
+}
+
+/// The evaluation of a predicate, along with its outcome.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PredicateEvaluation {
+    /// The evaluated predicate
+    pub predicate: Predicate,
+    /// Whether the predicate holds, or why it does not
+    pub outcome: Result<(), VerificationErrorDetail>,
+}
+
+/// The outcome of the check of the signature of a validator in a commit.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureOutcome {
+    /// The validator did not vote
+    Absent,
+    /// The validator is not part of the validator set, so its signature was not checked
+    UnknownValidator,
+    /// The signature is valid
+    Valid,
+    /// The signature is invalid
+    Invalid,
+}
+
+/// The check of the signature of a validator in a commit.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureCheck {
+    /// Address of the validator, unless it did not vote
+    pub validator_address: Option<ValidatorAddress>,
+    /// Whether the validator voted for the block, rather than for nil
+    pub for_block: bool,
+    /// Voting power of the validator in the validator set
+    pub voting_power: u64,
+    /// Outcome of the check
+    pub outcome: SignatureOutcome,
+}
+
+/// The checks performed while verifying a light block, in the order they were performed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationAudit {
+    /// The predicates evaluated until the verification succeeded or one of them failed
+    pub predicates: Vec<PredicateEvaluation>,
+    /// The checks of the signatures of the commit against the validator set of the header
+    pub signatures: Vec<SignatureCheck>,
+}
+
+impl VerificationAudit {
+    /// Record the evaluation of the given predicate.
+    pub fn record(&mut self, predicate: Predicate, outcome: &Result<(), VerificationError>) {
+        self.predicates.push(PredicateEvaluation {
+            predicate,
+            outcome: outcome.as_ref().map(|_| ()).map_err(|e| e.detail().clone()),
+        });
+    }
+}
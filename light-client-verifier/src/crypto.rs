@@ -0,0 +1,35 @@
+//! Provides an interface for the cryptographic primitives used during verification,
+//! and a default implementation backed by the pure Rust implementations of the
+//! `tendermint` crate.
+//!
+//! Integrators can supply their own provider, e.g. to use hardware-accelerated SHA-256,
+//! an alternative ed25519 backend, or the host functions of a constrained environment
+//! such as wasm or a zkVM, and plug it into a [`ProvidedVerifier`].
+//!
+//! [`ProvidedVerifier`]: crate::ProvidedVerifier
+
+use tendermint::{
+    crypto::{signature, Sha256},
+    merkle::MerkleHash,
+};
+
+/// The cryptographic primitives used to validate and verify light blocks.
+pub trait CryptoProvider: Send + Sync {
+    /// The implementation of the SHA-256 digest, used to hash headers and validator sets
+    type Sha256: MerkleHash + Sha256 + Default;
+
+    /// The implementation of signature verification, used to verify the votes of a commit
+    type SignatureVerifier: signature::Verifier;
+}
+
+/// The default crypto provider, using the pure Rust implementations
+/// enabled by the `rust-crypto` feature.
+#[cfg(feature = "rust-crypto")]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DefaultCryptoProvider;
+
+#[cfg(feature = "rust-crypto")]
+impl CryptoProvider for DefaultCryptoProvider {
+    type Sha256 = tendermint::crypto::default::Sha256;
+    type SignatureVerifier = tendermint::crypto::default::signature::Verifier;
+}
@@ -62,6 +62,33 @@ define_error! {
                 format_args!("missing signature")
             },
 
+        MissingVoteExtensionSignature
+            {
+                address: ValidatorAddress,
+            }
+            | e | {
+                format_args!("missing signature of the vote extension of validator with address {0}",
+                    e.address)
+            },
+
+        UnexpectedVoteExtension
+            {
+                address: ValidatorAddress,
+            }
+            | e | {
+                format_args!("vote extension of validator with address {0} which did not commit the block",
+                    e.address)
+            },
+
+        NotEnoughVoteExtensions
+            {
+                tally: VotingPowerTally,
+            }
+            | e | {
+                format_args!("not enough voting power in correctly signed vote extensions: {0}",
+                    e.tally)
+            },
+
         InvalidSignature
             {
                 signature: Vec<u8>,
@@ -113,6 +140,16 @@ define_error! {
                     e.got, e.expected)
             },
 
+        NonSequentialHeight
+            {
+                got: Height,
+                expected: Height,
+            }
+            | e | {
+                format_args!("non sequential height: got={0} expected={1}",
+                    e.got, e.expected)
+            },
+
         ChainIdMismatch
             {
                 got: String,
@@ -171,6 +208,24 @@ define_error! {
                 )
             },
 
+        TooManyAbsentSignatures
+            {
+                absent: usize,
+                max_absent_signatures: usize,
+            }
+            | e | {
+                format_args!("too many absent signatures in commit: {0} > {1}",
+                    e.absent, e.max_absent_signatures)
+            },
+
+        HeaderCheckFailed
+            {
+                reason: String,
+            }
+            | e | {
+                format_args!("header rejected by an additional check: {0}", e.reason)
+            },
+
     }
 }
 
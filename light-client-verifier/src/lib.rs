@@ -1,17 +1,43 @@
+//! The verification component of the Tendermint light client, usable without the Rust
+//! standard library.
+//!
+//! The crate only depends on `alloc`, and takes everything that depends on the environment
+//! as an input: the current time is given to each verification, and the cryptographic
+//! primitives are provided by a [`CryptoProvider`](crypto::CryptoProvider), which is plugged
+//! into a [`ProvidedVerifier`]. This makes it possible to verify light blocks inside zkVMs,
+//! enclaves or smart contracts, by disabling the default features:
+//!
+//! ```toml
+//! tendermint-light-client-verifier = { version = "0.34", default-features = false }
+//! ```
+//!
+//! The `rust-crypto` feature, which does not require `std` either, provides the
+//! [`ProdVerifier`] backed by the pure Rust implementations of the `tendermint` crate.
+//!
+//! The `parallel` feature, which requires `std`, verifies the signatures of commits across
+//! the thread pool of `rayon`.
+
 #![no_std]
 
 extern crate alloc;
 
 mod prelude;
 
+pub mod audit;
+pub mod crypto;
 pub mod errors;
+pub mod misbehaviour;
 pub mod operations;
 pub mod options;
 pub mod predicates;
+pub mod trace;
 pub mod types;
 mod verifier;
 
-pub use verifier::{PredicateVerifier, Verdict, Verifier};
+pub use verifier::{PredicateVerifier, ProvidedVerifier, Verdict, Verifier};
 
+#[cfg(feature = "rust-crypto")]
+pub use trace::verify_trace;
+pub use trace::{verify_trace_with, TraceVerdict, TraceVerification};
 #[cfg(feature = "rust-crypto")]
 pub use verifier::ProdVerifier;
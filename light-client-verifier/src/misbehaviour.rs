@@ -0,0 +1,228 @@
+//! Verification of misbehaviour, ie. two conflicting headers at the same height, as submitted
+//! to on-chain light clients with a `MsgSubmitMisbehaviour`, along with the classification of
+//! the attack they are the product of.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    options::Options,
+    types::{Header, SignedHeader, Time, TrustedBlockState, UntrustedBlockState},
+    Verdict, Verifier,
+};
+
+/// The kind of attack which produced two conflicting headers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttackType {
+    /// The validators signed two different blocks in the same round.
+    Equivocation,
+    /// The validators signed a block which is not the product of a valid state transition,
+    /// ie. whose validator sets, consensus parameters, application state or results
+    /// differ from the ones of the other block.
+    Lunatic,
+    /// The validators signed two different blocks in different rounds, breaking
+    /// the locking rules of the consensus.
+    Amnesia,
+}
+
+impl AttackType {
+    /// Classify the attack which produced the given conflicting headers at the same height.
+    pub fn classify(signed_header: &SignedHeader, conflicting: &SignedHeader) -> Self {
+        if !is_same_state_transition(&signed_header.header, &conflicting.header) {
+            Self::Lunatic
+        } else if signed_header.commit.round == conflicting.commit.round {
+            Self::Equivocation
+        } else {
+            Self::Amnesia
+        }
+    }
+}
+
+/// Whether the deterministic fields of the given headers are the same, meaning that both are
+/// the product of the same valid state transition.
+fn is_same_state_transition(header: &Header, conflicting: &Header) -> bool {
+    header.validators_hash == conflicting.validators_hash
+        && header.next_validators_hash == conflicting.next_validators_hash
+        && header.consensus_hash == conflicting.consensus_hash
+        && header.app_hash == conflicting.app_hash
+        && header.last_results_hash == conflicting.last_results_hash
+}
+
+/// Represents the result of the verification of misbehaviour.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MisbehaviourVerdict {
+    /// Both headers are valid with respect to the trusted state and conflict with each other,
+    /// as the product of the given attack.
+    Misbehaviour(AttackType),
+    /// The headers do not conflict, as they are at different heights, or are the same header.
+    NotConflicting,
+    /// The first header is not valid with respect to the trusted state.
+    InvalidHeader1(Verdict),
+    /// The second header is not valid with respect to the trusted state.
+    InvalidHeader2(Verdict),
+}
+
+/// Verify that the two given headers are conflicting headers at the same height, both valid
+/// with respect to the given trusted state, and classify the attack which produced them.
+///
+/// Each header is verified with [`Verifier::verify_misbehaviour_header`], which does not reject
+/// headers from the future, so as to catch forward lunatic attacks.
+pub fn verify_misbehaviour<V: Verifier + ?Sized>(
+    verifier: &V,
+    header1: UntrustedBlockState<'_>,
+    header2: UntrustedBlockState<'_>,
+    trusted: TrustedBlockState<'_>,
+    options: &Options,
+    now: Time,
+) -> MisbehaviourVerdict {
+    // Once verified, the hash of each header is the hash of the block id of its commit
+    if header1.height() != header2.height()
+        || header1.signed_header.commit.block_id == header2.signed_header.commit.block_id
+    {
+        return MisbehaviourVerdict::NotConflicting;
+    }
+
+    match verifier.verify_misbehaviour_header(header1, trusted, options, now) {
+        Verdict::Success => {},
+        verdict => return MisbehaviourVerdict::InvalidHeader1(verdict),
+    }
+
+    match verifier.verify_misbehaviour_header(header2, trusted, options, now) {
+        Verdict::Success => {},
+        verdict => return MisbehaviourVerdict::InvalidHeader2(verdict),
+    }
+
+    MisbehaviourVerdict::Misbehaviour(AttackType::classify(
+        header1.signed_header,
+        header2.signed_header,
+    ))
+}
+
+#[cfg(all(test, feature = "rust-crypto"))]
+mod tests {
+    use alloc::vec;
+    use core::{ops::Sub, time::Duration};
+
+    use tendermint::Time;
+    use tendermint_testgen::{
+        light_block::LightBlock as TestgenLightBlock, Commit, Generator, Header, Validator,
+    };
+
+    use super::{verify_misbehaviour, AttackType, MisbehaviourVerdict};
+    use crate::{options::Options, types::LightBlock, ProdVerifier, Verdict};
+
+    fn header(validators: &[Validator], height: u64, time: Time) -> Header {
+        Header::new(validators)
+            .height(height)
+            .chain_id("test-chain")
+            .next_validators(validators)
+            .time(time)
+    }
+
+    fn light_block(header: Header, round: u32) -> LightBlock {
+        TestgenLightBlock::new(header.clone(), Commit::new(header, round))
+            .generate()
+            .unwrap()
+            .into()
+    }
+
+    fn validators() -> [Validator; 2] {
+        [
+            Validator::new("1").voting_power(50),
+            Validator::new("2").voting_power(50),
+        ]
+    }
+
+    fn options() -> Options {
+        Options {
+            trust_threshold: Default::default(),
+            trusting_period: Duration::from_secs(60),
+            clock_drift: Default::default(),
+            verification_mode: Default::default(),
+            commit_signatures: Default::default(),
+        }
+    }
+
+    fn verify(
+        trusted: &LightBlock,
+        lb1: &LightBlock,
+        lb2: &LightBlock,
+        now: Time,
+    ) -> MisbehaviourVerdict {
+        verify_misbehaviour(
+            &ProdVerifier::default(),
+            lb1.as_untrusted_state(),
+            lb2.as_untrusted_state(),
+            trusted.as_trusted_state(),
+            &options(),
+            now,
+        )
+    }
+
+    #[test]
+    fn classifies_attacks() {
+        let now = Time::now();
+        let before = |secs| now.sub(Duration::from_secs(secs)).unwrap();
+
+        let trusted = light_block(header(&validators(), 1, before(20)), 1);
+        let header_a = header(&validators(), 3, before(10));
+        let header_b = header(&validators(), 3, before(9));
+
+        let lb = light_block(header_a.clone(), 1);
+
+        assert_eq!(
+            verify(&trusted, &lb, &light_block(header_b.clone(), 1), now),
+            MisbehaviourVerdict::Misbehaviour(AttackType::Equivocation)
+        );
+        assert_eq!(
+            verify(&trusted, &lb, &light_block(header_b.clone(), 2), now),
+            MisbehaviourVerdict::Misbehaviour(AttackType::Amnesia)
+        );
+
+        let lunatic = light_block(header_b.app_hash(vec![1, 2, 3].try_into().unwrap()), 1);
+        assert_eq!(
+            verify(&trusted, &lb, &lunatic, now),
+            MisbehaviourVerdict::Misbehaviour(AttackType::Lunatic)
+        );
+    }
+
+    #[test]
+    fn rejects_headers_which_do_not_conflict() {
+        let now = Time::now();
+        let before = |secs| now.sub(Duration::from_secs(secs)).unwrap();
+
+        let trusted = light_block(header(&validators(), 1, before(20)), 1);
+        let lb = light_block(header(&validators(), 3, before(10)), 1);
+        let higher = light_block(header(&validators(), 4, before(9)), 1);
+
+        assert_eq!(
+            verify(&trusted, &lb, &lb, now),
+            MisbehaviourVerdict::NotConflicting
+        );
+        assert_eq!(
+            verify(&trusted, &lb, &higher, now),
+            MisbehaviourVerdict::NotConflicting
+        );
+    }
+
+    #[test]
+    fn rejects_headers_which_cannot_be_trusted() {
+        let now = Time::now();
+        let before = |secs| now.sub(Duration::from_secs(secs)).unwrap();
+
+        let trusted = light_block(header(&validators(), 1, before(20)), 1);
+        let lb = light_block(header(&validators(), 3, before(10)), 1);
+
+        let untrusted_validators = [Validator::new("3").voting_power(50)];
+        let untrusted = light_block(header(&untrusted_validators, 3, before(9)), 1);
+
+        assert!(matches!(
+            verify(&trusted, &lb, &untrusted, now),
+            MisbehaviourVerdict::InvalidHeader2(Verdict::NotEnoughTrust(_))
+        ));
+        assert!(matches!(
+            verify(&trusted, &untrusted, &lb, now),
+            MisbehaviourVerdict::InvalidHeader1(Verdict::NotEnoughTrust(_))
+        ));
+    }
+}
@@ -8,13 +8,15 @@ use tendermint::{
     block::CommitSig,
     crypto::signature,
     trust_threshold::TrustThreshold as _,
-    vote::{SignedVote, ValidatorIndex, Vote},
+    vote::{CanonicalVoteSignBytes, ValidatorIndex, Vote},
+    PublicKey, Signature,
 };
 
 use crate::{
+    audit::{SignatureCheck, SignatureOutcome},
     errors::VerificationError,
     prelude::*,
-    types::{Commit, SignedHeader, TrustThreshold, ValidatorSet},
+    types::{Commit, SignedHeader, TrustThreshold, ValidatorSet, VoteExtension},
 };
 
 /// Tally for the voting power computed by the `VotingPowerCalculator`
@@ -87,6 +89,42 @@ pub trait VotingPowerCalculator: Send + Sync {
         }
     }
 
+    /// Check against the given threshold that enough validators of the validator set of
+    /// a header correctly signed their vote extensions for its commit
+    fn check_vote_extensions(
+        &self,
+        signed_header: &SignedHeader,
+        validator_set: &ValidatorSet,
+        vote_extensions: &[VoteExtension],
+        trust_threshold: TrustThreshold,
+    ) -> Result<(), VerificationError> {
+        let voting_power = self.voting_power_in_vote_extensions(
+            signed_header,
+            validator_set,
+            vote_extensions,
+            trust_threshold,
+        )?;
+
+        if trust_threshold.is_enough_power(voting_power.tallied, voting_power.total) {
+            Ok(())
+        } else {
+            Err(VerificationError::not_enough_vote_extensions(voting_power))
+        }
+    }
+
+    /// Check each signature of the commit of a header against a validator set, for auditing
+    /// purposes, without failing on invalid signatures.
+    ///
+    /// The default implementation does not check any signature and returns no check.
+    fn check_signatures(
+        &self,
+        signed_header: &SignedHeader,
+        validator_set: &ValidatorSet,
+    ) -> Vec<SignatureCheck> {
+        let _ = (signed_header, validator_set);
+        Vec::new()
+    }
+
     /// Compute the voting power in a header and its commit against a validator set.
     ///
     /// The `trust_threshold` is currently not used, but might be in the future
@@ -97,6 +135,19 @@ pub trait VotingPowerCalculator: Send + Sync {
         validator_set: &ValidatorSet,
         trust_threshold: TrustThreshold,
     ) -> Result<VotingPowerTally, VerificationError>;
+
+    /// Compute the voting power of the validators which correctly signed the given
+    /// vote extensions for the commit of a header, against the validator set of the header.
+    ///
+    /// Fails if any of the vote extensions is not correctly signed, or comes from a validator
+    /// which did not commit the block.
+    fn voting_power_in_vote_extensions(
+        &self,
+        signed_header: &SignedHeader,
+        validator_set: &ValidatorSet,
+        vote_extensions: &[VoteExtension],
+        trust_threshold: TrustThreshold,
+    ) -> Result<VotingPowerTally, VerificationError>;
 }
 
 /// Default implementation of a `VotingPowerCalculator`, parameterized with
@@ -118,6 +169,49 @@ impl<V> Default for ProvidedVotingPowerCalculator<V> {
     }
 }
 
+/// Verify all the given signatures, and return the index of the first invalid one, if any.
+///
+/// The signatures are first verified at once, and only one at a time to find out which one
+/// is invalid if not.
+#[cfg(not(feature = "parallel"))]
+fn first_invalid_signature<V: signature::Verifier>(
+    batch: &[(PublicKey, &[u8], &Signature)],
+) -> Option<usize> {
+    if V::verify_batch(batch).is_ok() {
+        return None;
+    }
+
+    batch
+        .iter()
+        .position(|(pubkey, msg, sig)| V::verify(*pubkey, msg, sig).is_err())
+}
+
+/// Verify all the given signatures across the thread pool of `rayon`, and return the index
+/// of the first invalid one, if any.
+///
+/// The signatures are split into one chunk per thread, which are each verified at once,
+/// and only one at a time to find out which one is invalid if not. The first invalid
+/// signature is the same as the one found when verifying them sequentially.
+#[cfg(feature = "parallel")]
+fn first_invalid_signature<V: signature::Verifier>(
+    batch: &[(PublicKey, &[u8], &Signature)],
+) -> Option<usize> {
+    use rayon::prelude::*;
+
+    let chunk_size = batch.len().div_ceil(rayon::current_num_threads()).max(1);
+
+    if batch
+        .par_chunks(chunk_size)
+        .all(|chunk| V::verify_batch(chunk).is_ok())
+    {
+        return None;
+    }
+
+    batch
+        .par_iter()
+        .position_first(|(pubkey, msg, sig)| V::verify(*pubkey, msg, sig).is_err())
+}
+
 /// Default implementation of a `VotingPowerCalculator`.
 #[cfg(feature = "rust-crypto")]
 pub type ProdVotingPowerCalculator =
@@ -145,6 +239,10 @@ impl<V: signature::Verifier> VotingPowerCalculator for ProvidedVotingPowerCalcul
             .map(|vote| (signature, vote))
         });
 
+        // The votes of the validators of the set, with their sign bytes
+        let mut votes = Vec::new();
+        let commit_sign_bytes = commit_sign_bytes(signed_header);
+
         for (signature, vote) in non_absent_votes {
             // Ensure we only count a validator's power once
             if seen_validators.contains(&vote.validator_address) {
@@ -160,23 +258,32 @@ impl<V: signature::Verifier> VotingPowerCalculator for ProvidedVotingPowerCalcul
                 None => continue, // Cannot find matching validator, so we skip the vote
             };
 
-            let signed_vote =
-                SignedVote::from_vote(vote.clone(), signed_header.header.chain_id.clone())
-                    .ok_or_else(VerificationError::missing_signature)?;
+            let sign_bytes = commit_sign_bytes.sign_bytes(&vote);
+            let vote_signature = vote
+                .signature
+                .ok_or_else(VerificationError::missing_signature)?;
 
-            // Check vote is valid
-            let sign_bytes = signed_vote.sign_bytes();
-            if validator
-                .verify_signature::<V>(&sign_bytes, signed_vote.signature())
-                .is_err()
-            {
-                return Err(VerificationError::invalid_signature(
-                    signed_vote.signature().as_bytes().to_vec(),
-                    Box::new(validator),
-                    sign_bytes,
-                ));
-            }
+            votes.push((signature, validator, vote_signature, sign_bytes));
+        }
 
+        let batch: Vec<_> = votes
+            .iter()
+            .map(|(_, validator, vote_signature, sign_bytes)| {
+                (validator.pub_key, sign_bytes.as_slice(), vote_signature)
+            })
+            .collect();
+
+        if let Some(index) = first_invalid_signature::<V>(&batch) {
+            let (_, validator, vote_signature, sign_bytes) = &votes[index];
+
+            return Err(VerificationError::invalid_signature(
+                vote_signature.as_bytes().to_vec(),
+                Box::new(validator.clone()),
+                sign_bytes.clone(),
+            ));
+        }
+
+        for (signature, validator, _, _) in &votes {
             // If the vote is neither absent nor nil, tally its power
             if signature.is_commit() {
                 tallied_voting_power += validator.power();
@@ -184,9 +291,6 @@ impl<V: signature::Verifier> VotingPowerCalculator for ProvidedVotingPowerCalcul
                 // It's OK. We include stray signatures (~votes for nil)
                 // to measure validator availability.
             }
-
-            // TODO: Break out of the loop when we have enough voting power.
-            // See https://github.com/informalsystems/tendermint-rs/issues/235
         }
 
         let voting_power = VotingPowerTally {
@@ -197,6 +301,152 @@ impl<V: signature::Verifier> VotingPowerCalculator for ProvidedVotingPowerCalcul
 
         Ok(voting_power)
     }
+
+    fn check_signatures(
+        &self,
+        signed_header: &SignedHeader,
+        validator_set: &ValidatorSet,
+    ) -> Vec<SignatureCheck> {
+        let commit = &signed_header.commit;
+        let commit_sign_bytes = commit_sign_bytes(signed_header);
+
+        commit
+            .signatures
+            .iter()
+            .enumerate()
+            .map(|(idx, signature)| {
+                let vote = match non_absent_vote(
+                    signature,
+                    ValidatorIndex::try_from(idx).unwrap(),
+                    commit,
+                ) {
+                    Some(vote) => vote,
+                    None => {
+                        return SignatureCheck {
+                            validator_address: None,
+                            for_block: false,
+                            voting_power: 0,
+                            outcome: SignatureOutcome::Absent,
+                        }
+                    },
+                };
+
+                let validator_address = vote.validator_address;
+                let for_block = signature.is_commit();
+
+                let validator = match validator_set.validator(validator_address) {
+                    Some(validator) => validator,
+                    None => {
+                        return SignatureCheck {
+                            validator_address: Some(validator_address),
+                            for_block,
+                            voting_power: 0,
+                            outcome: SignatureOutcome::UnknownValidator,
+                        }
+                    },
+                };
+
+                let valid = vote.signature.as_ref().is_some_and(|signature| {
+                    validator
+                        .verify_signature::<V>(&commit_sign_bytes.sign_bytes(&vote), signature)
+                        .is_ok()
+                });
+
+                SignatureCheck {
+                    validator_address: Some(validator_address),
+                    for_block,
+                    voting_power: validator.power(),
+                    outcome: if valid {
+                        SignatureOutcome::Valid
+                    } else {
+                        SignatureOutcome::Invalid
+                    },
+                }
+            })
+            .collect()
+    }
+
+    fn voting_power_in_vote_extensions(
+        &self,
+        signed_header: &SignedHeader,
+        validator_set: &ValidatorSet,
+        vote_extensions: &[VoteExtension],
+        trust_threshold: TrustThreshold,
+    ) -> Result<VotingPowerTally, VerificationError> {
+        let commit = &signed_header.commit;
+
+        let mut tallied_voting_power = 0_u64;
+        let mut seen_validators = HashSet::new();
+
+        for vote_extension in vote_extensions {
+            let address = vote_extension.validator_address;
+
+            // Ensure we only count a validator's power once
+            if !seen_validators.insert(address) {
+                return Err(VerificationError::duplicate_validator(address));
+            }
+
+            // Only precommits for the block carry a vote extension
+            let vote = commit
+                .signatures
+                .iter()
+                .enumerate()
+                .filter(|(_, signature)| signature.is_commit())
+                .find(|(_, signature)| signature.validator_address() == Some(address))
+                .and_then(|(idx, signature)| {
+                    non_absent_vote(signature, ValidatorIndex::try_from(idx).unwrap(), commit)
+                })
+                .ok_or_else(|| VerificationError::unexpected_vote_extension(address))?;
+
+            let validator = validator_set
+                .validator(address)
+                .ok_or_else(|| VerificationError::unexpected_vote_extension(address))?;
+
+            let extension_signature = vote_extension
+                .extension_signature
+                .as_ref()
+                .ok_or_else(|| VerificationError::missing_vote_extension_signature(address))?;
+
+            let sign_bytes = Vote {
+                extension: vote_extension.extension.clone(),
+                ..vote
+            }
+            .into_extension_signable_vec(signed_header.header.chain_id.clone());
+
+            if validator
+                .verify_signature::<V>(&sign_bytes, extension_signature)
+                .is_err()
+            {
+                return Err(VerificationError::invalid_signature(
+                    extension_signature.as_bytes().to_vec(),
+                    Box::new(validator),
+                    sign_bytes,
+                ));
+            }
+
+            tallied_voting_power += validator.power();
+        }
+
+        Ok(VotingPowerTally {
+            total: self.total_power_of(validator_set),
+            tallied: tallied_voting_power,
+            trust_threshold,
+        })
+    }
+}
+
+/// The sign bytes of the precommits of the given signed header's commit, which are computed
+/// once for the fields all of them share.
+fn commit_sign_bytes(signed_header: &SignedHeader) -> CanonicalVoteSignBytes {
+    let commit = &signed_header.commit;
+
+    CanonicalVoteSignBytes::new(
+        tendermint::vote::Type::Precommit,
+        commit.height,
+        commit.round,
+        Some(commit.block_id),
+        signed_header.header.chain_id.clone(),
+    )
 }
 
 fn non_absent_vote(
@@ -243,7 +493,7 @@ fn non_absent_vote(
 // TODO: We plan to add Lightweight MBT for `voting_power_in` in the near future
 #[cfg(test)]
 mod tests {
-    use tendermint::trust_threshold::TrustThresholdFraction;
+    use tendermint::{trust_threshold::TrustThresholdFraction, Time};
     use tendermint_testgen::{
         light_block::generate_signed_header, Commit, Generator, Header,
         LightBlock as TestgenLightBlock, ValidatorSet, Vote as TestgenVote,
@@ -351,6 +601,80 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_commit_of_many_validators() {
+        let vp_calculator = ProdVotingPowerCalculator::default();
+        let trust_threshold = TrustThreshold::default();
+
+        let ids: Vec<String> = (0..20).map(|i| format!("val-{i}")).collect();
+        let validators = ValidatorSet::new(ids.iter().map(String::as_str).collect())
+            .validators
+            .unwrap();
+        let header = Header::new(&validators)
+            .height(10)
+            .chain_id("test-chain")
+            .time(Time::from_unix_timestamp(10, 0).unwrap());
+        let light_block: LightBlock = TestgenLightBlock::new_default_with_header(header)
+            .generate()
+            .unwrap()
+            .into();
+
+        let tally = vp_calculator
+            .voting_power_in(
+                &light_block.signed_header,
+                &light_block.validators,
+                trust_threshold,
+            )
+            .unwrap();
+
+        assert_eq!(tally.tallied, tally.total);
+        assert_eq!(
+            tally.total,
+            light_block.validators.total_voting_power().value()
+        );
+    }
+
+    #[test]
+    fn test_invalid_signature_among_many_validators() {
+        let vp_calculator = ProdVotingPowerCalculator::default();
+        let trust_threshold = TrustThreshold::default();
+
+        let ids: Vec<String> = (0..20).map(|i| format!("val-{i}")).collect();
+        let validators = ValidatorSet::new(ids.iter().map(String::as_str).collect())
+            .validators
+            .unwrap();
+        let header = Header::new(&validators)
+            .height(10)
+            .chain_id("test-chain")
+            .time(Time::from_unix_timestamp(10, 0).unwrap());
+        let mut testgen_lb = TestgenLightBlock::new_default_with_header(header);
+
+        // Sign one of the votes for another chain
+        let mut commit = testgen_lb.commit.clone().unwrap();
+        let mut votes = commit.votes.unwrap();
+        let vote = votes.remove(7);
+        let invalid = vote.validator.clone().unwrap().generate().unwrap().address;
+        let vote_header = vote.clone().header.unwrap().chain_id("bad-chain");
+        votes.insert(7, vote.header(vote_header));
+        commit.votes = Some(votes);
+        testgen_lb.commit = Some(commit);
+
+        let light_block: LightBlock = testgen_lb.generate().unwrap().into();
+
+        let result_err = vp_calculator.voting_power_in(
+            &light_block.signed_header,
+            &light_block.validators,
+            trust_threshold,
+        );
+
+        match result_err {
+            Err(VerificationError(VerificationErrorDetail::InvalidSignature(e), _)) => {
+                assert_eq!(e.validator.address, invalid)
+            },
+            _ => panic!("expected InvalidSignature error"),
+        }
+    }
+
     #[test]
     fn test_all_signatures_invalid() {
         let vp_calculator = ProdVotingPowerCalculator::default();
@@ -395,4 +719,178 @@ mod tests {
         // ensure the result matches the expected result
         assert_eq!(result_ok.unwrap(), EXPECTED_RESULT);
     }
+
+    fn vote_extension(
+        validator: &tendermint_testgen::Validator,
+        light_block: &LightBlock,
+        extension: &[u8],
+    ) -> VoteExtension {
+        let commit = &light_block.signed_header.commit;
+        let sign_bytes = Vote {
+            vote_type: tendermint::vote::Type::Precommit,
+            height: commit.height,
+            round: commit.round,
+            block_id: Some(commit.block_id),
+            timestamp: None,
+            validator_address: validator.generate().unwrap().address,
+            validator_index: ValidatorIndex::try_from(0_u32).unwrap(),
+            signature: None,
+            extension: extension.to_vec(),
+            extension_signature: None,
+        }
+        .into_extension_signable_vec(light_block.signed_header.header.chain_id.clone());
+
+        VoteExtension {
+            validator_address: validator.generate().unwrap().address,
+            extension: extension.to_vec(),
+            extension_signature: Some(validator.sign(&sign_bytes).unwrap()),
+        }
+    }
+
+    fn vote_extensions_fixture() -> (LightBlock, Vec<tendermint_testgen::Validator>) {
+        let testgen_lb = TestgenLightBlock::new_default(10);
+        let validators = testgen_lb.validators.clone().unwrap();
+        (testgen_lb.generate().unwrap().into(), validators)
+    }
+
+    #[test]
+    fn test_vote_extensions_all_signed() {
+        let vp_calculator = ProdVotingPowerCalculator::default();
+        let (light_block, validators) = vote_extensions_fixture();
+
+        let vote_extensions: Vec<_> = validators
+            .iter()
+            .map(|val| vote_extension(val, &light_block, b"price=42"))
+            .collect();
+
+        let tally = vp_calculator
+            .voting_power_in_vote_extensions(
+                &light_block.signed_header,
+                &light_block.validators,
+                &vote_extensions,
+                TrustThreshold::TWO_THIRDS,
+            )
+            .unwrap();
+
+        assert_eq!(tally.tallied, 100);
+        assert_eq!(tally.total, 100);
+
+        vp_calculator
+            .check_vote_extensions(
+                &light_block.signed_header,
+                &light_block.validators,
+                &vote_extensions,
+                TrustThreshold::TWO_THIRDS,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_not_enough_vote_extensions() {
+        let vp_calculator = ProdVotingPowerCalculator::default();
+        let (light_block, validators) = vote_extensions_fixture();
+
+        let vote_extensions = [vote_extension(&validators[0], &light_block, b"price=42")];
+
+        let result_err = vp_calculator.check_vote_extensions(
+            &light_block.signed_header,
+            &light_block.validators,
+            &vote_extensions,
+            TrustThreshold::TWO_THIRDS,
+        );
+
+        match result_err {
+            Err(VerificationError(VerificationErrorDetail::NotEnoughVoteExtensions(e), _)) => {
+                assert_eq!(e.tally.tallied, 50);
+            },
+            _ => panic!("expected NotEnoughVoteExtensions error"),
+        }
+    }
+
+    #[test]
+    fn test_vote_extension_invalid_signature() {
+        let vp_calculator = ProdVotingPowerCalculator::default();
+        let (light_block, validators) = vote_extensions_fixture();
+
+        // The validator signed another extension than the one given
+        let mut vote_extension = vote_extension(&validators[0], &light_block, b"price=42");
+        vote_extension.extension = b"price=43".to_vec();
+
+        let result_err = vp_calculator.voting_power_in_vote_extensions(
+            &light_block.signed_header,
+            &light_block.validators,
+            &[vote_extension],
+            TrustThreshold::TWO_THIRDS,
+        );
+
+        match result_err {
+            Err(VerificationError(VerificationErrorDetail::InvalidSignature(_), _)) => {},
+            _ => panic!("expected InvalidSignature error"),
+        }
+    }
+
+    #[test]
+    fn test_vote_extension_missing_signature() {
+        let vp_calculator = ProdVotingPowerCalculator::default();
+        let (light_block, validators) = vote_extensions_fixture();
+
+        let mut vote_extension = vote_extension(&validators[0], &light_block, b"price=42");
+        vote_extension.extension_signature = None;
+
+        let result_err = vp_calculator.voting_power_in_vote_extensions(
+            &light_block.signed_header,
+            &light_block.validators,
+            &[vote_extension],
+            TrustThreshold::TWO_THIRDS,
+        );
+
+        match result_err {
+            Err(VerificationError(
+                VerificationErrorDetail::MissingVoteExtensionSignature(_),
+                _,
+            )) => {},
+            _ => panic!("expected MissingVoteExtensionSignature error"),
+        }
+    }
+
+    #[test]
+    fn test_vote_extension_from_non_committing_validator() {
+        let vp_calculator = ProdVotingPowerCalculator::default();
+        let (light_block, _) = vote_extensions_fixture();
+
+        let stranger = tendermint_testgen::Validator::new("stranger").voting_power(50);
+        let vote_extension = vote_extension(&stranger, &light_block, b"price=42");
+
+        let result_err = vp_calculator.voting_power_in_vote_extensions(
+            &light_block.signed_header,
+            &light_block.validators,
+            &[vote_extension],
+            TrustThreshold::TWO_THIRDS,
+        );
+
+        match result_err {
+            Err(VerificationError(VerificationErrorDetail::UnexpectedVoteExtension(_), _)) => {},
+            _ => panic!("expected UnexpectedVoteExtension error"),
+        }
+    }
+
+    #[test]
+    fn test_duplicate_vote_extensions() {
+        let vp_calculator = ProdVotingPowerCalculator::default();
+        let (light_block, validators) = vote_extensions_fixture();
+
+        let vote_extension = vote_extension(&validators[0], &light_block, b"price=42");
+
+        let result_err = vp_calculator.voting_power_in_vote_extensions(
+            &light_block.signed_header,
+            &light_block.validators,
+            &[vote_extension.clone(), vote_extension],
+            TrustThreshold::TWO_THIRDS,
+        );
+
+        match result_err {
+            Err(VerificationError(VerificationErrorDetail::DuplicateValidator(_), _)) => {},
+            _ => panic!("expected DuplicateValidator error"),
+        }
+    }
 }
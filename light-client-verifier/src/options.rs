@@ -7,7 +7,7 @@ use core::time::Duration;
 use derive_more::Display;
 use serde::{Deserialize, Serialize};
 
-use crate::types::TrustThreshold;
+use crate::types::{Height, TrustThreshold};
 
 /// Verification parameters
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Display, Serialize, Deserialize)]
@@ -27,4 +27,85 @@ pub struct Options {
     /// is the maximum amount that the local clock may drift behind a timestamp from the
     /// blockchain.
     pub clock_drift: Duration,
+
+    /// Whether the light client may skip over intermediate headers on the way to the
+    /// target header, or must verify every one of them (default: skipping).
+    #[serde(default)]
+    pub verification_mode: VerificationMode,
+
+    /// How strictly the signatures of the commits are validated, for chains with
+    /// known non-standard signers (default: as strictly as the consensus does).
+    #[serde(default)]
+    pub commit_signatures: CommitSignaturesOptions,
+}
+
+/// How the light client reaches the target header from a trusted header.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationMode {
+    /// Skip over intermediate headers as long as enough of the trusted validators signed
+    /// the commit of the untrusted header, and bisect otherwise.
+    #[default]
+    Skipping,
+
+    /// Verify every header between the trusted header and the target header, each one
+    /// against the previous one, for maximum assurance, or for chains whose validator sets
+    /// change too fast for skipping verification to succeed.
+    Sequential,
+}
+
+/// Tolerance of the verification for malformed or absent signatures in a commit.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[display(fmt = "{self:?}")]
+#[serde(default)]
+pub struct CommitSignaturesOptions {
+    /// What to do with the votes for nil which are malformed, ie. which have no signature
+    /// or come from a validator which is not part of the validator set (default: reject).
+    pub malformed_nil_votes: MalformedVotes,
+
+    /// Maximum number of absent signatures in a commit, if any (default: unlimited).
+    ///
+    /// The malformed votes for nil which are skipped do not count as absent.
+    pub max_absent_signatures: Option<usize>,
+}
+
+/// What to do with the malformed votes of a commit.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MalformedVotes {
+    /// Reject the commit
+    #[default]
+    Reject,
+
+    /// Treat the votes as absent and carry on with the verification of the commit.
+    ///
+    /// This does not weaken the verification, as votes for nil do not count towards
+    /// the voting power which committed the block.
+    Skip,
+}
+
+/// Parameters of the verification of the vote extensions of a commit, for chains
+/// running CometBFT 0.38 or later.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[display(fmt = "{self:?}")]
+pub struct VoteExtensionsOptions {
+    /// Height from which the chain requires vote extensions, as set by the
+    /// `vote_extensions_enable_height` consensus parameter. Commits below
+    /// this height carry no vote extensions, which are then not verified.
+    pub enable_height: Height,
+
+    /// Fraction of the total voting power of the validator set of a block which must
+    /// have correctly signed their vote extensions for the commit to be accepted.
+    pub trust_threshold: TrustThreshold,
+}
+
+impl VoteExtensionsOptions {
+    /// Verify the vote extensions of commits from the given height onwards, requiring
+    /// more than two-thirds of the voting power, as the consensus does.
+    pub fn new(enable_height: Height) -> Self {
+        Self {
+            enable_height,
+            trust_threshold: TrustThreshold::TWO_THIRDS,
+        }
+    }
 }
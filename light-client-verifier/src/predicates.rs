@@ -1,16 +1,21 @@
 //! Predicates for light block validation and verification.
 
-use core::time::Duration;
+use core::{fmt, marker::PhantomData, time::Duration};
 
 use tendermint::{
     block::Height, chain::Id as ChainId, crypto::Sha256, hash::Hash, merkle::MerkleHash,
 };
 
 use crate::{
+    crypto::CryptoProvider,
     errors::VerificationError,
     operations::{CommitValidator, VotingPowerCalculator},
+    options::VoteExtensionsOptions,
     prelude::*,
-    types::{Header, SignedHeader, Time, TrustThreshold, ValidatorSet},
+    types::{
+        Header, SignedHeader, Time, TrustThreshold, UntrustedBlockState, ValidatorSet,
+        VoteExtension,
+    },
 };
 
 /// Production predicates, using the default implementation
@@ -24,6 +29,218 @@ impl VerificationPredicates for ProdPredicates {
     type Sha256 = tendermint::crypto::default::Sha256;
 }
 
+/// Predicates using the default implementation of the `VerificationPredicates` trait,
+/// parameterized with the crypto provider to hash headers and validator sets with.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ProvidedPredicates<C> {
+    _crypto: PhantomData<C>,
+}
+
+impl<C> Default for ProvidedPredicates<C> {
+    fn default() -> Self {
+        Self {
+            _crypto: PhantomData,
+        }
+    }
+}
+
+impl<C: CryptoProvider> VerificationPredicates for ProvidedPredicates<C> {
+    type Sha256 = C::Sha256;
+}
+
+/// Predicates wrapping other predicates with an additional check of the untrusted
+/// light blocks, as returned by [`VerificationPredicates::with_check`].
+///
+/// Every predicate is delegated to the wrapped predicates, and the check is performed
+/// after the additional checks of the wrapped predicates, if any.
+#[derive(Clone, Copy)]
+pub struct WithCheck<P, F> {
+    predicates: P,
+    check: F,
+}
+
+impl<P, F> WithCheck<P, F> {
+    /// Wrap the given predicates with the given check.
+    pub fn new(predicates: P, check: F) -> Self {
+        Self { predicates, check }
+    }
+
+    /// The wrapped predicates.
+    pub fn predicates(&self) -> &P {
+        &self.predicates
+    }
+}
+
+impl<P: fmt::Debug, F> fmt::Debug for WithCheck<P, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WithCheck")
+            .field("predicates", &self.predicates)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P, F> VerificationPredicates for WithCheck<P, F>
+where
+    P: VerificationPredicates,
+    F: Fn(&UntrustedBlockState<'_>) -> Result<(), VerificationError> + Send + Sync,
+{
+    type Sha256 = P::Sha256;
+
+    fn validator_sets_match(
+        &self,
+        validators: &ValidatorSet,
+        header_validators_hash: Hash,
+    ) -> Result<(), VerificationError> {
+        self.predicates
+            .validator_sets_match(validators, header_validators_hash)
+    }
+
+    fn next_validators_match(
+        &self,
+        next_validators: &ValidatorSet,
+        header_next_validators_hash: Hash,
+    ) -> Result<(), VerificationError> {
+        self.predicates
+            .next_validators_match(next_validators, header_next_validators_hash)
+    }
+
+    fn header_matches_commit(
+        &self,
+        header: &Header,
+        commit_hash: Hash,
+    ) -> Result<(), VerificationError> {
+        self.predicates.header_matches_commit(header, commit_hash)
+    }
+
+    fn valid_commit(
+        &self,
+        signed_header: &SignedHeader,
+        validators: &ValidatorSet,
+        commit_validator: &dyn CommitValidator,
+    ) -> Result<(), VerificationError> {
+        self.predicates
+            .valid_commit(signed_header, validators, commit_validator)
+    }
+
+    fn is_within_trust_period(
+        &self,
+        trusted_header_time: Time,
+        trusting_period: Duration,
+        now: Time,
+    ) -> Result<(), VerificationError> {
+        self.predicates
+            .is_within_trust_period(trusted_header_time, trusting_period, now)
+    }
+
+    fn is_header_from_past(
+        &self,
+        untrusted_header_time: Time,
+        clock_drift: Duration,
+        now: Time,
+    ) -> Result<(), VerificationError> {
+        self.predicates
+            .is_header_from_past(untrusted_header_time, clock_drift, now)
+    }
+
+    fn is_monotonic_bft_time(
+        &self,
+        untrusted_header_time: Time,
+        trusted_header_time: Time,
+    ) -> Result<(), VerificationError> {
+        self.predicates
+            .is_monotonic_bft_time(untrusted_header_time, trusted_header_time)
+    }
+
+    fn is_monotonic_height(
+        &self,
+        untrusted_height: Height,
+        trusted_height: Height,
+    ) -> Result<(), VerificationError> {
+        self.predicates
+            .is_monotonic_height(untrusted_height, trusted_height)
+    }
+
+    fn is_matching_chain_id(
+        &self,
+        untrusted_chain_id: &ChainId,
+        trusted_chain_id: &ChainId,
+    ) -> Result<(), VerificationError> {
+        self.predicates
+            .is_matching_chain_id(untrusted_chain_id, trusted_chain_id)
+    }
+
+    fn is_within_absent_signatures_cap(
+        &self,
+        untrusted_sh: &SignedHeader,
+        max_absent_signatures: Option<usize>,
+    ) -> Result<(), VerificationError> {
+        self.predicates
+            .is_within_absent_signatures_cap(untrusted_sh, max_absent_signatures)
+    }
+
+    fn has_sufficient_validators_overlap(
+        &self,
+        untrusted_sh: &SignedHeader,
+        trusted_validators: &ValidatorSet,
+        trust_threshold: &TrustThreshold,
+        calculator: &dyn VotingPowerCalculator,
+    ) -> Result<(), VerificationError> {
+        self.predicates.has_sufficient_validators_overlap(
+            untrusted_sh,
+            trusted_validators,
+            trust_threshold,
+            calculator,
+        )
+    }
+
+    fn has_sufficient_signers_overlap(
+        &self,
+        untrusted_sh: &SignedHeader,
+        untrusted_validators: &ValidatorSet,
+        calculator: &dyn VotingPowerCalculator,
+    ) -> Result<(), VerificationError> {
+        self.predicates.has_sufficient_signers_overlap(
+            untrusted_sh,
+            untrusted_validators,
+            calculator,
+        )
+    }
+
+    fn has_sufficient_vote_extensions(
+        &self,
+        untrusted_sh: &SignedHeader,
+        untrusted_validators: &ValidatorSet,
+        vote_extensions: &[VoteExtension],
+        options: &VoteExtensionsOptions,
+        calculator: &dyn VotingPowerCalculator,
+    ) -> Result<(), VerificationError> {
+        self.predicates.has_sufficient_vote_extensions(
+            untrusted_sh,
+            untrusted_validators,
+            vote_extensions,
+            options,
+            calculator,
+        )
+    }
+
+    fn valid_next_validator_set(
+        &self,
+        untrusted_validators_hash: Hash,
+        trusted_next_validators_hash: Hash,
+    ) -> Result<(), VerificationError> {
+        self.predicates
+            .valid_next_validator_set(untrusted_validators_hash, trusted_next_validators_hash)
+    }
+
+    fn additional_checks(
+        &self,
+        untrusted: &UntrustedBlockState<'_>,
+    ) -> Result<(), VerificationError> {
+        self.predicates.additional_checks(untrusted)?;
+        (self.check)(untrusted)
+    }
+}
+
 /// Defines the various predicates used to validate and verify light blocks.
 ///
 /// A default, spec abiding implementation is provided for each method.
@@ -184,6 +401,34 @@ pub trait VerificationPredicates: Send + Sync {
         }
     }
 
+    /// Check that the commit of the untrusted signed header has at most the given number
+    /// of absent signatures, if any.
+    fn is_within_absent_signatures_cap(
+        &self,
+        untrusted_sh: &SignedHeader,
+        max_absent_signatures: Option<usize>,
+    ) -> Result<(), VerificationError> {
+        let Some(max_absent_signatures) = max_absent_signatures else {
+            return Ok(());
+        };
+
+        let absent = untrusted_sh
+            .commit
+            .signatures
+            .iter()
+            .filter(|signature| signature.is_absent())
+            .count();
+
+        if absent <= max_absent_signatures {
+            Ok(())
+        } else {
+            Err(VerificationError::too_many_absent_signatures(
+                absent,
+                max_absent_signatures,
+            ))
+        }
+    }
+
     /// Check that there is enough validators overlap between the trusted validator set
     /// and the untrusted signed header.
     fn has_sufficient_validators_overlap(
@@ -209,6 +454,30 @@ pub trait VerificationPredicates: Send + Sync {
         Ok(())
     }
 
+    /// Check that enough validators of the given, untrusted validator set correctly signed
+    /// the given vote extensions for the commit of the untrusted signed header, if the
+    /// chain requires vote extensions at its height.
+    fn has_sufficient_vote_extensions(
+        &self,
+        untrusted_sh: &SignedHeader,
+        untrusted_validators: &ValidatorSet,
+        vote_extensions: &[VoteExtension],
+        options: &VoteExtensionsOptions,
+        calculator: &dyn VotingPowerCalculator,
+    ) -> Result<(), VerificationError> {
+        if untrusted_sh.header.height < options.enable_height {
+            return Ok(());
+        }
+
+        calculator.check_vote_extensions(
+            untrusted_sh,
+            untrusted_validators,
+            vote_extensions,
+            options.trust_threshold,
+        )?;
+        Ok(())
+    }
+
     /// Check that the hash of the next validator set in the trusted block matches
     /// the hash of the validator set in the untrusted one.
     fn valid_next_validator_set(
@@ -225,13 +494,38 @@ pub trait VerificationPredicates: Send + Sync {
             ))
         }
     }
+
+    /// Perform additional checks of the untrusted light block, on top of the predicates
+    /// above, such as application specific requirements on its header.
+    ///
+    /// There are no additional checks by default. See [`VerificationPredicates::with_check`]
+    /// to add some to existing predicates.
+    fn additional_checks(
+        &self,
+        _untrusted: &UntrustedBlockState<'_>,
+    ) -> Result<(), VerificationError> {
+        Ok(())
+    }
+
+    /// Wrap these predicates with the given check of the untrusted light blocks, which is
+    /// performed after their own additional checks, if any.
+    ///
+    /// The check can reject a light block with [`VerificationError::header_check_failed`].
+    /// Calls can be chained to perform several checks, in order.
+    fn with_check<F>(self, check: F) -> WithCheck<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&UntrustedBlockState<'_>) -> Result<(), VerificationError> + Send + Sync,
+    {
+        WithCheck::new(self, check)
+    }
 }
 
 #[cfg(all(test, feature = "rust-crypto"))]
 mod tests {
     use core::{convert::TryInto, time::Duration};
 
-    use tendermint::{block::CommitSig, validator::Set};
+    use tendermint::{account, block::CommitSig, hash::AppHash, validator::Set};
     use tendermint_testgen::{
         light_block::{LightBlock as TestgenLightBlock, TmLightBlock},
         Commit, Generator, Header, Validator, ValidatorSet,
@@ -243,7 +537,7 @@ mod tests {
         operations::{ProdCommitValidator, ProdVotingPowerCalculator, VotingPowerTally},
         predicates::{ProdPredicates, VerificationPredicates},
         prelude::*,
-        types::{LightBlock, TrustThreshold},
+        types::{LightBlock, TrustThreshold, UntrustedBlockState},
     };
 
     impl From<TmLightBlock> for LightBlock {
@@ -719,4 +1013,76 @@ mod tests {
             _ => panic!("expected InsufficientSignersOverlap error"),
         }
     }
+
+    #[test]
+    #[allow(clippy::result_large_err)]
+    fn test_with_check() {
+        let mut light_block: LightBlock =
+            TestgenLightBlock::new_default(1).generate().unwrap().into();
+
+        let proposer = light_block.signed_header.header.proposer_address;
+        let allowed = [proposer];
+
+        let vp = ProdPredicates
+            .with_check(|untrusted: &UntrustedBlockState<'_>| {
+                if untrusted
+                    .signed_header
+                    .header
+                    .app_hash
+                    .as_bytes()
+                    .is_empty()
+                {
+                    Err(VerificationError::header_check_failed(
+                        "empty app hash".into(),
+                    ))
+                } else {
+                    Ok(())
+                }
+            })
+            .with_check(|untrusted: &UntrustedBlockState<'_>| {
+                let proposer = untrusted.signed_header.header.proposer_address;
+                if allowed.contains(&proposer) {
+                    Ok(())
+                } else {
+                    Err(VerificationError::header_check_failed(format!(
+                        "proposer {proposer} is not allowed"
+                    )))
+                }
+            });
+
+        // 1. ensure the first check rejects the light block
+        let result_err = vp.additional_checks(&light_block.as_untrusted_state());
+
+        match result_err {
+            Err(VerificationError(VerificationErrorDetail::HeaderCheckFailed(e), _)) => {
+                assert_eq!(e.reason, "empty app hash");
+            },
+            _ => panic!("expected HeaderCheckFailed error"),
+        }
+
+        // 2. ensure the light block passes both checks
+        light_block.signed_header.header.app_hash = AppHash::from_hex_upper("010203").unwrap();
+        let result_ok = vp.additional_checks(&light_block.as_untrusted_state());
+
+        assert!(result_ok.is_ok());
+
+        // 3. ensure the second check rejects a proposer which is not allowed
+        light_block.signed_header.header.proposer_address = account::Id::new([0xFF; 20]);
+        let result_err = vp.additional_checks(&light_block.as_untrusted_state());
+
+        match result_err {
+            Err(VerificationError(VerificationErrorDetail::HeaderCheckFailed(e), _)) => {
+                assert!(e.reason.ends_with("is not allowed"));
+            },
+            _ => panic!("expected HeaderCheckFailed error"),
+        }
+
+        // 4. ensure the other predicates are delegated to the wrapped predicates
+        let result_ok = vp.valid_next_validator_set(
+            light_block.signed_header.header.validators_hash,
+            light_block.signed_header.header.validators_hash,
+        );
+
+        assert!(result_ok.is_ok());
+    }
 }
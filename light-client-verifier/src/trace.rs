@@ -0,0 +1,386 @@
+//! One-shot verification of a trace of light blocks from a trusted light block, without any
+//! light store, I/O or clock, for stateless setups which are given all their inputs up front.
+
+use alloc::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    errors::VerificationError,
+    options::{Options, VerificationMode},
+    prelude::*,
+    types::{Height, LightBlock, Time},
+    Verdict, Verifier,
+};
+
+/// The inputs of [`verify_trace`], eg. to deserialize them in one go.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceVerification {
+    /// The trusted light block to verify the trace from
+    pub trusted: LightBlock,
+    /// The light blocks of the trace, in any order
+    pub trace: Vec<LightBlock>,
+    /// The verification options
+    pub options: Options,
+    /// The time at which to verify the trace
+    pub now: Time,
+}
+
+impl TraceVerification {
+    /// Verify the trace with the given verifier, see [`verify_trace_with`].
+    pub fn verify_with(&self, verifier: &dyn Verifier) -> TraceVerdict {
+        verify_trace_with(
+            verifier,
+            &self.trusted,
+            &self.trace,
+            &self.options,
+            self.now,
+        )
+    }
+
+    /// Verify the trace with the [`ProdVerifier`](crate::ProdVerifier), see [`verify_trace`].
+    #[cfg(feature = "rust-crypto")]
+    pub fn verify(&self) -> TraceVerdict {
+        self.verify_with(&crate::ProdVerifier::default())
+    }
+}
+
+/// The outcome of the verification of a trace of light blocks.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceVerdict {
+    /// Height of the highest light block of the trace, which was to be verified, or of the
+    /// trusted light block if the trace does not hold any higher light block
+    pub target_height: Height,
+    /// Heights of the light blocks of the trace which were verified, in ascending order,
+    /// ending with the target height if the trace was verified
+    pub verified_heights: Vec<Height>,
+    /// Height of the light block whose verification failed, if any
+    pub failed_height: Option<Height>,
+    /// The verdict of the last verification performed: `Success` if the trace was verified,
+    /// otherwise the reason why the light block at the failed height could not be verified
+    pub verdict: Verdict,
+}
+
+impl TraceVerdict {
+    /// Whether the light block at the target height was verified.
+    pub fn is_success(&self) -> bool {
+        self.verdict == Verdict::Success
+    }
+
+    /// Height of the highest light block which was verified, if any.
+    pub fn highest_verified_height(&self) -> Option<Height> {
+        self.verified_heights.last().copied()
+    }
+}
+
+/// Verify the highest light block of the given trace from the given trusted light block with
+/// the [`ProdVerifier`](crate::ProdVerifier), see [`verify_trace_with`].
+#[cfg(feature = "rust-crypto")]
+pub fn verify_trace(
+    trusted: &LightBlock,
+    trace: &[LightBlock],
+    options: &Options,
+    now: Time,
+) -> TraceVerdict {
+    verify_trace_with(
+        &crate::ProdVerifier::default(),
+        trusted,
+        trace,
+        options,
+        now,
+    )
+}
+
+/// Verify the highest light block of the given trace from the given trusted light block with
+/// the given verifier, taking `now` as the current time.
+///
+/// The verification skips straight to the highest light block of the trace, and when it
+/// cannot be trusted yet, falls back to the highest light block of the trace below it, until
+/// one can be verified, from which it skips again, like the light client does with a
+/// [`trace_scheduler`]. Only the light blocks of the trace are ever verified, those at or below
+/// the height of the trusted light block are ignored, and so are those at the same height as
+/// another light block of the trace which comes later in it.
+///
+/// With the [`Sequential`](VerificationMode::Sequential) verification mode, every light block of
+/// the trace is instead verified from the one right below it, starting from the trusted light
+/// block, so that the trace must hold all the heights up to its highest light block: the first
+/// missing height fails the verification of the light block above it.
+///
+/// [`trace_scheduler`]: https://docs.rs/tendermint-light-client/latest/tendermint_light_client/components/scheduler/fn.trace_scheduler.html
+pub fn verify_trace_with(
+    verifier: &dyn Verifier,
+    trusted: &LightBlock,
+    trace: &[LightBlock],
+    options: &Options,
+    now: Time,
+) -> TraceVerdict {
+    let light_blocks = trace
+        .iter()
+        .filter(|lb| lb.height() > trusted.height())
+        .map(|lb| (lb.height(), lb))
+        .collect::<BTreeMap<_, _>>()
+        .into_values()
+        .collect::<Vec<_>>();
+
+    let mut verdict = TraceVerdict {
+        target_height: light_blocks
+            .last()
+            .map_or(trusted.height(), |lb| lb.height()),
+        verified_heights: Vec::new(),
+        failed_height: None,
+        verdict: Verdict::Success,
+    };
+
+    if light_blocks.is_empty() {
+        return verdict;
+    }
+
+    match options.verification_mode {
+        VerificationMode::Skipping => {
+            verify_skipping(verifier, trusted, &light_blocks, options, now, &mut verdict)
+        },
+        VerificationMode::Sequential => {
+            verify_sequential(verifier, trusted, &light_blocks, options, now, &mut verdict)
+        },
+    }
+
+    verdict
+}
+
+fn verify_skipping(
+    verifier: &dyn Verifier,
+    trusted: &LightBlock,
+    light_blocks: &[&LightBlock],
+    options: &Options,
+    now: Time,
+    verdict: &mut TraceVerdict,
+) {
+    let target = light_blocks.len() - 1;
+
+    // Index of the highest verified light block, from which the next ones are verified
+    let mut verified: Option<usize> = None;
+    let mut current = target;
+
+    loop {
+        let trusted = verified.map_or(trusted, |index| light_blocks[index]);
+        let untrusted = light_blocks[current];
+
+        match verifier.verify_update_header(
+            untrusted.as_untrusted_state(),
+            trusted.as_trusted_state(),
+            options,
+            now,
+        ) {
+            Verdict::Success if current == target => {
+                verdict.verified_heights.push(untrusted.height());
+                return;
+            },
+            Verdict::Success => {
+                verdict.verified_heights.push(untrusted.height());
+                verified = Some(current);
+                current = target;
+            },
+            Verdict::NotEnoughTrust(_) if current > verified.map_or(0, |index| index + 1) => {
+                current -= 1;
+            },
+            failure => {
+                verdict.failed_height = Some(untrusted.height());
+                verdict.verdict = failure;
+                return;
+            },
+        }
+    }
+}
+
+fn verify_sequential(
+    verifier: &dyn Verifier,
+    trusted: &LightBlock,
+    light_blocks: &[&LightBlock],
+    options: &Options,
+    now: Time,
+    verdict: &mut TraceVerdict,
+) {
+    let mut trusted = trusted;
+
+    for &untrusted in light_blocks {
+        let expected = trusted.height().increment();
+
+        let result = if untrusted.height() == expected {
+            verifier.verify_update_header(
+                untrusted.as_untrusted_state(),
+                trusted.as_trusted_state(),
+                options,
+                now,
+            )
+        } else {
+            let VerificationError(e, _) =
+                VerificationError::non_sequential_height(untrusted.height(), expected);
+            Verdict::Invalid(e)
+        };
+
+        if result != Verdict::Success {
+            verdict.failed_height = Some(untrusted.height());
+            verdict.verdict = result;
+            return;
+        }
+
+        verdict.verified_heights.push(untrusted.height());
+        trusted = untrusted;
+    }
+}
+
+#[cfg(all(test, feature = "rust-crypto"))]
+mod tests {
+    use alloc::vec;
+    use core::{ops::Sub, time::Duration};
+
+    use tendermint::Time;
+    use tendermint_testgen::{
+        light_block::LightBlock as TestgenLightBlock, Generator, Header, Validator,
+    };
+
+    use super::{verify_trace, TraceVerdict, TraceVerification};
+    use crate::{
+        errors::VerificationErrorDetail,
+        options::{Options, VerificationMode},
+        types::{Height, LightBlock},
+        Verdict,
+    };
+
+    fn light_block(
+        now: Time,
+        height: u64,
+        validators: &[Validator],
+        next_validators: &[Validator],
+    ) -> LightBlock {
+        let header = Header::new(validators)
+            .height(height)
+            .chain_id("chain-1")
+            .next_validators(next_validators)
+            .time(now.sub(Duration::from_secs(20 - height)).unwrap());
+
+        TestgenLightBlock::new_default_with_header(header)
+            .next_validators(next_validators)
+            .generate()
+            .unwrap()
+            .into()
+    }
+
+    fn options() -> Options {
+        Options {
+            trust_threshold: Default::default(),
+            trusting_period: Duration::from_secs(60),
+            clock_drift: Default::default(),
+            verification_mode: Default::default(),
+            commit_signatures: Default::default(),
+        }
+    }
+
+    fn heights(heights: &[u64]) -> alloc::vec::Vec<Height> {
+        heights
+            .iter()
+            .map(|&h| Height::try_from(h).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_verify_trace() {
+        let now = Time::now();
+
+        let old = ["1", "2"].map(|id| Validator::new(id).voting_power(50));
+        let new = ["3", "4"].map(|id| Validator::new(id).voting_power(50));
+
+        let trusted = light_block(now, 1, &old, &old);
+        let trace = vec![
+            light_block(now, 4, &new, &new),
+            light_block(now, 2, &old, &new),
+            light_block(now, 3, &new, &new),
+        ];
+
+        // 1. the validator set changes entirely after height 2, so that the light blocks
+        //    above it can only be verified from it
+        let verdict = verify_trace(&trusted, &trace, &options(), now);
+        assert_eq!(
+            verdict,
+            TraceVerdict {
+                target_height: Height::try_from(4_u64).unwrap(),
+                verified_heights: heights(&[2, 4]),
+                failed_height: None,
+                verdict: Verdict::Success,
+            }
+        );
+
+        // 2. without the light block at height 2, no light block can be trusted
+        let verdict = verify_trace(&trusted, &[trace[0].clone()], &options(), now);
+        assert!(!verdict.is_success());
+        assert_eq!(verdict.highest_verified_height(), None);
+        assert_eq!(
+            verdict.failed_height,
+            Some(Height::try_from(4_u64).unwrap())
+        );
+        assert!(matches!(verdict.verdict, Verdict::NotEnoughTrust(_)));
+
+        // 3. an invalid light block fails the verification, without falling back to lower ones
+        let mut forged = trace.clone();
+        forged[2].signed_header.header.chain_id = "forged-chain".parse().unwrap();
+        let verdict = verify_trace(&trusted, &forged, &options(), now);
+        assert_eq!(verdict.verified_heights, heights(&[]));
+        assert_eq!(
+            verdict.failed_height,
+            Some(Height::try_from(3_u64).unwrap())
+        );
+        assert!(matches!(verdict.verdict, Verdict::Invalid(_)));
+
+        // 4. a trace without any light block above the trusted one is trivially verified
+        let input = TraceVerification {
+            trusted: trace[0].clone(),
+            trace: trace.clone(),
+            options: options(),
+            now,
+        };
+        let verdict = input.verify();
+        assert!(verdict.is_success());
+        assert_eq!(verdict.target_height, Height::try_from(4_u64).unwrap());
+        assert!(verdict.verified_heights.is_empty());
+    }
+
+    #[test]
+    fn test_verify_trace_sequentially() {
+        let now = Time::now();
+
+        let old = ["1", "2"].map(|id| Validator::new(id).voting_power(50));
+        let new = ["3", "4"].map(|id| Validator::new(id).voting_power(50));
+
+        let trusted = light_block(now, 1, &old, &old);
+        let trace = vec![
+            light_block(now, 4, &new, &new),
+            light_block(now, 2, &old, &new),
+            light_block(now, 3, &new, &new),
+        ];
+        let options = Options {
+            verification_mode: VerificationMode::Sequential,
+            ..options()
+        };
+
+        // 1. every light block is verified from the one right below it
+        let verdict = verify_trace(&trusted, &trace, &options, now);
+        assert!(verdict.is_success());
+        assert_eq!(verdict.verified_heights, heights(&[2, 3, 4]));
+
+        // 2. a missing height fails the verification of the light block above it, even though
+        //    it could be verified by skipping
+        let verdict = verify_trace(&trusted, &trace[..2], &options, now);
+        assert_eq!(verdict.verified_heights, heights(&[2]));
+        assert_eq!(
+            verdict.failed_height,
+            Some(Height::try_from(4_u64).unwrap())
+        );
+        match verdict.verdict {
+            Verdict::Invalid(VerificationErrorDetail::NonSequentialHeight(e)) => {
+                assert_eq!(e.got, Height::try_from(4_u64).unwrap());
+                assert_eq!(e.expected, Height::try_from(3_u64).unwrap());
+            },
+            verdict => panic!("unexpected verdict: {verdict:?}"),
+        }
+    }
+}
@@ -11,6 +11,7 @@ use tendermint::{
     chain::Id as ChainId,
     trust_threshold::TrustThresholdFraction,
     validator::{Info as TMValidatorInfo, Set as TMValidatorSet},
+    Signature,
 };
 pub use tendermint::{block::Height, hash::Hash, time::Time};
 
@@ -78,6 +79,7 @@ impl Status {
 }
 
 /// Trusted block parameters needed for light client verification.
+#[derive(Copy, Clone, Debug)]
 pub struct TrustedBlockState<'a> {
     pub chain_id: &'a ChainId,
     pub header_time: Time,
@@ -87,6 +89,7 @@ pub struct TrustedBlockState<'a> {
 }
 
 /// Untrusted block parameters needed for light client verification.
+#[derive(Copy, Clone, Debug)]
 pub struct UntrustedBlockState<'a> {
     pub signed_header: &'a SignedHeader,
     pub validators: &'a ValidatorSet,
@@ -207,6 +210,22 @@ impl LatestStatus {
     }
 }
 
+/// The vote extension of a validator for a block, along with its signature,
+/// as found in the extended commit of chains running CometBFT 0.38 or later.
+///
+/// Extended commits are not part of light blocks, so applications relying on vote
+/// extensions have to obtain them separately, eg. from the block proposed at the
+/// next height.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VoteExtension {
+    /// Address of the validator which extended its precommit
+    pub validator_address: ValidatorAddress,
+    /// Vote extension provided by the application
+    pub extension: Vec<u8>,
+    /// Signature of the validator over the vote extension
+    pub extension_signature: Option<Signature>,
+}
+
 #[cfg(test)]
 mod tests {
 
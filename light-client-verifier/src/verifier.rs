@@ -1,24 +1,28 @@
 //! Provides an interface and default implementation of the `Verifier` component
 
 use serde::{Deserialize, Serialize};
+use tendermint::block::CommitSig;
 
 use crate::{
+    audit::{Predicate, VerificationAudit},
+    crypto::CryptoProvider,
     errors::{ErrorExt, VerificationError, VerificationErrorDetail},
-    operations::{voting_power::VotingPowerTally, CommitValidator, VotingPowerCalculator},
-    options::Options,
-    predicates::VerificationPredicates,
-    types::{Time, TrustedBlockState, UntrustedBlockState},
+    operations::{
+        voting_power::VotingPowerTally, CommitValidator, ProdCommitValidator,
+        ProvidedVotingPowerCalculator, VotingPowerCalculator,
+    },
+    options::{CommitSignaturesOptions, MalformedVotes, Options, VoteExtensionsOptions},
+    predicates::{ProvidedPredicates, VerificationPredicates},
+    prelude::*,
+    types::{SignedHeader, Time, TrustedBlockState, UntrustedBlockState, VoteExtension},
 };
 
 #[cfg(feature = "rust-crypto")]
-use crate::{
-    operations::{ProdCommitValidator, ProdVotingPowerCalculator},
-    predicates::ProdPredicates,
-};
+use crate::{operations::ProdVotingPowerCalculator, predicates::ProdPredicates};
 
 /// Represents the result of the verification performed by the
 /// verifier component.
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub enum Verdict {
     /// Verification succeeded, the block is valid.
     Success,
@@ -71,6 +75,34 @@ pub trait Verifier: Send + Sync {
         options: &Options,
         now: Time,
     ) -> Verdict;
+
+    /// Verify a header received in a `MsgUpdateClient` like
+    /// [`Verifier::verify_update_header`] does, and return a record of the checks
+    /// performed along with the verdict.
+    ///
+    /// The default implementation does not record anything.
+    fn verify_update_header_audited(
+        &self,
+        untrusted: UntrustedBlockState<'_>,
+        trusted: TrustedBlockState<'_>,
+        options: &Options,
+        now: Time,
+    ) -> (Verdict, VerificationAudit) {
+        let verdict = self.verify_update_header(untrusted, trusted, options, now);
+        (verdict, VerificationAudit::default())
+    }
+
+    /// Verify the vote extensions of the commit of a header, for chains running
+    /// CometBFT 0.38 or later.
+    ///
+    /// This does not verify the header itself, which must already have been verified,
+    /// eg. with [`Verifier::verify_update_header`].
+    fn verify_vote_extensions(
+        &self,
+        untrusted: UntrustedBlockState<'_>,
+        vote_extensions: &[VoteExtension],
+        options: &VoteExtensionsOptions,
+    ) -> Verdict;
 }
 
 macro_rules! verdict {
@@ -82,6 +114,18 @@ macro_rules! verdict {
     }};
 }
 
+macro_rules! audited_verdict {
+    ($audit:expr, $predicate:expr, $e:expr) => {{
+        let result = $e;
+        if let Some(audit) = $audit.as_deref_mut() {
+            audit.record($predicate, &result);
+        }
+        if result.is_err() {
+            return result.into();
+        }
+    }};
+}
+
 macro_rules! ensure_verdict_success {
     ($e:expr) => {{
         let verdict = $e;
@@ -100,6 +144,9 @@ pub struct PredicateVerifier<P, C, V> {
     commit_validator: V,
 }
 
+/// Where to record the evaluation of the predicates, if anywhere.
+type Audit<'a> = Option<&'a mut VerificationAudit>;
+
 impl<P, C, V> PredicateVerifier<P, C, V>
 where
     P: VerificationPredicates,
@@ -117,43 +164,119 @@ where
 
     /// Validates an `UntrustedBlockState`.
     pub fn verify_validator_sets(&self, untrusted: &UntrustedBlockState<'_>) -> Verdict {
+        self.audited_verify_validator_sets(untrusted, false, &mut None)
+    }
+
+    /// Validates an `UntrustedBlockState`, without hashing its validator set again if it is
+    /// known to be the next validator set of the trusted block.
+    fn audited_verify_validator_sets(
+        &self,
+        untrusted: &UntrustedBlockState<'_>,
+        unchanged_validators: bool,
+        audit: &mut Audit<'_>,
+    ) -> Verdict {
         // Ensure the header validator hashes match the given validators
-        verdict!(self.predicates.validator_sets_match(
-            untrusted.validators,
-            untrusted.signed_header.header.validators_hash,
-        ));
+        if !unchanged_validators {
+            audited_verdict!(
+                audit,
+                Predicate::ValidatorSetsMatch,
+                self.predicates.validator_sets_match(
+                    untrusted.validators,
+                    untrusted.signed_header.header.validators_hash,
+                )
+            );
+        }
 
         // Ensure the header next validator hashes match the given next validators
         if let Some(untrusted_next_validators) = untrusted.next_validators {
-            verdict!(self.predicates.next_validators_match(
-                untrusted_next_validators,
-                untrusted.signed_header.header.next_validators_hash,
-            ));
+            audited_verdict!(
+                audit,
+                Predicate::NextValidatorsMatch,
+                self.predicates.next_validators_match(
+                    untrusted_next_validators,
+                    untrusted.signed_header.header.next_validators_hash,
+                )
+            );
         }
 
         // Ensure the header matches the commit
-        verdict!(self.predicates.header_matches_commit(
-            &untrusted.signed_header.header,
-            untrusted.signed_header.commit.block_id.hash,
-        ));
+        audited_verdict!(
+            audit,
+            Predicate::HeaderMatchesCommit,
+            self.predicates.header_matches_commit(
+                &untrusted.signed_header.header,
+                untrusted.signed_header.commit.block_id.hash,
+            )
+        );
 
         // Additional implementation specific validation
-        verdict!(self.predicates.valid_commit(
-            untrusted.signed_header,
-            untrusted.validators,
-            &self.commit_validator,
-        ));
+        audited_verdict!(
+            audit,
+            Predicate::ValidCommit,
+            self.predicates.valid_commit(
+                untrusted.signed_header,
+                untrusted.validators,
+                &self.commit_validator,
+            )
+        );
+
+        // Checks supplied on top of the predicates, if any
+        audited_verdict!(
+            audit,
+            Predicate::AdditionalChecks,
+            self.predicates.additional_checks(untrusted)
+        );
 
         Verdict::Success
     }
 
     /// Verify that more than 2/3 of the validators correctly committed the block.
     pub fn verify_commit(&self, untrusted: &UntrustedBlockState<'_>) -> Verdict {
-        verdict!(self.predicates.has_sufficient_signers_overlap(
-            untrusted.signed_header,
-            untrusted.validators,
-            &self.voting_power_calculator,
-        ));
+        self.audited_verify_commit(untrusted, &mut None)
+    }
+
+    fn audited_verify_commit(
+        &self,
+        untrusted: &UntrustedBlockState<'_>,
+        audit: &mut Audit<'_>,
+    ) -> Verdict {
+        audited_verdict!(
+            audit,
+            Predicate::HasSufficientSignersOverlap,
+            self.predicates.has_sufficient_signers_overlap(
+                untrusted.signed_header,
+                untrusted.validators,
+                &self.voting_power_calculator,
+            )
+        );
+
+        Verdict::Success
+    }
+
+    /// Check that the commit does not have more absent signatures than the given options
+    /// tolerate.
+    pub fn check_commit_signatures(
+        &self,
+        untrusted: &UntrustedBlockState<'_>,
+        options: &Options,
+    ) -> Verdict {
+        self.audited_check_commit_signatures(untrusted, options, &mut None)
+    }
+
+    fn audited_check_commit_signatures(
+        &self,
+        untrusted: &UntrustedBlockState<'_>,
+        options: &Options,
+        audit: &mut Audit<'_>,
+    ) -> Verdict {
+        audited_verdict!(
+            audit,
+            Predicate::IsWithinAbsentSignaturesCap,
+            self.predicates.is_within_absent_signatures_cap(
+                untrusted.signed_header,
+                options.commit_signatures.max_absent_signatures,
+            )
+        );
 
         Verdict::Success
     }
@@ -166,39 +289,67 @@ where
         trusted: &TrustedBlockState<'_>,
         options: &Options,
         now: Time,
+    ) -> Verdict {
+        self.audited_validate_against_trusted(untrusted, trusted, options, now, &mut None)
+    }
+
+    fn audited_validate_against_trusted(
+        &self,
+        untrusted: &UntrustedBlockState<'_>,
+        trusted: &TrustedBlockState<'_>,
+        options: &Options,
+        now: Time,
+        audit: &mut Audit<'_>,
     ) -> Verdict {
         // Ensure the latest trusted header hasn't expired
-        verdict!(self.predicates.is_within_trust_period(
-            trusted.header_time,
-            options.trusting_period,
-            now,
-        ));
+        audited_verdict!(
+            audit,
+            Predicate::IsWithinTrustPeriod,
+            self.predicates.is_within_trust_period(
+                trusted.header_time,
+                options.trusting_period,
+                now,
+            )
+        );
 
         // Check that the untrusted block is more recent than the trusted state
-        verdict!(self
-            .predicates
-            .is_monotonic_bft_time(untrusted.signed_header.header.time, trusted.header_time));
+        audited_verdict!(
+            audit,
+            Predicate::IsMonotonicBftTime,
+            self.predicates
+                .is_monotonic_bft_time(untrusted.signed_header.header.time, trusted.header_time)
+        );
 
         // Check that the chain-id of the untrusted block matches that of the trusted state
-        verdict!(self
-            .predicates
-            .is_matching_chain_id(&untrusted.signed_header.header.chain_id, trusted.chain_id));
+        audited_verdict!(
+            audit,
+            Predicate::IsMatchingChainId,
+            self.predicates
+                .is_matching_chain_id(&untrusted.signed_header.header.chain_id, trusted.chain_id)
+        );
 
         let trusted_next_height = trusted.height.increment();
 
         if untrusted.height() == trusted_next_height {
             // If the untrusted block is the very next block after the trusted block,
             // check that their (next) validator sets hashes match.
-            verdict!(self.predicates.valid_next_validator_set(
-                untrusted.signed_header.header.validators_hash,
-                trusted.next_validators_hash,
-            ));
+            audited_verdict!(
+                audit,
+                Predicate::ValidNextValidatorSet,
+                self.predicates.valid_next_validator_set(
+                    untrusted.signed_header.header.validators_hash,
+                    trusted.next_validators_hash,
+                )
+            );
         } else {
             // Otherwise, ensure that the untrusted block has a greater height than
             // the trusted block.
-            verdict!(self
-                .predicates
-                .is_monotonic_height(untrusted.signed_header.header.height, trusted.height));
+            audited_verdict!(
+                audit,
+                Predicate::IsMonotonicHeight,
+                self.predicates
+                    .is_monotonic_height(untrusted.signed_header.header.height, trusted.height)
+            );
         }
 
         Verdict::Success
@@ -211,11 +362,25 @@ where
         options: &Options,
         now: Time,
     ) -> Verdict {
-        verdict!(self.predicates.is_header_from_past(
-            untrusted.signed_header.header.time,
-            options.clock_drift,
-            now,
-        ));
+        self.audited_check_header_is_from_past(untrusted, options, now, &mut None)
+    }
+
+    fn audited_check_header_is_from_past(
+        &self,
+        untrusted: &UntrustedBlockState<'_>,
+        options: &Options,
+        now: Time,
+        audit: &mut Audit<'_>,
+    ) -> Verdict {
+        audited_verdict!(
+            audit,
+            Predicate::IsHeaderFromPast,
+            self.predicates.is_header_from_past(
+                untrusted.signed_header.header.time,
+                options.clock_drift,
+                now,
+            )
+        );
 
         Verdict::Success
     }
@@ -227,22 +392,79 @@ where
         untrusted: &UntrustedBlockState<'_>,
         trusted: &TrustedBlockState<'_>,
         options: &Options,
+    ) -> Verdict {
+        self.audited_verify_commit_against_trusted(untrusted, trusted, options, false, &mut None)
+    }
+
+    /// Check there is enough overlap between the validator sets of the trusted and untrusted
+    /// blocks, unless the validator set is unchanged and the trust threshold is at most the
+    /// two-thirds required from the signers of the commit by [`Self::verify_commit`].
+    fn audited_verify_commit_against_trusted(
+        &self,
+        untrusted: &UntrustedBlockState<'_>,
+        trusted: &TrustedBlockState<'_>,
+        options: &Options,
+        unchanged_validators: bool,
+        audit: &mut Audit<'_>,
     ) -> Verdict {
         let trusted_next_height = trusted.height.increment();
 
-        if untrusted.height() != trusted_next_height {
+        let implied_by_signers_overlap = unchanged_validators
+            && u128::from(options.trust_threshold.numerator()) * 3
+                <= u128::from(options.trust_threshold.denominator()) * 2;
+
+        if untrusted.height() != trusted_next_height && !implied_by_signers_overlap {
             // Check there is enough overlap between the validator sets of
             // the trusted and untrusted blocks.
-            verdict!(self.predicates.has_sufficient_validators_overlap(
-                untrusted.signed_header,
-                trusted.next_validators,
-                &options.trust_threshold,
-                &self.voting_power_calculator,
-            ));
+            audited_verdict!(
+                audit,
+                Predicate::HasSufficientValidatorsOverlap,
+                self.predicates.has_sufficient_validators_overlap(
+                    untrusted.signed_header,
+                    trusted.next_validators,
+                    &options.trust_threshold,
+                    &self.voting_power_calculator,
+                )
+            );
         }
 
         Verdict::Success
     }
+
+    fn audited_verify_update_header(
+        &self,
+        untrusted: UntrustedBlockState<'_>,
+        trusted: TrustedBlockState<'_>,
+        options: &Options,
+        now: Time,
+        audit: &mut Audit<'_>,
+    ) -> Verdict {
+        ensure_verdict_success!(self.audited_check_commit_signatures(&untrusted, options, audit));
+
+        let skipped = skip_malformed_nil_votes(&untrusted, &options.commit_signatures);
+        let untrusted = skipped
+            .as_ref()
+            .map_or(untrusted, |signed_header| UntrustedBlockState {
+                signed_header,
+                ..untrusted
+            });
+
+        let unchanged = has_unchanged_validators(&untrusted, &trusted);
+
+        ensure_verdict_success!(self.audited_verify_validator_sets(&untrusted, unchanged, audit));
+        ensure_verdict_success!(
+            self.audited_validate_against_trusted(&untrusted, &trusted, options, now, audit)
+        );
+        ensure_verdict_success!(
+            self.audited_check_header_is_from_past(&untrusted, options, now, audit)
+        );
+        ensure_verdict_success!(self.audited_verify_commit_against_trusted(
+            &untrusted, &trusted, options, unchanged, audit
+        ));
+        ensure_verdict_success!(self.audited_verify_commit(&untrusted, audit));
+
+        Verdict::Success
+    }
 }
 
 impl<P, C, V> Verifier for PredicateVerifier<P, C, V>
@@ -253,11 +475,15 @@ where
 {
     /// Validate the given light block state by performing the following checks ->
     ///
+    /// - Ensure the commit does not have more absent signatures than tolerated by the options,
+    ///   and skip its malformed votes for nil if the options say so
     /// - Validate the untrusted header
-    ///     - Ensure the header validator hashes match the given validators
+    ///     - Ensure the header validator hashes match the given validators, unless they are
+    ///       the next validators of the trusted header
     ///     - Ensure the header next validator hashes match the given next validators
     ///     - Ensure the header matches the commit
     ///     - Ensure commit is valid
+    ///     - Ensure the header passes the additional checks of the predicates, if any
     /// - Validate the untrusted header against the trusted header
     ///     - Ensure the latest trusted header hasn't expired
     ///     - Ensure the header isn't from a future time
@@ -267,7 +493,7 @@ where
     ///     - Otherwise, ensure that the untrusted block has a greater height than the trusted
     ///       block.
     /// - Check there is enough overlap between the validator sets of the trusted and untrusted
-    ///   blocks.
+    ///   blocks, unless they are the same and the trust threshold is at most 2/3.
     /// - Verify that more than 2/3 of the validators correctly committed the block.
     ///
     /// **NOTE**: If the untrusted state's `next_validators` field is `None`,
@@ -285,13 +511,36 @@ where
         options: &Options,
         now: Time,
     ) -> Verdict {
-        ensure_verdict_success!(self.verify_validator_sets(&untrusted));
-        ensure_verdict_success!(self.validate_against_trusted(&untrusted, &trusted, options, now));
-        ensure_verdict_success!(self.check_header_is_from_past(&untrusted, options, now));
-        ensure_verdict_success!(self.verify_commit_against_trusted(&untrusted, &trusted, options));
-        ensure_verdict_success!(self.verify_commit(&untrusted));
+        self.audited_verify_update_header(untrusted, trusted, options, now, &mut None)
+    }
 
-        Verdict::Success
+    /// Verify a header received in a `MsgUpdateClient` like
+    /// [`Verifier::verify_update_header`] does, recording every predicate evaluation
+    /// as well as the check of every signature of the commit against the validator set
+    /// of the header.
+    fn verify_update_header_audited(
+        &self,
+        untrusted: UntrustedBlockState<'_>,
+        trusted: TrustedBlockState<'_>,
+        options: &Options,
+        now: Time,
+    ) -> (Verdict, VerificationAudit) {
+        let mut audit = VerificationAudit {
+            predicates: Vec::new(),
+            signatures: self
+                .voting_power_calculator
+                .check_signatures(untrusted.signed_header, untrusted.validators),
+        };
+
+        let verdict = self.audited_verify_update_header(
+            untrusted,
+            trusted,
+            options,
+            now,
+            &mut Some(&mut audit),
+        );
+
+        (verdict, audit)
     }
 
     /// Verify a header received in `MsgSubmitMisbehaviour`.
@@ -304,14 +553,115 @@ where
         options: &Options,
         now: Time,
     ) -> Verdict {
+        ensure_verdict_success!(self.check_commit_signatures(&untrusted, options));
+
+        let skipped = skip_malformed_nil_votes(&untrusted, &options.commit_signatures);
+        let untrusted = skipped
+            .as_ref()
+            .map_or(untrusted, |signed_header| UntrustedBlockState {
+                signed_header,
+                ..untrusted
+            });
+
         ensure_verdict_success!(self.verify_validator_sets(&untrusted));
         ensure_verdict_success!(self.validate_against_trusted(&untrusted, &trusted, options, now));
         ensure_verdict_success!(self.verify_commit_against_trusted(&untrusted, &trusted, options));
         ensure_verdict_success!(self.verify_commit(&untrusted));
         Verdict::Success
     }
+
+    /// Verify the vote extensions of the commit of a header, by checking that:
+    ///
+    /// - every vote extension comes from a validator which committed the block,
+    ///   and is correctly signed by that validator
+    /// - the validators which signed their vote extensions have enough voting power
+    ///   with respect to the trust threshold in the options
+    ///
+    /// Commits below the height at which the chain enabled vote extensions are not checked.
+    fn verify_vote_extensions(
+        &self,
+        untrusted: UntrustedBlockState<'_>,
+        vote_extensions: &[VoteExtension],
+        options: &VoteExtensionsOptions,
+    ) -> Verdict {
+        verdict!(self.predicates.has_sufficient_vote_extensions(
+            untrusted.signed_header,
+            untrusted.validators,
+            vote_extensions,
+            options,
+            &self.voting_power_calculator,
+        ));
+
+        Verdict::Success
+    }
+}
+
+/// Whether the validator set of the untrusted block is the next validator set of the trusted
+/// block, as when the validator set of the chain did not change in between, in which case
+/// there is no need to hash it again nor to check that the trusted validators signed the
+/// commit on top of the validators of the untrusted block.
+///
+/// As the hash of a validator set only depends on its validators, the untrusted validator set
+/// then matches the hash in the untrusted header, given that the trusted next validator set
+/// matches the trusted next validators hash.
+fn has_unchanged_validators(
+    untrusted: &UntrustedBlockState<'_>,
+    trusted: &TrustedBlockState<'_>,
+) -> bool {
+    untrusted.signed_header.header.validators_hash == trusted.next_validators_hash
+        && untrusted.validators.validators() == trusted.next_validators.validators()
+}
+
+/// Replace the malformed votes for nil of the commit of the given light block, ie. the ones
+/// which have no signature or come from a validator which is not part of the validator set,
+/// with absent votes if the given options say to skip them.
+///
+/// Returns `None` if there is nothing to replace.
+fn skip_malformed_nil_votes(
+    untrusted: &UntrustedBlockState<'_>,
+    options: &CommitSignaturesOptions,
+) -> Option<SignedHeader> {
+    if options.malformed_nil_votes != MalformedVotes::Skip {
+        return None;
+    }
+
+    let is_malformed = |commit_sig: &CommitSig| match commit_sig {
+        CommitSig::BlockIdFlagNil {
+            validator_address,
+            signature,
+            ..
+        } => signature.is_none() || untrusted.validators.validator(*validator_address).is_none(),
+        _ => false,
+    };
+
+    if !untrusted
+        .signed_header
+        .commit
+        .signatures
+        .iter()
+        .any(is_malformed)
+    {
+        return None;
+    }
+
+    let mut signed_header = untrusted.signed_header.clone();
+    for commit_sig in signed_header.commit.signatures.iter_mut() {
+        if is_malformed(commit_sig) {
+            *commit_sig = CommitSig::BlockIdFlagAbsent;
+        }
+    }
+
+    Some(signed_header)
 }
 
+/// An implementation of the [`PredicateVerifier`] which hashes and verifies signatures with
+/// the given [`CryptoProvider`], and otherwise behaves like the [`ProdVerifier`].
+pub type ProvidedVerifier<C> = PredicateVerifier<
+    ProvidedPredicates<C>,
+    ProvidedVotingPowerCalculator<<C as CryptoProvider>::SignatureVerifier>,
+    ProdCommitValidator,
+>;
+
 #[cfg(feature = "rust-crypto")]
 /// The default production implementation of the [`PredicateVerifier`].
 pub type ProdVerifier =
@@ -319,15 +669,24 @@ pub type ProdVerifier =
 
 #[cfg(test)]
 mod tests {
-    use alloc::{borrow::ToOwned, string::ToString};
-    use core::{ops::Sub, time::Duration};
+    use alloc::{borrow::ToOwned, string::ToString, vec::Vec};
+    use core::{
+        ops::{Add, Sub},
+        time::Duration,
+    };
 
-    use tendermint::Time;
-    use tendermint_testgen::{light_block::LightBlock as TestgenLightBlock, Generator};
+    use tendermint::{block::CommitSig, crypto::signature, PublicKey, Signature, Time};
+    use tendermint_testgen::{
+        light_block::LightBlock as TestgenLightBlock, Generator, Header, Validator,
+    };
 
     use crate::{
-        errors::VerificationErrorDetail, options::Options, types::LightBlock, ProdVerifier,
-        Verdict, Verifier,
+        audit::{Predicate, SignatureOutcome, VerificationAudit},
+        crypto::{CryptoProvider, DefaultCryptoProvider},
+        errors::VerificationErrorDetail,
+        options::{MalformedVotes, Options},
+        types::{LightBlock, TrustThreshold},
+        ProdVerifier, ProvidedVerifier, Verdict, Verifier,
     };
 
     #[cfg(feature = "rust-crypto")]
@@ -367,6 +726,8 @@ mod tests {
             trust_threshold: Default::default(),
             trusting_period: Duration::from_secs(60),
             clock_drift: Default::default(),
+            verification_mode: Default::default(),
+            commit_signatures: Default::default(),
         };
 
         let verdict = vp.verify_update_header(
@@ -386,4 +747,318 @@ mod tests {
             v => panic!("expected ChainIdMismatch error, got: {:?}", v),
         }
     }
+
+    /// A crypto provider whose signature verification rejects every signature.
+    struct RejectingCryptoProvider;
+
+    impl CryptoProvider for RejectingCryptoProvider {
+        type Sha256 = tendermint::crypto::default::Sha256;
+        type SignatureVerifier = Self;
+    }
+
+    impl signature::Verifier for RejectingCryptoProvider {
+        fn verify(_: PublicKey, _: &[u8], _: &Signature) -> Result<(), signature::Error> {
+            Err(signature::Error::VerificationFailed)
+        }
+    }
+
+    #[test]
+    fn test_verification_uses_crypto_provider() {
+        let now = Time::now();
+
+        let light_block_1: LightBlock = TestgenLightBlock::new_default_with_time_and_chain_id(
+            "chain-1".to_owned(),
+            now.sub(Duration::from_secs(20)).unwrap(),
+            1u64,
+        )
+        .generate()
+        .unwrap()
+        .into();
+
+        let light_block_2: LightBlock = TestgenLightBlock::new_default_with_time_and_chain_id(
+            "chain-1".to_owned(),
+            now.sub(Duration::from_secs(10)).unwrap(),
+            2u64,
+        )
+        .generate()
+        .unwrap()
+        .into();
+
+        let opt = Options {
+            trust_threshold: Default::default(),
+            trusting_period: Duration::from_secs(60),
+            clock_drift: Default::default(),
+            verification_mode: Default::default(),
+            commit_signatures: Default::default(),
+        };
+
+        let verdict = ProvidedVerifier::<DefaultCryptoProvider>::default().verify_update_header(
+            light_block_2.as_untrusted_state(),
+            light_block_1.as_trusted_state(),
+            &opt,
+            Time::now(),
+        );
+
+        assert_eq!(verdict, Verdict::Success);
+
+        let verdict = ProvidedVerifier::<RejectingCryptoProvider>::default().verify_update_header(
+            light_block_2.as_untrusted_state(),
+            light_block_1.as_trusted_state(),
+            &opt,
+            Time::now(),
+        );
+
+        match verdict {
+            Verdict::Invalid(VerificationErrorDetail::InvalidSignature(_)) => {},
+            v => panic!("expected InvalidSignature error, got: {:?}", v),
+        }
+    }
+
+    #[test]
+    fn test_verification_audit() {
+        let now = Time::now();
+
+        let light_block_1: LightBlock = TestgenLightBlock::new_default_with_time_and_chain_id(
+            "chain-1".to_owned(),
+            now.sub(Duration::from_secs(20)).unwrap(),
+            1u64,
+        )
+        .generate()
+        .unwrap()
+        .into();
+
+        let light_block_2: LightBlock = TestgenLightBlock::new_default_with_time_and_chain_id(
+            "chain-1".to_owned(),
+            now.sub(Duration::from_secs(10)).unwrap(),
+            2u64,
+        )
+        .generate()
+        .unwrap()
+        .into();
+
+        let opt = Options {
+            trust_threshold: Default::default(),
+            trusting_period: Duration::from_secs(60),
+            clock_drift: Default::default(),
+            verification_mode: Default::default(),
+            commit_signatures: Default::default(),
+        };
+
+        let predicates = |audit: &VerificationAudit| {
+            audit
+                .predicates
+                .iter()
+                .map(|evaluation| (evaluation.predicate, evaluation.outcome.is_ok()))
+                .collect::<Vec<_>>()
+        };
+
+        let (verdict, audit) = ProdVerifier::default().verify_update_header_audited(
+            light_block_2.as_untrusted_state(),
+            light_block_1.as_trusted_state(),
+            &opt,
+            now,
+        );
+
+        assert_eq!(verdict, Verdict::Success);
+        assert_eq!(
+            predicates(&audit),
+            [
+                (Predicate::IsWithinAbsentSignaturesCap, true),
+                // The validator set is the next one of the trusted block, and is not hashed again
+                (Predicate::NextValidatorsMatch, true),
+                (Predicate::HeaderMatchesCommit, true),
+                (Predicate::ValidCommit, true),
+                (Predicate::AdditionalChecks, true),
+                (Predicate::IsWithinTrustPeriod, true),
+                (Predicate::IsMonotonicBftTime, true),
+                (Predicate::IsMatchingChainId, true),
+                (Predicate::ValidNextValidatorSet, true),
+                (Predicate::IsHeaderFromPast, true),
+                (Predicate::HasSufficientSignersOverlap, true),
+            ]
+        );
+        assert_eq!(audit.signatures.len(), 2);
+        assert!(audit
+            .signatures
+            .iter()
+            .all(|check| check.outcome == SignatureOutcome::Valid && check.for_block));
+
+        // The evaluation stops at the first failing predicate
+        let expired = now.add(Duration::from_secs(60)).unwrap();
+        let (verdict, audit) = ProdVerifier::default().verify_update_header_audited(
+            light_block_2.as_untrusted_state(),
+            light_block_1.as_trusted_state(),
+            &opt,
+            expired,
+        );
+
+        assert!(matches!(
+            verdict,
+            Verdict::Invalid(VerificationErrorDetail::NotWithinTrustPeriod(_))
+        ));
+        assert_eq!(
+            predicates(&audit).last(),
+            Some(&(Predicate::IsWithinTrustPeriod, false))
+        );
+
+        // Signatures are checked regardless
+        let (verdict, audit) = ProvidedVerifier::<RejectingCryptoProvider>::default()
+            .verify_update_header_audited(
+                light_block_2.as_untrusted_state(),
+                light_block_1.as_trusted_state(),
+                &opt,
+                now,
+            );
+
+        assert!(matches!(
+            verdict,
+            Verdict::Invalid(VerificationErrorDetail::InvalidSignature(_))
+        ));
+        assert!(audit
+            .signatures
+            .iter()
+            .all(|check| check.outcome == SignatureOutcome::Invalid));
+    }
+
+    #[test]
+    fn test_commit_signatures_tolerance() {
+        let now = Time::now();
+
+        let validators = ["1", "2", "3", "4"].map(|id| Validator::new(id).voting_power(25));
+        let light_block = |height: u64, time: Time| -> LightBlock {
+            let header = Header::new(&validators)
+                .height(height)
+                .chain_id("chain-1")
+                .next_validators(&validators)
+                .time(time);
+
+            TestgenLightBlock::new_default_with_header(header)
+                .generate()
+                .unwrap()
+                .into()
+        };
+
+        let light_block_1 = light_block(1, now.sub(Duration::from_secs(20)).unwrap());
+        let mut light_block_2 = light_block(2, now.sub(Duration::from_secs(10)).unwrap());
+
+        // One of the validators voted for nil without a signature
+        let validator_address = light_block_2.signed_header.commit.signatures[3]
+            .validator_address()
+            .unwrap();
+        light_block_2.signed_header.commit.signatures[3] = CommitSig::BlockIdFlagNil {
+            validator_address,
+            timestamp: now,
+            signature: None,
+        };
+
+        let mut opt = Options {
+            trust_threshold: Default::default(),
+            trusting_period: Duration::from_secs(60),
+            clock_drift: Default::default(),
+            verification_mode: Default::default(),
+            commit_signatures: Default::default(),
+        };
+
+        let verify = |light_block_2: &LightBlock, opt: &Options| {
+            ProdVerifier::default().verify_update_header(
+                light_block_2.as_untrusted_state(),
+                light_block_1.as_trusted_state(),
+                opt,
+                now,
+            )
+        };
+
+        // 1. malformed votes for nil are rejected by default
+        assert!(matches!(
+            verify(&light_block_2, &opt),
+            Verdict::Invalid(VerificationErrorDetail::MissingSignature(_))
+        ));
+
+        // 2. unless the options say to skip them, in which case they do not count as absent
+        opt.commit_signatures.malformed_nil_votes = MalformedVotes::Skip;
+        assert_eq!(verify(&light_block_2, &opt), Verdict::Success);
+
+        opt.commit_signatures.max_absent_signatures = Some(0);
+        assert_eq!(verify(&light_block_2, &opt), Verdict::Success);
+
+        // 3. commits with more absent signatures than tolerated are rejected
+        light_block_2.signed_header.commit.signatures[3] = CommitSig::BlockIdFlagAbsent;
+        match verify(&light_block_2, &opt) {
+            Verdict::Invalid(VerificationErrorDetail::TooManyAbsentSignatures(e)) => {
+                assert_eq!(e.absent, 1);
+                assert_eq!(e.max_absent_signatures, 0);
+            },
+            verdict => panic!("expected TooManyAbsentSignatures error, got {verdict:?}"),
+        }
+
+        opt.commit_signatures.max_absent_signatures = Some(1);
+        assert_eq!(verify(&light_block_2, &opt), Verdict::Success);
+    }
+
+    #[test]
+    fn test_unchanged_validators_fast_path() {
+        let now = Time::now();
+
+        let light_block = |height: u64, validators: &[Validator]| -> LightBlock {
+            let header = Header::new(validators)
+                .height(height)
+                .chain_id("chain-1")
+                .next_validators(validators)
+                .time(now.sub(Duration::from_secs(20 - height)).unwrap());
+
+            TestgenLightBlock::new_default_with_header(header)
+                .generate()
+                .unwrap()
+                .into()
+        };
+
+        let validators = ["1", "2"].map(|id| Validator::new(id).voting_power(50));
+        let light_block_1 = light_block(1, &validators);
+        let light_block_3 = light_block(3, &validators);
+
+        let mut opt = Options {
+            trust_threshold: Default::default(),
+            trusting_period: Duration::from_secs(60),
+            clock_drift: Default::default(),
+            verification_mode: Default::default(),
+            commit_signatures: Default::default(),
+        };
+
+        let predicates = |trusted: &LightBlock, untrusted: &LightBlock, opt: &Options| {
+            let (verdict, audit) = ProdVerifier::default().verify_update_header_audited(
+                untrusted.as_untrusted_state(),
+                trusted.as_trusted_state(),
+                opt,
+                now,
+            );
+            assert_eq!(verdict, Verdict::Success);
+
+            audit
+                .predicates
+                .iter()
+                .map(|evaluation| evaluation.predicate)
+                .collect::<Vec<_>>()
+        };
+
+        // 1. the unchanged validator set is neither hashed again, nor checked against
+        //    the trust threshold on top of the signers overlap
+        let evaluated = predicates(&light_block_1, &light_block_3, &opt);
+        assert!(!evaluated.contains(&Predicate::ValidatorSetsMatch));
+        assert!(!evaluated.contains(&Predicate::HasSufficientValidatorsOverlap));
+        assert!(evaluated.contains(&Predicate::HasSufficientSignersOverlap));
+
+        // 2. unless the trust threshold is higher than the one of the signers overlap
+        opt.trust_threshold = TrustThreshold::new(3, 4).unwrap();
+        let evaluated = predicates(&light_block_1, &light_block_3, &opt);
+        assert!(!evaluated.contains(&Predicate::ValidatorSetsMatch));
+        assert!(evaluated.contains(&Predicate::HasSufficientValidatorsOverlap));
+
+        // 3. a changed validator set is checked as usual
+        opt.trust_threshold = Default::default();
+        let other_validators = ["1", "2", "3"].map(|id| Validator::new(id).voting_power(50));
+        let light_block_3 = light_block(3, &other_validators);
+        let evaluated = predicates(&light_block_1, &light_block_3, &opt);
+        assert!(evaluated.contains(&Predicate::ValidatorSetsMatch));
+        assert!(evaluated.contains(&Predicate::HasSufficientValidatorsOverlap));
+    }
 }
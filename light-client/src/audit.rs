@@ -0,0 +1,167 @@
+//! Audit log of the verifications performed by the light client, recording the decisions of
+//! the scheduler and every check performed by the verifier, which can be replayed afterwards
+//! to tell why a header was accepted or rejected.
+
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::verifier::{
+    audit::VerificationAudit,
+    options::Options,
+    types::{Height, LightBlock, Time},
+    Verdict, Verifier,
+};
+
+/// The audit log of a verification, as returned by
+/// [`LightClient::verify_to_target_audited`](crate::light_client::LightClient::verify_to_target_audited).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditLog {
+    /// The entries of the log, in the order they were recorded
+    pub entries: Vec<AuditEntry>,
+}
+
+/// An entry of an [`AuditLog`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AuditEntry {
+    /// The scheduler picked the next height to verify
+    Scheduled(ScheduleDecision),
+
+    /// A light block was verified against a trusted light block
+    Verified(Box<VerificationRecord>),
+
+    /// The trusted light block to verify from was outside of the trusting period
+    TrustExpired {
+        /// Height of the trusted light block
+        trusted_height: Height,
+        /// Time at which the trusting period was checked
+        now: Time,
+    },
+}
+
+/// A decision of the scheduler.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduleDecision {
+    /// Height of the light block which was last verified, or trusted
+    pub current_height: Height,
+    /// Height of the target of the verification
+    pub target_height: Height,
+    /// Height of the next light block to verify
+    pub scheduled_height: Height,
+}
+
+/// The verification of a light block against a trusted light block, with all its inputs,
+/// so that it can be re-executed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationRecord {
+    /// The trusted light block
+    pub trusted: LightBlock,
+    /// The verified light block
+    pub untrusted: LightBlock,
+    /// The verification options
+    pub options: Options,
+    /// The time at which the verification was performed
+    pub now: Time,
+    /// The verdict of the verifier
+    pub verdict: Verdict,
+    /// The checks performed by the verifier
+    pub audit: VerificationAudit,
+}
+
+impl VerificationRecord {
+    /// Verify the recorded light block against the recorded trusted light block with
+    /// the given verifier, using the recorded options and time.
+    pub fn verify(
+        trusted: LightBlock,
+        untrusted: LightBlock,
+        options: Options,
+        now: Time,
+        verifier: &dyn Verifier,
+    ) -> Self {
+        let (verdict, audit) = verifier.verify_update_header_audited(
+            untrusted.as_untrusted_state(),
+            trusted.as_trusted_state(),
+            &options,
+            now,
+        );
+
+        Self {
+            trusted,
+            untrusted,
+            options,
+            now,
+            verdict,
+            audit,
+        }
+    }
+
+    /// Re-execute this verification with the given verifier.
+    pub fn replay(&self, verifier: &dyn Verifier) -> Self {
+        Self::verify(
+            self.trusted.clone(),
+            self.untrusted.clone(),
+            self.options,
+            self.now,
+            verifier,
+        )
+    }
+}
+
+/// A verification of an audit log which did not reach the same outcome when replayed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReplayMismatch {
+    /// Index of the entry of the verification in the audit log
+    pub index: usize,
+    /// The verification as recorded in the audit log
+    pub recorded: Box<VerificationRecord>,
+    /// The verification as replayed
+    pub replayed: Box<VerificationRecord>,
+}
+
+impl fmt::Display for ReplayMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "replaying the verification of the header at height {} against the trusted header \
+             at height {} (entry {}) ",
+            self.recorded.untrusted.height(),
+            self.recorded.trusted.height(),
+            self.index,
+        )?;
+
+        if self.recorded.verdict != self.replayed.verdict {
+            write!(
+                f,
+                "led to verdict {:?} instead of {:?}",
+                self.replayed.verdict, self.recorded.verdict
+            )
+        } else {
+            write!(f, "performed different checks than recorded")
+        }
+    }
+}
+
+/// Re-execute every verification of the given audit log with the given verifier, against the
+/// light blocks, options and time recorded in the log, and check that each of them performs
+/// the same checks with the same outcomes, and reaches the same verdict.
+///
+/// The decisions of the scheduler depend on the content of the light store at the time they
+/// were made, and are not replayed.
+pub fn replay(audit_log: &AuditLog, verifier: &dyn Verifier) -> Result<(), ReplayMismatch> {
+    for (index, entry) in audit_log.entries.iter().enumerate() {
+        if let AuditEntry::Verified(recorded) = entry {
+            let replayed = recorded.replay(verifier);
+
+            if replayed != **recorded {
+                return Err(ReplayMismatch {
+                    index,
+                    recorded: recorded.clone(),
+                    replayed: Box::new(replayed),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,187 @@
+//! Bounds on the work performed by the light client to verify a target height, and cooperative
+//! cancellation of the verification, so that callers with latency requirements can bound it.
+
+use core::{fmt, time::Duration};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+use crate::{errors::Error, verifier::types::Height};
+
+/// Bounds on the work performed by the light client to verify a target height, as given to
+/// [`LightClient::verify_to_target_with_budget`](crate::light_client::LightClient::verify_to_target_with_budget).
+///
+/// The budget is checked before every step of the verification, ie. before verifying a light
+/// block against a trusted one, so that a step which started is not interrupted. The default
+/// budget does not bound the verification.
+#[derive(Clone, Debug, Default)]
+pub struct Budget {
+    /// Maximum wall time spent verifying the target height
+    pub max_duration: Option<Duration>,
+    /// Maximum number of light blocks verified against a trusted light block
+    pub max_steps: Option<usize>,
+    /// Maximum number of light blocks fetched from the primary
+    pub max_fetches: Option<usize>,
+    /// Token with which to cancel the verification
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl Budget {
+    /// Bound the wall time spent verifying the target height.
+    pub fn with_max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    /// Bound the number of light blocks verified against a trusted light block.
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = Some(max_steps);
+        self
+    }
+
+    /// Bound the number of light blocks fetched from the primary.
+    pub fn with_max_fetches(mut self, max_fetches: usize) -> Self {
+        self.max_fetches = Some(max_fetches);
+        self
+    }
+
+    /// Cancel the verification once the given token is cancelled.
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = Some(cancellation);
+        self
+    }
+}
+
+/// Token with which to cancel a verification from another thread or task.
+///
+/// Clones of a token share its state, so that cancelling one of them cancels all of them.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// A token which is not cancelled yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the verifications using this token, before their next step.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether this token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// The bound of a [`Budget`] which was reached.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BudgetLimit {
+    /// The verification took longer than the given duration
+    Duration(Duration),
+    /// The verification performed the given number of steps
+    Steps(usize),
+    /// The verification fetched the given number of light blocks
+    Fetches(usize),
+    /// The verification was cancelled
+    Cancelled,
+}
+
+impl fmt::Display for BudgetLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Duration(duration) => write!(f, "max duration of {duration:?} reached"),
+            Self::Steps(steps) => write!(f, "max number of steps ({steps}) reached"),
+            Self::Fetches(fetches) => write!(f, "max number of fetches ({fetches}) reached"),
+            Self::Cancelled => write!(f, "verification cancelled"),
+        }
+    }
+}
+
+/// The progress of a verification when its budget was exceeded.
+///
+/// The light blocks verified until then are kept in the light store, so that verifying the
+/// target height again resumes from the highest of them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Progress {
+    /// Height of the target of the verification
+    pub target_height: Height,
+    /// Height of the highest light block trusted or verified on the way to the target height
+    pub verified_height: Height,
+    /// Number of light blocks verified against a trusted light block
+    pub steps: usize,
+    /// Number of light blocks fetched from the primary
+    pub fetches: usize,
+    /// Wall time spent verifying the target height
+    pub elapsed: Duration,
+}
+
+/// Keeps track of the work performed by a verification against its budget.
+pub(crate) struct BudgetTracker<'a> {
+    budget: &'a Budget,
+    started: Instant,
+    steps: usize,
+    fetches: usize,
+}
+
+impl<'a> BudgetTracker<'a> {
+    pub(crate) fn start(budget: &'a Budget) -> Self {
+        Self {
+            budget,
+            started: Instant::now(),
+            steps: 0,
+            fetches: 0,
+        }
+    }
+
+    /// Check that the budget allows for another step of the verification of the target height
+    /// from the light block at the given verified height, fetching a light block from the
+    /// primary if `fetch` is set, and account for it.
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn charge_step(
+        &mut self,
+        target_height: Height,
+        verified_height: Height,
+        fetch: bool,
+    ) -> Result<(), Error> {
+        let elapsed = self.started.elapsed();
+        let budget = self.budget;
+
+        let limit = if budget
+            .cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            BudgetLimit::Cancelled
+        } else if let Some(max) = budget.max_duration.filter(|max| elapsed >= *max) {
+            BudgetLimit::Duration(max)
+        } else if let Some(max) = budget.max_steps.filter(|max| self.steps >= *max) {
+            BudgetLimit::Steps(max)
+        } else if let Some(max) = budget
+            .max_fetches
+            .filter(|max| fetch && self.fetches >= *max)
+        {
+            BudgetLimit::Fetches(max)
+        } else {
+            self.steps += 1;
+            self.fetches += usize::from(fetch);
+            return Ok(());
+        };
+
+        Err(Error::budget_exceeded(
+            limit,
+            Progress {
+                target_height,
+                verified_height,
+                steps: self.steps,
+                fetches: self.fetches,
+                elapsed,
+            },
+        ))
+    }
+}
@@ -3,4 +3,5 @@
 mod light_client;
 pub use light_client::LightClientBuilder;
 
+pub mod config;
 pub mod error;
@@ -0,0 +1,164 @@
+//! Configuration of a light client, which can be deserialized from TOML or JSON, eg. from
+//! the configuration file of a service embedding the light client, to build a light client
+//! [`Instance`](crate::instance::Instance) with [`LightClientBuilder::from_config`].
+//!
+//! [`LightClientBuilder::from_config`]: crate::builder::LightClientBuilder::from_config
+
+use std::{path::PathBuf, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use tendermint::{block::Height, Hash};
+use tendermint_rpc::Url;
+
+use crate::{
+    builder::error::Error,
+    components::scheduler::{self, BoundedStepSchedule, Scheduler},
+    store::{memory::MemoryStore, LightStore},
+    verifier::{options::Options, types::PeerId},
+};
+
+/// Configuration of a light client.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LightClientConfig {
+    /// RPC address of the primary node
+    pub primary: Url,
+
+    /// RPC addresses of the witness nodes, to detect forks with them, eg. with
+    /// `tendermint-light-client-detector` (not used by the builder)
+    #[serde(default)]
+    pub witnesses: Vec<Url>,
+
+    /// Peer id of the primary node (default: fetched from the status of the primary node)
+    #[serde(default)]
+    pub peer_id: Option<PeerId>,
+
+    /// Height of the initial trusted header
+    pub trusted_height: Height,
+
+    /// Hash of the initial trusted header
+    pub trusted_hash: Hash,
+
+    /// Verification options
+    pub options: Options,
+
+    /// Light store (default: in memory)
+    #[serde(default)]
+    pub store: StoreConfig,
+
+    /// Scheduler deciding which block to verify next when bisecting
+    /// (default: basic bisection)
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
+
+    /// Timeout of the requests to the primary node (default: none)
+    #[serde(default)]
+    pub timeout: Option<Duration>,
+}
+
+/// Configuration of the light store of a light client.
+///
+/// All the implementations can be configured, but the persistent ones can only be opened
+/// when the corresponding `lightstore-*` feature is enabled.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum StoreConfig {
+    /// A transient, in-memory light store
+    #[default]
+    Memory,
+
+    /// A sled database at the given path, created if needed
+    Sled {
+        /// Path of the database
+        path: PathBuf,
+    },
+
+    /// An SQLite database file at the given path, created if needed
+    Sqlite {
+        /// Path of the database file
+        path: PathBuf,
+    },
+
+    /// A RocksDB database at the given path, created if needed
+    Rocksdb {
+        /// Path of the database
+        path: PathBuf,
+    },
+}
+
+impl StoreConfig {
+    /// Open the configured light store.
+    #[allow(clippy::result_large_err)]
+    pub fn open(&self) -> Result<Box<dyn LightStore>, Error> {
+        let light_store: Box<dyn LightStore> = match self {
+            Self::Memory => Box::new(MemoryStore::new()),
+
+            #[cfg(feature = "lightstore-sled")]
+            Self::Sled { path } => Box::new(
+                crate::store::sled::SledStore::open(path)
+                    .map_err(|e| Error::open_light_store(path.clone(), e.to_string()))?,
+            ),
+
+            #[cfg(feature = "lightstore-sqlite")]
+            Self::Sqlite { path } => Box::new(
+                crate::store::sqlite::SqliteStore::open(path)
+                    .map_err(|e| Error::open_light_store(path.clone(), e.to_string()))?,
+            ),
+
+            #[cfg(feature = "lightstore-rocksdb")]
+            Self::Rocksdb { path } => Box::new(
+                crate::store::rocksdb::RocksDbStore::open(path)
+                    .map_err(|e| Error::open_light_store(path.clone(), e.to_string()))?,
+            ),
+
+            #[allow(unreachable_patterns)]
+            _ => return Err(Error::unsupported_light_store(self.backend().to_string())),
+        };
+
+        Ok(light_store)
+    }
+
+    /// The name of the configured implementation.
+    pub fn backend(&self) -> &'static str {
+        match self {
+            Self::Memory => "memory",
+            Self::Sled { .. } => "sled",
+            Self::Sqlite { .. } => "sqlite",
+            Self::Rocksdb { .. } => "rocksdb",
+        }
+    }
+}
+
+/// Configuration of the scheduler of a light client.
+///
+/// See [`scheduler`](crate::components::scheduler) for the available schedulers.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SchedulerConfig {
+    /// [`basic_bisecting_schedule`](scheduler::basic_bisecting_schedule)
+    #[default]
+    Bisecting,
+
+    /// [`exponential_probing_schedule`](scheduler::exponential_probing_schedule)
+    ExponentialProbing,
+
+    /// [`BoundedStepSchedule`] with the given maximum step
+    BoundedStep {
+        /// Maximum number of blocks to skip ahead by, which must be positive
+        max_step: u64,
+    },
+}
+
+impl SchedulerConfig {
+    /// Create the configured scheduler.
+    #[allow(clippy::result_large_err)]
+    pub fn scheduler(&self) -> Result<Box<dyn Scheduler>, Error> {
+        let scheduler: Box<dyn Scheduler> = match *self {
+            Self::Bisecting => Box::new(scheduler::basic_bisecting_schedule),
+            Self::ExponentialProbing => Box::new(scheduler::exponential_probing_schedule),
+            Self::BoundedStep { max_step: 0 } => return Err(Error::invalid_max_step()),
+            Self::BoundedStep { max_step } => Box::new(BoundedStepSchedule::new(max_step)),
+        };
+
+        Ok(scheduler)
+    }
+}
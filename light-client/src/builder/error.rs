@@ -1,5 +1,7 @@
 //! Errors raised by the builder DSL
 
+use std::path::PathBuf;
+
 use flex_error::define_error;
 use tendermint::{block::Height, Hash};
 
@@ -41,5 +43,25 @@ define_error! {
         EmptyWitnessList
             | _ | { "empty witness list" },
 
+        OpenLightStore
+            {
+                path: PathBuf,
+                reason: String,
+            }
+            | e | {
+                format_args!("failed to open light store at {0}: {1}",
+                    e.path.display(), e.reason)
+            },
+
+        UnsupportedLightStore
+            { backend: String }
+            | e | {
+                format_args!("unsupported light store: {0}, enable the lightstore-{0} feature",
+                    e.backend)
+            },
+
+        InvalidMaxStep
+            | _ | { "the maximum step of the scheduler must be positive" },
+
     }
 }
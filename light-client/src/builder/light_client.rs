@@ -1,14 +1,17 @@
 //! DSL for building a light client [`Instance`]
 
+use core::time::Duration;
+
 use tendermint::{block::Height, crypto::Sha256, merkle::MerkleHash, Hash};
 
 #[cfg(feature = "rpc-client")]
 use {
+    crate::builder::config::LightClientConfig,
     crate::components::clock::SystemClock,
+    crate::components::io::IoError,
     crate::components::io::ProdIo,
     crate::components::scheduler,
     crate::verifier::{predicates::ProdPredicates, ProdVerifier},
-    core::time::Duration,
     tendermint_rpc as rpc,
 };
 
@@ -17,14 +20,17 @@ use crate::{
     components::{
         clock::Clock,
         io::{AtHeight, Io},
+        observer::{Observer, Observers},
+        pruner::PruningPolicy,
         scheduler::Scheduler,
     },
     instance::Instance,
     light_client::LightClient,
     state::{State, VerificationTrace},
     store::LightStore,
+    upgrade::ChainUpgrade,
     verifier::{
-        options::Options,
+        options::{Options, VoteExtensionsOptions},
         predicates::VerificationPredicates,
         types::{LightBlock, PeerId, Status},
         Verifier,
@@ -48,6 +54,11 @@ pub struct LightClientBuilder<State, H: MerkleHash + Sha256 + Default> {
     scheduler: Box<dyn Scheduler>,
     predicates: Box<dyn VerificationPredicates<Sha256 = H>>,
     light_store: Box<dyn LightStore>,
+    pruning_policy: PruningPolicy,
+    observers: Observers,
+    vote_extensions: Option<VoteExtensionsOptions>,
+    chain_upgrade: Option<ChainUpgrade>,
+    clock_drift_warning: Option<Duration>,
 
     #[allow(dead_code)]
     state: State,
@@ -68,9 +79,66 @@ where
             scheduler: self.scheduler,
             predicates: self.predicates,
             light_store: self.light_store,
+            pruning_policy: self.pruning_policy,
+            observers: self.observers,
+            vote_extensions: self.vote_extensions,
+            chain_upgrade: self.chain_upgrade,
+            clock_drift_warning: self.clock_drift_warning,
             state,
         }
     }
+
+    /// Set the policy deciding which light blocks to prune from the light store
+    /// after each successful verification (default: never prune).
+    pub fn pruning_policy(mut self, pruning_policy: PruningPolicy) -> Self {
+        self.pruning_policy = pruning_policy;
+        self
+    }
+
+    /// Set the scheduler deciding which block to verify next when bisecting
+    /// (default: the one given when initializing the builder).
+    ///
+    /// See [`scheduler`](crate::components::scheduler) for the available schedulers.
+    pub fn scheduler(self, scheduler: impl Scheduler + 'static) -> Self {
+        self.scheduler_boxed(Box::new(scheduler))
+    }
+
+    fn scheduler_boxed(mut self, scheduler: Box<dyn Scheduler>) -> Self {
+        self.scheduler = scheduler;
+        self
+    }
+
+    /// Register an observer to notify of the progress of the light client.
+    pub fn observer(mut self, observer: impl Observer + 'static) -> Self {
+        self.observers.add(Box::new(observer));
+        self
+    }
+
+    /// Enable the verification of vote extensions with the given options
+    /// (default: disabled), for chains running CometBFT 0.38 or later.
+    ///
+    /// See [`LightClient::verify_vote_extensions`].
+    pub fn vote_extensions(mut self, options: VoteExtensionsOptions) -> Self {
+        self.vote_extensions = Some(options);
+        self
+    }
+
+    /// Make the light client aware of the given upgrade of the chain (default: none).
+    ///
+    /// See [`LightClient::with_chain_upgrade`].
+    pub fn chain_upgrade(mut self, upgrade: ChainUpgrade) -> Self {
+        self.chain_upgrade = Some(upgrade);
+        self
+    }
+
+    /// Notify the observers of the light client when the time of a light block it fetched
+    /// is ahead of the local time by more than the given margin (default: never).
+    ///
+    /// See [`LightClient::with_clock_drift_warning`].
+    pub fn clock_drift_warning(mut self, margin: Duration) -> Self {
+        self.clock_drift_warning = Some(margin);
+        self
+    }
 }
 
 #[cfg(feature = "rpc-client")]
@@ -95,6 +163,77 @@ impl LightClientBuilder<NoTrustedState, tendermint::crypto::default::Sha256> {
             Box::new(ProdPredicates),
         )
     }
+
+    /// Initialize a builder for a production (non-mock) light client from the given
+    /// configuration, connecting to its primary node, opening its light store, and
+    /// setting its scheduler.
+    ///
+    /// See [`LightClientConfig::build`] to build the light client [`Instance`] right away.
+    pub fn from_config(config: &LightClientConfig) -> Result<Self, Error> {
+        let rpc_client =
+            rpc::HttpClient::new(config.primary.clone()).map_err(|e| Error::io(IoError::rpc(e)))?;
+
+        let peer_id = match config.peer_id {
+            Some(peer_id) => peer_id,
+            None => fetch_peer_id(rpc_client.clone(), config.timeout)?,
+        };
+
+        let light_store = config.store.open()?;
+        let scheduler = config.scheduler.scheduler()?;
+
+        Ok(Self::prod(
+            peer_id,
+            rpc_client,
+            light_store,
+            config.options,
+            config.timeout,
+        )
+        .scheduler_boxed(scheduler))
+    }
+}
+
+#[cfg(feature = "rpc-client")]
+fn fetch_peer_id(rpc_client: rpc::HttpClient, timeout: Option<Duration>) -> Result<PeerId, Error> {
+    use rpc::Client;
+
+    let status = crate::utils::block_on(timeout, async move { rpc_client.status().await })
+        .map_err(Error::io)?
+        .map_err(|e| Error::io(IoError::rpc(e)))?;
+
+    Ok(status.node_info.id)
+}
+
+#[cfg(feature = "rpc-client")]
+impl LightClientConfig {
+    /// Build the light client [`Instance`] configured by this configuration.
+    ///
+    /// The light client resumes from the highest trusted or verified light block of the light
+    /// store, if it is not lower than the configured trusted height, and trusts the header of the
+    /// primary node at the configured trusted height otherwise.
+    pub fn build(&self) -> Result<Instance, Error> {
+        let builder = LightClientBuilder::from_config(self)?;
+
+        let stored_block = builder.light_store.highest_trusted_or_verified();
+        let trusted_block = builder
+            .light_store
+            .get_trusted_or_verified(self.trusted_height);
+
+        let builder = match stored_block {
+            Some(stored_block) if stored_block.height() >= self.trusted_height => {
+                if let Some(trusted_block) = trusted_block {
+                    let stored_hash = trusted_block.signed_header.header.hash();
+                    if stored_hash != self.trusted_hash {
+                        return Err(Error::hash_mismatch(self.trusted_hash, stored_hash));
+                    }
+                }
+
+                builder.trust_from_store()?
+            },
+            _ => builder.trust_primary_at(self.trusted_height, self.trusted_hash)?,
+        };
+
+        Ok(builder.build())
+    }
 }
 
 impl<H> LightClientBuilder<NoTrustedState, H>
@@ -123,6 +262,11 @@ where
             scheduler,
             options,
             predicates,
+            pruning_policy: PruningPolicy::default(),
+            observers: Observers::default(),
+            vote_extensions: None,
+            chain_upgrade: None,
+            clock_drift_warning: None,
             state: NoTrustedState,
         }
     }
@@ -202,6 +346,10 @@ where
             )
             .map_err(Error::invalid_light_block)?;
 
+        self.predicates
+            .additional_checks(&light_block.as_untrusted_state())
+            .map_err(Error::invalid_light_block)?;
+
         Ok(())
     }
 }
@@ -225,7 +373,24 @@ where
             self.scheduler,
             self.verifier,
             self.io,
-        );
+        )
+        .with_pruning_policy(self.pruning_policy)
+        .with_observers(self.observers);
+
+        let light_client = match self.vote_extensions {
+            Some(options) => light_client.with_vote_extensions(options),
+            None => light_client,
+        };
+
+        let light_client = match self.chain_upgrade {
+            Some(upgrade) => light_client.with_chain_upgrade(upgrade),
+            None => light_client,
+        };
+
+        let light_client = match self.clock_drift_warning {
+            Some(margin) => light_client.with_clock_drift_warning(margin),
+            None => light_client,
+        };
 
         Instance::new(light_client, state)
     }
@@ -2,6 +2,9 @@
 
 pub mod clock;
 pub mod io;
+pub mod observer;
+pub mod prefetch;
+pub mod pruner;
 pub mod scheduler;
 
 // Re-export for backward compatibility
@@ -1,13 +1,14 @@
 //! Provides an interface and a default implementation of the `Io` component
 
-use std::time::Duration;
+use std::{collections::BTreeMap, time::Duration};
 
+use async_trait::async_trait;
 use flex_error::{define_error, TraceError};
 use tendermint_rpc as rpc;
 #[cfg(feature = "rpc-client")]
 use tendermint_rpc::Client;
 
-use crate::verifier::types::{Height, LightBlock};
+use crate::verifier::types::{Height, LightBlock, PeerId};
 
 #[cfg(feature = "tokio")]
 type TimeoutError = flex_error::DisplayOnly<tokio::time::error::Elapsed>;
@@ -115,6 +116,20 @@ impl IoErrorDetail {
 pub trait Io: Send + Sync {
     /// Fetch a light block at the given height from a peer
     fn fetch_light_block(&self, height: AtHeight) -> Result<LightBlock, IoError>;
+
+    /// Fetch a light block at the given height from a peer, to be verified against the given
+    /// trusted light block, whose validator sets may be reused instead of fetched again when the
+    /// header of the fetched light block has the same hashes.
+    ///
+    /// Defaults to [`Io::fetch_light_block`].
+    fn fetch_light_block_after(
+        &self,
+        height: AtHeight,
+        trusted: &LightBlock,
+    ) -> Result<LightBlock, IoError> {
+        let _ = trusted;
+        self.fetch_light_block(height)
+    }
 }
 
 impl<F: Send + Sync> Io for F
@@ -126,16 +141,98 @@ where
     }
 }
 
+/// Asynchronous interface for fetching light blocks from a full node, for use by
+/// [`AsyncLightClient`](crate::light_client::AsyncLightClient) within async applications,
+/// instead of blocking the calling thread like [`Io`] does.
+#[async_trait]
+pub trait AsyncIo: Send + Sync {
+    /// Fetch a light block at the given height from a peer
+    async fn fetch_light_block(&self, height: AtHeight) -> Result<LightBlock, IoError>;
+
+    /// Fetch a light block at the given height from a peer, to be verified against the given
+    /// trusted light block, as [`Io::fetch_light_block_after`] does.
+    ///
+    /// Defaults to [`AsyncIo::fetch_light_block`].
+    async fn fetch_light_block_after(
+        &self,
+        height: AtHeight,
+        trusted: &LightBlock,
+    ) -> Result<LightBlock, IoError> {
+        let _ = trusted;
+        self.fetch_light_block(height).await
+    }
+}
+
+/// An `Io` component which serves light blocks from a trace instead of fetching them from a node,
+/// eg. to verify headers without talking to any node.
+///
+/// All light blocks are re-attributed to the given peer, so that they can be
+/// fed to a light client instance for that peer.
+#[derive(Clone, Debug)]
+pub struct TraceIo {
+    light_blocks: BTreeMap<Height, LightBlock>,
+}
+
+impl TraceIo {
+    pub fn new(peer_id: PeerId, trace: Vec<LightBlock>) -> Self {
+        let light_blocks = trace
+            .into_iter()
+            .map(|mut lb| {
+                lb.provider = peer_id;
+                (lb.height(), lb)
+            })
+            .collect();
+
+        Self { light_blocks }
+    }
+
+    /// The heights of all the light blocks in the trace, in ascending order.
+    pub fn heights(&self) -> Vec<Height> {
+        self.light_blocks.keys().copied().collect()
+    }
+
+    /// The light block of greatest height in the trace.
+    pub fn highest(&self) -> Option<&LightBlock> {
+        self.light_blocks.values().next_back()
+    }
+}
+
+impl Io for TraceIo {
+    fn fetch_light_block(&self, height: AtHeight) -> Result<LightBlock, IoError> {
+        let latest = self.highest().map(|lb| lb.height()).unwrap_or_default();
+
+        let height = match height {
+            AtHeight::Highest => latest,
+            AtHeight::At(height) => height,
+        };
+
+        if height > latest {
+            return Err(IoError::height_too_high(height, latest));
+        }
+
+        self.light_blocks.get(&height).cloned().ok_or_else(|| {
+            IoError::rpc(rpc::Error::client_internal(format!(
+                "trace does not contain a light block at height {height}"
+            )))
+        })
+    }
+}
+
 #[cfg(feature = "rpc-client")]
-pub use self::prod::ProdIo;
+pub use self::prod::{ProdIo, ValidatorSetCache};
 
 #[cfg(feature = "rpc-client")]
 mod prod {
-    use std::time::Duration;
+    use std::{
+        collections::VecDeque,
+        future::Future,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
 
     use tendermint::{
         account::Id as TMAccountId, block::signed_header::SignedHeader as TMSignedHeader,
-        validator::Set as TMValidatorSet,
+        validator::Set as TMValidatorSet, Hash,
     };
     use tendermint_rpc::Paging;
 
@@ -149,25 +246,36 @@ mod prod {
         peer_id: PeerId,
         rpc_client: rpc::HttpClient,
         timeout: Option<Duration>,
+        validator_sets: ValidatorSetCache,
     }
 
     impl Io for ProdIo {
         fn fetch_light_block(&self, height: AtHeight) -> Result<LightBlock, IoError> {
-            let signed_header = self.fetch_signed_header(height)?;
-            let height = signed_header.header.height;
-            let proposer_address = signed_header.header.proposer_address;
+            self.fetch_light_block_reusing(height, None)
+        }
 
-            let validator_set = self.fetch_validator_set(height.into(), Some(proposer_address))?;
-            let next_validator_set = self.fetch_validator_set(height.increment().into(), None)?;
+        fn fetch_light_block_after(
+            &self,
+            height: AtHeight,
+            trusted: &LightBlock,
+        ) -> Result<LightBlock, IoError> {
+            self.fetch_light_block_reusing(height, Some(trusted))
+        }
+    }
 
-            let light_block = LightBlock::new(
-                signed_header,
-                validator_set,
-                next_validator_set,
-                self.peer_id,
-            );
+    #[async_trait]
+    impl AsyncIo for ProdIo {
+        async fn fetch_light_block(&self, height: AtHeight) -> Result<LightBlock, IoError> {
+            self.fetch_light_block_reusing_async(height, None).await
+        }
 
-            Ok(light_block)
+        async fn fetch_light_block_after(
+            &self,
+            height: AtHeight,
+            trusted: &LightBlock,
+        ) -> Result<LightBlock, IoError> {
+            self.fetch_light_block_reusing_async(height, Some(trusted))
+                .await
         }
     }
 
@@ -185,9 +293,17 @@ mod prod {
                 peer_id,
                 rpc_client,
                 timeout,
+                validator_sets: ValidatorSetCache::new(ValidatorSetCache::DEFAULT_CAPACITY),
             }
         }
 
+        /// Cache up to the given number of validator sets instead of
+        /// [`ValidatorSetCache::DEFAULT_CAPACITY`], or none if it is zero.
+        pub fn with_validator_set_cache_capacity(mut self, capacity: usize) -> Self {
+            self.validator_sets = ValidatorSetCache::new(capacity);
+            self
+        }
+
         pub fn peer_id(&self) -> PeerId {
             self.peer_id
         }
@@ -200,48 +316,521 @@ mod prod {
             self.timeout
         }
 
-        pub fn fetch_signed_header(&self, height: AtHeight) -> Result<TMSignedHeader, IoError> {
-            let client = self.rpc_client.clone();
-            let res = block_on(self.timeout, async move {
-                match height {
-                    AtHeight::Highest => client.latest_commit().await,
-                    AtHeight::At(height) => client.commit(height).await,
-                }
-            })?;
+        /// Fetch the light block at the given height, only fetching the validator sets which are
+        /// neither validator sets of the given trusted light block nor cached.
+        fn fetch_light_block_reusing(
+            &self,
+            height: AtHeight,
+            trusted: Option<&LightBlock>,
+        ) -> Result<LightBlock, IoError> {
+            let signed_header = self.fetch_signed_header(height)?;
+            let header = &signed_header.header;
+            let proposer_address = header.proposer_address;
+
+            let height = header.height;
+            let next_height = height.increment();
+
+            let known = known_validator_sets(trusted);
+            let validator_set = match self.known_validator_set(
+                &known,
+                header.validators_hash,
+                Some(proposer_address),
+            ) {
+                Some(validator_set) => validator_set,
+                None => self
+                    .validator_sets
+                    .insert(self.fetch_validator_set(height.into(), Some(proposer_address))?),
+            };
+
+            let known = with_validator_set(known, header.validators_hash, &validator_set);
+            let next_validator_set =
+                match self.known_validator_set(&known, header.next_validators_hash, None) {
+                    Some(validator_set) => validator_set,
+                    None => self
+                        .validator_sets
+                        .insert(self.fetch_validator_set(next_height.into(), None)?),
+                };
 
-            match res {
-                Ok(response) => Ok(response.signed_header),
-                Err(err) => Err(IoError::from_rpc(err)),
+            let light_block = LightBlock::new(
+                signed_header,
+                validator_set,
+                next_validator_set,
+                self.peer_id,
+            );
+
+            Ok(light_block)
+        }
+
+        /// Asynchronous counterpart of [`ProdIo::fetch_light_block_reusing`].
+        async fn fetch_light_block_reusing_async(
+            &self,
+            height: AtHeight,
+            trusted: Option<&LightBlock>,
+        ) -> Result<LightBlock, IoError> {
+            let client = &self.rpc_client;
+
+            let signed_header =
+                with_timeout(self.timeout, fetch_signed_header(client, height)).await??;
+            let header = &signed_header.header;
+            let proposer_address = header.proposer_address;
+
+            let height = header.height;
+            let next_height = height.increment();
+
+            let known = known_validator_sets(trusted);
+            let validator_set = match self.known_validator_set(
+                &known,
+                header.validators_hash,
+                Some(proposer_address),
+            ) {
+                Some(validator_set) => validator_set,
+                None => self.validator_sets.insert(
+                    with_timeout(
+                        self.timeout,
+                        fetch_validator_set(client, height.into(), Some(proposer_address)),
+                    )
+                    .await??,
+                ),
+            };
+
+            let known = with_validator_set(known, header.validators_hash, &validator_set);
+            let next_validator_set =
+                match self.known_validator_set(&known, header.next_validators_hash, None) {
+                    Some(validator_set) => validator_set,
+                    None => self.validator_sets.insert(
+                        with_timeout(
+                            self.timeout,
+                            fetch_validator_set(client, next_height.into(), None),
+                        )
+                        .await??,
+                    ),
+                };
+
+            let light_block = LightBlock::new(
+                signed_header,
+                validator_set,
+                next_validator_set,
+                self.peer_id,
+            );
+
+            Ok(light_block)
+        }
+
+        /// The validator set with the given hash among the given known validator sets, or else
+        /// in the cache, with the given proposer.
+        fn known_validator_set(
+            &self,
+            known: &[(Hash, &TMValidatorSet)],
+            hash: Hash,
+            proposer_address: Option<TMAccountId>,
+        ) -> Option<TMValidatorSet> {
+            match known.iter().find(|(h, _)| *h == hash) {
+                Some((_, validator_set)) => with_proposer(validator_set, proposer_address),
+                None => self.validator_sets.get(hash, proposer_address),
             }
         }
 
+        pub fn fetch_signed_header(&self, height: AtHeight) -> Result<TMSignedHeader, IoError> {
+            let client = self.rpc_client.clone();
+            block_on(self.timeout, async move {
+                fetch_signed_header(&client, height).await
+            })?
+        }
+
         pub fn fetch_validator_set(
             &self,
             height: AtHeight,
             proposer_address: Option<TMAccountId>,
         ) -> Result<TMValidatorSet, IoError> {
-            let height = match height {
-                AtHeight::Highest => {
-                    return Err(IoError::invalid_height());
-                },
-                AtHeight::At(height) => height,
-            };
-
             let client = self.rpc_client.clone();
-            let response = block_on(self.timeout, async move {
-                client.validators(height, Paging::All).await
+            block_on(self.timeout, async move {
+                fetch_validator_set(&client, height, proposer_address).await
             })?
+        }
+    }
+
+    /// A cache of the most recently fetched validator sets, keyed by their hash.
+    ///
+    /// Consecutive light blocks frequently share the same validator set, and the next
+    /// validator set of a light block is the validator set of the following one, so that
+    /// [`ProdIo`] looks the validator sets of a header up by the hashes it holds before
+    /// fetching them, at any height. Validator sets are cached under the hash computed from
+    /// their content, so that a validator set found in the cache always matches the hash in the
+    /// header, and their proposer is set from the header they are looked up for.
+    ///
+    /// The proposer priorities of the validators are not covered by the hash, and change at
+    /// every height. A validator set reused at another height than the one it was fetched at
+    /// keeps the priorities of the latter, which may differ from those the node would return.
+    /// Verification does not depend on them, and is cheaper for the requests it saves, as the
+    /// validator sets of the heights skipped over during bisection often have the same hash.
+    ///
+    /// The cache is shared by the clones of the [`ProdIo`] it belongs to.
+    #[derive(Clone, Debug)]
+    pub struct ValidatorSetCache {
+        capacity: usize,
+        sets: Arc<Mutex<VecDeque<(Hash, TMValidatorSet)>>>,
+    }
+
+    impl ValidatorSetCache {
+        /// Number of validator sets cached by [`ProdIo`] by default
+        pub const DEFAULT_CAPACITY: usize = 16;
+
+        /// Create a cache holding up to the given number of validator sets, which caches
+        /// nothing if it is zero.
+        pub fn new(capacity: usize) -> Self {
+            Self {
+                capacity,
+                sets: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            }
+        }
+
+        /// Get the validator set with the given hash, if cached, with the given proposer.
+        pub fn get(
+            &self,
+            hash: Hash,
+            proposer_address: Option<TMAccountId>,
+        ) -> Option<TMValidatorSet> {
+            let mut sets = self.sets.lock().unwrap();
+
+            // Move the validator set to the back, as the most recently used one
+            let index = sets.iter().position(|(h, _)| *h == hash)?;
+            let entry = sets.remove(index)?;
+            let validator_set = with_proposer(&entry.1, proposer_address);
+            sets.push_back(entry);
+
+            validator_set
+        }
+
+        /// Cache the given validator set, evicting the least recently used one if the cache is
+        /// full, and return it.
+        pub fn insert(&self, validator_set: TMValidatorSet) -> TMValidatorSet {
+            if self.capacity == 0 {
+                return validator_set;
+            }
+
+            let hash = validator_set.hash();
+            let mut sets = self.sets.lock().unwrap();
+
+            if !sets.iter().any(|(h, _)| *h == hash) {
+                if sets.len() == self.capacity {
+                    sets.pop_front();
+                }
+
+                sets.push_back((hash, validator_set.clone()));
+            }
+
+            validator_set
+        }
+
+        /// Number of validator sets in the cache.
+        pub fn len(&self) -> usize {
+            self.sets.lock().unwrap().len()
+        }
+
+        /// Whether the cache is empty.
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+    }
+
+    /// The validator sets of the given trusted light block, by the hashes of its header.
+    ///
+    /// They match these hashes, as the validator set of a light block is only trusted once
+    /// verified against them.
+    fn known_validator_sets(trusted: Option<&LightBlock>) -> Vec<(Hash, &TMValidatorSet)> {
+        match trusted {
+            Some(trusted) => {
+                let header = &trusted.signed_header.header;
+                vec![
+                    (header.next_validators_hash, &trusted.next_validators),
+                    (header.validators_hash, &trusted.validators),
+                ]
+            },
+            None => Vec::new(),
+        }
+    }
+
+    /// Add the given validator set, which matches the given hash, to the known validator sets.
+    fn with_validator_set<'a>(
+        mut known: Vec<(Hash, &'a TMValidatorSet)>,
+        hash: Hash,
+        validator_set: &'a TMValidatorSet,
+    ) -> Vec<(Hash, &'a TMValidatorSet)> {
+        known.push((hash, validator_set));
+        known
+    }
+
+    /// A copy of the given validator set, with the given proposer if any, or `None` if the
+    /// proposer is not part of the validator set.
+    fn with_proposer(
+        validator_set: &TMValidatorSet,
+        proposer_address: Option<TMAccountId>,
+    ) -> Option<TMValidatorSet> {
+        let validators = validator_set.validators().clone();
+
+        match proposer_address {
+            Some(proposer_address) => {
+                TMValidatorSet::with_proposer(validators, proposer_address).ok()
+            },
+            None => Some(TMValidatorSet::without_proposer(validators)),
+        }
+    }
+
+    /// Run a future to completion within the current async runtime, with the given timeout.
+    async fn with_timeout<F: Future>(
+        timeout: Option<Duration>,
+        f: F,
+    ) -> Result<F::Output, IoError> {
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, f)
+                .await
+                .map_err(|e| IoError::timeout(timeout, e)),
+            None => Ok(f.await),
+        }
+    }
+
+    async fn fetch_signed_header(
+        client: &rpc::HttpClient,
+        height: AtHeight,
+    ) -> Result<TMSignedHeader, IoError> {
+        let res = match height {
+            AtHeight::Highest => client.latest_commit().await,
+            AtHeight::At(height) => client.commit(height).await,
+        };
+
+        match res {
+            Ok(response) => Ok(response.signed_header),
+            Err(err) => Err(IoError::from_rpc(err)),
+        }
+    }
+
+    async fn fetch_validator_set(
+        client: &rpc::HttpClient,
+        height: AtHeight,
+        proposer_address: Option<TMAccountId>,
+    ) -> Result<TMValidatorSet, IoError> {
+        let height = match height {
+            AtHeight::Highest => {
+                return Err(IoError::invalid_height());
+            },
+            AtHeight::At(height) => height,
+        };
+
+        let response = client
+            .validators(height, Paging::All)
+            .await
             .map_err(IoError::rpc)?;
 
-            let validator_set = match proposer_address {
-                Some(proposer_address) => {
-                    TMValidatorSet::with_proposer(response.validators, proposer_address)
-                        .map_err(IoError::invalid_validator_set)?
-                },
-                None => TMValidatorSet::without_proposer(response.validators),
-            };
+        let validator_set = match proposer_address {
+            Some(proposer_address) => {
+                TMValidatorSet::with_proposer(response.validators, proposer_address)
+                    .map_err(IoError::invalid_validator_set)?
+            },
+            None => TMValidatorSet::without_proposer(response.validators),
+        };
+
+        Ok(validator_set)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::{
+            io::{BufRead, BufReader, Read, Write},
+            net::TcpListener,
+            sync::atomic::{AtomicUsize, Ordering},
+        };
+
+        use tendermint_rpc::endpoint::{commit, validators};
+        use tendermint_testgen::{Generator, Validator};
+
+        use super::*;
+        use crate::tests::light_blocks;
+
+        /// A node serving the commits and validator sets of the given light blocks, from height
+        /// 1, along with the number of validator sets it served.
+        fn serve(light_blocks: Vec<LightBlock>) -> (rpc::HttpClient, Arc<AtomicUsize>) {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let served = Arc::new(AtomicUsize::new(0));
+
+            let count = served.clone();
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let mut stream = BufReader::new(stream.unwrap());
+
+                    let mut content_length = 0;
+                    loop {
+                        let mut line = String::new();
+                        stream.read_line(&mut line).unwrap();
+                        match line.to_ascii_lowercase().strip_prefix("content-length:") {
+                            Some(len) => content_length = len.trim().parse().unwrap(),
+                            None if line == "\r\n" => break,
+                            None => {},
+                        }
+                    }
+                    let mut body = vec![0; content_length];
+                    stream.read_exact(&mut body).unwrap();
+
+                    let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                    let height: usize = request["params"]["height"]
+                        .as_str()
+                        .unwrap()
+                        .parse()
+                        .unwrap();
+                    let light_block = &light_blocks[height - 1];
+
+                    let result = match request["method"].as_str().unwrap() {
+                        "commit" => serde_json::to_value(commit::Response {
+                            signed_header: light_block.signed_header.clone(),
+                            canonical: true,
+                        }),
+                        "validators" => {
+                            count.fetch_add(1, Ordering::SeqCst);
+                            let validators = light_block.validators.validators().clone();
+                            let total = validators.len() as i32;
+                            serde_json::to_value(validators::Response::new(
+                                light_block.height(),
+                                validators,
+                                total,
+                            ))
+                        },
+                        method => panic!("unexpected request: {method}"),
+                    }
+                    .unwrap();
+
+                    let body = serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": request["id"],
+                        "result": result,
+                    })
+                    .to_string();
+                    write!(
+                        stream.get_mut(),
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+                        content-length: {}\r\nconnection: close\r\n\r\n{body}",
+                        body.len()
+                    )
+                    .unwrap();
+                }
+            });
+
+            (rpc::HttpClient::new(url.as_str()).unwrap(), served)
+        }
+
+        fn validator_set(ids: &[&str]) -> TMValidatorSet {
+            let validators = ids
+                .iter()
+                .map(|id| Validator::new(id).voting_power(50).generate().unwrap())
+                .collect();
+
+            TMValidatorSet::without_proposer(validators)
+        }
+
+        #[test]
+        fn caches_validator_sets_by_hash() {
+            let cache = ValidatorSetCache::new(2);
+            let set = validator_set(&["a", "b"]);
+            let proposer = set.validators()[1].address;
+
+            assert_eq!(cache.get(set.hash(), None), None);
+            cache.insert(set.clone());
+            cache.insert(set.clone());
+            assert_eq!(cache.len(), 1);
+
+            assert_eq!(cache.get(set.hash(), None), Some(set.clone()));
+
+            let with_proposer = cache.get(set.hash(), Some(proposer)).unwrap();
+            assert_eq!(with_proposer.proposer().as_ref().unwrap().address, proposer);
+            assert_eq!(with_proposer.hash(), set.hash());
+        }
+
+        #[test]
+        fn reuses_validator_sets_across_heights() {
+            let cache = ValidatorSetCache::new(2);
+            let set = validator_set(&["a", "b"]);
+            let proposer = set.validators()[0].address;
+
+            let mut validators = set.validators().clone();
+            validators[0].proposer_priority = 42.into();
+            validators[1].proposer_priority = (-42).into();
+            let with_priorities = TMValidatorSet::without_proposer(validators);
+            assert_eq!(with_priorities.hash(), set.hash());
+
+            cache.insert(with_priorities);
+
+            // Looked up for the header of another height, the validator set gets the proposer
+            // of that header, but keeps the priorities of the height it was fetched at
+            let cached = cache.get(set.hash(), Some(proposer)).unwrap();
+            assert_eq!(cached.hash(), set.hash());
+            assert_eq!(cached.proposer().as_ref().unwrap().address, proposer);
+            assert_eq!(cached.validators()[0].proposer_priority, 42.into());
+        }
+
+        #[test]
+        fn fetches_validator_sets_once_across_heights() {
+            let light_blocks = light_blocks(8);
+            let peer_id = light_blocks[0].provider;
+            let (rpc_client, served) = serve(light_blocks.clone());
+            let io = ProdIo::new(peer_id, rpc_client, None);
+
+            // The validator set of the default chain never changes
+            for height in [3_u32, 7, 5] {
+                let light_block = Io::fetch_light_block(&io, AtHeight::At(height.into())).unwrap();
+                let expected = &light_blocks[height as usize - 1];
+                assert_eq!(light_block.signed_header, expected.signed_header);
+                assert_eq!(light_block.validators.hash(), expected.validators.hash());
+            }
+            assert_eq!(served.load(Ordering::SeqCst), 1);
+        }
+
+        #[test]
+        fn reuses_validator_sets_of_trusted_light_block() {
+            let light_blocks = light_blocks(8);
+            let peer_id = light_blocks[0].provider;
+            let (rpc_client, served) = serve(light_blocks.clone());
+            let io = ProdIo::new(peer_id, rpc_client, None).with_validator_set_cache_capacity(0);
+
+            let trusted = &light_blocks[1];
+            let light_block =
+                Io::fetch_light_block_after(&io, AtHeight::At(6_u32.into()), trusted).unwrap();
+            assert_eq!(
+                light_block.validators.hash(),
+                trusted.next_validators.hash()
+            );
+            assert_eq!(served.load(Ordering::SeqCst), 0);
+
+            // The next validator set is the validator set when it did not change
+            Io::fetch_light_block(&io, AtHeight::At(6_u32.into())).unwrap();
+            assert_eq!(served.load(Ordering::SeqCst), 1);
+        }
+
+        #[test]
+        fn evicts_least_recently_used_validator_set() {
+            let cache = ValidatorSetCache::new(2);
+            let (a, b, c) = (
+                validator_set(&["a"]),
+                validator_set(&["b"]),
+                validator_set(&["c"]),
+            );
+
+            cache.insert(a.clone());
+            cache.insert(b.clone());
+            cache.get(a.hash(), None).unwrap();
+            cache.insert(c.clone());
+
+            assert!(cache.get(a.hash(), None).is_some());
+            assert!(cache.get(b.hash(), None).is_none());
+            assert!(cache.get(c.hash(), None).is_some());
+        }
+
+        #[test]
+        fn caches_nothing_without_capacity() {
+            let cache = ValidatorSetCache::new(0);
+            let set = validator_set(&["a"]);
 
-            Ok(validator_set)
+            cache.insert(set.clone());
+            assert!(cache.is_empty());
+            assert_eq!(cache.get(set.hash(), None), None);
         }
     }
 }
@@ -0,0 +1,124 @@
+//! Provides an interface for observing the progress of the light client
+
+use core::{fmt, time::Duration};
+
+use crate::verifier::{
+    types::{Height, LightBlock, PeerId, Time},
+    Verdict,
+};
+
+/// A light block whose time is ahead of the local time by more than the margin set with
+/// [`LightClient::with_clock_drift_warning`](crate::light_client::LightClient::with_clock_drift_warning),
+/// as reported to [`Observer::on_clock_drift`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ClockDrift {
+    /// Height of the light block
+    pub height: Height,
+    /// Time of the header of the light block
+    pub header_time: Time,
+    /// Local time at which the light block was about to be verified
+    pub local_time: Time,
+    /// How far the time of the header is ahead of the local time
+    pub drift: Duration,
+    /// The margin the drift exceeds
+    pub margin: Duration,
+}
+
+/// An observer is notified of the progress of the light client, so that embedding applications
+/// do not have to poll the light store or parse the logs to follow it.
+///
+/// Every method does nothing by default, so that observers only need to implement the ones for
+/// the events they care about. Observers are called synchronously during verification, so they
+/// should return quickly, eg. by sending the event over a channel.
+#[allow(unused_variables)]
+pub trait Observer: Send + Sync {
+    /// Called once the light block at the target height of a verification has been verified.
+    fn on_block_verified(&self, light_block: &LightBlock) {}
+
+    /// Called after each step of forward verification, once the light block at the current
+    /// height has been verified against the trusted light block, with the resulting verdict.
+    fn on_bisection_step(
+        &self,
+        trusted_block: &LightBlock,
+        current_block: &LightBlock,
+        verdict: &Verdict,
+    ) {
+    }
+
+    /// Called when a witness reported a light block conflicting with a light block verified
+    /// by this light client, as found by the attack detector.
+    fn on_fork_detected(
+        &self,
+        verified_block: &LightBlock,
+        witness: PeerId,
+        conflicting_block: &LightBlock,
+    ) {
+    }
+
+    /// Called when the trusted light block to verify from is outside of the trusting period,
+    /// which aborts the verification.
+    fn on_trust_expired(&self, trusted_block: &LightBlock) {}
+
+    /// Called when the time of a light block fetched for verification is ahead of the local
+    /// time by more than the configured margin, which hints at the local clock lagging behind,
+    /// before the verification of the light block is attempted.
+    fn on_clock_drift(&self, drift: &ClockDrift) {}
+}
+
+/// The observers registered with a light client, which are all notified of every event.
+#[derive(Default)]
+pub(crate) struct Observers(Vec<Box<dyn Observer>>);
+
+impl Observers {
+    pub(crate) fn add(&mut self, observer: Box<dyn Observer>) {
+        self.0.push(observer);
+    }
+}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Observers({})", self.0.len())
+    }
+}
+
+impl Observer for Observers {
+    fn on_block_verified(&self, light_block: &LightBlock) {
+        for observer in &self.0 {
+            observer.on_block_verified(light_block);
+        }
+    }
+
+    fn on_bisection_step(
+        &self,
+        trusted_block: &LightBlock,
+        current_block: &LightBlock,
+        verdict: &Verdict,
+    ) {
+        for observer in &self.0 {
+            observer.on_bisection_step(trusted_block, current_block, verdict);
+        }
+    }
+
+    fn on_fork_detected(
+        &self,
+        verified_block: &LightBlock,
+        witness: PeerId,
+        conflicting_block: &LightBlock,
+    ) {
+        for observer in &self.0 {
+            observer.on_fork_detected(verified_block, witness, conflicting_block);
+        }
+    }
+
+    fn on_trust_expired(&self, trusted_block: &LightBlock) {
+        for observer in &self.0 {
+            observer.on_trust_expired(trusted_block);
+        }
+    }
+
+    fn on_clock_drift(&self, drift: &ClockDrift) {
+        for observer in &self.0 {
+            observer.on_clock_drift(drift);
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! Provides a scheduler and `Io` combination which fetches the light blocks the light client is
+//! likely to verify next in the background, while it verifies the current one.
+//!
+//! Every time the light client schedules the verification of a light block, the
+//! [`PrefetchingSchedule`] guesses which heights it will be scheduled next, ie. the target height
+//! if the light block can be verified, and the midpoint between the trusted height and its height
+//! otherwise, like [`basic_bisecting_schedule`] would. The light blocks at these heights are then
+//! fetched in the background by the [`PrefetchingIo`], along with the one at the scheduled height,
+//! and served to the light client once it actually needs them, instead of fetching them again.
+//! This overlaps the network latency of fetching light blocks with the verification of the
+//! current one.
+//!
+//! ```ignore
+//! let (scheduler, io) = prefetching_bisecting_schedule(ProdIo::new(peer_id, rpc_client, None));
+//! let light_client = LightClient::new(peer_id, options, clock, scheduler, verifier, io);
+//! ```
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::{Arc, Condvar, Mutex},
+    thread,
+};
+
+use contracts::contract_trait;
+
+use crate::{
+    components::{
+        io::{AtHeight, Io, IoError},
+        scheduler::{basic_bisecting_schedule, midpoint, Scheduler},
+    },
+    store::LightStore,
+    verifier::types::{Height, LightBlock},
+};
+
+/// A light block being fetched in the background.
+#[derive(Default)]
+struct Prefetch {
+    /// The fetched light block, or `None` if it could not be fetched, once the fetch is done
+    light_block: Mutex<Option<Option<LightBlock>>>,
+    done: Condvar,
+}
+
+impl Prefetch {
+    fn complete(&self, light_block: Option<LightBlock>) {
+        *self.light_block.lock().unwrap() = Some(light_block);
+        self.done.notify_all();
+    }
+
+    fn wait(&self) -> Option<LightBlock> {
+        let mut light_block = self.light_block.lock().unwrap();
+
+        while light_block.is_none() {
+            light_block = self.done.wait(light_block).unwrap();
+        }
+
+        light_block.take().flatten()
+    }
+}
+
+/// The number of threads a [`PrefetchingIo`] fetches light blocks with by default, enough to
+/// fetch all the light blocks guessed by a [`PrefetchingSchedule`] at once.
+pub const DEFAULT_PREFETCHING_THREADS: usize = 3;
+
+/// The light blocks prefetched by a [`PrefetchingIo`] and its clones.
+#[derive(Default)]
+struct Prefetches {
+    /// The light blocks prefetched or being prefetched, by height
+    by_height: HashMap<Height, Arc<Prefetch>>,
+    /// The heights of the light blocks whose fetch has not started yet, in order
+    queued: VecDeque<Height>,
+    /// The number of threads fetching light blocks
+    threads: usize,
+}
+
+/// An `Io` component which serves the light blocks prefetched in the background when it is asked
+/// for them, and otherwise fetches them with the wrapped `Io` component.
+///
+/// The light blocks are prefetched by a bounded number of threads, which are started whenever
+/// there are light blocks to fetch, and stop once there are no more.
+///
+/// Clones of a `PrefetchingIo` share their prefetched light blocks, so that the one handed to the
+/// light client serves the light blocks prefetched through the one handed to the
+/// [`PrefetchingSchedule`].
+#[derive(Clone)]
+pub struct PrefetchingIo {
+    io: Arc<dyn Io>,
+    max_threads: usize,
+    prefetches: Arc<Mutex<Prefetches>>,
+}
+
+impl PrefetchingIo {
+    /// Wrap the given `Io` component, without prefetching any light block yet, fetching light
+    /// blocks with at most [`DEFAULT_PREFETCHING_THREADS`] threads.
+    pub fn new(io: impl Io + 'static) -> Self {
+        Self::with_max_threads(io, DEFAULT_PREFETCHING_THREADS)
+    }
+
+    /// Wrap the given `Io` component, without prefetching any light block yet, fetching light
+    /// blocks with at most the given number of threads, or one if it is 0.
+    pub fn with_max_threads(io: impl Io + 'static, max_threads: usize) -> Self {
+        Self {
+            io: Arc::new(io),
+            max_threads: max_threads.max(1),
+            prefetches: Arc::new(Mutex::new(Prefetches::default())),
+        }
+    }
+
+    /// Fetch the light blocks at the given heights in the background, unless they are being
+    /// fetched already.
+    ///
+    /// The light blocks previously prefetched at other heights are discarded, so that only the
+    /// light blocks of the latest guess are kept around. Their fetches are cancelled if they have
+    /// not started yet, and their light blocks dropped otherwise.
+    pub fn prefetch(&self, heights: &[Height]) {
+        let mut prefetches = self.prefetches.lock().unwrap();
+        prefetches
+            .by_height
+            .retain(|height, _| heights.contains(height));
+        prefetches.queued.retain(|height| heights.contains(height));
+
+        for &height in heights {
+            if prefetches.by_height.contains_key(&height) {
+                continue;
+            }
+
+            prefetches.by_height.insert(height, Arc::default());
+            prefetches.queued.push_back(height);
+        }
+
+        while prefetches.threads < self.max_threads && prefetches.threads < prefetches.queued.len()
+        {
+            prefetches.threads += 1;
+
+            let this = self.clone();
+            thread::spawn(move || this.run_fetches());
+        }
+    }
+
+    /// Fetch the queued light blocks, until there are none left.
+    fn run_fetches(&self) {
+        loop {
+            let (height, prefetch) = {
+                let mut prefetches = self.prefetches.lock().unwrap();
+                let next = prefetches.queued.pop_front().map(|height| {
+                    let prefetch = prefetches.by_height.get(&height).cloned();
+                    (height, prefetch)
+                });
+
+                match next {
+                    Some((height, Some(prefetch))) => (height, prefetch),
+                    Some((_, None)) => continue,
+                    None => {
+                        prefetches.threads -= 1;
+                        return;
+                    },
+                }
+            };
+
+            prefetch.complete(self.io.fetch_light_block(AtHeight::At(height)).ok());
+        }
+    }
+
+    /// The heights of the light blocks which are prefetched or being prefetched.
+    pub fn prefetched_heights(&self) -> Vec<Height> {
+        let mut heights: Vec<_> = self
+            .prefetches
+            .lock()
+            .unwrap()
+            .by_height
+            .keys()
+            .copied()
+            .collect();
+        heights.sort();
+        heights
+    }
+
+    /// The light block prefetched at the given height, waiting for its fetch to complete, or
+    /// `None` if it is not prefetched or could not be fetched.
+    fn take_prefetched(&self, height: &AtHeight) -> Option<LightBlock> {
+        let prefetch = match height {
+            &AtHeight::At(height) => {
+                let mut prefetches = self.prefetches.lock().unwrap();
+                let prefetch = prefetches.by_height.remove(&height);
+
+                // Rather than waiting for the fetch to start, fetch the light block right away
+                match prefetches
+                    .queued
+                    .iter()
+                    .position(|&queued| queued == height)
+                {
+                    Some(index) => {
+                        prefetches.queued.remove(index);
+                        None
+                    },
+                    None => prefetch,
+                }
+            },
+            AtHeight::Highest => None,
+        };
+
+        prefetch.and_then(|prefetch| prefetch.wait())
+    }
+}
+
+impl fmt::Debug for PrefetchingIo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrefetchingIo")
+            .field("prefetched_heights", &self.prefetched_heights())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Io for PrefetchingIo {
+    fn fetch_light_block(&self, height: AtHeight) -> Result<LightBlock, IoError> {
+        self.take_prefetched(&height)
+            .map_or_else(|| self.io.fetch_light_block(height), Ok)
+    }
+
+    fn fetch_light_block_after(
+        &self,
+        height: AtHeight,
+        trusted: &LightBlock,
+    ) -> Result<LightBlock, IoError> {
+        self.take_prefetched(&height)
+            .map_or_else(|| self.io.fetch_light_block_after(height, trusted), Ok)
+    }
+}
+
+/// A scheduler which schedules the same heights as the wrapped scheduler, and prefetches the
+/// light blocks at the heights it is likely to schedule next with a [`PrefetchingIo`].
+///
+/// The guess assumes that the wrapped scheduler bisects like [`basic_bisecting_schedule`], and
+/// only costs useless fetches when it does not.
+pub struct PrefetchingSchedule {
+    scheduler: Box<dyn Scheduler>,
+    io: PrefetchingIo,
+}
+
+impl PrefetchingSchedule {
+    /// Wrap the given scheduler, prefetching light blocks with the given `Io` component, which
+    /// must be a clone of the one handed to the light client.
+    pub fn new(scheduler: impl Scheduler + 'static, io: PrefetchingIo) -> Self {
+        Self {
+            scheduler: Box::new(scheduler),
+            io,
+        }
+    }
+}
+
+impl fmt::Debug for PrefetchingSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrefetchingSchedule")
+            .field("io", &self.io)
+            .finish_non_exhaustive()
+    }
+}
+
+#[contract_trait]
+impl Scheduler for PrefetchingSchedule {
+    fn schedule(
+        &self,
+        light_store: &dyn LightStore,
+        current_height: Height,
+        target_height: Height,
+    ) -> Height {
+        let next_height = self
+            .scheduler
+            .schedule(light_store, current_height, target_height);
+
+        let trusted_height = light_store
+            .highest_trusted_or_verified_before(target_height)
+            .map(|lb| lb.height())
+            .unwrap();
+
+        // Besides the next height, which is about to be fetched, the height scheduled next once
+        // the light block at the next height is verified, or when it cannot be trusted, like
+        // `basic_bisecting_schedule` does
+        let mut heights = vec![next_height, target_height];
+        if trusted_height < next_height {
+            heights.push(midpoint(trusted_height, next_height));
+        }
+
+        heights.sort();
+        heights.dedup();
+        heights.retain(|&height| {
+            height > trusted_height && light_store.get_non_failed(height).is_none()
+        });
+
+        self.io.prefetch(&heights);
+
+        next_height
+    }
+}
+
+/// A bisecting scheduler, scheduling the same heights as [`basic_bisecting_schedule`], along with
+/// the `Io` component wrapping the given one from which it prefetches the light blocks it is
+/// likely to schedule next, to be handed to the light client together.
+pub fn prefetching_bisecting_schedule(
+    io: impl Io + 'static,
+) -> (PrefetchingSchedule, PrefetchingIo) {
+    let io = PrefetchingIo::new(io);
+    let scheduler = PrefetchingSchedule::new(basic_bisecting_schedule, io.clone());
+
+    (scheduler, io)
+}
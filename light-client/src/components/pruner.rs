@@ -0,0 +1,122 @@
+//! Provides pruning policies for the light store, so that long-running
+//! light clients do not grow their store unboundedly.
+
+use core::time::Duration;
+
+use tendermint::Time;
+
+use crate::{
+    store::LightStore,
+    verifier::types::{Height, LightBlock, Status},
+};
+
+/// Policy deciding which light blocks to remove from the light store
+/// after each successful verification.
+///
+/// The default policy never prunes anything. The policies can be combined,
+/// in which case a light block gets pruned as soon as one of them would prune it.
+///
+/// Whatever the policy, the highest trusted or verified light block, as well as the
+/// light block which was just verified, are always kept, so that the light client
+/// can carry on verifying from them.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PruningPolicy {
+    /// Keep only the given number of highest trusted or verified light blocks,
+    /// along with the light blocks of any other status above the lowest of them
+    pub keep_last: Option<usize>,
+
+    /// Keep only the light blocks whose header is not older than the given duration
+    pub keep_for: Option<Duration>,
+
+    /// Keep only the verification checkpoints, ie. the trusted and verified light blocks,
+    /// and remove the unverified and failed light blocks fetched during bisection
+    pub keep_checkpoints_only: bool,
+}
+
+impl PruningPolicy {
+    /// A policy which keeps only the given number of highest trusted or verified light blocks.
+    pub fn keep_last(count: usize) -> Self {
+        Self {
+            keep_last: Some(count),
+            ..Self::default()
+        }
+    }
+
+    /// A policy which keeps only the light blocks whose header is not older
+    /// than the given duration.
+    pub fn keep_for(duration: Duration) -> Self {
+        Self {
+            keep_for: Some(duration),
+            ..Self::default()
+        }
+    }
+
+    /// A policy which keeps only the trusted and verified light blocks.
+    pub fn keep_checkpoints_only() -> Self {
+        Self {
+            keep_checkpoints_only: true,
+            ..Self::default()
+        }
+    }
+
+    /// Whether this policy ever prunes any light block.
+    pub fn is_enabled(&self) -> bool {
+        self.keep_last.is_some() || self.keep_for.is_some() || self.keep_checkpoints_only
+    }
+
+    /// Remove the light blocks which this policy does not keep from the given light store,
+    /// at the given time, except for the light block at the `verified` height.
+    ///
+    /// Returns the number of light blocks which were removed.
+    pub fn prune(&self, light_store: &mut dyn LightStore, now: Time, verified: Height) -> usize {
+        if !self.is_enabled() {
+            return 0;
+        }
+
+        let Some(highest) = light_store.highest_trusted_or_verified() else {
+            return 0;
+        };
+
+        let lowest_kept = self.keep_last.and_then(|count| {
+            let mut heights = light_store
+                .heights(Status::Trusted)
+                .chain(light_store.heights(Status::Verified))
+                .collect::<Vec<_>>();
+
+            heights.sort_unstable_by(|a, b| b.cmp(a));
+            heights.get(count.max(1) - 1).copied()
+        });
+
+        let oldest_kept = self.keep_for.and_then(|duration| now.checked_sub(duration));
+
+        let is_stale = |light_block: &LightBlock, status: Status| {
+            let is_pinned = matches!(status, Status::Trusted | Status::Verified)
+                && (light_block.height() == highest.height() || light_block.height() == verified);
+
+            if is_pinned {
+                return false;
+            }
+
+            (self.keep_checkpoints_only && matches!(status, Status::Unverified | Status::Failed))
+                || lowest_kept.is_some_and(|height| light_block.height() < height)
+                || oldest_kept.is_some_and(|time| light_block.time() < time)
+        };
+
+        let mut pruned = 0;
+
+        for status in Status::iter() {
+            let stale = light_store
+                .all(*status)
+                .filter(|light_block| is_stale(light_block, *status))
+                .map(|light_block| light_block.height())
+                .collect::<Vec<_>>();
+
+            for height in stale {
+                light_store.remove(height, *status);
+                pruned += 1;
+            }
+        }
+
+        pruned
+    }
+}
@@ -1,10 +1,32 @@
-//! Provides an interface and default implementation of the `Scheduler` component
+//! Provides an interface and implementations of the `Scheduler` component
+//!
+//! Besides [`basic_bisecting_schedule`], which is used by default, the following schedulers
+//! are available, and can be set with `LightClientBuilder::scheduler`:
+//!
+//! - [`exponential_probing_schedule`], which only skips ahead by twice the span verified so far;
+//! - [`BoundedStepSchedule`], which never skips ahead by more than a given number of blocks;
+//! - [`ValidatorSetChangeSchedule`], which jumps to the known heights at which the validator
+//!   set changes.
+//! - [`trace_scheduler`], which only picks heights that are part of a trace of light blocks,
+//!   such as the one served by [`TraceIo`](crate::components::io::TraceIo).
+//! - [`prefetching_bisecting_schedule`](crate::components::prefetch::prefetching_bisecting_schedule),
+//!   which bisects like [`basic_bisecting_schedule`] while fetching the light blocks it is likely
+//!   to pick next in the background.
+//!
+//! Custom schedulers can be checked against the specification with [`check_scheduler`].
 
-use core::convert::TryInto;
+use core::{convert::TryInto, fmt};
+use std::{
+    collections::BTreeSet,
+    panic::{self, AssertUnwindSafe},
+};
 
 use contracts::*;
 
-use crate::{store::LightStore, verifier::types::Height};
+use crate::{
+    store::{memory::MemoryStore, LightStore},
+    verifier::types::{Height, LightBlock, Status},
+};
 
 /// The scheduler decides what block to verify next given the current and target heights.
 ///
@@ -75,6 +97,289 @@ pub fn basic_bisecting_schedule(
     }
 }
 
+/// A bisecting scheduler which only picks heights that are part of the given trace,
+/// when possible.
+///
+/// When the block at the current height cannot be trusted yet, the scheduler picks
+/// the highest height of the trace between the trusted height and the current height,
+/// and otherwise falls back to [`basic_bisecting_schedule`].
+pub fn trace_scheduler(heights: Vec<Height>) -> impl Scheduler {
+    move |light_store: &dyn LightStore, current_height: Height, target_height: Height| {
+        let trusted_height = light_store
+            .highest_trusted_or_verified_before(target_height)
+            .map(|lb| lb.height());
+
+        let next_height = trusted_height.and_then(|trusted_height| {
+            heights
+                .iter()
+                .rev()
+                .find(|&&h| trusted_height < h && h < current_height)
+                .copied()
+        });
+
+        match next_height {
+            Some(height) if trusted_height != Some(current_height) => height,
+            _ => basic_bisecting_schedule(light_store, current_height, target_height),
+        }
+    }
+}
+
+/// Exponential probing scheduler, which skips ahead by twice the span verified so far,
+/// instead of trying to jump straight to the target height.
+///
+/// The span verified so far goes from the lowest to the highest trusted or verified block
+/// in the light store, so that the light client starts with small steps, and grows them
+/// as long as verification succeeds. When a block cannot be verified, this scheduler
+/// falls back to bisecting like [`basic_bisecting_schedule`].
+///
+/// ## Precondition
+/// - The light store contains at least one verified block. [LCV-SCHEDULE-PRE.1]
+///
+/// ## Postcondition
+/// - The resulting height must be valid according to `valid_schedule`. [LCV-SCHEDULE-POST.1]
+#[requires(light_store.highest_trusted_or_verified().is_some())]
+#[ensures(valid_schedule(ret, target_height, current_height, light_store))]
+pub fn exponential_probing_schedule(
+    light_store: &dyn LightStore,
+    current_height: Height,
+    target_height: Height,
+) -> Height {
+    let trusted_height = light_store
+        .highest_trusted_or_verified_before(target_height)
+        .map(|lb| lb.height())
+        .unwrap();
+
+    if trusted_height != current_height {
+        return basic_bisecting_schedule(light_store, current_height, target_height);
+    }
+
+    let lowest_height = light_store
+        .lowest_trusted_or_verified()
+        .map_or(trusted_height, |lb| lb.height());
+
+    let span = trusted_height.value() - lowest_height.value().min(trusted_height.value());
+    skip_ahead(trusted_height, span.max(1), target_height)
+}
+
+/// Scheduler which never skips ahead by more than `max_step` blocks from the highest
+/// trusted or verified block, so that the light client verifies at least one header in
+/// every window of `max_step` blocks. With a `max_step` of 1, verification is sequential.
+///
+/// When a block cannot be verified, this scheduler falls back to bisecting like
+/// [`basic_bisecting_schedule`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BoundedStepSchedule {
+    max_step: u64,
+}
+
+/// The scheduler used in [`VerificationMode::Sequential`](crate::verifier::options::VerificationMode::Sequential).
+pub(crate) const SEQUENTIAL_SCHEDULE: BoundedStepSchedule = BoundedStepSchedule { max_step: 1 };
+
+impl BoundedStepSchedule {
+    /// Create a scheduler skipping ahead by at most `max_step` blocks, which must be positive.
+    #[requires(max_step > 0)]
+    pub fn new(max_step: u64) -> Self {
+        Self { max_step }
+    }
+
+    /// Create a scheduler which verifies every header sequentially.
+    pub fn sequential() -> Self {
+        Self::new(1)
+    }
+
+    /// The maximum number of blocks this scheduler skips ahead by.
+    pub fn max_step(&self) -> u64 {
+        self.max_step
+    }
+}
+
+#[contract_trait]
+impl Scheduler for BoundedStepSchedule {
+    fn schedule(
+        &self,
+        light_store: &dyn LightStore,
+        current_height: Height,
+        target_height: Height,
+    ) -> Height {
+        let trusted_height = light_store
+            .highest_trusted_or_verified_before(target_height)
+            .map(|lb| lb.height())
+            .unwrap();
+
+        if trusted_height == current_height {
+            skip_ahead(trusted_height, self.max_step, target_height)
+        } else {
+            basic_bisecting_schedule(light_store, current_height, target_height)
+        }
+    }
+}
+
+/// Scheduler which makes use of the heights at which the validator set of the chain is known
+/// to change, ie. the heights of the first blocks with a different validator set than the
+/// previous block.
+///
+/// Between two such heights, the validator set stays the same, so skipping ahead to the block
+/// right before the next change always has enough trust, and the block at which the validator
+/// set changes can then be verified sequentially, without having to bisect. Past the last
+/// known change, this scheduler jumps straight to the target height, and when a block cannot
+/// be verified, it falls back to bisecting like [`basic_bisecting_schedule`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidatorSetChangeSchedule {
+    change_heights: BTreeSet<Height>,
+}
+
+impl ValidatorSetChangeSchedule {
+    /// Create a scheduler for a chain whose validator set changes at the given heights.
+    pub fn new(change_heights: impl IntoIterator<Item = Height>) -> Self {
+        Self {
+            change_heights: change_heights.into_iter().collect(),
+        }
+    }
+
+    /// Record that the validator set of the chain changes at the given height.
+    pub fn add_change_height(&mut self, height: Height) {
+        self.change_heights.insert(height);
+    }
+
+    /// The heights at which the validator set of the chain is known to change.
+    pub fn change_heights(&self) -> impl Iterator<Item = Height> + '_ {
+        self.change_heights.iter().copied()
+    }
+}
+
+#[contract_trait]
+impl Scheduler for ValidatorSetChangeSchedule {
+    fn schedule(
+        &self,
+        light_store: &dyn LightStore,
+        current_height: Height,
+        target_height: Height,
+    ) -> Height {
+        let trusted_height = light_store
+            .highest_trusted_or_verified_before(target_height)
+            .map(|lb| lb.height())
+            .unwrap();
+
+        if trusted_height != current_height {
+            return basic_bisecting_schedule(light_store, current_height, target_height);
+        }
+
+        let next_change = self
+            .change_heights
+            .range(trusted_height.increment()..)
+            .next()
+            .copied();
+
+        match next_change {
+            Some(change_height) if change_height <= target_height => {
+                let before_change = (change_height.value() - 1).try_into().unwrap();
+
+                if before_change > trusted_height {
+                    before_change
+                } else {
+                    change_height
+                }
+            },
+            _ => target_height,
+        }
+    }
+}
+
+/// A violation of the specification of schedulers found by [`check_scheduler`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ScheduleViolation {
+    /// Height of the highest trusted or verified block in the light store
+    pub trusted_height: Height,
+    /// Height of the block which was just verified, or failed to be
+    pub current_height: Height,
+    /// Height of the block to verify
+    pub target_height: Height,
+    /// Height picked by the scheduler, or `None` if the scheduler panicked, eg. because
+    /// the postcondition of [`Scheduler::schedule`] does not hold
+    pub scheduled_height: Option<Height>,
+}
+
+impl fmt::Display for ScheduleViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.scheduled_height {
+            Some(scheduled_height) => write!(f, "scheduled height {scheduled_height} is invalid")?,
+            None => write!(f, "scheduler panicked")?,
+        }
+
+        write!(
+            f,
+            " with trusted height {}, current height {} and target height {}",
+            self.trusted_height, self.current_height, self.target_height
+        )
+    }
+}
+
+/// Check that the given scheduler satisfies the specification of schedulers, as given by
+/// [`valid_schedule`], for every combination of trusted, current and target heights between 1
+/// and `max_height`, and return the first violation found, if any. A scheduler which panics,
+/// eg. because the contracts of [`Scheduler::schedule`] are checked, is reported as such.
+///
+/// The block right after the trusted one is verified sequentially, so it can never lack trust,
+/// hence a block at the current height is only considered to have failed verification when
+/// there is at least one height between the trusted and the current height to schedule.
+///
+/// The light store given to the scheduler only contains copies of the given light block,
+/// at the trusted height and, for schedulers which depend on the lowest trusted block,
+/// at height 1, with the `Trusted` status.
+pub fn check_scheduler(
+    scheduler: &dyn Scheduler,
+    light_block: &LightBlock,
+    max_height: u64,
+) -> Result<(), ScheduleViolation> {
+    let at_height = |height: u64| {
+        let mut light_block = light_block.clone();
+        light_block.signed_header.header.height = height.try_into().unwrap();
+        light_block
+    };
+
+    for trusted in 1..=max_height {
+        let mut light_store = MemoryStore::new();
+        light_store.insert(at_height(1), Status::Trusted);
+        light_store.insert(at_height(trusted), Status::Trusted);
+
+        for target in trusted..=max_height {
+            for current in trusted..=target {
+                if current == trusted + 1 {
+                    continue;
+                }
+
+                let [trusted_height, current_height, target_height]: [Height; 3] =
+                    [trusted, current, target].map(|h| h.try_into().unwrap());
+
+                let scheduled_height = panic::catch_unwind(AssertUnwindSafe(|| {
+                    scheduler.schedule(&light_store, current_height, target_height)
+                }))
+                .ok();
+
+                let valid = scheduled_height.is_some_and(|scheduled_height| {
+                    valid_schedule(
+                        scheduled_height,
+                        target_height,
+                        current_height,
+                        &light_store,
+                    )
+                });
+
+                if !valid {
+                    return Err(ScheduleViolation {
+                        trusted_height,
+                        current_height,
+                        target_height,
+                        scheduled_height,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Checks whether the given `scheduled_height` is a valid schedule according to the
 /// following specification.
 ///
@@ -120,9 +425,22 @@ pub fn valid_schedule(
     }
 }
 
+/// The height `step` blocks after the `trusted_height`, but not past the `target_height`.
+#[requires(trusted_height <= target_height && step > 0)]
+#[ensures(ret <= target_height && (trusted_height < ret || ret == target_height))]
+fn skip_ahead(trusted_height: Height, step: u64, target_height: Height) -> Height {
+    let height = trusted_height.value().saturating_add(step);
+
+    if height >= target_height.value() {
+        target_height
+    } else {
+        height.try_into().unwrap()
+    }
+}
+
 #[requires(low <= high)]
 #[ensures(low <= ret && ret <= high)]
-fn midpoint(low: Height, high: Height) -> Height {
+pub(crate) fn midpoint(low: Height, high: Height) -> Height {
     (low.value() + (high.value() + 1 - low.value()) / 2)
         .try_into()
         .unwrap() // Will panic if midpoint is higher than i64::MAX
@@ -4,10 +4,12 @@ use std::{fmt::Debug, time::Duration};
 
 use crossbeam_channel as crossbeam;
 use flex_error::{define_error, DisplayError, TraceError};
+use tendermint::chain;
 
 // Re-export for backward compatibility
 pub use crate::verifier::errors::ErrorExt;
 use crate::{
+    budget::{BudgetLimit, Progress},
     components::io::IoError,
     verifier::{
         errors::VerificationErrorDetail,
@@ -23,6 +25,12 @@ type SledError = TraceError<sled::Error>;
 #[cfg(not(feature = "sled"))]
 type SledError = flex_error::NoSource;
 
+#[cfg(feature = "snap")]
+type SnappyError = TraceError<snap::Error>;
+
+#[cfg(not(feature = "snap"))]
+type SnappyError = flex_error::NoSource;
+
 define_error! {
     #[derive(Debug)]
     Error {
@@ -95,10 +103,70 @@ define_error! {
                     e.target_height, e.trusted_height)
             },
 
+        BudgetExceeded
+            {
+                limit: BudgetLimit,
+                progress: Progress,
+            }
+            | e | {
+                format_args!("verification budget exceeded ({0}) after reaching height {1} on the way to target height {2}",
+                    e.limit, e.progress.verified_height, e.progress.target_height)
+            },
+
         InvalidLightBlock
             [ DisplayError<VerificationErrorDetail> ]
             | _ | { "invalid light block" },
 
+        VoteExtensionsDisabled
+            | _ | { "verification of vote extensions is not enabled" },
+
+        InvalidVoteExtensions
+            [ DisplayError<VerificationErrorDetail> ]
+            | _ | { "invalid vote extensions" },
+
+        NoConflictingBlock
+            | _ | { "no conflicting light blocks at the same height in the traces" },
+
+        NoCommonBlock
+            { height: Height }
+            | e | {
+                format_args!("the light block from which the conflicting light block at height {0} was verified is not part of the trusted trace",
+                    e.height)
+            },
+
+        TargetBeyondUpgradeHeight
+            {
+                target_height: Height,
+                upgrade_height: Height,
+            }
+            | e | {
+                format_args!("target height ({0}) is beyond the height of the chain upgrade ({1})",
+                    e.target_height, e.upgrade_height)
+            },
+
+        NoChainUpgrade
+            | _ | { "no chain upgrade" },
+
+        InvalidUpgradeHeight
+            {
+                height: Height,
+                upgrade_height: Height,
+            }
+            | e | {
+                format_args!("light block at height {0} is not the last block before the chain upgrade at height {1}",
+                    e.height, e.upgrade_height)
+            },
+
+        InvalidUpgradeChainId
+            {
+                chain_id: chain::Id,
+                successor_chain_id: chain::Id,
+            }
+            | e | {
+                format_args!("light block after the chain upgrade has chain id {0} instead of {1}",
+                    e.chain_id, e.successor_chain_id)
+            },
+
         InvalidAdjacentHeaders
             {
                 hash1: Hash,
@@ -127,6 +195,17 @@ define_error! {
             [ TraceError<serde_cbor::Error> ]
             | _ | { "serde cbor error" },
 
+        Snappy
+            [ SnappyError ]
+            | _ | { "snappy compression error" },
+
+        UnknownStorageFormat
+            { version: u8 }
+            | e | {
+                format_args!("unknown storage format version {0}",
+                    e.version)
+            },
+
     }
 }
 
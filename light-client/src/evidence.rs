@@ -0,0 +1,192 @@
+//! Construction of the evidence of an attack on the light client, from the conflicting
+//! light blocks verified by two different peers.
+
+use std::cmp::Ordering;
+
+use tendermint::{
+    block::signed_header::SignedHeader,
+    evidence::{ConflictingBlock, LightClientAttackEvidence},
+    validator,
+};
+
+use crate::{
+    errors::Error,
+    verifier::{misbehaviour::AttackType, types::LightBlock},
+};
+
+/// Form the evidence of the attack which resulted in the given conflicting traces,
+/// eg. the ones used by two light clients connected to different peers to verify
+/// the headers they disagree on.
+///
+/// Each trace holds the light blocks verified to reach a header, in any order, starting
+/// from a common trusted light block. The conflicting light block is the first light block
+/// of `conflicting_trace` which differs from the light block at the same height in
+/// `trace`. The light block it was verified from, ie. the one before it in
+/// `conflicting_trace`, must be part of `trace` too, as it is the last light block both
+/// traces agree on.
+///
+/// The evidence is then formed with [`make_evidence`], holding `trace` as the source of truth.
+#[allow(clippy::result_large_err)]
+pub fn evidence_from_traces(
+    trace: &[LightBlock],
+    conflicting_trace: &[LightBlock],
+) -> Result<LightClientAttackEvidence, Error> {
+    let mut conflicting_trace = conflicting_trace.to_vec();
+    conflicting_trace.sort_unstable_by_key(|lb| lb.height());
+
+    let block_at = |height| trace.iter().find(|lb| lb.height() == height);
+
+    let (index, conflicted, trusted) = conflicting_trace
+        .iter()
+        .enumerate()
+        .find_map(|(index, conflicted)| {
+            block_at(conflicted.height())
+                .filter(|trusted| trusted.signed_header != conflicted.signed_header)
+                .map(|trusted| (index, conflicted, trusted))
+        })
+        .ok_or_else(Error::no_conflicting_block)?;
+
+    let common = index
+        .checked_sub(1)
+        .map(|index| &conflicting_trace[index])
+        .filter(|common| {
+            block_at(common.height()).is_some_and(|lb| lb.signed_header == common.signed_header)
+        })
+        .ok_or_else(|| Error::no_common_block(conflicted.height()))?;
+
+    Ok(make_evidence(
+        conflicted.clone(),
+        trusted.clone(),
+        common.clone(),
+    ))
+}
+
+/// Determines the type of attack and then forms the evidence filling out
+/// all the fields such that it is ready to be sent to a full node.
+///
+/// The `conflicted` light block conflicts with the `trusted` light block at the same height,
+/// and was verified from the `common` light block, which is trusted too.
+pub fn make_evidence(
+    conflicted: LightBlock,
+    trusted: LightBlock,
+    common: LightBlock,
+) -> LightClientAttackEvidence {
+    let attack_type = AttackType::classify(&trusted.signed_header, &conflicted.signed_header);
+
+    let conflicting_block = ConflictingBlock {
+        signed_header: conflicted.signed_header,
+        validator_set: conflicted.validators,
+    };
+
+    let byzantine_validators = get_byzantine_validators(
+        attack_type,
+        &conflicting_block,
+        &common.validators,
+        &trusted.signed_header,
+    );
+
+    let witness = if attack_type == AttackType::Lunatic {
+        common
+    } else {
+        trusted
+    };
+
+    LightClientAttackEvidence {
+        conflicting_block,
+        byzantine_validators,
+        common_height: witness.height(),
+        total_voting_power: witness.validators.total_voting_power(),
+        timestamp: witness.time(),
+    }
+}
+
+/// Find out what style of attack `LightClientAttackEvidence` was and then works out who
+/// the malicious validators were and returns them. This is used both for forming the `byzantine_validators`
+/// field and for validating that it is correct. Validators are ordered based on validator power.
+fn get_byzantine_validators(
+    attack_type: AttackType,
+    conflicted: &ConflictingBlock,
+    common_validators: &validator::Set,
+    trusted: &SignedHeader,
+) -> Vec<validator::Info> {
+    match attack_type {
+        // The header is invalid, and we therefore take the validators who are in the
+        // `common_validators` and voted for the lunatic header
+        AttackType::Lunatic => find_lunatic_validators(conflicted, common_validators),
+
+        // Both commits are in the same round. We then find the validators from the conflicting
+        // light block validator set that voted in both headers.
+        // Validator hashes are the same therefore the indexing order of validators are the same and thus we
+        // only need a single loop to find the validators that voted twice.
+        AttackType::Equivocation => find_equivocating_validators(conflicted, trusted),
+
+        // Unfortunately, given the nature of the attack, we aren't able yet to deduce which are
+        // malicious validators and which are not hence we return an empty validator set.
+        AttackType::Amnesia => Vec::new(),
+    }
+}
+
+fn find_lunatic_validators(
+    conflicted: &ConflictingBlock,
+    common_validators: &validator::Set,
+) -> Vec<validator::Info> {
+    let mut validators = Vec::new();
+
+    for commit_sig in &conflicted.signed_header.commit.signatures {
+        if !commit_sig.is_commit() {
+            continue;
+        }
+
+        let validator = commit_sig
+            .validator_address()
+            .and_then(|addr| common_validators.validator(addr));
+
+        if let Some(validator) = validator {
+            validators.push(validator);
+        }
+    }
+
+    validators.sort_by(cmp_voting_power_then_address);
+    validators
+}
+
+fn find_equivocating_validators(
+    conflicted: &ConflictingBlock,
+    trusted: &SignedHeader,
+) -> Vec<validator::Info> {
+    let mut validators = Vec::new();
+
+    for (i, sig_a) in conflicted
+        .signed_header
+        .commit
+        .signatures
+        .iter()
+        .enumerate()
+    {
+        if sig_a.is_absent() {
+            continue;
+        }
+
+        let sig_b = &trusted.commit.signatures[i];
+        if sig_b.is_nil() {
+            continue;
+        }
+
+        let validator = sig_a
+            .validator_address()
+            .and_then(|addr| conflicted.validator_set.validator(addr));
+
+        if let Some(validator) = validator {
+            validators.push(validator);
+        }
+    }
+
+    validators.sort_by(cmp_voting_power_then_address);
+    validators
+}
+
+fn cmp_voting_power_then_address(a: &validator::Info, b: &validator::Info) -> Ordering {
+    a.power
+        .cmp(&b.power)
+        .then_with(|| a.address.cmp(&b.address))
+}
@@ -1,14 +1,43 @@
 //! Supervisor and Handle implementation.
 
+use core::{
+    mem,
+    ops::{Deref, DerefMut, Range},
+    time::Duration,
+};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
 use tendermint::block::Height;
 
 use crate::{
+    audit::AuditLog,
+    budget::Budget,
+    components::observer::Observer,
     errors::Error,
-    light_client::LightClient,
-    state::State,
-    verifier::types::{LightBlock, Status},
+    light_client::{AsyncLightClient, LightClient},
+    state::{Checkpoint, State, VerificationTrace},
+    store::{memory::MemoryStore, overlay::OverlayStore, LightStore},
+    verifier::{
+        options::Options,
+        types::{LightBlock, Status, Time, TrustThreshold, VoteExtension},
+    },
 };
 
+/// A snapshot of the trusted and verified light blocks of an instance, along with the
+/// options of its light client, as produced by [`Instance::export_state`], to migrate a
+/// light node to another machine, or seed new instances with it.
+///
+/// The light blocks of a snapshot are trusted as they are when importing it, so that
+/// snapshots must only be imported from trusted sources.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// The options of the light client
+    pub options: Options,
+    /// The trusted and verified light blocks, with their status, sorted by height
+    pub light_blocks: Vec<(LightBlock, Status)>,
+}
+
 /// A light client `Instance` packages a `LightClient` together with its `State`.
 #[derive(Debug)]
 pub struct Instance {
@@ -38,11 +67,62 @@ impl Instance {
         self.state.light_store.highest(Status::Trusted)
     }
 
+    /// Get the latest trusted block whose header time is at or before the given time.
+    pub fn latest_trusted_at(&self, time: Time) -> Option<LightBlock> {
+        self.state.light_store.latest_trusted_at(time)
+    }
+
     /// Trust the given block.
     pub fn trust_block(&mut self, lb: &LightBlock) {
         self.state.light_store.update(lb, Status::Trusted);
     }
 
+    /// Register an observer to notify of the progress of the light client of this instance.
+    pub fn add_observer(&mut self, observer: impl Observer + 'static) {
+        self.light_client.add_observer(observer);
+    }
+
+    /// Export the trusted and verified light blocks of this instance, along with
+    /// the options of its light client.
+    pub fn export_state(&self) -> Snapshot {
+        export_state(self.light_client.options, self.state.light_store.as_ref())
+    }
+
+    /// Import the light blocks and options of the given snapshot, and return the number
+    /// of light blocks imported.
+    ///
+    /// The options of the light client are replaced by the ones of the snapshot. Light blocks
+    /// which are already in the light store with the same or a more trusted status are left
+    /// as they are.
+    pub fn import_state(&mut self, snapshot: Snapshot) -> usize {
+        self.light_client.options = snapshot.options;
+        import_state(snapshot.light_blocks, self.state.light_store.as_mut())
+    }
+
+    /// How long to wait from now for the refresh of the trusted state to be due.
+    ///
+    /// See [`LightClient::refresh_delay`] for details.
+    pub fn refresh_delay(&self, margin: Duration) -> Option<Duration> {
+        self.light_client.refresh_delay(&self.state, margin)
+    }
+
+    /// Verify the highest header of the primary if the trusted state expires within
+    /// the given margin.
+    ///
+    /// See [`LightClient::refresh_trusted_state`] for details.
+    pub fn refresh_trusted_state(&mut self, margin: Duration) -> Result<Option<LightBlock>, Error> {
+        self.light_client
+            .refresh_trusted_state(margin, &mut self.state)
+    }
+
+    /// Verify the first header of the primary after the chain upgrade against the last
+    /// header before it.
+    ///
+    /// See [`LightClient::hand_off_upgrade`] for details.
+    pub fn hand_off_upgrade(&mut self) -> Result<LightBlock, Error> {
+        self.light_client.hand_off_upgrade(&mut self.state)
+    }
+
     /// Get or fetch the block at the given height
     pub fn get_or_fetch_block(&mut self, height: Height) -> Result<LightBlock, Error> {
         let (block, _) = self
@@ -62,4 +142,393 @@ impl Instance {
 
         Ok(block)
     }
+
+    /// Fork this instance speculatively, so that the light blocks verified, trusted or removed
+    /// through the returned [`Speculation`] can be either committed to the light store of this
+    /// instance or discarded, eg. to check what would happen when trusting a header.
+    ///
+    /// The light store is not copied, but layered with a copy-on-write overlay for as long as
+    /// the speculation lasts.
+    pub fn speculative(&mut self) -> Speculation<'_> {
+        let base = mem::replace(&mut self.state.light_store, Box::new(MemoryStore::new()));
+        let overlay = SharedOverlay(Arc::new(Mutex::new(OverlayStore::new(base))));
+
+        self.state.light_store = Box::new(overlay.clone());
+
+        Speculation {
+            verification_trace: self.state.verification_trace.clone(),
+            instance: self,
+            overlay: Some(overlay),
+        }
+    }
+
+    /// Verify the headers at all the given heights, in ascending order, so that each
+    /// verification starts from the highest light block verified by the previous ones
+    /// instead of bisecting again from the same trusted state.
+    ///
+    /// Returns the result of the verification of each height, sorted by height and without
+    /// duplicates. Failing to verify a height does not prevent verifying the next ones.
+    pub fn verify_to_heights(
+        &mut self,
+        heights: &[Height],
+    ) -> Vec<(Height, Result<LightBlock, Error>)> {
+        sorted_heights(heights)
+            .into_iter()
+            .map(|height| {
+                let result = self.light_client.verify_to_target(height, &mut self.state);
+                (height, result)
+            })
+            .collect()
+    }
+}
+
+/// A speculative fork of an [`Instance`], as returned by [`Instance::speculative`], which
+/// dereferences to the instance.
+///
+/// The changes made to the state of the instance through the speculation are only kept when
+/// committing it with [`Speculation::commit`], and are discarded otherwise, including when
+/// the speculation is dropped. Changes made to the light client itself, such as to its
+/// options, are always kept.
+#[derive(Debug)]
+pub struct Speculation<'a> {
+    instance: &'a mut Instance,
+    overlay: Option<SharedOverlay>,
+    verification_trace: VerificationTrace,
+}
+
+impl Speculation<'_> {
+    /// Apply the changes made to the state of the instance during the speculation.
+    pub fn commit(mut self) {
+        self.finish(true);
+    }
+
+    /// Discard the changes made to the state of the instance during the speculation.
+    pub fn discard(mut self) {
+        self.finish(false);
+    }
+
+    fn finish(&mut self, commit: bool) {
+        let Some(overlay) = self.overlay.take() else {
+            return;
+        };
+
+        let overlay = mem::replace(
+            &mut *overlay.lock(),
+            OverlayStore::new(Box::new(MemoryStore::new())),
+        );
+
+        if commit {
+            self.instance.state.light_store = overlay.commit();
+        } else {
+            self.instance.state.light_store = overlay.discard();
+            self.instance.state.verification_trace = mem::take(&mut self.verification_trace);
+        }
+    }
+}
+
+impl Deref for Speculation<'_> {
+    type Target = Instance;
+
+    fn deref(&self) -> &Instance {
+        self.instance
+    }
+}
+
+impl DerefMut for Speculation<'_> {
+    fn deref_mut(&mut self) -> &mut Instance {
+        self.instance
+    }
+}
+
+impl Drop for Speculation<'_> {
+    fn drop(&mut self) {
+        self.finish(false);
+    }
+}
+
+/// The overlay of a [`Speculation`], shared with the state of its instance.
+#[derive(Clone, Debug)]
+struct SharedOverlay(Arc<Mutex<OverlayStore>>);
+
+impl SharedOverlay {
+    fn lock(&self) -> MutexGuard<'_, OverlayStore> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl LightStore for SharedOverlay {
+    fn get(&self, height: Height, status: Status) -> Option<LightBlock> {
+        self.lock().get(height, status)
+    }
+
+    fn update(&mut self, light_block: &LightBlock, status: Status) {
+        self.lock().update(light_block, status)
+    }
+
+    fn insert(&mut self, light_block: LightBlock, status: Status) {
+        self.lock().insert(light_block, status)
+    }
+
+    fn remove(&mut self, height: Height, status: Status) {
+        self.lock().remove(height, status)
+    }
+
+    fn highest(&self, status: Status) -> Option<LightBlock> {
+        self.lock().highest(status)
+    }
+
+    fn highest_before(&self, height: Height, status: Status) -> Option<LightBlock> {
+        self.lock().highest_before(height, status)
+    }
+
+    fn lowest(&self, status: Status) -> Option<LightBlock> {
+        self.lock().lowest(status)
+    }
+
+    fn all(&self, status: Status) -> Box<dyn Iterator<Item = LightBlock>> {
+        self.lock().all(status)
+    }
+
+    fn range(&self, status: Status, range: Range<Height>) -> Box<dyn Iterator<Item = LightBlock>> {
+        self.lock().range(status, range)
+    }
+
+    fn heights(&self, status: Status) -> Box<dyn Iterator<Item = Height>> {
+        self.lock().heights(status)
+    }
+}
+
+/// An `AsyncInstance` packages an `AsyncLightClient` together with its `State`,
+/// for use within async applications.
+#[derive(Debug)]
+pub struct AsyncInstance {
+    /// The light client for this instance
+    pub light_client: AsyncLightClient,
+
+    /// The state of the light client for this instance
+    pub state: State,
+}
+
+impl AsyncInstance {
+    /// Constructs a new instance from the given light client and its state.
+    pub fn new(light_client: AsyncLightClient, state: State) -> Self {
+        Self {
+            light_client,
+            state,
+        }
+    }
+
+    /// Return the peer id of this instance.
+    pub fn peer_id(&self) -> &tendermint::node::Id {
+        &self.light_client.peer
+    }
+
+    /// Get the latest trusted block.
+    pub fn latest_trusted(&self) -> Option<LightBlock> {
+        self.state.light_store.highest(Status::Trusted)
+    }
+
+    /// Get the latest trusted block whose header time is at or before the given time.
+    pub fn latest_trusted_at(&self, time: Time) -> Option<LightBlock> {
+        self.state.light_store.latest_trusted_at(time)
+    }
+
+    /// Trust the given block.
+    pub fn trust_block(&mut self, lb: &LightBlock) {
+        self.state.light_store.update(lb, Status::Trusted);
+    }
+
+    /// Register an observer to notify of the progress of the light client of this instance.
+    pub fn add_observer(&mut self, observer: impl Observer + 'static) {
+        self.light_client.add_observer(observer);
+    }
+
+    /// Export the trusted and verified light blocks of this instance, along with
+    /// the options of its light client.
+    ///
+    /// See [`Instance::export_state`] for details.
+    pub fn export_state(&self) -> Snapshot {
+        export_state(self.light_client.options, self.state.light_store.as_ref())
+    }
+
+    /// Import the light blocks and options of the given snapshot, and return the number
+    /// of light blocks imported.
+    ///
+    /// See [`Instance::import_state`] for details.
+    pub fn import_state(&mut self, snapshot: Snapshot) -> usize {
+        self.light_client.options = snapshot.options;
+        import_state(snapshot.light_blocks, self.state.light_store.as_mut())
+    }
+
+    /// Verify the header at the given height.
+    pub async fn verify_to_target(&mut self, height: Height) -> Result<LightBlock, Error> {
+        self.light_client
+            .verify_to_target(height, &mut self.state)
+            .await
+    }
+
+    /// Verify the header at the given height with the given trust threshold, if any,
+    /// instead of the one in the options of the light client.
+    ///
+    /// See [`LightClient::verify_to_target_with_trust_threshold`] for details.
+    pub async fn verify_to_target_with_trust_threshold(
+        &mut self,
+        height: Height,
+        trust_threshold: Option<TrustThreshold>,
+    ) -> Result<LightBlock, Error> {
+        self.light_client
+            .verify_to_target_with_trust_threshold(height, trust_threshold, &mut self.state)
+            .await
+    }
+
+    /// Verify the header at the given height and the given vote extensions of its commit.
+    ///
+    /// See [`LightClient::verify_vote_extensions`] for details.
+    pub async fn verify_vote_extensions(
+        &mut self,
+        height: Height,
+        vote_extensions: &[VoteExtension],
+    ) -> Result<LightBlock, Error> {
+        self.light_client
+            .verify_vote_extensions(height, vote_extensions, &mut self.state)
+            .await
+    }
+
+    /// Verify the header at the given height, and return the audit log of the verification
+    /// along with its result.
+    ///
+    /// See [`LightClient::verify_to_target_audited`] for details.
+    pub async fn verify_to_target_audited(
+        &mut self,
+        height: Height,
+    ) -> (Result<LightBlock, Error>, AuditLog) {
+        self.light_client
+            .verify_to_target_audited(height, &mut self.state)
+            .await
+    }
+
+    /// Verify the header at the given height within the given budget.
+    ///
+    /// See [`LightClient::verify_to_target_with_budget`] for details.
+    pub async fn verify_to_target_with_budget(
+        &mut self,
+        height: Height,
+        budget: &Budget,
+    ) -> Result<LightBlock, Error> {
+        self.light_client
+            .verify_to_target_with_budget(height, budget, &mut self.state)
+            .await
+    }
+
+    /// Record the progress of the verification of the header at the given height
+    /// in a checkpoint.
+    ///
+    /// See [`LightClient::checkpoint`] for details.
+    pub fn checkpoint(&self, height: Height) -> Checkpoint {
+        self.light_client.checkpoint(height, &self.state)
+    }
+
+    /// Resume the verification recorded in the given checkpoint.
+    ///
+    /// See [`LightClient::resume_from_checkpoint`] for details.
+    pub async fn resume_from_checkpoint(
+        &mut self,
+        checkpoint: &Checkpoint,
+    ) -> Result<LightBlock, Error> {
+        self.light_client
+            .resume_from_checkpoint(checkpoint, &mut self.state)
+            .await
+    }
+
+    /// Verify the highest header of the primary.
+    pub async fn verify_to_highest(&mut self) -> Result<LightBlock, Error> {
+        self.light_client.verify_to_highest(&mut self.state).await
+    }
+
+    /// How long to wait from now for the refresh of the trusted state to be due.
+    ///
+    /// See [`LightClient::refresh_delay`] for details.
+    pub fn refresh_delay(&self, margin: Duration) -> Option<Duration> {
+        self.light_client.refresh_delay(&self.state, margin)
+    }
+
+    /// Verify the highest header of the primary if the trusted state expires within
+    /// the given margin.
+    ///
+    /// See [`LightClient::refresh_trusted_state`] for details.
+    pub async fn refresh_trusted_state(
+        &mut self,
+        margin: Duration,
+    ) -> Result<Option<LightBlock>, Error> {
+        self.light_client
+            .refresh_trusted_state(margin, &mut self.state)
+            .await
+    }
+
+    /// Verify the first header of the primary after the chain upgrade against the last
+    /// header before it.
+    ///
+    /// See [`LightClient::hand_off_upgrade`] for details.
+    pub async fn hand_off_upgrade(&mut self) -> Result<LightBlock, Error> {
+        self.light_client.hand_off_upgrade(&mut self.state).await
+    }
+
+    /// Verify the headers at all the given heights, in ascending order.
+    ///
+    /// See [`Instance::verify_to_heights`] for details.
+    pub async fn verify_to_heights(
+        &mut self,
+        heights: &[Height],
+    ) -> Vec<(Height, Result<LightBlock, Error>)> {
+        let mut results = Vec::new();
+
+        for height in sorted_heights(heights) {
+            let result = self.verify_to_target(height).await;
+            results.push((height, result));
+        }
+
+        results
+    }
+}
+
+fn export_state(options: Options, light_store: &dyn LightStore) -> Snapshot {
+    // The trusted and verified statuses cannot both hold a light block at the same height
+    let mut light_blocks: Vec<_> = [Status::Trusted, Status::Verified]
+        .into_iter()
+        .flat_map(|status| light_store.all(status).map(move |lb| (lb, status)))
+        .collect();
+    light_blocks.sort_by_key(|(lb, _)| lb.height());
+
+    Snapshot {
+        options,
+        light_blocks,
+    }
+}
+
+fn import_state(
+    light_blocks: Vec<(LightBlock, Status)>,
+    light_store: &mut dyn LightStore,
+) -> usize {
+    let mut count = 0;
+
+    for (light_block, status) in light_blocks {
+        let current = light_store
+            .get_non_failed(light_block.height())
+            .map(|(_, status)| status);
+
+        // Do not downgrade the light blocks which are already more trusted in the light store
+        if current.is_none_or(|current| Status::most_trusted(current, status) != current) {
+            light_store.update(&light_block, status);
+            count += 1;
+        }
+    }
+
+    count
+}
+
+fn sorted_heights(heights: &[Height]) -> Vec<Height> {
+    let mut heights = heights.to_vec();
+    heights.sort_unstable();
+    heights.dedup();
+    heights
 }
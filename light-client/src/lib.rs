@@ -15,14 +15,19 @@
 
 //! See the `light_client` module for the main documentation.
 
+pub mod audit;
+pub mod budget;
 pub mod builder;
 pub mod components;
 pub mod contracts;
 pub mod errors;
+pub mod evidence;
 pub mod instance;
 pub mod light_client;
+pub mod metrics;
 pub mod state;
 pub mod store;
+pub mod upgrade;
 
 pub(crate) mod utils;
 
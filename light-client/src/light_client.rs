@@ -2,19 +2,32 @@
 //!
 //! [1]: https://github.com/informalsystems/tendermint-rs/blob/main/docs/spec/lightclient/verification/verification.md
 
-use core::fmt;
+use core::{fmt, time::Duration};
 
 use contracts::*;
+use tendermint::Time;
 
 // Re-export for backward compatibility
 pub use crate::verifier::options::Options;
 use crate::{
-    components::{clock::Clock, io::*, scheduler::*},
+    audit::{AuditEntry, AuditLog, ScheduleDecision, VerificationRecord},
+    budget::{Budget, BudgetTracker},
+    components::{
+        clock::Clock,
+        io::*,
+        observer::{ClockDrift, Observer, Observers},
+        pruner::PruningPolicy,
+        scheduler::*,
+    },
     contracts::*,
     errors::Error,
-    state::State,
+    metrics,
+    state::{Checkpoint, State},
+    upgrade::ChainUpgrade,
     verifier::{
-        types::{Height, LightBlock, PeerId, Status},
+        errors::VerificationError,
+        options::{VerificationMode, VoteExtensionsOptions},
+        types::{Height, LightBlock, PeerId, Status, TrustThreshold, VoteExtension},
         Verdict, Verifier,
     },
 };
@@ -39,6 +52,11 @@ pub struct LightClient {
     scheduler: Box<dyn Scheduler>,
     verifier: Box<dyn Verifier>,
     io: Box<dyn Io>,
+    pruning_policy: PruningPolicy,
+    observers: Observers,
+    vote_extensions: Option<VoteExtensionsOptions>,
+    chain_upgrade: Option<ChainUpgrade>,
+    clock_drift_warning: Option<Duration>,
 }
 
 impl fmt::Debug for LightClient {
@@ -46,6 +64,10 @@ impl fmt::Debug for LightClient {
         f.debug_struct("LightClient")
             .field("peer", &self.peer)
             .field("options", &self.options)
+            .field("observers", &self.observers)
+            .field("vote_extensions", &self.vote_extensions)
+            .field("chain_upgrade", &self.chain_upgrade)
+            .field("clock_drift_warning", &self.clock_drift_warning)
             .finish()
     }
 }
@@ -67,6 +89,11 @@ impl LightClient {
             scheduler: Box::new(scheduler),
             verifier: Box::new(verifier),
             io: Box::new(io),
+            pruning_policy: PruningPolicy::default(),
+            observers: Observers::default(),
+            vote_extensions: None,
+            chain_upgrade: None,
+            clock_drift_warning: None,
         }
     }
 
@@ -86,10 +113,81 @@ impl LightClient {
             scheduler,
             verifier,
             io,
+            pruning_policy: PruningPolicy::default(),
+            observers: Observers::default(),
+            vote_extensions: None,
+            chain_upgrade: None,
+            clock_drift_warning: None,
         }
     }
 
-    /// Attempt to update the light client to the highest block of the primary node.
+    /// Set the policy deciding which light blocks to prune from the light store
+    /// after each successful verification (default: never prune).
+    pub fn with_pruning_policy(mut self, pruning_policy: PruningPolicy) -> Self {
+        self.pruning_policy = pruning_policy;
+        self
+    }
+
+    /// Register an observer to notify of the progress of this light client.
+    pub fn with_observer(mut self, observer: impl Observer + 'static) -> Self {
+        self.add_observer(observer);
+        self
+    }
+
+    /// Register an observer to notify of the progress of this light client.
+    pub fn add_observer(&mut self, observer: impl Observer + 'static) {
+        self.observers.add(Box::new(observer));
+    }
+
+    pub(crate) fn with_observers(mut self, observers: Observers) -> Self {
+        self.observers = observers;
+        self
+    }
+
+    /// Enable the verification of vote extensions with the given options,
+    /// for chains running CometBFT 0.38 or later.
+    pub fn with_vote_extensions(mut self, options: VoteExtensionsOptions) -> Self {
+        self.vote_extensions = Some(options);
+        self
+    }
+
+    /// Make this light client aware of the given upgrade of the chain, so that it verifies
+    /// the last block before the upgrade instead of skipping across it, and refuses to verify
+    /// any block after it.
+    ///
+    /// See [`LightClient::hand_off_upgrade`] to move on to the chain after the upgrade.
+    pub fn with_chain_upgrade(mut self, upgrade: ChainUpgrade) -> Self {
+        self.chain_upgrade = Some(upgrade);
+        self
+    }
+
+    /// Notify the observers of this light client when the time of a light block it fetched
+    /// is ahead of the local time by more than the given margin, so that operators learn
+    /// about the skew of their clock before the light blocks fail verification, once it
+    /// exceeds the maximum clock drift of the options.
+    ///
+    /// See [`Observer::on_clock_drift`].
+    pub fn with_clock_drift_warning(mut self, margin: Duration) -> Self {
+        self.clock_drift_warning = Some(margin);
+        self
+    }
+
+    /// Notify the observers of this light client that a witness reported a light block
+    /// conflicting with the given light block, verified by this light client.
+    pub fn notify_fork_detected(
+        &self,
+        verified_block: &LightBlock,
+        witness: PeerId,
+        conflicting_block: &LightBlock,
+    ) {
+        metrics::record_fork_detected();
+
+        self.observers
+            .on_fork_detected(verified_block, witness, conflicting_block);
+    }
+
+    /// Attempt to update the light client to the highest block of the primary node,
+    /// or to the last block before the chain upgrade, if any.
     ///
     /// Note: This function delegates the actual work to `verify_to_target`.
     pub fn verify_to_highest(&mut self, state: &mut State) -> Result<LightBlock, Error> {
@@ -98,7 +196,43 @@ impl LightClient {
             .fetch_light_block(AtHeight::Highest)
             .map_err(Error::io)?;
 
-        self.verify_to_target(target_block.height(), state)
+        let target_height = capped_at_upgrade(self.chain_upgrade.as_ref(), target_block.height());
+        self.verify_to_target(target_height, state)
+    }
+
+    /// Verify the last block before the chain upgrade, and then the first block of the
+    /// primary node after the upgrade against it, as if both were part of the same chain.
+    ///
+    /// Returns the first block after the upgrade, which can then be trusted by a light client
+    /// of the chain after the upgrade, to hand off the trusted state of this light client.
+    ///
+    /// ## Error conditions
+    /// - This light client is not aware of any upgrade of the chain
+    /// - The verification of the last block before the upgrade fails
+    /// - The first block after the upgrade does not have the chain id of the chain after the
+    ///   upgrade, or its verification against the last block before the upgrade fails
+    pub fn hand_off_upgrade(&self, state: &mut State) -> Result<LightBlock, Error> {
+        let upgrade = self
+            .chain_upgrade
+            .as_ref()
+            .ok_or_else(Error::no_chain_upgrade)?;
+
+        let last_block = self.verify_to_target(upgrade.height, state)?;
+
+        let first_block = self
+            .io
+            .fetch_light_block(AtHeight::At(upgrade.height.increment()))
+            .map_err(Error::io)?;
+
+        upgrade.verify_hand_off(
+            self.verifier.as_ref(),
+            &self.options,
+            self.clock.now(),
+            &last_block,
+            &first_block,
+        )?;
+
+        Ok(first_block)
     }
 
     /// Update the light client to a block of the primary node at the given height.
@@ -143,12 +277,200 @@ impl LightClient {
         target_height: Height,
         state: &mut State,
     ) -> Result<LightBlock, Error> {
+        self.verify_to_target_with_trust_threshold(target_height, None, state)
+    }
+
+    /// Update the light client to a block of the primary node at the given height, like
+    /// [`LightClient::verify_to_target`] does, and verify that the given vote extensions of
+    /// its commit are correctly signed by enough of its validators.
+    ///
+    /// Vote extensions are not part of light blocks, so they have to be obtained separately,
+    /// eg. from the block proposed at the next height. Fails if the verification of vote
+    /// extensions has not been enabled with [`LightClient::with_vote_extensions`].
+    pub fn verify_vote_extensions(
+        &self,
+        target_height: Height,
+        vote_extensions: &[VoteExtension],
+        state: &mut State,
+    ) -> Result<LightBlock, Error> {
+        let options = self
+            .vote_extensions
+            .as_ref()
+            .ok_or_else(Error::vote_extensions_disabled)?;
+
+        let verified = self.verify_to_target(target_height, state)?;
+        verify_vote_extensions(self.verifier.as_ref(), options, &verified, vote_extensions)?;
+
+        Ok(verified)
+    }
+
+    /// Update the light client to a block of the primary node at the given height, like
+    /// [`LightClient::verify_to_target`] does, but with the given trust threshold, if any,
+    /// instead of the one in the options of this light client.
+    ///
+    /// Light blocks which are already trusted or verified in the light store are not verified
+    /// again with the given trust threshold, and are still used as trusted states to skip
+    /// from: to verify the target with a stricter threshold, use a light store which only
+    /// contains the light blocks trusted with such a threshold.
+    #[allow(clippy::nonminimal_bool)]
+    #[ensures(
+        ret.is_ok() -> trusted_store_contains_block_at_target_height(
+            state.light_store.as_ref(),
+            target_height,
+        )
+    )]
+    pub fn verify_to_target_with_trust_threshold(
+        &self,
+        target_height: Height,
+        trust_threshold: Option<TrustThreshold>,
+        state: &mut State,
+    ) -> Result<LightBlock, Error> {
+        self.verify_to_target_recording(
+            target_height,
+            trust_threshold,
+            state,
+            None,
+            &Budget::default(),
+        )
+    }
+
+    /// Update the light client to a block of the primary node at the given height, like
+    /// [`LightClient::verify_to_target`] does, and return the audit log of the verification
+    /// along with its result.
+    ///
+    /// The audit log records the decisions of the scheduler and every check performed by the
+    /// verifier, along with the light blocks, options and time they were performed with, so that
+    /// it can be replayed with [`audit::replay`](crate::audit::replay). Light blocks which are
+    /// already trusted or verified in the light store are not verified again, and backward
+    /// verification is not recorded.
+    pub fn verify_to_target_audited(
+        &self,
+        target_height: Height,
+        state: &mut State,
+    ) -> (Result<LightBlock, Error>, AuditLog) {
+        let mut audit_log = AuditLog::default();
+        let result = self.verify_to_target_recording(
+            target_height,
+            None,
+            state,
+            Some(&mut audit_log),
+            &Budget::default(),
+        );
+        (result, audit_log)
+    }
+
+    /// Update the light client to a block of the primary node at the given height, like
+    /// [`LightClient::verify_to_target`] does, within the given budget.
+    ///
+    /// The budget bounds the wall time, the number of light blocks verified and the number of
+    /// light blocks fetched from the primary by the forward verification, and lets it be
+    /// cancelled from another thread. It is checked before every step of the verification.
+    ///
+    /// ## Error conditions
+    /// - Those of [`LightClient::verify_to_target`]
+    /// - The budget is exceeded before reaching the target height, in which case the error
+    ///   holds the progress made until then. The light blocks verified until then are kept in
+    ///   the light store, so that verifying the target height again resumes from there.
+    pub fn verify_to_target_with_budget(
+        &self,
+        target_height: Height,
+        budget: &Budget,
+        state: &mut State,
+    ) -> Result<LightBlock, Error> {
+        self.verify_to_target_recording(target_height, None, state, None, budget)
+    }
+
+    /// Record the progress of the verification of the given target height in a checkpoint,
+    /// eg. after the verification was interrupted by an I/O error, so that it can be resumed
+    /// with [`LightClient::resume_from_checkpoint`].
+    ///
+    /// See [`State::checkpoint`] for details.
+    pub fn checkpoint(&self, target_height: Height, state: &State) -> Checkpoint {
+        state.checkpoint(target_height)
+    }
+
+    /// Resume the verification recorded in the given checkpoint, without verifying again
+    /// the light blocks it holds, nor fetching again those it holds already.
+    ///
+    /// See [`State::restore_checkpoint`] for details.
+    pub fn resume_from_checkpoint(
+        &self,
+        checkpoint: &Checkpoint,
+        state: &mut State,
+    ) -> Result<LightBlock, Error> {
+        state.restore_checkpoint(checkpoint);
+        self.verify_to_target(checkpoint.target_height, state)
+    }
+
+    /// Time at which the highest trusted or verified light block of the light store falls
+    /// out of the trusting period, after which the light client cannot verify any light
+    /// block from it anymore, or `None` if the light store does not hold any.
+    pub fn trust_expires_at(&self, state: &State) -> Option<Time> {
+        trust_expires_at(&self.options, state)
+    }
+
+    /// Time from which [`LightClient::refresh_trusted_state`] refreshes the trusted state,
+    /// ie. the given margin before it expires.
+    pub fn refresh_due_at(&self, state: &State, margin: Duration) -> Option<Time> {
+        refresh_due_at(&self.options, state, margin)
+    }
+
+    /// How long to wait from now for the refresh of the trusted state to be due, which is zero
+    /// when it is due already, so that background tasks can schedule the refreshes, eg.:
+    ///
+    /// ```ignore
+    /// loop {
+    ///     let delay = light_client.refresh_delay(&state, margin).unwrap_or(margin);
+    ///     std::thread::sleep(delay);
+    ///     light_client.refresh_trusted_state(margin, &mut state)?;
+    /// }
+    /// ```
+    pub fn refresh_delay(&self, state: &State, margin: Duration) -> Option<Duration> {
+        refresh_delay(&self.options, state, margin, self.clock.now())
+    }
+
+    /// Verify the highest block of the primary node if the trusted state of the light client
+    /// expires within the given margin, so that the trusted state does not expire while the
+    /// light client is idle, eg. over a weekend.
+    ///
+    /// Returns the newly verified light block, or `None` if the refresh was not due yet.
+    ///
+    /// ## Error conditions
+    /// - The light store does not contain any trusted or verified light block
+    /// - The verification of the highest block of the primary node fails
+    pub fn refresh_trusted_state(
+        &mut self,
+        margin: Duration,
+        state: &mut State,
+    ) -> Result<Option<LightBlock>, Error> {
+        if !is_refresh_due(&self.options, state, margin, self.clock.now())? {
+            return Ok(None);
+        }
+
+        self.verify_to_highest(state).map(Some)
+    }
+
+    fn verify_to_target_recording(
+        &self,
+        target_height: Height,
+        trust_threshold: Option<TrustThreshold>,
+        state: &mut State,
+        audit_log: Option<&mut AuditLog>,
+        budget: &Budget,
+    ) -> Result<LightBlock, Error> {
+        check_upgrade_height(self.chain_upgrade.as_ref(), target_height)?;
+
         // Let's first look in the store to see whether
         // we have already successfully verified this block.
-        if let Some(light_block) = state.light_store.get_trusted_or_verified(target_height) {
+        let light_block = state.light_store.get_trusted_or_verified(target_height);
+        metrics::record_store_lookup(light_block.is_some());
+
+        if let Some(light_block) = light_block {
             return Ok(light_block);
         }
 
+        let options = with_trust_threshold(self.options, trust_threshold);
+
         // Get the highest trusted state
         let highest = state
             .light_store
@@ -156,99 +478,98 @@ impl LightClient {
             .or_else(|| state.light_store.lowest_trusted_or_verified())
             .ok_or_else(Error::no_initial_trusted_state)?;
 
-        if target_height >= highest.height() {
+        let stopwatch = metrics::Stopwatch::start();
+
+        let verified = if target_height >= highest.height() {
             // Perform forward verification with bisection
-            self.verify_forward(target_height, state)
+            self.verify_forward(target_height, &options, state, audit_log, budget)
         } else {
             // Perform sequential backward verification
-            self.verify_backward(target_height, state)
-        }
+            self.verify_backward(target_height, &options, state)
+        };
+
+        metrics::record_verification(stopwatch, &verified);
+        let verified = verified?;
+
+        self.observers.on_block_verified(&verified);
+
+        self.pruning_policy.prune(
+            state.light_store.as_mut(),
+            self.clock.now(),
+            verified.height(),
+        );
+
+        Ok(verified)
     }
 
     /// Perform forward verification with bisection.
     fn verify_forward(
         &self,
         target_height: Height,
+        options: &Options,
         state: &mut State,
+        mut audit_log: Option<&mut AuditLog>,
+        budget: &Budget,
     ) -> Result<LightBlock, Error> {
-        let mut current_height = target_height;
+        let scheduler = scheduler_for(self.scheduler.as_ref(), options);
+        let mut budget = BudgetTracker::start(budget);
 
-        loop {
-            let now = self.clock.now();
+        let mut current_height =
+            first_forward_height(scheduler, target_height, state, audit_log.as_deref_mut());
 
-            // Get the latest trusted state
-            let trusted_block = state
-                .light_store
-                .highest_trusted_or_verified_before(target_height)
-                .ok_or_else(Error::no_initial_trusted_state)?;
+        let mut depth = 0;
 
-            if target_height < trusted_block.height() {
-                return Err(Error::target_lower_than_trusted_state(
-                    target_height,
-                    trusted_block.height(),
-                ));
-            }
+        loop {
+            let now = self.clock.now();
 
-            // Check invariant [LCV-INV-TP.1]
-            if !is_within_trust_period(&trusted_block, self.options.trusting_period, now) {
-                return Err(Error::trusted_state_outside_trusting_period(
-                    Box::new(trusted_block),
-                    self.options,
-                ));
-            }
+            let trusted_block = match start_forward_step(
+                &self.observers,
+                options,
+                now,
+                target_height,
+                current_height,
+                state,
+                audit_log.as_deref_mut(),
+            )? {
+                ForwardStep::Done(trusted_block) => {
+                    metrics::record_bisection_depth(depth);
+                    return Ok(trusted_block);
+                },
+                ForwardStep::Verify(trusted_block) => trusted_block,
+            };
 
-            // Log the current height as a dependency of the block at the target height
-            state.trace_block(target_height, current_height);
+            depth += 1;
 
-            // If the trusted state is now at a height equal to the target height, we are done.
-            // [LCV-DIST-LIFE.1]
-            if target_height == trusted_block.height() {
-                return Ok(trusted_block);
-            }
+            // Stop if the budget does not allow for another step, fetching the block if needed.
+            let fetch = state.light_store.get_non_failed(current_height).is_none();
+            budget.charge_step(target_height, trusted_block.height(), fetch)?;
 
             // Fetch the block at the current height from the light store if already present,
             // or from the primary peer otherwise.
-            let (current_block, status) = self.get_or_fetch_block(current_height, state)?;
+            let (current_block, status) =
+                self.get_or_fetch_block_after(current_height, Some(&trusted_block), state)?;
 
-            // Validate and verify the current block
-            let verdict = self.verifier.verify_update_header(
-                current_block.as_untrusted_state(),
-                trusted_block.as_trusted_state(),
-                &self.options,
+            notify_clock_drift(
+                &self.observers,
+                self.clock_drift_warning,
+                &current_block,
                 now,
             );
 
-            match verdict {
-                Verdict::Success => {
-                    // Verification succeeded, add the block to the light store with
-                    // the `Verified` status or higher if already trusted.
-                    let new_status = Status::most_trusted(Status::Verified, status);
-                    state.light_store.update(&current_block, new_status);
-
-                    // Log the trusted height as a dependency of the block at the current height
-                    state.trace_block(current_height, trusted_block.height());
-                },
-                Verdict::Invalid(e) => {
-                    // Verification failed, add the block to the light store with `Failed` status,
-                    // and abort.
-                    state.light_store.update(&current_block, Status::Failed);
-
-                    return Err(Error::invalid_light_block(e));
-                },
-                Verdict::NotEnoughTrust(_) => {
-                    // The current block cannot be trusted because of a missing overlap in the
-                    // validator sets. Add the block to the light store with
-                    // the `Unverified` status. This will engage bisection in an
-                    // attempt to raise the height of the highest trusted state
-                    // until there is enough overlap.
-                    state.light_store.update(&current_block, Status::Unverified);
-                },
-            }
-
             // Compute the next height to fetch and verify
-            current_height =
-                self.scheduler
-                    .schedule(state.light_store.as_ref(), current_height, target_height);
+            current_height = finish_forward_step(
+                self.verifier.as_ref(),
+                &self.observers,
+                scheduler,
+                options,
+                now,
+                &trusted_block,
+                current_block,
+                status,
+                target_height,
+                state,
+                audit_log.as_deref_mut(),
+            )?;
         }
     }
 
@@ -258,6 +579,7 @@ impl LightClient {
     fn verify_backward(
         &self,
         target_height: Height,
+        _options: &Options,
         state: &mut State,
     ) -> Result<LightBlock, Error> {
         let trusted_state = state
@@ -294,6 +616,7 @@ impl LightClient {
     fn verify_backward(
         &self,
         target_height: Height,
+        options: &Options,
         state: &mut State,
     ) -> Result<LightBlock, Error> {
         use tendermint::crypto::default::Sha256;
@@ -307,10 +630,10 @@ impl LightClient {
         assert!(root.height() >= target_height);
 
         // Check invariant [LCV-INV-TP.1]
-        if !is_within_trust_period(&root, self.options.trusting_period, self.clock.now()) {
+        if !is_within_trust_period(&root, options.trusting_period, self.clock.now()) {
             return Err(Error::trusted_state_outside_trusting_period(
                 Box::new(root),
-                self.options,
+                *options,
             ));
         }
 
@@ -368,17 +691,34 @@ impl LightClient {
         &self,
         height: Height,
         state: &mut State,
+    ) -> Result<(LightBlock, Status), Error> {
+        self.get_or_fetch_block_after(height, None, state)
+    }
+
+    /// Same as [`LightClient::get_or_fetch_block`], but fetches the block to be verified
+    /// against the given trusted block, if any, so that the `Io` component may reuse its
+    /// validator sets.
+    #[ensures(ret.as_ref().map(|(lb, _)| lb.provider == self.peer).unwrap_or(true))]
+    fn get_or_fetch_block_after(
+        &self,
+        height: Height,
+        trusted: Option<&LightBlock>,
+        state: &mut State,
     ) -> Result<(LightBlock, Status), Error> {
         let block = state.light_store.get_non_failed(height);
+        metrics::record_store_lookup(block.is_some());
 
         if let Some(block) = block {
             return Ok(block);
         }
 
-        let block = self
-            .io
-            .fetch_light_block(AtHeight::At(height))
-            .map_err(Error::io)?;
+        let block = match trusted {
+            Some(trusted) => self
+                .io
+                .fetch_light_block_after(AtHeight::At(height), trusted),
+            None => self.io.fetch_light_block(AtHeight::At(height)),
+        }
+        .map_err(Error::io)?;
 
         state.light_store.insert(block.clone(), Status::Unverified);
 
@@ -421,3 +761,853 @@ pub enum TargetOrLatest {
     Latest(LightBlock),
     Target(LightBlock),
 }
+
+/// The outcome of the checks performed at the start of each step of forward verification.
+enum ForwardStep {
+    /// The trusted state reached the target height, and verification is over.
+    Done(LightBlock),
+    /// The block at the current height must be verified against this trusted block.
+    Verify(LightBlock),
+}
+
+/// When the highest trusted or verified state falls out of the trusting period, if there
+/// is such a state.
+fn trust_expires_at(options: &Options, state: &State) -> Option<Time> {
+    state
+        .light_store
+        .highest_trusted_or_verified()
+        .and_then(|light_block| light_block.time().checked_add(options.trusting_period))
+}
+
+fn refresh_due_at(options: &Options, state: &State, margin: Duration) -> Option<Time> {
+    trust_expires_at(options, state).map(|expires_at| {
+        // Saturate at the Unix epoch, which is always in the past
+        expires_at
+            .checked_sub(margin)
+            .unwrap_or_else(Time::unix_epoch)
+    })
+}
+
+fn refresh_delay(
+    options: &Options,
+    state: &State,
+    margin: Duration,
+    now: Time,
+) -> Option<Duration> {
+    refresh_due_at(options, state, margin)
+        .map(|due_at| due_at.duration_since(now).unwrap_or_default())
+}
+
+fn is_refresh_due(
+    options: &Options,
+    state: &State,
+    margin: Duration,
+    now: Time,
+) -> Result<bool, Error> {
+    let due_at =
+        refresh_due_at(options, state, margin).ok_or_else(Error::no_initial_trusted_state)?;
+    Ok(due_at <= now)
+}
+
+/// The given target height, or the height of the chain upgrade if the target lies beyond it.
+fn capped_at_upgrade(upgrade: Option<&ChainUpgrade>, target_height: Height) -> Height {
+    match upgrade {
+        Some(upgrade) if target_height > upgrade.height => upgrade.height,
+        _ => target_height,
+    }
+}
+
+/// Ensure that the given target height does not lie beyond the chain upgrade, if any.
+fn check_upgrade_height(
+    upgrade: Option<&ChainUpgrade>,
+    target_height: Height,
+) -> Result<(), Error> {
+    match upgrade {
+        Some(upgrade) if target_height > upgrade.height => Err(
+            Error::target_beyond_upgrade_height(target_height, upgrade.height),
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// The given options, with the trust threshold replaced by the given one, if any.
+fn with_trust_threshold(options: Options, trust_threshold: Option<TrustThreshold>) -> Options {
+    match trust_threshold {
+        Some(trust_threshold) => Options {
+            trust_threshold,
+            ..options
+        },
+        None => options,
+    }
+}
+
+/// Start a step of forward verification, by checking the highest trusted state below
+/// the target height.
+fn start_forward_step(
+    observer: &dyn Observer,
+    options: &Options,
+    now: Time,
+    target_height: Height,
+    current_height: Height,
+    state: &mut State,
+    audit_log: Option<&mut AuditLog>,
+) -> Result<ForwardStep, Error> {
+    // Get the latest trusted state
+    let trusted_block = state
+        .light_store
+        .highest_trusted_or_verified_before(target_height)
+        .ok_or_else(Error::no_initial_trusted_state)?;
+
+    if target_height < trusted_block.height() {
+        return Err(Error::target_lower_than_trusted_state(
+            target_height,
+            trusted_block.height(),
+        ));
+    }
+
+    // Check invariant [LCV-INV-TP.1]
+    if !is_within_trust_period(&trusted_block, options.trusting_period, now) {
+        observer.on_trust_expired(&trusted_block);
+
+        if let Some(audit_log) = audit_log {
+            audit_log.entries.push(AuditEntry::TrustExpired {
+                trusted_height: trusted_block.height(),
+                now,
+            });
+        }
+
+        return Err(Error::trusted_state_outside_trusting_period(
+            Box::new(trusted_block),
+            *options,
+        ));
+    }
+
+    // Log the current height as a dependency of the block at the target height
+    state.trace_block(target_height, current_height);
+
+    // If the trusted state is now at a height equal to the target height, we are done.
+    // [LCV-DIST-LIFE.1]
+    if target_height == trusted_block.height() {
+        return Ok(ForwardStep::Done(trusted_block));
+    }
+
+    Ok(ForwardStep::Verify(trusted_block))
+}
+
+/// The scheduler to use with the given options, which is the scheduler of the light client
+/// unless the options require every header to be verified.
+fn scheduler_for<'a>(scheduler: &'a dyn Scheduler, options: &Options) -> &'a dyn Scheduler {
+    match options.verification_mode {
+        VerificationMode::Skipping => scheduler,
+        VerificationMode::Sequential => &SEQUENTIAL_SCHEDULE,
+    }
+}
+
+/// The first height to verify on the way to the target height, picked by the scheduler
+/// as if the highest trusted block before the target height had just been verified.
+fn first_forward_height(
+    scheduler: &dyn Scheduler,
+    target_height: Height,
+    state: &State,
+    audit_log: Option<&mut AuditLog>,
+) -> Height {
+    match state
+        .light_store
+        .highest_trusted_or_verified_before(target_height)
+    {
+        Some(trusted_block) if trusted_block.height() < target_height => schedule(
+            scheduler,
+            trusted_block.height(),
+            target_height,
+            state,
+            audit_log,
+        ),
+        _ => target_height,
+    }
+}
+
+/// Ask the scheduler for the next height to verify, and record its decision in the audit log.
+fn schedule(
+    scheduler: &dyn Scheduler,
+    current_height: Height,
+    target_height: Height,
+    state: &State,
+    audit_log: Option<&mut AuditLog>,
+) -> Height {
+    let scheduled_height =
+        scheduler.schedule(state.light_store.as_ref(), current_height, target_height);
+
+    if let Some(audit_log) = audit_log {
+        audit_log
+            .entries
+            .push(AuditEntry::Scheduled(ScheduleDecision {
+                current_height,
+                target_height,
+                scheduled_height,
+            }));
+    }
+
+    scheduled_height
+}
+
+/// Verify the given vote extensions of the commit of the given light block, which must
+/// already have been verified.
+fn verify_vote_extensions(
+    verifier: &dyn Verifier,
+    options: &VoteExtensionsOptions,
+    light_block: &LightBlock,
+    vote_extensions: &[VoteExtension],
+) -> Result<(), Error> {
+    match verifier.verify_vote_extensions(
+        light_block.as_untrusted_state(),
+        vote_extensions,
+        options,
+    ) {
+        Verdict::Success => Ok(()),
+        Verdict::Invalid(e) => Err(Error::invalid_vote_extensions(e)),
+        Verdict::NotEnoughTrust(tally) => Err(Error::invalid_vote_extensions(
+            VerificationError::not_enough_vote_extensions(tally).0,
+        )),
+    }
+}
+
+/// Notify the given observer when the time of the given light block is ahead of the local time
+/// by more than the given margin, if any.
+fn notify_clock_drift(
+    observer: &dyn Observer,
+    margin: Option<Duration>,
+    light_block: &LightBlock,
+    now: Time,
+) {
+    let Some(margin) = margin else {
+        return;
+    };
+
+    let header_time = light_block.signed_header.header.time;
+
+    match header_time.duration_since(now) {
+        Ok(drift) if drift > margin => observer.on_clock_drift(&ClockDrift {
+            height: light_block.height(),
+            header_time,
+            local_time: now,
+            drift,
+            margin,
+        }),
+        _ => {},
+    }
+}
+
+/// Finish a step of forward verification, by verifying the block at the current height
+/// against the trusted block, and return the next height to fetch and verify.
+#[allow(clippy::too_many_arguments)]
+fn finish_forward_step(
+    verifier: &dyn Verifier,
+    observer: &dyn Observer,
+    scheduler: &dyn Scheduler,
+    options: &Options,
+    now: Time,
+    trusted_block: &LightBlock,
+    current_block: LightBlock,
+    status: Status,
+    target_height: Height,
+    state: &mut State,
+    mut audit_log: Option<&mut AuditLog>,
+) -> Result<Height, Error> {
+    let current_height = current_block.height();
+
+    // Validate and verify the current block, recording every check in the audit log if any
+    let verdict = match audit_log.as_deref_mut() {
+        Some(audit_log) => {
+            let record = VerificationRecord::verify(
+                trusted_block.clone(),
+                current_block.clone(),
+                *options,
+                now,
+                verifier,
+            );
+            let verdict = record.verdict.clone();
+            audit_log
+                .entries
+                .push(AuditEntry::Verified(Box::new(record)));
+            verdict
+        },
+        None => verifier.verify_update_header(
+            current_block.as_untrusted_state(),
+            trusted_block.as_trusted_state(),
+            options,
+            now,
+        ),
+    };
+
+    observer.on_bisection_step(trusted_block, &current_block, &verdict);
+    metrics::record_verification_step(trusted_block, &current_block, &verdict);
+
+    match verdict {
+        Verdict::Success => {
+            // Verification succeeded, add the block to the light store with
+            // the `Verified` status or higher if already trusted.
+            let new_status = Status::most_trusted(Status::Verified, status);
+            state.light_store.update(&current_block, new_status);
+
+            // Log the trusted height as a dependency of the block at the current height
+            state.trace_block(current_height, trusted_block.height());
+        },
+        Verdict::Invalid(e) => {
+            // Verification failed, add the block to the light store with `Failed` status,
+            // and abort.
+            state.light_store.update(&current_block, Status::Failed);
+
+            return Err(Error::invalid_light_block(e));
+        },
+        Verdict::NotEnoughTrust(_) => {
+            // The current block cannot be trusted because of a missing overlap in the
+            // validator sets. Add the block to the light store with
+            // the `Unverified` status. This will engage bisection in an
+            // attempt to raise the height of the highest trusted state
+            // until there is enough overlap.
+            state.light_store.update(&current_block, Status::Unverified);
+        },
+    }
+
+    Ok(schedule(
+        scheduler,
+        current_height,
+        target_height,
+        state,
+        audit_log,
+    ))
+}
+
+/// A light client which fetches light blocks with an [`AsyncIo`] component, so that it can
+/// be driven natively from an async runtime such as tokio, instead of blocking a thread
+/// on every fetch like [`LightClient`] does.
+///
+/// It performs the same forward verification with bisection as [`LightClient`], but does not
+/// support backward verification: verifying a header below the highest trusted state fails
+/// with a `TargetLowerThanTrustedState` error.
+pub struct AsyncLightClient {
+    /// The peer id of the peer this client is connected to
+    pub peer: PeerId,
+    /// Options for this light client
+    pub options: Options,
+
+    clock: Box<dyn Clock>,
+    scheduler: Box<dyn Scheduler>,
+    verifier: Box<dyn Verifier>,
+    io: Box<dyn AsyncIo>,
+    pruning_policy: PruningPolicy,
+    observers: Observers,
+    vote_extensions: Option<VoteExtensionsOptions>,
+    chain_upgrade: Option<ChainUpgrade>,
+    clock_drift_warning: Option<Duration>,
+}
+
+impl fmt::Debug for AsyncLightClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncLightClient")
+            .field("peer", &self.peer)
+            .field("options", &self.options)
+            .field("observers", &self.observers)
+            .field("vote_extensions", &self.vote_extensions)
+            .field("chain_upgrade", &self.chain_upgrade)
+            .field("clock_drift_warning", &self.clock_drift_warning)
+            .finish()
+    }
+}
+
+impl AsyncLightClient {
+    /// Constructs a new async light client
+    pub fn new(
+        peer: PeerId,
+        options: Options,
+        clock: impl Clock + 'static,
+        scheduler: impl Scheduler + 'static,
+        verifier: impl Verifier + 'static,
+        io: impl AsyncIo + 'static,
+    ) -> Self {
+        Self {
+            peer,
+            options,
+            clock: Box::new(clock),
+            scheduler: Box::new(scheduler),
+            verifier: Box::new(verifier),
+            io: Box::new(io),
+            pruning_policy: PruningPolicy::default(),
+            observers: Observers::default(),
+            vote_extensions: None,
+            chain_upgrade: None,
+            clock_drift_warning: None,
+        }
+    }
+
+    /// Constructs a new async light client from boxed components
+    pub fn from_boxed(
+        peer: PeerId,
+        options: Options,
+        clock: Box<dyn Clock>,
+        scheduler: Box<dyn Scheduler>,
+        verifier: Box<dyn Verifier>,
+        io: Box<dyn AsyncIo>,
+    ) -> Self {
+        Self {
+            peer,
+            options,
+            clock,
+            scheduler,
+            verifier,
+            io,
+            pruning_policy: PruningPolicy::default(),
+            observers: Observers::default(),
+            vote_extensions: None,
+            chain_upgrade: None,
+            clock_drift_warning: None,
+        }
+    }
+
+    /// Set the policy deciding which light blocks to prune from the light store
+    /// after each successful verification (default: never prune).
+    pub fn with_pruning_policy(mut self, pruning_policy: PruningPolicy) -> Self {
+        self.pruning_policy = pruning_policy;
+        self
+    }
+
+    /// Register an observer to notify of the progress of this light client.
+    pub fn with_observer(mut self, observer: impl Observer + 'static) -> Self {
+        self.add_observer(observer);
+        self
+    }
+
+    /// Register an observer to notify of the progress of this light client.
+    pub fn add_observer(&mut self, observer: impl Observer + 'static) {
+        self.observers.add(Box::new(observer));
+    }
+
+    /// Enable the verification of vote extensions with the given options,
+    /// for chains running CometBFT 0.38 or later.
+    pub fn with_vote_extensions(mut self, options: VoteExtensionsOptions) -> Self {
+        self.vote_extensions = Some(options);
+        self
+    }
+
+    /// Make this light client aware of the given upgrade of the chain.
+    ///
+    /// See [`LightClient::with_chain_upgrade`] for details.
+    pub fn with_chain_upgrade(mut self, upgrade: ChainUpgrade) -> Self {
+        self.chain_upgrade = Some(upgrade);
+        self
+    }
+
+    /// Notify the observers of this light client when the time of a light block it fetched
+    /// is ahead of the local time by more than the given margin.
+    ///
+    /// See [`LightClient::with_clock_drift_warning`] for details.
+    pub fn with_clock_drift_warning(mut self, margin: Duration) -> Self {
+        self.clock_drift_warning = Some(margin);
+        self
+    }
+
+    /// Notify the observers of this light client that a witness reported a light block
+    /// conflicting with the given light block, verified by this light client.
+    pub fn notify_fork_detected(
+        &self,
+        verified_block: &LightBlock,
+        witness: PeerId,
+        conflicting_block: &LightBlock,
+    ) {
+        metrics::record_fork_detected();
+
+        self.observers
+            .on_fork_detected(verified_block, witness, conflicting_block);
+    }
+
+    /// Attempt to update the light client to the highest block of the primary node,
+    /// or to the last block before the chain upgrade, if any.
+    ///
+    /// Note: This function delegates the actual work to `verify_to_target`.
+    pub async fn verify_to_highest(&self, state: &mut State) -> Result<LightBlock, Error> {
+        let target_block = self
+            .io
+            .fetch_light_block(AtHeight::Highest)
+            .await
+            .map_err(Error::io)?;
+
+        let target_height = capped_at_upgrade(self.chain_upgrade.as_ref(), target_block.height());
+        self.verify_to_target(target_height, state).await
+    }
+
+    /// Verify the first block of the primary node after the chain upgrade against the last
+    /// block before it.
+    ///
+    /// See [`LightClient::hand_off_upgrade`] for details.
+    pub async fn hand_off_upgrade(&self, state: &mut State) -> Result<LightBlock, Error> {
+        let upgrade = self
+            .chain_upgrade
+            .as_ref()
+            .ok_or_else(Error::no_chain_upgrade)?;
+
+        let last_block = self.verify_to_target(upgrade.height, state).await?;
+
+        let first_block = self
+            .io
+            .fetch_light_block(AtHeight::At(upgrade.height.increment()))
+            .await
+            .map_err(Error::io)?;
+
+        upgrade.verify_hand_off(
+            self.verifier.as_ref(),
+            &self.options,
+            self.clock.now(),
+            &last_block,
+            &first_block,
+        )?;
+
+        Ok(first_block)
+    }
+
+    /// Update the light client to a block of the primary node at the given height, and verify
+    /// that the given vote extensions of its commit are correctly signed by enough of its
+    /// validators.
+    ///
+    /// See [`LightClient::verify_vote_extensions`] for details.
+    pub async fn verify_vote_extensions(
+        &self,
+        target_height: Height,
+        vote_extensions: &[VoteExtension],
+        state: &mut State,
+    ) -> Result<LightBlock, Error> {
+        let options = self
+            .vote_extensions
+            .as_ref()
+            .ok_or_else(Error::vote_extensions_disabled)?;
+
+        let verified = self.verify_to_target(target_height, state).await?;
+        verify_vote_extensions(self.verifier.as_ref(), options, &verified, vote_extensions)?;
+
+        Ok(verified)
+    }
+
+    /// Update the light client to a block of the primary node at the given height.
+    ///
+    /// See [`LightClient::verify_to_target`] for details.
+    pub async fn verify_to_target(
+        &self,
+        target_height: Height,
+        state: &mut State,
+    ) -> Result<LightBlock, Error> {
+        self.verify_to_target_with_trust_threshold(target_height, None, state)
+            .await
+    }
+
+    /// Update the light client to a block of the primary node at the given height, with the
+    /// given trust threshold, if any, instead of the one in the options of this light client.
+    ///
+    /// See [`LightClient::verify_to_target_with_trust_threshold`] for details.
+    pub async fn verify_to_target_with_trust_threshold(
+        &self,
+        target_height: Height,
+        trust_threshold: Option<TrustThreshold>,
+        state: &mut State,
+    ) -> Result<LightBlock, Error> {
+        self.verify_to_target_recording(
+            target_height,
+            trust_threshold,
+            state,
+            None,
+            &Budget::default(),
+        )
+        .await
+    }
+
+    /// Update the light client to a block of the primary node at the given height, and return
+    /// the audit log of the verification along with its result.
+    ///
+    /// See [`LightClient::verify_to_target_audited`] for details.
+    pub async fn verify_to_target_audited(
+        &self,
+        target_height: Height,
+        state: &mut State,
+    ) -> (Result<LightBlock, Error>, AuditLog) {
+        let mut audit_log = AuditLog::default();
+        let result = self
+            .verify_to_target_recording(
+                target_height,
+                None,
+                state,
+                Some(&mut audit_log),
+                &Budget::default(),
+            )
+            .await;
+        (result, audit_log)
+    }
+
+    /// Update the light client to a block of the primary node at the given height within
+    /// the given budget.
+    ///
+    /// See [`LightClient::verify_to_target_with_budget`] for details.
+    pub async fn verify_to_target_with_budget(
+        &self,
+        target_height: Height,
+        budget: &Budget,
+        state: &mut State,
+    ) -> Result<LightBlock, Error> {
+        self.verify_to_target_recording(target_height, None, state, None, budget)
+            .await
+    }
+
+    /// Record the progress of the verification of the given target height in a checkpoint.
+    ///
+    /// See [`LightClient::checkpoint`] for details.
+    pub fn checkpoint(&self, target_height: Height, state: &State) -> Checkpoint {
+        state.checkpoint(target_height)
+    }
+
+    /// Resume the verification recorded in the given checkpoint.
+    ///
+    /// See [`LightClient::resume_from_checkpoint`] for details.
+    pub async fn resume_from_checkpoint(
+        &self,
+        checkpoint: &Checkpoint,
+        state: &mut State,
+    ) -> Result<LightBlock, Error> {
+        state.restore_checkpoint(checkpoint);
+        self.verify_to_target(checkpoint.target_height, state).await
+    }
+
+    /// Time at which the trusted state of the light client expires.
+    ///
+    /// See [`LightClient::trust_expires_at`] for details.
+    pub fn trust_expires_at(&self, state: &State) -> Option<Time> {
+        trust_expires_at(&self.options, state)
+    }
+
+    /// Time from which the trusted state of the light client is due for a refresh.
+    ///
+    /// See [`LightClient::refresh_due_at`] for details.
+    pub fn refresh_due_at(&self, state: &State, margin: Duration) -> Option<Time> {
+        refresh_due_at(&self.options, state, margin)
+    }
+
+    /// How long to wait from now for the refresh of the trusted state to be due.
+    ///
+    /// See [`LightClient::refresh_delay`] for details.
+    pub fn refresh_delay(&self, state: &State, margin: Duration) -> Option<Duration> {
+        refresh_delay(&self.options, state, margin, self.clock.now())
+    }
+
+    /// Verify the highest block of the primary node if the trusted state of the light client
+    /// expires within the given margin.
+    ///
+    /// See [`LightClient::refresh_trusted_state`] for details.
+    pub async fn refresh_trusted_state(
+        &self,
+        margin: Duration,
+        state: &mut State,
+    ) -> Result<Option<LightBlock>, Error> {
+        if !is_refresh_due(&self.options, state, margin, self.clock.now())? {
+            return Ok(None);
+        }
+
+        self.verify_to_highest(state).await.map(Some)
+    }
+
+    async fn verify_to_target_recording(
+        &self,
+        target_height: Height,
+        trust_threshold: Option<TrustThreshold>,
+        state: &mut State,
+        audit_log: Option<&mut AuditLog>,
+        budget: &Budget,
+    ) -> Result<LightBlock, Error> {
+        check_upgrade_height(self.chain_upgrade.as_ref(), target_height)?;
+
+        // Let's first look in the store to see whether
+        // we have already successfully verified this block.
+        let light_block = state.light_store.get_trusted_or_verified(target_height);
+        metrics::record_store_lookup(light_block.is_some());
+
+        if let Some(light_block) = light_block {
+            return Ok(light_block);
+        }
+
+        let options = with_trust_threshold(self.options, trust_threshold);
+
+        // Get the highest trusted state
+        let highest = state
+            .light_store
+            .highest_trusted_or_verified_before(target_height)
+            .or_else(|| state.light_store.lowest_trusted_or_verified())
+            .ok_or_else(Error::no_initial_trusted_state)?;
+
+        if target_height < highest.height() {
+            return Err(Error::target_lower_than_trusted_state(
+                target_height,
+                highest.height(),
+            ));
+        }
+
+        let stopwatch = metrics::Stopwatch::start();
+
+        let verified = self
+            .verify_forward(target_height, &options, state, audit_log, budget)
+            .await;
+
+        metrics::record_verification(stopwatch, &verified);
+        let verified = verified?;
+
+        self.observers.on_block_verified(&verified);
+
+        self.pruning_policy.prune(
+            state.light_store.as_mut(),
+            self.clock.now(),
+            verified.height(),
+        );
+
+        Ok(verified)
+    }
+
+    /// Perform forward verification with bisection.
+    async fn verify_forward(
+        &self,
+        target_height: Height,
+        options: &Options,
+        state: &mut State,
+        mut audit_log: Option<&mut AuditLog>,
+        budget: &Budget,
+    ) -> Result<LightBlock, Error> {
+        let scheduler = scheduler_for(self.scheduler.as_ref(), options);
+        let mut budget = BudgetTracker::start(budget);
+
+        let mut current_height =
+            first_forward_height(scheduler, target_height, state, audit_log.as_deref_mut());
+
+        let mut depth = 0;
+
+        loop {
+            let now = self.clock.now();
+
+            let trusted_block = match start_forward_step(
+                &self.observers,
+                options,
+                now,
+                target_height,
+                current_height,
+                state,
+                audit_log.as_deref_mut(),
+            )? {
+                ForwardStep::Done(trusted_block) => {
+                    metrics::record_bisection_depth(depth);
+                    return Ok(trusted_block);
+                },
+                ForwardStep::Verify(trusted_block) => trusted_block,
+            };
+
+            depth += 1;
+
+            let fetch = state.light_store.get_non_failed(current_height).is_none();
+            budget.charge_step(target_height, trusted_block.height(), fetch)?;
+
+            let (current_block, status) = self
+                .get_or_fetch_block_after(current_height, Some(&trusted_block), state)
+                .await?;
+
+            notify_clock_drift(
+                &self.observers,
+                self.clock_drift_warning,
+                &current_block,
+                now,
+            );
+
+            current_height = finish_forward_step(
+                self.verifier.as_ref(),
+                &self.observers,
+                scheduler,
+                options,
+                now,
+                &trusted_block,
+                current_block,
+                status,
+                target_height,
+                state,
+                audit_log.as_deref_mut(),
+            )?;
+        }
+    }
+
+    /// Look in the light store for a block from the given peer at the given height,
+    /// which has not previously failed verification (ie. its status is not `Failed`).
+    ///
+    /// If one cannot be found, fetch the block from the given peer and store
+    /// it in the light store with `Unverified` status.
+    pub async fn get_or_fetch_block(
+        &self,
+        height: Height,
+        state: &mut State,
+    ) -> Result<(LightBlock, Status), Error> {
+        self.get_or_fetch_block_after(height, None, state).await
+    }
+
+    /// Same as [`AsyncLightClient::get_or_fetch_block`], but fetches the block to be verified
+    /// against the given trusted block, if any, so that the `AsyncIo` component may reuse its
+    /// validator sets.
+    async fn get_or_fetch_block_after(
+        &self,
+        height: Height,
+        trusted: Option<&LightBlock>,
+        state: &mut State,
+    ) -> Result<(LightBlock, Status), Error> {
+        let block = state.light_store.get_non_failed(height);
+        metrics::record_store_lookup(block.is_some());
+
+        if let Some(block) = block {
+            return Ok(block);
+        }
+
+        let block = match trusted {
+            Some(trusted) => {
+                self.io
+                    .fetch_light_block_after(AtHeight::At(height), trusted)
+                    .await
+            },
+            None => self.io.fetch_light_block(AtHeight::At(height)).await,
+        }
+        .map_err(Error::io)?;
+
+        state.light_store.insert(block.clone(), Status::Unverified);
+
+        Ok((block, Status::Unverified))
+    }
+
+    /// Get the block at the given height or the latest block from the chain if the given height is
+    /// lower than the latest height.
+    ///
+    /// See [`LightClient::get_target_block_or_latest`] for details.
+    pub async fn get_target_block_or_latest(
+        &self,
+        height: Height,
+        state: &mut State,
+    ) -> Result<TargetOrLatest, Error> {
+        let block = state.light_store.get_non_failed(height);
+
+        if let Some((block, _)) = block {
+            return Ok(TargetOrLatest::Target(block));
+        }
+
+        let block = self.io.fetch_light_block(AtHeight::At(height)).await;
+
+        if let Ok(block) = block {
+            return Ok(TargetOrLatest::Target(block));
+        }
+
+        let latest = self
+            .io
+            .fetch_light_block(AtHeight::Highest)
+            .await
+            .map_err(Error::io)?;
+
+        if latest.height() == height {
+            Ok(TargetOrLatest::Target(latest))
+        } else {
+            Ok(TargetOrLatest::Latest(latest))
+        }
+    }
+}
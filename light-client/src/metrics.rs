@@ -0,0 +1,171 @@
+//! Metrics of the light client, recorded with the [`metrics`](https://docs.rs/metrics) facade
+//! when the `metrics` feature is enabled, so that they are exported by whichever recorder
+//! the embedding application installs.
+//!
+//! Without the `metrics` feature, nothing is recorded and the instrumentation compiles away.
+//!
+//! The following metrics are recorded:
+//!
+//! | Name | Type | Labels | Description |
+//! |------|------|--------|-------------|
+//! | [`VERIFICATION_DURATION`] | histogram | `result` | Duration of the verifications of a target height, in seconds |
+//! | [`BISECTION_DEPTH`] | histogram | | Number of light blocks verified to reach the target height of a forward verification |
+//! | [`VERIFICATION_STEPS`] | counter | `verdict` | Verifications of a light block against a trusted light block |
+//! | [`SIGNATURE_VERIFICATIONS`] | counter | | Signatures of the commits checked by the verifier |
+//! | [`STORE_LOOKUPS`] | counter | `result` | Lookups of light blocks in the light store |
+//! | [`FORKS_DETECTED`] | counter | | Forks reported with [`LightClient::notify_fork_detected`] |
+//!
+//! The `result` label of [`VERIFICATION_DURATION`] is either `success` or `failure`, the one of
+//! [`STORE_LOOKUPS`] is either `hit` or `miss`, and the `verdict` label of
+//! [`VERIFICATION_STEPS`] is one of `success`, `invalid` and `not_enough_trust`.
+//!
+//! [`LightClient::notify_fork_detected`]: crate::light_client::LightClient::notify_fork_detected
+
+use crate::verifier::{types::LightBlock, Verdict};
+
+/// Name of the histogram of the durations of the verifications of a target height, in seconds
+pub const VERIFICATION_DURATION: &str = "light_client_verification_duration_seconds";
+
+/// Name of the histogram of the number of light blocks verified to reach the target height
+pub const BISECTION_DEPTH: &str = "light_client_bisection_depth";
+
+/// Name of the counter of the verifications of a light block against a trusted light block
+pub const VERIFICATION_STEPS: &str = "light_client_verification_steps_total";
+
+/// Name of the counter of the signatures of the commits checked by the verifier
+pub const SIGNATURE_VERIFICATIONS: &str = "light_client_signature_verifications_total";
+
+/// Name of the counter of the lookups of light blocks in the light store
+pub const STORE_LOOKUPS: &str = "light_client_store_lookups_total";
+
+/// Name of the counter of the forks reported to the light client
+pub const FORKS_DETECTED: &str = "light_client_forks_detected_total";
+
+/// Register the descriptions of the metrics of the light client with the installed recorder.
+#[cfg(feature = "metrics")]
+pub fn describe() {
+    use ::metrics::{describe_counter, describe_histogram, Unit};
+
+    describe_histogram!(
+        VERIFICATION_DURATION,
+        Unit::Seconds,
+        "Duration of the verifications of a target height"
+    );
+    describe_histogram!(
+        BISECTION_DEPTH,
+        Unit::Count,
+        "Number of light blocks verified to reach the target height"
+    );
+    describe_counter!(
+        VERIFICATION_STEPS,
+        Unit::Count,
+        "Verifications of a light block against a trusted light block"
+    );
+    describe_counter!(
+        SIGNATURE_VERIFICATIONS,
+        Unit::Count,
+        "Signatures of the commits checked by the verifier"
+    );
+    describe_counter!(
+        STORE_LOOKUPS,
+        Unit::Count,
+        "Lookups of light blocks in the light store"
+    );
+    describe_counter!(
+        FORKS_DETECTED,
+        Unit::Count,
+        "Forks reported to the light client"
+    );
+}
+
+/// Measures the duration of a verification.
+pub(crate) struct Stopwatch(#[cfg(feature = "metrics")] std::time::Instant);
+
+impl Stopwatch {
+    pub(crate) fn start() -> Self {
+        Self(
+            #[cfg(feature = "metrics")]
+            std::time::Instant::now(),
+        )
+    }
+}
+
+/// Record the duration and outcome of the verification of a target height.
+pub(crate) fn record_verification<T, E>(stopwatch: Stopwatch, result: &Result<T, E>) {
+    #[cfg(feature = "metrics")]
+    {
+        let result = if result.is_ok() { "success" } else { "failure" };
+        ::metrics::histogram!(VERIFICATION_DURATION, "result" => result)
+            .record(stopwatch.0.elapsed());
+    }
+
+    #[cfg(not(feature = "metrics"))]
+    let _ = (stopwatch, result);
+}
+
+/// Record the number of light blocks verified to reach the target height.
+pub(crate) fn record_bisection_depth(depth: usize) {
+    #[cfg(feature = "metrics")]
+    ::metrics::histogram!(BISECTION_DEPTH).record(depth as f64);
+
+    #[cfg(not(feature = "metrics"))]
+    let _ = depth;
+}
+
+/// Record the verification of the given light block against the given trusted light block.
+///
+/// Every signature of the commit of the light block which is not absent is checked against
+/// its validator set, and once more against the next validator set of the trusted light block
+/// when skipping over intermediate light blocks.
+pub(crate) fn record_verification_step(
+    trusted_block: &LightBlock,
+    current_block: &LightBlock,
+    verdict: &Verdict,
+) {
+    #[cfg(feature = "metrics")]
+    {
+        let label = match verdict {
+            Verdict::Success => "success",
+            Verdict::Invalid(_) => "invalid",
+            Verdict::NotEnoughTrust(_) => "not_enough_trust",
+        };
+        ::metrics::counter!(VERIFICATION_STEPS, "verdict" => label).increment(1);
+
+        let signatures = current_block
+            .signed_header
+            .commit
+            .signatures
+            .iter()
+            .filter(|signature| !signature.is_absent())
+            .count() as u64;
+
+        let checks = if current_block.height() == trusted_block.height().increment() {
+            1
+        } else {
+            2
+        };
+
+        ::metrics::counter!(SIGNATURE_VERIFICATIONS).increment(signatures * checks);
+    }
+
+    #[cfg(not(feature = "metrics"))]
+    let _ = (trusted_block, current_block, verdict);
+}
+
+/// Record a lookup of a light block in the light store.
+pub(crate) fn record_store_lookup(hit: bool) {
+    #[cfg(feature = "metrics")]
+    {
+        let result = if hit { "hit" } else { "miss" };
+        ::metrics::counter!(STORE_LOOKUPS, "result" => result).increment(1);
+    }
+
+    #[cfg(not(feature = "metrics"))]
+    let _ = hit;
+}
+
+/// Record a fork reported to the light client.
+pub(crate) fn record_fork_detected() {
+    #[cfg(feature = "metrics")]
+    ::metrics::counter!(FORKS_DETECTED).increment(1);
+}
@@ -3,15 +3,36 @@
 use std::collections::{HashMap, HashSet};
 
 use contracts::*;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     store::LightStore,
-    verifier::types::{Height, LightBlock},
+    verifier::types::{Height, LightBlock, Status},
 };
 
 /// Records which blocks were needed to verify a target block, eg. during bisection.
 pub type VerificationTrace = HashMap<Height, HashSet<Height>>;
 
+/// The progress of the verification of a target height, as recorded in the [`State`] of the
+/// light client, so that an interrupted verification can be resumed from where it stopped,
+/// eg. in another process, instead of starting over.
+///
+/// The light blocks of a checkpoint are trusted as they are when resuming from it, so that
+/// checkpoints must be kept as safe from tampering as the light store itself.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Height of the target of the verification
+    pub target_height: Height,
+    /// The trusted and verified light blocks needed so far to verify the target, along with
+    /// the highest one below the target, which the verification resumes from
+    pub verified: Vec<(LightBlock, Status)>,
+    /// The light blocks above the highest verified one which were fetched but not verified
+    /// yet, up to the target height
+    pub unverified: Vec<LightBlock>,
+    /// Heights of the light blocks needed so far to verify the target
+    pub trace: Vec<Height>,
+}
+
 /// The state managed by the light client.
 #[derive(Debug)]
 pub struct State {
@@ -60,4 +81,77 @@ impl State {
         trace.sort_by_key(|lb| lb.height());
         trace
     }
+
+    /// Record the progress of the verification of the given target height in a checkpoint.
+    pub fn checkpoint(&self, target_height: Height) -> Checkpoint {
+        let mut trace: Vec<Height> = self
+            .verification_trace
+            .get(&target_height)
+            .map(|trace| trace.iter().copied().collect())
+            .unwrap_or_default();
+        trace.sort_unstable();
+
+        let highest = self
+            .light_store
+            .highest_trusted_or_verified_before(target_height);
+
+        let mut verified: Vec<(LightBlock, Status)> = trace
+            .iter()
+            .chain(highest.as_ref().map(|lb| lb.height()).iter())
+            .flat_map(|&height| {
+                [Status::Trusted, Status::Verified]
+                    .into_iter()
+                    .find_map(|status| Some((self.light_store.get(height, status)?, status)))
+            })
+            .collect();
+        verified.sort_by_key(|(lb, _)| lb.height());
+        verified.dedup_by_key(|(lb, _)| lb.height());
+
+        let unverified = self
+            .light_store
+            .all(Status::Unverified)
+            .filter(|lb| {
+                lb.height() <= target_height
+                    && highest.as_ref().is_none_or(|h| lb.height() > h.height())
+            })
+            .collect();
+
+        Checkpoint {
+            target_height,
+            verified,
+            unverified,
+            trace,
+        }
+    }
+
+    /// Restore the progress of a verification recorded in the given checkpoint, without
+    /// overwriting the light blocks of the light store which are already trusted or verified.
+    pub fn restore_checkpoint(&mut self, checkpoint: &Checkpoint) {
+        for (light_block, status) in &checkpoint.verified {
+            if self
+                .light_store
+                .get_trusted_or_verified(light_block.height())
+                .is_none()
+            {
+                self.light_store.update(light_block, *status);
+            }
+        }
+
+        for light_block in &checkpoint.unverified {
+            if self
+                .light_store
+                .get_non_failed(light_block.height())
+                .is_none()
+            {
+                self.light_store
+                    .insert(light_block.clone(), Status::Unverified);
+            }
+        }
+
+        for &height in &checkpoint.trace {
+            if height <= checkpoint.target_height {
+                self.trace_block(checkpoint.target_height, height);
+            }
+        }
+    }
 }
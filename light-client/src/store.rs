@@ -1,23 +1,37 @@
 //! Interface and implementations of the light block store.
 //!
-//! See the `memory` and `sled` modules for:
+//! See the `memory`, `overlay`, `sled`, `rocksdb` and `sqlite` modules for:
 //!
 //! - a transient, in-memory implementation for testing purposes
+//! - a copy-on-write implementation layered over another store, whose changes can be
+//!   committed to it or discarded
 //! - a persistent, on-disk, sled-backed implementation for production
+//! - a persistent, on-disk, RocksDB-backed implementation for production,
+//!   with a higher write throughput
+//! - a persistent, SQLite-backed implementation for embedded and mobile targets
 
-use std::fmt::Debug;
+use std::{fmt::Debug, ops::Range};
 
 use crate::{
     utils::std_ext,
-    verifier::types::{Height, LightBlock, Status},
+    verifier::types::{Height, LightBlock, Status, Time},
 };
 
 pub mod memory;
+pub mod overlay;
 
 #[cfg(feature = "lightstore-sled")]
 #[cfg_attr(docsrs, doc(cfg(feature = "lightstore-sled")))]
 pub mod sled;
 
+#[cfg(feature = "lightstore-rocksdb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "lightstore-rocksdb")))]
+pub mod rocksdb;
+
+#[cfg(feature = "lightstore-sqlite")]
+#[cfg_attr(docsrs, doc(cfg(feature = "lightstore-sqlite")))]
+pub mod sqlite;
+
 /// Store for light blocks.
 ///
 /// The light store records light blocks received from peers, and their verification status.
@@ -49,9 +63,30 @@ pub trait LightStore: Debug + Send + Sync {
     /// Get the light block of lowest height with the given status.
     fn lowest(&self, status: Status) -> Option<LightBlock>;
 
-    /// Get an iterator of all light blocks with the given status.
+    /// Get an iterator of all light blocks with the given status, in ascending order of height.
+    ///
+    /// Implementations must keep to this order, which the default implementations of
+    /// [`LightStore::range`] and [`LightStore::heights`] rely on.
     fn all(&self, status: Status) -> Box<dyn Iterator<Item = LightBlock>>;
 
+    /// Get an iterator of the light blocks with the given status and a height within the given
+    /// range, in ascending order of height.
+    ///
+    /// Defaults to filtering [`LightStore::all`], which implementations should override when
+    /// they can look the range up without reading every light block.
+    fn range(&self, status: Status, range: Range<Height>) -> Box<dyn Iterator<Item = LightBlock>> {
+        Box::new(
+            self.all(status)
+                .filter(move |lb| range.contains(&lb.height())),
+        )
+    }
+
+    /// Get an iterator of the heights of all light blocks with the given status,
+    /// in ascending order.
+    fn heights(&self, status: Status) -> Box<dyn Iterator<Item = Height>> {
+        Box::new(self.all(status).map(|lb| lb.height()))
+    }
+
     /// Get a block at a given height whatever its verification status as long as it hasn't failed
     /// verification (ie. its status is not `Status::Failed`).
     fn get_non_failed(&self, height: Height) -> Option<(LightBlock, Status)> {
@@ -104,4 +139,53 @@ pub trait LightStore: Debug + Send + Sync {
         self.get(height, Status::Trusted)
             .or_else(|| self.get(height, Status::Verified))
     }
+
+    /// Get the light block of greatest height with the given status, whose header time is at
+    /// or before the given time.
+    ///
+    /// As the time of the headers of a chain increases with their height, the light blocks
+    /// are searched by bisecting over their heights rather than by scanning the store.
+    fn highest_at(&self, time: Time, status: Status) -> Option<LightBlock> {
+        let heights: Vec<Height> = self.heights(status).collect();
+
+        let index = heights.partition_point(|height| {
+            self.get(*height, status)
+                .is_some_and(|lb| lb.time() <= time)
+        });
+
+        index
+            .checked_sub(1)
+            .and_then(|index| self.get(heights[index], status))
+    }
+
+    /// Get the latest trusted light block whose header time is at or before the given time,
+    /// ie. the trusted state of the chain as of that time.
+    fn latest_trusted_at(&self, time: Time) -> Option<LightBlock> {
+        self.highest_at(time, Status::Trusted)
+    }
+}
+
+/// Copy all the light blocks of the `from` store into the `to` store, preserving their status,
+/// e.g. to move from one light store implementation to another.
+///
+/// The light blocks are copied from the least to the most trusted status, so that stores
+/// which keep a single light block per height end up with the most trusted one.
+///
+/// Returns the number of light blocks which were copied.
+pub fn migrate(from: &dyn LightStore, to: &mut dyn LightStore) -> usize {
+    let mut count = 0;
+
+    for status in [
+        Status::Failed,
+        Status::Unverified,
+        Status::Verified,
+        Status::Trusted,
+    ] {
+        for light_block in from.all(status) {
+            to.insert(light_block, status);
+            count += 1;
+        }
+    }
+
+    count
 }
@@ -1,32 +1,148 @@
 //! Transient in-memory store
 
-use std::collections::{btree_map::Entry::*, BTreeMap};
+use std::{
+    collections::{btree_map::Entry::*, BTreeMap, HashMap},
+    ops::Range,
+    sync::Arc,
+};
+
+use tendermint::validator::{Info, ProposerPriority};
 
 use crate::{
     store::{LightStore, Status},
-    verifier::types::{Height, LightBlock},
+    verifier::types::{Hash, Height, LightBlock, PeerId, SignedHeader, ValidatorSet},
 };
 
 /// Internal entry for the memory store
 #[derive(Clone, Debug, PartialEq)]
 struct StoreEntry {
-    light_block: LightBlock,
+    signed_header: SignedHeader,
+    validators: StoredValidatorSet,
+    next_validators: StoredValidatorSet,
+    provider: PeerId,
     status: Status,
 }
 
 impl StoreEntry {
-    fn new(light_block: LightBlock, status: Status) -> Self {
+    fn new(light_block: LightBlock, status: Status, validator_sets: &mut ValidatorSets) -> Self {
+        let header = &light_block.signed_header.header;
+
         Self {
-            light_block,
+            validators: validator_sets.intern(header.validators_hash, &light_block.validators),
+            next_validators: validator_sets
+                .intern(header.next_validators_hash, &light_block.next_validators),
+            signed_header: light_block.signed_header,
+            provider: light_block.provider,
             status,
         }
     }
+
+    fn light_block(&self) -> LightBlock {
+        LightBlock {
+            signed_header: self.signed_header.clone(),
+            validators: self.validators.validator_set(),
+            next_validators: self.next_validators.validator_set(),
+            provider: self.provider,
+        }
+    }
+}
+
+/// A validator set as stored in the memory store, whose validators are shared with the other
+/// light blocks of the store with the same validator set.
+#[derive(Clone, Debug, PartialEq)]
+struct StoredValidatorSet {
+    /// The validators, without their proposer priority
+    validators: Arc<[Info]>,
+    /// The proposer priorities of the validators, which change from one block to the next
+    priorities: Vec<ProposerPriority>,
+    proposer: Option<Info>,
+}
+
+impl StoredValidatorSet {
+    fn validator_set(&self) -> ValidatorSet {
+        let validators = self
+            .validators
+            .iter()
+            .zip(&self.priorities)
+            .map(|(validator, priority)| Info {
+                proposer_priority: *priority,
+                ..validator.clone()
+            })
+            .collect();
+
+        ValidatorSet::new(validators, self.proposer.clone())
+    }
+}
+
+/// The validators of the validator sets of the memory store, keyed by the hash of the
+/// validator sets in the headers, so that every validator set is held only once no matter
+/// how many light blocks of the store have it.
+#[derive(Clone, Debug, Default)]
+struct ValidatorSets {
+    sets: HashMap<Hash, Vec<Arc<[Info]>>>,
+}
+
+impl ValidatorSets {
+    /// Share the validators of the given validator set with the ones of the other validator
+    /// sets with the given hash and the same validators, if any.
+    ///
+    /// The hash comes from the header of an untrusted light block, which may not match the
+    /// validator set, so the validators are only shared with the ones they are equal to.
+    fn intern(&mut self, hash: Hash, validator_set: &ValidatorSet) -> StoredValidatorSet {
+        let validators: Vec<Info> = validator_set
+            .validators()
+            .iter()
+            .map(|validator| Info {
+                proposer_priority: ProposerPriority::default(),
+                ..validator.clone()
+            })
+            .collect();
+
+        let shared = self.sets.entry(hash).or_default();
+        let validators = match shared.iter().find(|shared| ***shared == validators[..]) {
+            Some(shared) => shared.clone(),
+            None => {
+                let validators: Arc<[Info]> = validators.into();
+                shared.push(validators.clone());
+                validators
+            },
+        };
+
+        StoredValidatorSet {
+            validators,
+            priorities: validator_set
+                .validators()
+                .iter()
+                .map(|validator| validator.proposer_priority)
+                .collect(),
+            proposer: validator_set.proposer().clone(),
+        }
+    }
+
+    /// Drop the validators which are not shared with any light block of the store anymore.
+    fn prune(&mut self) {
+        self.sets.retain(|_, shared| {
+            shared.retain(|validators| Arc::strong_count(validators) > 1);
+            !shared.is_empty()
+        });
+    }
+
+    /// Number of distinct validator sets held.
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.sets.values().map(Vec::len).sum()
+    }
 }
 
 /// Transient in-memory store.
+///
+/// The validators of the light blocks are held once per validator set rather than once per
+/// light block, as the validator set of a chain usually changes far less often than every
+/// block.
 #[derive(Debug, Clone, Default)]
 pub struct MemoryStore {
     store: BTreeMap<Height, StoreEntry>,
+    validator_sets: ValidatorSets,
 }
 
 impl MemoryStore {
@@ -34,6 +150,7 @@ impl MemoryStore {
     pub fn new() -> Self {
         Self {
             store: BTreeMap::new(),
+            validator_sets: ValidatorSets::default(),
         }
     }
 }
@@ -43,19 +160,26 @@ impl LightStore for MemoryStore {
         self.store
             .get(&height)
             .filter(|e| e.status == status)
-            .cloned()
-            .map(|e| e.light_block)
+            .map(|e| e.light_block())
     }
 
     fn insert(&mut self, light_block: LightBlock, status: Status) {
-        self.store
-            .insert(light_block.height(), StoreEntry::new(light_block, status));
+        let entry = StoreEntry::new(light_block, status, &mut self.validator_sets);
+
+        if self
+            .store
+            .insert(entry.signed_header.header.height, entry)
+            .is_some()
+        {
+            self.validator_sets.prune();
+        }
     }
 
     fn remove(&mut self, height: Height, status: Status) {
         if let Occupied(e) = self.store.entry(height) {
             if e.get().status == status {
                 e.remove_entry();
+                self.validator_sets.prune();
             }
         }
     }
@@ -69,7 +193,7 @@ impl LightStore for MemoryStore {
             .iter()
             .filter(|(_, e)| e.status == status)
             .max_by_key(|(&height, _)| height)
-            .map(|(_, e)| e.light_block.clone())
+            .map(|(_, e)| e.light_block())
     }
 
     fn highest_before(&self, height: Height, status: Status) -> Option<LightBlock> {
@@ -78,7 +202,7 @@ impl LightStore for MemoryStore {
             .filter(|(_, e)| e.status == status)
             .filter(|(h, _)| h <= &&height)
             .max_by_key(|(&height, _)| height)
-            .map(|(_, e)| e.light_block.clone())
+            .map(|(_, e)| e.light_block())
     }
 
     fn lowest(&self, status: Status) -> Option<LightBlock> {
@@ -86,7 +210,7 @@ impl LightStore for MemoryStore {
             .iter()
             .filter(|(_, e)| e.status == status)
             .min_by_key(|(&height, _)| height)
-            .map(|(_, e)| e.light_block.clone())
+            .map(|(_, e)| e.light_block())
     }
 
     #[allow(clippy::needless_collect)]
@@ -95,9 +219,82 @@ impl LightStore for MemoryStore {
             .store
             .iter()
             .filter(|(_, e)| e.status == status)
-            .map(|(_, e)| e.light_block.clone())
+            .map(|(_, e)| e.light_block())
             .collect();
 
         Box::new(light_blocks.into_iter())
     }
+
+    #[allow(clippy::needless_collect)]
+    fn range(&self, status: Status, range: Range<Height>) -> Box<dyn Iterator<Item = LightBlock>> {
+        let light_blocks: Vec<_> = self
+            .store
+            .range(range)
+            .filter(|(_, e)| e.status == status)
+            .map(|(_, e)| e.light_block())
+            .collect();
+
+        Box::new(light_blocks.into_iter())
+    }
+
+    #[allow(clippy::needless_collect)]
+    fn heights(&self, status: Status) -> Box<dyn Iterator<Item = Height>> {
+        let heights: Vec<_> = self
+            .store
+            .iter()
+            .filter(|(_, e)| e.status == status)
+            .map(|(&height, _)| height)
+            .collect();
+
+        Box::new(heights.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::light_blocks;
+
+    #[test]
+    fn light_blocks_round_trip() {
+        let blocks = light_blocks(5);
+
+        let mut store = MemoryStore::new();
+        for block in &blocks {
+            store.insert(block.clone(), Status::Verified);
+        }
+
+        assert_eq!(store.all(Status::Verified).collect::<Vec<_>>(), blocks);
+    }
+
+    #[test]
+    fn validator_sets_are_held_once() {
+        let blocks = light_blocks(10);
+
+        let mut store = MemoryStore::new();
+        for block in &blocks {
+            store.insert(block.clone(), Status::Verified);
+        }
+
+        // All the blocks of the chain have the same validator set
+        assert_eq!(store.validator_sets.len(), 1);
+
+        // Unless the proposer priorities differ
+        let mut block = blocks[0].clone();
+        let mut validators = block.validators.validators().clone();
+        validators[0].proposer_priority = 42.into();
+        block.validators = ValidatorSet::new(validators, block.validators.proposer().clone());
+        store.insert(block.clone(), Status::Trusted);
+
+        assert_eq!(store.validator_sets.len(), 1);
+        assert_eq!(store.get(block.height(), Status::Trusted), Some(block));
+
+        // The validator sets of removed blocks are dropped
+        for block in &blocks {
+            store.remove(block.height(), Status::Verified);
+            store.remove(block.height(), Status::Trusted);
+        }
+
+        assert_eq!(store.validator_sets.len(), 0);
+    }
 }
@@ -0,0 +1,278 @@
+//! Copy-on-write store layered over another light store
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::{Range, RangeBounds},
+};
+
+use crate::{
+    store::{LightStore, Status},
+    utils::std_ext,
+    verifier::types::{Height, LightBlock},
+};
+
+/// A change made to an [`OverlayStore`], to be applied to the underlying store on commit.
+#[derive(Clone, Debug)]
+enum Change {
+    Insert(LightBlock, Status),
+    Update(LightBlock, Status),
+    Remove(Height, Status),
+}
+
+/// Copy-on-write store layered over another light store.
+///
+/// Light blocks are read from the underlying store until they are inserted, updated or
+/// removed, after which they are read from the overlay instead. The underlying store is left
+/// untouched until the changes are committed with [`OverlayStore::commit`], in the order they
+/// were made, or discarded with [`OverlayStore::discard`].
+#[derive(Debug)]
+pub struct OverlayStore {
+    base: Box<dyn LightStore>,
+    /// The light blocks of the overlay, or `None` for the ones removed from the overlay
+    overlay: BTreeMap<(Height, Status), Option<LightBlock>>,
+    changes: Vec<Change>,
+}
+
+impl OverlayStore {
+    /// Create an overlay over the given store, without any change.
+    pub fn new(base: Box<dyn LightStore>) -> Self {
+        Self {
+            base,
+            overlay: BTreeMap::new(),
+            changes: Vec::new(),
+        }
+    }
+
+    /// Whether any change was made to the overlay.
+    pub fn has_changes(&self) -> bool {
+        !self.changes.is_empty()
+    }
+
+    /// Apply the changes made to the overlay to the underlying store, and return it.
+    pub fn commit(mut self) -> Box<dyn LightStore> {
+        for change in self.changes {
+            match change {
+                Change::Insert(light_block, status) => self.base.insert(light_block, status),
+                Change::Update(light_block, status) => self.base.update(&light_block, status),
+                Change::Remove(height, status) => self.base.remove(height, status),
+            }
+        }
+
+        self.base
+    }
+
+    /// Discard the changes made to the overlay, and return the underlying store.
+    pub fn discard(self) -> Box<dyn LightStore> {
+        self.base
+    }
+
+    /// Get the light block of greatest height up to the given one with the given status
+    /// in the underlying store, which was not changed in the overlay.
+    fn base_highest_before(&self, height: Height, status: Status) -> Option<LightBlock> {
+        let mut light_block = self.base.highest_before(height, status)?;
+
+        while self.overlay.contains_key(&(light_block.height(), status)) {
+            let below = light_block.height().value().checked_sub(1)?;
+            light_block = self
+                .base
+                .highest_before(Height::try_from(below).ok()?, status)?;
+        }
+
+        Some(light_block)
+    }
+
+    /// The changes of the overlay to the light blocks with the given status and a height within
+    /// the given range, in ascending order of height, with `None` for the removed ones.
+    fn changes_within(
+        &self,
+        status: Status,
+        range: impl RangeBounds<Height>,
+    ) -> impl Iterator<Item = (Height, Option<&LightBlock>)> {
+        self.overlay
+            .iter()
+            .filter(move |((h, s), _)| *s == status && range.contains(h))
+            .map(|((h, _), light_block)| (*h, light_block.as_ref()))
+    }
+
+    /// Apply the changes of the overlay within the given range to the given light blocks of the
+    /// underlying store.
+    fn apply_changes(
+        &self,
+        light_blocks: impl Iterator<Item = LightBlock>,
+        status: Status,
+        range: impl RangeBounds<Height>,
+    ) -> Box<dyn Iterator<Item = LightBlock>> {
+        let mut light_blocks: BTreeMap<Height, LightBlock> =
+            light_blocks.map(|lb| (lb.height(), lb)).collect();
+
+        for (height, light_block) in self.changes_within(status, range) {
+            match light_block {
+                Some(light_block) => light_blocks.insert(height, light_block.clone()),
+                None => light_blocks.remove(&height),
+            };
+        }
+
+        Box::new(light_blocks.into_values())
+    }
+
+    /// Get the light block of greatest height up to the given one with the given status
+    /// in the overlay.
+    fn overlay_highest_before(&self, height: Height, status: Status) -> Option<LightBlock> {
+        self.overlay
+            .iter()
+            .rev()
+            .filter(|((h, s), _)| *h <= height && *s == status)
+            .find_map(|(_, light_block)| light_block.clone())
+    }
+}
+
+impl LightStore for OverlayStore {
+    fn get(&self, height: Height, status: Status) -> Option<LightBlock> {
+        match self.overlay.get(&(height, status)) {
+            Some(light_block) => light_block.clone(),
+            None => self.base.get(height, status),
+        }
+    }
+
+    fn insert(&mut self, light_block: LightBlock, status: Status) {
+        self.overlay
+            .insert((light_block.height(), status), Some(light_block.clone()));
+        self.changes.push(Change::Insert(light_block, status));
+    }
+
+    fn remove(&mut self, height: Height, status: Status) {
+        self.overlay.insert((height, status), None);
+        self.changes.push(Change::Remove(height, status));
+    }
+
+    fn update(&mut self, light_block: &LightBlock, status: Status) {
+        let height = light_block.height();
+
+        for other in Status::iter() {
+            if status != *other {
+                self.overlay.insert((height, *other), None);
+            }
+        }
+
+        self.overlay
+            .insert((height, status), Some(light_block.clone()));
+        self.changes
+            .push(Change::Update(light_block.clone(), status));
+    }
+
+    fn highest(&self, status: Status) -> Option<LightBlock> {
+        let highest = self.base.highest(status).map(|lb| lb.height());
+        let overlay_highest = self
+            .overlay
+            .keys()
+            .filter(|(_, s)| *s == status)
+            .map(|(h, _)| *h)
+            .max();
+
+        let height = highest.max(overlay_highest)?;
+        self.highest_before(height, status)
+    }
+
+    fn highest_before(&self, height: Height, status: Status) -> Option<LightBlock> {
+        let base = self.base_highest_before(height, status);
+        let overlay = self.overlay_highest_before(height, status);
+
+        std_ext::option::select(base, overlay, |b, o| {
+            std_ext::cmp::max_by_key(b, o, |lb| lb.height())
+        })
+    }
+
+    fn lowest(&self, status: Status) -> Option<LightBlock> {
+        let height = self.heights(status).next()?;
+        self.get(height, status)
+    }
+
+    fn all(&self, status: Status) -> Box<dyn Iterator<Item = LightBlock>> {
+        self.apply_changes(self.base.all(status), status, ..)
+    }
+
+    fn range(&self, status: Status, range: Range<Height>) -> Box<dyn Iterator<Item = LightBlock>> {
+        self.apply_changes(self.base.range(status, range.clone()), status, range)
+    }
+
+    fn heights(&self, status: Status) -> Box<dyn Iterator<Item = Height>> {
+        let mut heights: BTreeSet<Height> = self.base.heights(status).collect();
+
+        for (height, light_block) in self.changes_within(status, ..) {
+            match light_block {
+                Some(_) => heights.insert(height),
+                None => heights.remove(&height),
+            };
+        }
+
+        Box::new(heights.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{store::memory::MemoryStore, tests::light_blocks};
+
+    /// An overlay over a store verifying the light blocks of the default test chain at the
+    /// given heights.
+    fn overlay(heights: &[u64]) -> (OverlayStore, Vec<LightBlock>) {
+        let light_blocks = light_blocks(8);
+        let mut base = MemoryStore::new();
+        for &height in heights {
+            base.insert(light_blocks[height as usize - 1].clone(), Status::Verified);
+        }
+
+        (OverlayStore::new(Box::new(base)), light_blocks)
+    }
+
+    fn height_range(from: u32, to: u32) -> Range<Height> {
+        Height::from(from)..Height::from(to)
+    }
+
+    fn values(heights: impl Iterator<Item = Height>) -> Vec<u64> {
+        heights.map(|height| height.value()).collect()
+    }
+
+    #[test]
+    fn lists_heights_with_changes_applied() {
+        let (mut overlay, light_blocks) = overlay(&[2, 4, 6]);
+        overlay.insert(light_blocks[4].clone(), Status::Verified);
+        overlay.insert(light_blocks[0].clone(), Status::Verified);
+        overlay.remove(4_u32.into(), Status::Verified);
+        overlay.update(&light_blocks[5], Status::Trusted);
+
+        assert_eq!(values(overlay.heights(Status::Verified)), [1, 2, 5]);
+        assert_eq!(values(overlay.heights(Status::Trusted)), [6]);
+        assert_eq!(
+            overlay.lowest(Status::Verified),
+            Some(light_blocks[0].clone())
+        );
+    }
+
+    #[test]
+    fn lists_range_with_changes_applied() {
+        let (mut overlay, light_blocks) = overlay(&[2, 4, 6, 8]);
+        overlay.insert(light_blocks[2].clone(), Status::Verified);
+        overlay.insert(light_blocks[6].clone(), Status::Verified);
+        overlay.remove(4_u32.into(), Status::Verified);
+
+        let range = |from, to| {
+            let light_blocks = overlay.range(Status::Verified, height_range(from, to));
+            values(light_blocks.map(|lb| lb.height()))
+        };
+
+        assert_eq!(range(3, 7), [3, 6]);
+        assert_eq!(range(1, 9), [2, 3, 6, 7, 8]);
+        assert_eq!(range(7, 8), [7]);
+        assert!(range(4, 6).is_empty());
+
+        let all: Vec<_> = overlay.all(Status::Verified).collect();
+        assert_eq!(
+            all,
+            overlay
+                .range(Status::Verified, height_range(1, 9))
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,304 @@
+//! Persistent store backed by an on-disk RocksDB database.
+//!
+//! The light blocks of each status are kept in their own column family, keyed by height,
+//! and encoded with the CBOR binary encoding.
+
+use std::{fmt, ops::Range, path::Path};
+
+use rocksdb::{
+    ColumnFamily, ColumnFamilyDescriptor, Direction, IteratorMode, Options, WriteBatch, DB,
+};
+
+use super::{LightStore, Status};
+use crate::verifier::types::{Height, LightBlock};
+
+const UNVERIFIED: &str = "unverified";
+const VERIFIED: &str = "verified";
+const TRUSTED: &str = "trusted";
+const FAILED: &str = "failed";
+
+/// Persistent store backed by an on-disk RocksDB database.
+pub struct RocksDbStore {
+    db: DB,
+}
+
+impl fmt::Debug for RocksDbStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RocksDbStore")
+            .field("path", &self.db.path())
+            .finish()
+    }
+}
+
+impl RocksDbStore {
+    /// Open a RocksDB database, creating it and its column families if needed,
+    /// and create a new persistent store from it.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, rocksdb::Error> {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        options.create_missing_column_families(true);
+
+        Self::open_with(&options, path)
+    }
+
+    /// Open a RocksDB database with the given options, and create a new persistent store from it.
+    pub fn open_with(options: &Options, path: impl AsRef<Path>) -> Result<Self, rocksdb::Error> {
+        let column_families = Status::iter()
+            .iter()
+            .map(|status| ColumnFamilyDescriptor::new(column_family(*status), Options::default()));
+
+        let db = DB::open_cf_descriptors(options, path, column_families)?;
+
+        Ok(Self { db })
+    }
+
+    /// Insert all the given light blocks with their status in a single batch,
+    /// which is written atomically, and faster than inserting them one by one.
+    pub fn insert_batch(
+        &mut self,
+        light_blocks: impl IntoIterator<Item = (LightBlock, Status)>,
+    ) -> Result<(), rocksdb::Error> {
+        let mut batch = WriteBatch::default();
+
+        for (light_block, status) in light_blocks {
+            if let Ok(bytes) = serde_cbor::to_vec(&light_block) {
+                batch.put_cf(self.cf(status), key_bytes(light_block.height()), bytes);
+            }
+        }
+
+        self.db.write(batch)
+    }
+
+    fn cf(&self, status: Status) -> &ColumnFamily {
+        self.db
+            .cf_handle(column_family(status))
+            .expect("column families are created when opening the database")
+    }
+
+    fn iter(
+        &self,
+        status: Status,
+        mode: IteratorMode<'_>,
+    ) -> impl Iterator<Item = (Height, LightBlock)> + '_ {
+        self.db
+            .iterator_cf(self.cf(status), mode)
+            .flatten()
+            .flat_map(|(key, value)| {
+                let height = height_from_key(&key)?;
+                let light_block: LightBlock = serde_cbor::from_slice(&value).ok()?;
+                Some((height, light_block))
+            })
+    }
+}
+
+fn column_family(status: Status) -> &'static str {
+    match status {
+        Status::Unverified => UNVERIFIED,
+        Status::Verified => VERIFIED,
+        Status::Trusted => TRUSTED,
+        Status::Failed => FAILED,
+    }
+}
+
+fn key_bytes(height: Height) -> [u8; 8] {
+    // The height is stored in big-endian form so that the lexicographic ordering
+    // of the keys matches the ordering of the heights.
+    height.value().to_be_bytes()
+}
+
+fn height_from_key(key: &[u8]) -> Option<Height> {
+    let bytes = <[u8; 8]>::try_from(key).ok()?;
+    Height::try_from(u64::from_be_bytes(bytes)).ok()
+}
+
+impl LightStore for RocksDbStore {
+    fn get(&self, height: Height, status: Status) -> Option<LightBlock> {
+        let bytes = self
+            .db
+            .get_pinned_cf(self.cf(status), key_bytes(height))
+            .ok()
+            .flatten()?;
+
+        serde_cbor::from_slice(&bytes).ok()
+    }
+
+    fn update(&mut self, light_block: &LightBlock, status: Status) {
+        let Ok(bytes) = serde_cbor::to_vec(light_block) else {
+            return;
+        };
+
+        let key = key_bytes(light_block.height());
+        let mut batch = WriteBatch::default();
+
+        for other in Status::iter() {
+            if status != *other {
+                batch.delete_cf(self.cf(*other), key);
+            }
+        }
+
+        batch.put_cf(self.cf(status), key, bytes);
+
+        self.db.write(batch).ok();
+    }
+
+    fn insert(&mut self, light_block: LightBlock, status: Status) {
+        if let Ok(bytes) = serde_cbor::to_vec(&light_block) {
+            self.db
+                .put_cf(self.cf(status), key_bytes(light_block.height()), bytes)
+                .ok();
+        }
+    }
+
+    fn remove(&mut self, height: Height, status: Status) {
+        self.db.delete_cf(self.cf(status), key_bytes(height)).ok();
+    }
+
+    fn highest(&self, status: Status) -> Option<LightBlock> {
+        self.iter(status, IteratorMode::End)
+            .next()
+            .map(|(_, light_block)| light_block)
+    }
+
+    fn highest_before(&self, height: Height, status: Status) -> Option<LightBlock> {
+        let key = key_bytes(height);
+
+        self.iter(status, IteratorMode::From(&key, Direction::Reverse))
+            .next()
+            .map(|(_, light_block)| light_block)
+    }
+
+    fn lowest(&self, status: Status) -> Option<LightBlock> {
+        self.iter(status, IteratorMode::Start)
+            .next()
+            .map(|(_, light_block)| light_block)
+    }
+
+    #[allow(clippy::needless_collect)]
+    fn all(&self, status: Status) -> Box<dyn Iterator<Item = LightBlock>> {
+        let light_blocks: Vec<_> = self
+            .iter(status, IteratorMode::Start)
+            .map(|(_, light_block)| light_block)
+            .collect();
+
+        Box::new(light_blocks.into_iter())
+    }
+
+    #[allow(clippy::needless_collect)]
+    fn range(&self, status: Status, range: Range<Height>) -> Box<dyn Iterator<Item = LightBlock>> {
+        let key = key_bytes(range.start);
+
+        let light_blocks: Vec<_> = self
+            .iter(status, IteratorMode::From(&key, Direction::Forward))
+            .take_while(|(height, _)| *height < range.end)
+            .map(|(_, light_block)| light_block)
+            .collect();
+
+        Box::new(light_blocks.into_iter())
+    }
+
+    #[allow(clippy::needless_collect)]
+    fn heights(&self, status: Status) -> Box<dyn Iterator<Item = Height>> {
+        let heights: Vec<_> = self
+            .db
+            .iterator_cf(self.cf(status), IteratorMode::Start)
+            .flatten()
+            .flat_map(|(key, _)| height_from_key(&key))
+            .collect();
+
+        Box::new(heights.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::tests::light_blocks;
+
+    #[test]
+    fn highest_returns_latest_block() {
+        with_blocks(10, |mut db, blocks| {
+            for block in blocks {
+                db.insert(block.clone(), Status::Verified);
+                assert_eq!(db.highest(Status::Verified), Some(block));
+            }
+        })
+    }
+
+    #[test]
+    fn highest_before_returns_correct_block() {
+        with_blocks(10, |mut db, blocks| {
+            for block in blocks {
+                db.insert(block.clone(), Status::Verified);
+                assert_eq!(
+                    db.highest_before(block.height(), Status::Verified).as_ref(),
+                    Some(&block)
+                );
+                assert_eq!(
+                    db.highest_before(block.height().increment(), Status::Verified)
+                        .as_ref(),
+                    Some(&block)
+                );
+            }
+        })
+    }
+
+    #[test]
+    fn lowest_returns_earliest_block() {
+        with_blocks(10, |mut db, blocks| {
+            let initial_block = blocks[0].clone();
+            db.insert(initial_block.clone(), Status::Verified);
+            assert_eq!(db.lowest(Status::Verified).as_ref(), Some(&initial_block));
+
+            for block in blocks.into_iter().skip(1) {
+                db.insert(block, Status::Verified);
+                assert_eq!(db.lowest(Status::Verified).as_ref(), Some(&initial_block));
+            }
+        })
+    }
+
+    #[test]
+    fn update_moves_block_to_new_status() {
+        with_blocks(3, |mut db, blocks| {
+            db.insert_batch(blocks.iter().cloned().map(|lb| (lb, Status::Unverified)))
+                .unwrap();
+
+            db.update(&blocks[1], Status::Verified);
+
+            assert_eq!(db.get(blocks[1].height(), Status::Unverified), None);
+            assert_eq!(
+                db.get(blocks[1].height(), Status::Verified).as_ref(),
+                Some(&blocks[1])
+            );
+
+            let heights = db
+                .heights(Status::Unverified)
+                .map(|h| h.value())
+                .collect::<Vec<_>>();
+            assert_eq!(heights, vec![1, 3]);
+        })
+    }
+
+    #[test]
+    fn range_returns_blocks_within_range() {
+        with_blocks(10, |mut db, blocks| {
+            db.insert_batch(blocks.into_iter().map(|lb| (lb, Status::Verified)))
+                .unwrap();
+
+            let heights = db
+                .range(Status::Verified, 3_u32.into()..8_u32.into())
+                .map(|lb| lb.height().value())
+                .collect::<Vec<_>>();
+
+            assert_eq!(heights, vec![3, 4, 5, 6, 7]);
+        })
+    }
+
+    fn with_blocks(height: u64, f: impl FnOnce(RocksDbStore, Vec<LightBlock>)) {
+        let tmp_dir = tempdir().unwrap();
+        let db = RocksDbStore::open(tmp_dir.path()).unwrap();
+
+        f(db, light_blocks(height))
+    }
+}
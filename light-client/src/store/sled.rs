@@ -1,12 +1,19 @@
 //! Persistent store backed by an on-disk `sled` database.
+//!
+//! The light blocks are stored compressed with snappy. Stores written by previous versions,
+//! which stored them uncompressed, can still be read, and compressed with
+//! [`SledStore::compress_all`].
 
 pub mod utils;
-use std::path::Path;
+use std::{ops::Range, path::Path};
 
 use utils::HeightIndexedDb;
 
 use super::{LightStore, Status};
-use crate::verifier::types::{Height, LightBlock};
+use crate::{
+    errors::Error,
+    verifier::types::{Height, LightBlock},
+};
 
 const UNVERIFIED: &str = "unverified";
 const VERIFIED: &str = "verified";
@@ -38,6 +45,19 @@ impl SledStore {
         }
     }
 
+    /// Compress all the light blocks of this store which were written uncompressed by
+    /// previous versions, and return how many were compressed.
+    #[allow(clippy::result_large_err)]
+    pub fn compress_all(&self) -> Result<usize, Error> {
+        let mut count = 0;
+
+        for status in Status::iter() {
+            count += self.db(*status).compress_all()?;
+        }
+
+        Ok(count)
+    }
+
     fn db(&self, status: Status) -> &HeightIndexedDb<LightBlock> {
         match status {
             Status::Unverified => &self.unverified_db,
@@ -90,14 +110,22 @@ impl LightStore for SledStore {
     fn all(&self, status: Status) -> Box<dyn Iterator<Item = LightBlock>> {
         Box::new(self.db(status).iter())
     }
+
+    fn range(&self, status: Status, range: Range<Height>) -> Box<dyn Iterator<Item = LightBlock>> {
+        Box::new(self.db(status).range(range))
+    }
+
+    fn heights(&self, status: Status) -> Box<dyn Iterator<Item = Height>> {
+        Box::new(self.db(status).heights())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use tempfile::tempdir;
-    use tendermint_testgen::{light_block::TmLightBlock as TGLightBlock, Generator, LightChain};
 
     use super::*;
+    use crate::tests::light_blocks;
 
     #[test]
     fn highest_returns_latest_block() {
@@ -141,27 +169,70 @@ mod tests {
         })
     }
 
-    fn with_blocks(height: u64, f: impl FnOnce(SledStore, Vec<LightBlock>)) {
-        let tmp_dir = tempdir().unwrap();
-        let db = SledStore::open(tmp_dir).unwrap();
+    #[test]
+    fn range_returns_blocks_within_range() {
+        with_blocks(10, |mut db, blocks| {
+            for block in blocks.iter().cloned() {
+                db.insert(block, Status::Verified);
+            }
+            db.update(&blocks[4], Status::Trusted);
 
-        let chain = LightChain::default_with_length(height);
-        let blocks = chain
-            .light_blocks
-            .into_iter()
-            .map(|lb| lb.generate().unwrap())
-            .map(testgen_to_lb)
-            .collect::<Vec<_>>();
+            let heights = db
+                .range(Status::Verified, 3_u32.into()..8_u32.into())
+                .map(|lb| lb.height().value())
+                .collect::<Vec<_>>();
 
-        f(db, blocks)
+            assert_eq!(heights, vec![3, 4, 6, 7]);
+        })
     }
 
-    fn testgen_to_lb(tm_lb: TGLightBlock) -> LightBlock {
-        LightBlock {
-            signed_header: tm_lb.signed_header,
-            validators: tm_lb.validators,
-            next_validators: tm_lb.next_validators,
-            provider: tm_lb.provider,
+    #[test]
+    fn heights_returns_all_heights_in_order() {
+        with_blocks(10, |mut db, blocks| {
+            for block in blocks.into_iter().rev() {
+                db.insert(block, Status::Verified);
+            }
+
+            let heights = db
+                .heights(Status::Verified)
+                .map(|h| h.value())
+                .collect::<Vec<_>>();
+
+            assert_eq!(heights, (1..=10).collect::<Vec<_>>());
+            assert_eq!(db.heights(Status::Trusted).count(), 0);
+        })
+    }
+
+    #[test]
+    fn reads_and_compresses_uncompressed_blocks() {
+        let tmp_dir = tempdir().unwrap();
+        let db = sled::open(tmp_dir).unwrap();
+        let blocks = light_blocks(3);
+
+        // Write the blocks as previous versions did
+        let tree = db.open_tree(VERIFIED).unwrap();
+        for block in &blocks {
+            let bytes = serde_cbor::to_vec(block).unwrap();
+            tree.insert(block.height().value().to_be_bytes(), bytes)
+                .unwrap();
         }
+
+        let store = SledStore::new(db);
+        assert_eq!(store.all(Status::Verified).collect::<Vec<_>>(), blocks);
+
+        assert_eq!(store.compress_all().unwrap(), 3);
+        assert_eq!(store.compress_all().unwrap(), 0);
+        assert_eq!(store.all(Status::Verified).collect::<Vec<_>>(), blocks);
+
+        let uncompressed = serde_cbor::to_vec(&blocks[0]).unwrap();
+        let compressed = tree.get(1_u64.to_be_bytes()).unwrap().unwrap();
+        assert!(compressed.len() < uncompressed.len());
+    }
+
+    fn with_blocks(height: u64, f: impl FnOnce(SledStore, Vec<LightBlock>)) {
+        let tmp_dir = tempdir().unwrap();
+        let db = SledStore::open(tmp_dir).unwrap();
+
+        f(db, light_blocks(height))
     }
 }
@@ -1,6 +1,12 @@
 //! This modules provides type-safe interfaces over the `sled` API,
 //! by taking care of (de)serializing keys and values with the
 //! CBOR binary encoding.
+//!
+//! Values are compressed with snappy, and prefixed with a byte holding the version
+//! of their format. Values written before compression was introduced are plain CBOR
+//! maps, whose first byte is always at least `0x80`, which tells them apart from
+//! compressed values: they can still be read, and be compressed with
+//! [`HeightIndexedDb::compress_all`].
 
 use std::marker::PhantomData;
 use std::ops::{Bound, RangeBounds};
@@ -33,6 +39,40 @@ fn key_bytes(height: Height) -> [u8; 8] {
     height.value().to_be_bytes()
 }
 
+/// Version of the format of values compressed with snappy
+const SNAPPY_V1: u8 = 0x01;
+
+/// Lowest first byte of a value which is uncompressed CBOR
+const UNCOMPRESSED: u8 = 0x80;
+
+fn encode<V: Serialize>(value: &V) -> Result<Vec<u8>, Error> {
+    let cbor = serde_cbor::to_vec(value).map_err(Error::serde_cbor)?;
+
+    let mut bytes = Vec::with_capacity(1 + snap::raw::max_compress_len(cbor.len()));
+    bytes.push(SNAPPY_V1);
+    bytes.extend(
+        snap::raw::Encoder::new()
+            .compress_vec(&cbor)
+            .map_err(Error::snappy)?,
+    );
+
+    Ok(bytes)
+}
+
+fn decode<V: DeserializeOwned>(bytes: &[u8]) -> Result<V, Error> {
+    match bytes.first() {
+        Some(&SNAPPY_V1) => {
+            let cbor = snap::raw::Decoder::new()
+                .decompress_vec(&bytes[1..])
+                .map_err(Error::snappy)?;
+
+            serde_cbor::from_slice(&cbor).map_err(Error::serde_cbor)
+        },
+        Some(&version) if version < UNCOMPRESSED => Err(Error::unknown_storage_format(version)),
+        _ => serde_cbor::from_slice(bytes).map_err(Error::serde_cbor),
+    }
+}
+
 // Can be removed once bound_map is stabilized. See https://github.com/rust-lang/rust/issues/86026
 fn map_bound(bound: Bound<&Height>) -> Bound<[u8; 8]> {
     match bound {
@@ -53,8 +93,8 @@ where
 
         match value {
             Some(bytes) => {
-                let value = serde_cbor::from_slice(&bytes).map_err(Error::serde_cbor)?;
-                Ok(value)
+                let value = decode(&bytes)?;
+                Ok(Some(value))
             },
             None => Ok(None),
         }
@@ -72,7 +112,7 @@ where
     /// Insert a value associated with a height within this tree
     pub fn insert(&self, height: Height, value: &V) -> Result<(), Error> {
         let key = key_bytes(height);
-        let bytes = serde_cbor::to_vec(&value).map_err(Error::serde_cbor)?;
+        let bytes = encode(value)?;
 
         self.tree.insert(key, bytes).map_err(Error::sled)?;
 
@@ -93,7 +133,15 @@ where
         self.tree
             .iter()
             .flatten()
-            .flat_map(|(_, v)| serde_cbor::from_slice(&v))
+            .flat_map(|(_, v)| decode(&v).ok())
+    }
+
+    /// Return an iterator over the heights of all values within this tree
+    pub fn heights(&self) -> impl DoubleEndedIterator<Item = Height> {
+        self.tree.iter().keys().flatten().flat_map(|k| {
+            let bytes = <[u8; 8]>::try_from(k.as_ref()).ok()?;
+            Height::try_from(u64::from_be_bytes(bytes)).ok()
+        })
     }
 
     /// Return an iterator over the given range
@@ -106,7 +154,27 @@ where
         self.tree
             .range(range)
             .flatten()
-            .flat_map(|(_, v)| serde_cbor::from_slice(&v))
+            .flat_map(|(_, v)| decode(&v).ok())
+    }
+
+    /// Compress all the values within this tree which were written uncompressed,
+    /// and return how many were compressed.
+    pub fn compress_all(&self) -> Result<usize, Error> {
+        let mut count = 0;
+
+        for entry in self.tree.iter() {
+            let (key, bytes) = entry.map_err(Error::sled)?;
+
+            if bytes.first().is_some_and(|&byte| byte >= UNCOMPRESSED) {
+                let value: V = decode(&bytes)?;
+                self.tree
+                    .insert(key, encode(&value)?)
+                    .map_err(Error::sled)?;
+                count += 1;
+            }
+        }
+
+        Ok(count)
     }
 }
 
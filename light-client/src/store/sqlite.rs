@@ -0,0 +1,310 @@
+//! Persistent store backed by an SQLite database.
+//!
+//! The light blocks are kept in a single table, encoded with the CBOR binary encoding,
+//! along with their height and status in indexed columns.
+
+use std::{ops::Range, path::Path, sync::Mutex};
+
+use rusqlite::{params, Connection, OptionalExtension, Params};
+
+use super::{LightStore, Status};
+use crate::verifier::types::{Height, LightBlock};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS light_blocks (
+        height INTEGER NOT NULL,
+        status TEXT NOT NULL,
+        light_block BLOB NOT NULL,
+        PRIMARY KEY (height, status)
+    );
+
+    CREATE INDEX IF NOT EXISTS light_blocks_by_status ON light_blocks (status, height);
+";
+
+/// Persistent store backed by an SQLite database.
+#[derive(Debug)]
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// Open an SQLite database, creating it if needed, and create a new persistent store from it.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, rusqlite::Error> {
+        Self::new(Connection::open(path)?)
+    }
+
+    /// Create a new persistent store from an SQLite connection that is already open,
+    /// creating the table holding the light blocks if needed.
+    pub fn new(conn: Connection) -> Result<Self, rusqlite::Error> {
+        conn.execute_batch(SCHEMA)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        // A panic while holding the lock cannot leave the database in an inconsistent
+        // state, as every write is done in a single statement or transaction.
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn query_one(&self, sql: &str, params: impl Params) -> Option<LightBlock> {
+        let bytes: Vec<u8> = self
+            .conn()
+            .query_row(sql, params, |row| row.get(0))
+            .optional()
+            .ok()
+            .flatten()?;
+
+        serde_cbor::from_slice(&bytes).ok()
+    }
+
+    fn query_all(&self, sql: &str, params: impl Params) -> Vec<LightBlock> {
+        let conn = self.conn();
+
+        let Ok(mut statement) = conn.prepare_cached(sql) else {
+            return Vec::new();
+        };
+
+        let Ok(rows) = statement.query_map(params, |row| row.get::<_, Vec<u8>>(0)) else {
+            return Vec::new();
+        };
+
+        rows.flatten()
+            .flat_map(|bytes| serde_cbor::from_slice(&bytes))
+            .collect()
+    }
+}
+
+fn status_name(status: Status) -> &'static str {
+    match status {
+        Status::Unverified => "unverified",
+        Status::Verified => "verified",
+        Status::Trusted => "trusted",
+        Status::Failed => "failed",
+    }
+}
+
+fn height_value(height: Height) -> i64 {
+    // Heights are bounded by `i64::MAX`
+    height.value() as i64
+}
+
+impl LightStore for SqliteStore {
+    fn get(&self, height: Height, status: Status) -> Option<LightBlock> {
+        self.query_one(
+            "SELECT light_block FROM light_blocks WHERE height = ?1 AND status = ?2",
+            params![height_value(height), status_name(status)],
+        )
+    }
+
+    fn update(&mut self, light_block: &LightBlock, status: Status) {
+        let Ok(bytes) = serde_cbor::to_vec(light_block) else {
+            return;
+        };
+
+        let height = height_value(light_block.height());
+        let mut conn = self.conn();
+
+        let result = conn.transaction().and_then(|tx| {
+            tx.execute(
+                "DELETE FROM light_blocks WHERE height = ?1",
+                params![height],
+            )?;
+            tx.execute(
+                "INSERT INTO light_blocks (height, status, light_block) VALUES (?1, ?2, ?3)",
+                params![height, status_name(status), bytes],
+            )?;
+            tx.commit()
+        });
+
+        result.ok();
+    }
+
+    fn insert(&mut self, light_block: LightBlock, status: Status) {
+        let Ok(bytes) = serde_cbor::to_vec(&light_block) else {
+            return;
+        };
+
+        self.conn()
+            .execute(
+                "INSERT OR REPLACE INTO light_blocks (height, status, light_block) \
+                VALUES (?1, ?2, ?3)",
+                params![
+                    height_value(light_block.height()),
+                    status_name(status),
+                    bytes
+                ],
+            )
+            .ok();
+    }
+
+    fn remove(&mut self, height: Height, status: Status) {
+        self.conn()
+            .execute(
+                "DELETE FROM light_blocks WHERE height = ?1 AND status = ?2",
+                params![height_value(height), status_name(status)],
+            )
+            .ok();
+    }
+
+    fn highest(&self, status: Status) -> Option<LightBlock> {
+        self.query_one(
+            "SELECT light_block FROM light_blocks WHERE status = ?1 \
+            ORDER BY height DESC LIMIT 1",
+            params![status_name(status)],
+        )
+    }
+
+    fn highest_before(&self, height: Height, status: Status) -> Option<LightBlock> {
+        self.query_one(
+            "SELECT light_block FROM light_blocks WHERE status = ?1 AND height <= ?2 \
+            ORDER BY height DESC LIMIT 1",
+            params![status_name(status), height_value(height)],
+        )
+    }
+
+    fn lowest(&self, status: Status) -> Option<LightBlock> {
+        self.query_one(
+            "SELECT light_block FROM light_blocks WHERE status = ?1 \
+            ORDER BY height ASC LIMIT 1",
+            params![status_name(status)],
+        )
+    }
+
+    fn all(&self, status: Status) -> Box<dyn Iterator<Item = LightBlock>> {
+        let light_blocks = self.query_all(
+            "SELECT light_block FROM light_blocks WHERE status = ?1 ORDER BY height ASC",
+            params![status_name(status)],
+        );
+
+        Box::new(light_blocks.into_iter())
+    }
+
+    fn range(&self, status: Status, range: Range<Height>) -> Box<dyn Iterator<Item = LightBlock>> {
+        let light_blocks = self.query_all(
+            "SELECT light_block FROM light_blocks \
+            WHERE status = ?1 AND height >= ?2 AND height < ?3 ORDER BY height ASC",
+            params![
+                status_name(status),
+                height_value(range.start),
+                height_value(range.end)
+            ],
+        );
+
+        Box::new(light_blocks.into_iter())
+    }
+
+    fn heights(&self, status: Status) -> Box<dyn Iterator<Item = Height>> {
+        let conn = self.conn();
+
+        let heights = conn
+            .prepare_cached("SELECT height FROM light_blocks WHERE status = ?1 ORDER BY height ASC")
+            .and_then(|mut statement| {
+                let rows = statement
+                    .query_map(params![status_name(status)], |row| row.get::<_, i64>(0))?;
+
+                Ok(rows
+                    .flatten()
+                    .flat_map(|height| Height::try_from(height).ok())
+                    .collect::<Vec<_>>())
+            })
+            .unwrap_or_default();
+
+        Box::new(heights.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::light_blocks;
+
+    #[test]
+    fn highest_returns_latest_block() {
+        with_blocks(10, |mut db, blocks| {
+            for block in blocks {
+                db.insert(block.clone(), Status::Verified);
+                assert_eq!(db.highest(Status::Verified), Some(block));
+            }
+        })
+    }
+
+    #[test]
+    fn highest_before_returns_correct_block() {
+        with_blocks(10, |mut db, blocks| {
+            for block in blocks {
+                db.insert(block.clone(), Status::Verified);
+                assert_eq!(
+                    db.highest_before(block.height(), Status::Verified).as_ref(),
+                    Some(&block)
+                );
+                assert_eq!(
+                    db.highest_before(block.height().increment(), Status::Verified)
+                        .as_ref(),
+                    Some(&block)
+                );
+            }
+        })
+    }
+
+    #[test]
+    fn lowest_returns_earliest_block() {
+        with_blocks(10, |mut db, blocks| {
+            let initial_block = blocks[0].clone();
+            db.insert(initial_block.clone(), Status::Verified);
+            assert_eq!(db.lowest(Status::Verified).as_ref(), Some(&initial_block));
+
+            for block in blocks.into_iter().skip(1) {
+                db.insert(block, Status::Verified);
+                assert_eq!(db.lowest(Status::Verified).as_ref(), Some(&initial_block));
+            }
+        })
+    }
+
+    #[test]
+    fn update_moves_block_to_new_status() {
+        with_blocks(3, |mut db, blocks| {
+            for block in blocks.iter().cloned() {
+                db.insert(block, Status::Unverified);
+            }
+
+            db.update(&blocks[1], Status::Verified);
+
+            assert_eq!(db.get(blocks[1].height(), Status::Unverified), None);
+            assert_eq!(
+                db.get(blocks[1].height(), Status::Verified).as_ref(),
+                Some(&blocks[1])
+            );
+
+            let heights = db
+                .heights(Status::Unverified)
+                .map(|h| h.value())
+                .collect::<Vec<_>>();
+            assert_eq!(heights, vec![1, 3]);
+        })
+    }
+
+    #[test]
+    fn range_returns_blocks_within_range() {
+        with_blocks(10, |mut db, blocks| {
+            for block in blocks {
+                db.insert(block, Status::Verified);
+            }
+
+            let heights = db
+                .range(Status::Verified, 3_u32.into()..8_u32.into())
+                .map(|lb| lb.height().value())
+                .collect::<Vec<_>>();
+
+            assert_eq!(heights, vec![3, 4, 5, 6, 7]);
+        })
+    }
+
+    fn with_blocks(height: u64, f: impl FnOnce(SqliteStore, Vec<LightBlock>)) {
+        let db = SqliteStore::new(Connection::open_in_memory().unwrap()).unwrap();
+
+        f(db, light_blocks(height))
+    }
+}
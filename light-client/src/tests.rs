@@ -5,6 +5,7 @@ use std::collections::HashMap;
 #[cfg(feature = "rust-crypto")]
 use std::time::Duration;
 
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use tendermint::{block::Height as HeightStr, evidence::Duration as DurationStr};
 use tendermint_rpc as rpc;
@@ -12,10 +13,10 @@ use tendermint_rpc as rpc;
 use crate::{
     components::{
         clock::Clock,
-        io::{AtHeight, Io, IoError},
+        io::{AsyncIo, AtHeight, Io, IoError},
     },
     errors::Error,
-    light_client::LightClient,
+    light_client::{AsyncLightClient, LightClient},
     state::State,
     verifier::types::{Height, LightBlock, SignedHeader, Time, TrustThreshold, ValidatorSet},
 };
@@ -125,6 +126,13 @@ impl Io for MockIo {
     }
 }
 
+#[async_trait]
+impl AsyncIo for MockIo {
+    async fn fetch_light_block(&self, height: AtHeight) -> Result<LightBlock, IoError> {
+        Io::fetch_light_block(self, height)
+    }
+}
+
 #[cfg(feature = "rust-crypto")]
 pub fn verify_single(
     trusted_block: LightBlock,
@@ -142,6 +150,8 @@ pub fn verify_single(
         trust_threshold,
         trusting_period,
         clock_drift,
+        verification_mode: Default::default(),
+        commit_signatures: Default::default(),
     };
 
     let result = verifier.verify_update_header(
@@ -166,3 +176,119 @@ pub fn verify_bisection(
         .verify_to_target(untrusted_height, state)
         .map(|_| state.get_trace(untrusted_height))
 }
+
+pub async fn verify_bisection_async(
+    untrusted_height: Height,
+    light_client: &AsyncLightClient,
+    state: &mut State,
+) -> Result<Vec<LightBlock>, Error> {
+    light_client
+        .verify_to_target(untrusted_height, state)
+        .await
+        .map(|_| state.get_trace(untrusted_height))
+}
+
+#[cfg(feature = "testgen")]
+pub use self::fixtures::*;
+
+/// Light blocks of generated chains, and light clients verifying them, shared by the tests.
+#[cfg(feature = "testgen")]
+mod fixtures {
+    use std::time::Duration;
+
+    use tendermint_testgen::{
+        light_block::{default_peer_id, TmLightBlock},
+        Generator, LightChain,
+    };
+
+    use super::{MockClock, MockIo};
+    use crate::{
+        components::{
+            io::{AtHeight, Io},
+            scheduler::{self, Scheduler},
+        },
+        instance::Instance,
+        light_client::LightClient,
+        state::State,
+        store::{memory::MemoryStore, LightStore},
+        verifier::{
+            options::Options,
+            types::{LightBlock, Status, Time},
+            ProdVerifier,
+        },
+    };
+
+    pub fn testgen_to_lb(tm_lb: TmLightBlock) -> LightBlock {
+        LightBlock {
+            signed_header: tm_lb.signed_header,
+            validators: tm_lb.validators,
+            next_validators: tm_lb.next_validators,
+            provider: tm_lb.provider,
+        }
+    }
+
+    /// The light blocks of the default chain of the given length, from height 1.
+    pub fn light_blocks(length: u64) -> Vec<LightBlock> {
+        LightChain::default_with_length(length)
+            .light_blocks
+            .into_iter()
+            .map(|lb| lb.generate().unwrap())
+            .map(testgen_to_lb)
+            .collect()
+    }
+
+    /// A time right after the last block of the default chain of the given length.
+    pub fn now(length: u64) -> Time {
+        tendermint_testgen::helpers::get_time(length + 1).unwrap()
+    }
+
+    /// Options trusting the validator sets for 10 days, with a clock drift of 10 seconds.
+    pub fn options() -> Options {
+        Options {
+            trust_threshold: Default::default(),
+            trusting_period: Duration::from_secs(60 * 60 * 24 * 10),
+            clock_drift: Duration::from_secs(10),
+            verification_mode: Default::default(),
+            commit_signatures: Default::default(),
+        }
+    }
+
+    /// A state whose light store only holds the given light block, trusted.
+    pub fn make_state(trusted_block: LightBlock) -> State {
+        let mut light_store = MemoryStore::new();
+        light_store.insert(trusted_block, Status::Trusted);
+
+        State::new(light_store)
+    }
+
+    /// A light client verifying the light blocks of the given I/O with the given scheduler,
+    /// the [`options`] and the production verifier, at the given time.
+    pub fn make_light_client(
+        io: impl Io + 'static,
+        scheduler: impl Scheduler + 'static,
+        now: Time,
+    ) -> LightClient {
+        LightClient::new(
+            default_peer_id(),
+            options(),
+            MockClock { now },
+            scheduler,
+            ProdVerifier::default(),
+            io,
+        )
+    }
+
+    /// An instance trusting the first light block of the default chain of the given length,
+    /// which verifies the others by bisection, right after the last one.
+    pub fn make_instance(length: u64) -> Instance {
+        let io = MockIo::new(light_blocks(length));
+
+        let trusted_state = io
+            .fetch_light_block(AtHeight::At(1_u32.into()))
+            .expect("could not find trusted light block");
+
+        let light_client = make_light_client(io, scheduler::basic_bisecting_schedule, now(length));
+
+        Instance::new(light_client, make_state(trusted_state))
+    }
+}
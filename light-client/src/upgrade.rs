@@ -0,0 +1,84 @@
+//! Support for chains which hard-fork with a coordinated upgrade, halting at an upgrade height
+//! and restarting with a new chain id, so that the light client does not skip across the
+//! upgrade, and can hand off its trusted state to a light client of the new chain.
+
+use serde::{Deserialize, Serialize};
+use tendermint::chain;
+
+use crate::{
+    errors::Error,
+    verifier::{
+        errors::VerificationError,
+        options::Options,
+        types::{Height, LightBlock, Time},
+        Verdict, Verifier,
+    },
+};
+
+/// A coordinated upgrade of a chain, which halts at the upgrade height and restarts
+/// with the successor chain id.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainUpgrade {
+    /// Height of the last block of the chain before the upgrade
+    pub height: Height,
+
+    /// Chain id of the chain after the upgrade
+    pub successor_chain_id: chain::Id,
+}
+
+impl ChainUpgrade {
+    /// An upgrade halting the chain at the given height, and restarting it
+    /// with the given chain id.
+    pub fn new(height: Height, successor_chain_id: chain::Id) -> Self {
+        Self {
+            height,
+            successor_chain_id,
+        }
+    }
+
+    /// Verify the given first light block of the successor chain against the given last
+    /// light block of the chain before the upgrade, which must have been verified already,
+    /// as if both were part of the same chain, so that the former can be trusted by a light
+    /// client of the successor chain.
+    #[allow(clippy::result_large_err)]
+    pub fn verify_hand_off(
+        &self,
+        verifier: &dyn Verifier,
+        options: &Options,
+        now: Time,
+        last_block: &LightBlock,
+        first_block: &LightBlock,
+    ) -> Result<(), Error> {
+        if last_block.height() != self.height {
+            return Err(Error::invalid_upgrade_height(
+                last_block.height(),
+                self.height,
+            ));
+        }
+
+        let chain_id = &first_block.signed_header.header.chain_id;
+        if *chain_id != self.successor_chain_id {
+            return Err(Error::invalid_upgrade_chain_id(
+                chain_id.clone(),
+                self.successor_chain_id.clone(),
+            ));
+        }
+
+        // The chain id check of the verifier is against the successor chain id instead
+        let mut trusted_state = last_block.as_trusted_state();
+        trusted_state.chain_id = &self.successor_chain_id;
+
+        match verifier.verify_update_header(
+            first_block.as_untrusted_state(),
+            trusted_state,
+            options,
+            now,
+        ) {
+            Verdict::Success => Ok(()),
+            Verdict::Invalid(e) => Err(Error::invalid_light_block(e)),
+            Verdict::NotEnoughTrust(tally) => Err(Error::invalid_light_block(
+                VerificationError::not_enough_trust(tally).0,
+            )),
+        }
+    }
+}
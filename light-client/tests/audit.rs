@@ -0,0 +1,147 @@
+use std::time::Duration;
+
+use tendermint::block::CommitSig;
+use tendermint_light_client::{
+    audit::{self, AuditEntry, AuditLog, ScheduleDecision},
+    components::{
+        io::{AtHeight, Io},
+        scheduler::BoundedStepSchedule,
+    },
+    light_client::LightClient,
+    state::State,
+    tests::{light_blocks, make_light_client, make_state, now, MockIo},
+    verifier::{
+        audit::SignatureOutcome,
+        errors::VerificationErrorDetail,
+        types::{Height, Time},
+        ProdVerifier, Verdict,
+    },
+};
+
+const LENGTH: u64 = 10;
+
+fn make_client(now: Time) -> (LightClient, State) {
+    let io = MockIo::new(light_blocks(LENGTH));
+
+    let trusted_state = io
+        .fetch_light_block(AtHeight::At(1_u32.into()))
+        .expect("could not find trusted light block");
+
+    let light_client = make_light_client(io, BoundedStepSchedule::new(3), now);
+
+    (light_client, make_state(trusted_state))
+}
+
+fn height(height: u64) -> Height {
+    Height::try_from(height).unwrap()
+}
+
+fn verify_to_target_audited() -> AuditLog {
+    let (light_client, mut state) = make_client(now(LENGTH));
+
+    let (result, audit_log) = light_client.verify_to_target_audited(height(LENGTH), &mut state);
+    assert_eq!(result.unwrap().height(), height(LENGTH));
+
+    audit_log
+}
+
+#[test]
+fn records_scheduler_decisions_and_verifications() {
+    let audit_log = verify_to_target_audited();
+
+    let scheduled = |current: u64, scheduled: u64| {
+        AuditEntry::Scheduled(ScheduleDecision {
+            current_height: height(current),
+            target_height: height(LENGTH),
+            scheduled_height: height(scheduled),
+        })
+    };
+
+    let mut verified = Vec::new();
+
+    for entry in &audit_log.entries {
+        if let AuditEntry::Verified(record) = entry {
+            assert_eq!(record.verdict, Verdict::Success);
+            assert!(!record.audit.predicates.is_empty());
+            assert!(record
+                .audit
+                .signatures
+                .iter()
+                .all(|check| check.outcome == SignatureOutcome::Valid));
+
+            verified.push((
+                record.trusted.height().value(),
+                record.untrusted.height().value(),
+            ));
+        }
+    }
+
+    assert_eq!(verified, [(1, 4), (4, 7), (7, 10)]);
+    assert_eq!(audit_log.entries[0], scheduled(1, 4));
+    assert_eq!(audit_log.entries[2], scheduled(4, 7));
+    assert_eq!(audit_log.entries.last(), Some(&scheduled(10, 10)));
+}
+
+#[test]
+fn audit_log_replays_deterministically() {
+    let audit_log = verify_to_target_audited();
+
+    audit::replay(&audit_log, &ProdVerifier::default()).unwrap();
+
+    // The audit log survives serialization
+    let json = serde_json::to_string(&audit_log).unwrap();
+    let deserialized: AuditLog = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, audit_log);
+
+    audit::replay(&deserialized, &ProdVerifier::default()).unwrap();
+}
+
+#[test]
+fn replay_detects_tampered_inputs() {
+    let mut audit_log = verify_to_target_audited();
+
+    // Replace a signature of the commit of the last verified light block by another one
+    let record = audit_log
+        .entries
+        .iter_mut()
+        .rev()
+        .find_map(|entry| match entry {
+            AuditEntry::Verified(record) => Some(record),
+            _ => None,
+        })
+        .unwrap();
+
+    let signatures = &mut record.untrusted.signed_header.commit.signatures;
+    let other_signature = match &signatures[1] {
+        CommitSig::BlockIdFlagCommit { signature, .. } => signature.clone(),
+        _ => panic!("expected a commit signature"),
+    };
+    if let CommitSig::BlockIdFlagCommit { signature, .. } = &mut signatures[0] {
+        *signature = other_signature;
+    }
+
+    let mismatch = audit::replay(&audit_log, &ProdVerifier::default()).unwrap_err();
+
+    assert_eq!(mismatch.recorded.verdict, Verdict::Success);
+    assert!(matches!(
+        mismatch.replayed.verdict,
+        Verdict::Invalid(VerificationErrorDetail::InvalidSignature(_))
+    ));
+}
+
+#[test]
+fn records_expired_trust() {
+    let expired = (now(LENGTH) + Duration::from_secs(60 * 60 * 24 * 30)).unwrap();
+    let (light_client, mut state) = make_client(expired);
+
+    let (result, audit_log) = light_client.verify_to_target_audited(height(LENGTH), &mut state);
+    result.unwrap_err();
+
+    assert_eq!(
+        audit_log.entries.last(),
+        Some(&AuditEntry::TrustExpired {
+            trusted_height: height(1),
+            now: expired,
+        })
+    );
+}
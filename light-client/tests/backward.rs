@@ -1,7 +1,5 @@
 #![cfg(feature = "unstable")]
 
-use std::{collections::HashMap, time::Duration};
-
 use proptest::{prelude::*, test_runner::TestRng};
 use tendermint::{hash::Algorithm, Hash, Time};
 use tendermint_light_client::{
@@ -12,27 +10,10 @@ use tendermint_light_client::{
     errors::Error,
     light_client::LightClient,
     state::State,
-    store::{memory::MemoryStore, LightStore},
-    tests::{MockClock, MockIo},
-    verifier::{
-        options::Options,
-        types::{Height, LightBlock, Status},
-        ProdVerifier,
-    },
-};
-use tendermint_testgen::{
-    light_block::{default_peer_id, TmLightBlock as TGLightBlock},
-    Generator, LightChain,
+    tests::{make_light_client, make_state, now, testgen_to_lb, MockIo},
+    verifier::types::{Height, LightBlock},
 };
-
-fn testgen_to_lb(tm_lb: TGLightBlock) -> LightBlock {
-    LightBlock {
-        signed_header: tm_lb.signed_header,
-        validators: tm_lb.validators,
-        next_validators: tm_lb.next_validators,
-        provider: tm_lb.provider,
-    }
-}
+use tendermint_testgen::{Generator, LightChain};
 
 #[derive(Clone, Debug)]
 struct TestCase {
@@ -43,18 +24,8 @@ struct TestCase {
 }
 
 fn make(chain: LightChain, trusted_height: Height) -> (LightClient, State) {
-    let primary = default_peer_id();
-
-    let clock = MockClock {
-        /// Set the current time to be ahead of the latest block in the chain
-        now: tendermint_testgen::helpers::get_time(chain.light_blocks.len() as u64 + 1).unwrap(),
-    };
-
-    let options = Options {
-        trust_threshold: Default::default(),
-        trusting_period: Duration::from_secs(60 * 60 * 24 * 10),
-        clock_drift: Duration::from_secs(10),
-    };
+    // Set the current time to be ahead of the latest block in the chain
+    let now = now(chain.light_blocks.len() as u64);
 
     let light_blocks = chain
         .light_blocks
@@ -69,26 +40,9 @@ fn make(chain: LightChain, trusted_height: Height) -> (LightClient, State) {
         .fetch_light_block(AtHeight::At(trusted_height))
         .expect("could not find trusted light block");
 
-    let mut light_store = MemoryStore::new();
-    light_store.insert(trusted_state, Status::Trusted);
-
-    let state = State {
-        light_store: Box::new(light_store),
-        verification_trace: HashMap::new(),
-    };
-
-    let verifier = ProdVerifier::default();
-
-    let light_client = LightClient::new(
-        primary,
-        options,
-        clock,
-        scheduler::basic_bisecting_schedule,
-        verifier,
-        io,
-    );
+    let light_client = make_light_client(io, scheduler::basic_bisecting_schedule, now);
 
-    (light_client, state)
+    (light_client, make_state(trusted_state))
 }
 
 fn verify(tc: TestCase) -> Result<LightBlock, Error> {
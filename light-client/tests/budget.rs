@@ -0,0 +1,136 @@
+use std::time::Duration;
+
+use tendermint_light_client::{
+    budget::{Budget, BudgetLimit, CancellationToken, Progress},
+    components::{
+        io::{AtHeight, Io},
+        scheduler::BoundedStepSchedule,
+    },
+    errors::ErrorDetail,
+    instance::Instance,
+    tests::{light_blocks, make_light_client, make_state, now, MockIo},
+    verifier::types::{Height, LightBlock},
+};
+
+const LENGTH: u64 = 10;
+
+fn make_instance() -> Instance {
+    let io = MockIo::new(light_blocks(LENGTH));
+
+    let trusted_state = io
+        .fetch_light_block(AtHeight::At(1_u32.into()))
+        .expect("could not find trusted light block");
+
+    // Verifies the blocks at heights 4, 7 and 10 on the way to height 10
+    let light_client = make_light_client(io, BoundedStepSchedule::new(3), now(LENGTH));
+
+    Instance::new(light_client, make_state(trusted_state))
+}
+
+fn verify_with_budget(
+    instance: &mut Instance,
+    budget: &Budget,
+) -> Result<LightBlock, (BudgetLimit, Progress)> {
+    let target_height = Height::try_from(LENGTH).unwrap();
+
+    instance
+        .light_client
+        .verify_to_target_with_budget(target_height, budget, &mut instance.state)
+        .map_err(|e| match e.detail() {
+            ErrorDetail::BudgetExceeded(e) => (e.limit, e.progress.clone()),
+            e => panic!("unexpected error: {e}"),
+        })
+}
+
+fn progress(verified_height: u64, steps: usize, fetches: usize, elapsed: Duration) -> Progress {
+    Progress {
+        target_height: Height::try_from(LENGTH).unwrap(),
+        verified_height: Height::try_from(verified_height).unwrap(),
+        steps,
+        fetches,
+        elapsed,
+    }
+}
+
+#[test]
+fn default_budget_does_not_bound_verification() {
+    let mut instance = make_instance();
+
+    let verified = verify_with_budget(&mut instance, &Budget::default()).unwrap();
+    assert_eq!(verified.height().value(), LENGTH);
+}
+
+#[test]
+fn stops_after_max_steps_and_resumes() {
+    let mut instance = make_instance();
+
+    let (limit, partial) =
+        verify_with_budget(&mut instance, &Budget::default().with_max_steps(2)).unwrap_err();
+
+    assert_eq!(limit, BudgetLimit::Steps(2));
+    assert_eq!(partial, progress(7, 2, 2, partial.elapsed));
+
+    // The light blocks verified within the budget are kept
+    let highest = instance.state.light_store.highest_trusted_or_verified();
+    assert_eq!(highest.unwrap().height().value(), 7);
+
+    let verified = verify_with_budget(&mut instance, &Budget::default().with_max_steps(1)).unwrap();
+    assert_eq!(verified.height().value(), LENGTH);
+}
+
+#[test]
+fn stops_after_max_fetches() {
+    let mut instance = make_instance();
+
+    let (limit, partial) =
+        verify_with_budget(&mut instance, &Budget::default().with_max_fetches(1)).unwrap_err();
+
+    assert_eq!(limit, BudgetLimit::Fetches(1));
+    assert_eq!(partial, progress(4, 1, 1, partial.elapsed));
+}
+
+#[test]
+fn light_blocks_in_store_are_not_counted_as_fetches() {
+    let mut instance = make_instance();
+
+    for height in [4_u32, 7, 10] {
+        instance
+            .light_client
+            .get_or_fetch_block(height.into(), &mut instance.state)
+            .unwrap();
+    }
+
+    let verified =
+        verify_with_budget(&mut instance, &Budget::default().with_max_fetches(0)).unwrap();
+    assert_eq!(verified.height().value(), LENGTH);
+}
+
+#[test]
+fn stops_after_max_duration() {
+    let mut instance = make_instance();
+
+    let (limit, partial) = verify_with_budget(
+        &mut instance,
+        &Budget::default().with_max_duration(Duration::ZERO),
+    )
+    .unwrap_err();
+
+    assert_eq!(limit, BudgetLimit::Duration(Duration::ZERO));
+    assert_eq!(partial, progress(1, 0, 0, partial.elapsed));
+}
+
+#[test]
+fn stops_when_cancelled() {
+    let mut instance = make_instance();
+
+    let token = CancellationToken::new();
+    let budget = Budget::default().with_cancellation(token.clone());
+
+    token.cancel();
+    assert!(budget.cancellation.as_ref().unwrap().is_cancelled());
+
+    let (limit, partial) = verify_with_budget(&mut instance, &budget).unwrap_err();
+
+    assert_eq!(limit, BudgetLimit::Cancelled);
+    assert_eq!(partial, progress(1, 0, 0, partial.elapsed));
+}
@@ -0,0 +1,88 @@
+use tendermint_light_client::{
+    components::{
+        io::{AtHeight, Io},
+        scheduler::BoundedStepSchedule,
+    },
+    light_client::LightClient,
+    state::Checkpoint,
+    tests::{self, light_blocks, make_state, now, MockIo},
+    verifier::types::{Height, LightBlock, Status},
+};
+
+const LENGTH: u64 = 10;
+
+/// A light client verifying with steps of 3 blocks, whose primary only serves the given blocks.
+fn make_light_client(light_blocks: Vec<LightBlock>) -> LightClient {
+    tests::make_light_client(
+        MockIo::new(light_blocks),
+        BoundedStepSchedule::new(3),
+        now(LENGTH),
+    )
+}
+
+fn heights(light_blocks: impl IntoIterator<Item = LightBlock>) -> Vec<u64> {
+    light_blocks
+        .into_iter()
+        .map(|lb| lb.height().value())
+        .collect()
+}
+
+/// Interrupt the verification of the last block, as the primary stops serving blocks above 7.
+fn interrupted_checkpoint(light_blocks: &[LightBlock]) -> Checkpoint {
+    let light_client = make_light_client(light_blocks[..7].to_vec());
+    let mut state = make_state(light_blocks[0].clone());
+
+    let target_height = Height::try_from(LENGTH).unwrap();
+    light_client
+        .verify_to_target(target_height, &mut state)
+        .unwrap_err();
+
+    light_client.checkpoint(target_height, &state)
+}
+
+#[test]
+fn records_progress_of_interrupted_verification() {
+    let light_blocks = light_blocks(LENGTH);
+    let checkpoint = interrupted_checkpoint(&light_blocks);
+
+    assert_eq!(checkpoint.target_height.value(), LENGTH);
+    assert_eq!(
+        checkpoint
+            .verified
+            .iter()
+            .map(|(lb, status)| (lb.height().value(), *status))
+            .collect::<Vec<_>>(),
+        [(4, Status::Verified), (7, Status::Verified)]
+    );
+    assert!(checkpoint.unverified.is_empty());
+
+    let json = serde_json::to_string(&checkpoint).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Checkpoint>(&json).unwrap(),
+        checkpoint
+    );
+}
+
+#[test]
+fn resumes_from_checkpoint() {
+    let light_blocks = light_blocks(LENGTH);
+    let checkpoint = interrupted_checkpoint(&light_blocks);
+
+    // Resume with a fresh state, and a primary which only serves the last block
+    let light_client = make_light_client(vec![light_blocks[9].clone()]);
+    let mut state = make_state(light_blocks[0].clone());
+
+    let verified = light_client
+        .resume_from_checkpoint(&checkpoint, &mut state)
+        .unwrap();
+
+    assert_eq!(verified.height().value(), LENGTH);
+    assert_eq!(
+        heights(state.get_trace(checkpoint.target_height)),
+        [4, 7, 10]
+    );
+
+    let io = MockIo::new(light_blocks.clone());
+    let target = io.fetch_light_block(AtHeight::At(checkpoint.target_height));
+    assert_eq!(state.light_store.highest(Status::Verified), target.ok());
+}
@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use tendermint_light_client::{
+    builder::config::{LightClientConfig, SchedulerConfig, StoreConfig},
+    types::TrustThreshold,
+};
+
+const TOML: &str = r#"
+primary = "http://127.0.0.1:26657"
+witnesses = ["http://127.0.0.1:26667", "http://127.0.0.1:26677"]
+trusted_height = "1000"
+trusted_hash = "C6C2A5F3CB7A2BEB5E0E4C4EE2C45780DDA6D0DE74230E6E07AF18E5D7E0F0D6"
+
+[options]
+trust_threshold = { numerator = "1", denominator = "3" }
+trusting_period = { secs = 1209600, nanos = 0 }
+clock_drift = { secs = 5, nanos = 0 }
+verification_mode = "sequential"
+
+[store]
+backend = "sled"
+path = "/var/lib/light-client"
+
+[scheduler]
+type = "bounded_step"
+max_step = 100
+"#;
+
+#[test]
+fn deserialize_from_toml() {
+    let config: LightClientConfig = toml::from_str(TOML).unwrap();
+
+    assert_eq!(config.primary.to_string(), "http://127.0.0.1:26657/");
+    assert_eq!(config.witnesses.len(), 2);
+    assert_eq!(config.peer_id, None);
+    assert_eq!(config.trusted_height.value(), 1000);
+    assert_eq!(config.options.trust_threshold, TrustThreshold::ONE_THIRD);
+    assert_eq!(config.options.trusting_period, Duration::from_secs(1209600));
+    assert_eq!(
+        config.store,
+        StoreConfig::Sled {
+            path: "/var/lib/light-client".into()
+        }
+    );
+    assert_eq!(
+        config.scheduler,
+        SchedulerConfig::BoundedStep { max_step: 100 }
+    );
+    assert_eq!(config.timeout, None);
+}
+
+#[test]
+fn deserialize_from_json_with_defaults() {
+    let json = r#"{
+        "primary": "http://127.0.0.1:26657",
+        "peer_id": "badfadad0befeedc0c0adeadbeefc0ffeefacade",
+        "trusted_height": "1",
+        "trusted_hash": "C6C2A5F3CB7A2BEB5E0E4C4EE2C45780DDA6D0DE74230E6E07AF18E5D7E0F0D6",
+        "options": {
+            "trust_threshold": { "numerator": "2", "denominator": "3" },
+            "trusting_period": { "secs": 1209600, "nanos": 0 },
+            "clock_drift": { "secs": 5, "nanos": 0 }
+        }
+    }"#;
+
+    let config: LightClientConfig = serde_json::from_str(json).unwrap();
+
+    assert!(config.witnesses.is_empty());
+    assert!(config.peer_id.is_some());
+    assert_eq!(config.options.verification_mode, Default::default());
+    assert_eq!(config.store, StoreConfig::Memory);
+    assert_eq!(config.scheduler, SchedulerConfig::Bisecting);
+
+    let serialized = serde_json::to_string(&config).unwrap();
+    assert_eq!(
+        serde_json::from_str::<LightClientConfig>(&serialized).unwrap(),
+        config
+    );
+}
+
+#[test]
+fn open_store_and_create_scheduler() {
+    assert!(StoreConfig::Memory.open().is_ok());
+
+    for scheduler in [
+        SchedulerConfig::Bisecting,
+        SchedulerConfig::ExponentialProbing,
+        SchedulerConfig::BoundedStep { max_step: 3 },
+    ] {
+        assert!(scheduler.scheduler().is_ok());
+    }
+
+    assert!(SchedulerConfig::BoundedStep { max_step: 0 }
+        .scheduler()
+        .is_err());
+}
+
+#[cfg(feature = "lightstore-sled")]
+#[test]
+fn open_sled_store() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let config = StoreConfig::Sled {
+        path: dir.path().join("store"),
+    };
+
+    assert!(config.open().is_ok());
+}
@@ -0,0 +1,76 @@
+use tendermint::AppHash;
+use tendermint_light_client::{
+    errors::ErrorDetail,
+    evidence::evidence_from_traces,
+    tests::{self, testgen_to_lb},
+    verifier::types::LightBlock,
+};
+use tendermint_testgen::{Generator, LightChain};
+
+/// The light blocks of a chain of length 5, along with a light block at height 5
+/// with a different app hash, signed by the same validators.
+fn light_blocks() -> (Vec<LightBlock>, LightBlock) {
+    let header = LightChain::default_with_length(5).light_blocks[4]
+        .header
+        .clone()
+        .unwrap()
+        .app_hash(AppHash::try_from(vec![1; 32]).unwrap());
+
+    let forged = tendermint_testgen::LightBlock::new_default_with_header(header);
+
+    (
+        tests::light_blocks(5),
+        testgen_to_lb(forged.generate().unwrap()),
+    )
+}
+
+#[test]
+fn forms_evidence_of_lunatic_attack() {
+    let (blocks, forged) = light_blocks();
+
+    let trace = [blocks[0].clone(), blocks[4].clone(), blocks[2].clone()];
+    let conflicting_trace = [blocks[0].clone(), blocks[2].clone(), forged.clone()];
+
+    let evidence = evidence_from_traces(&trace, &conflicting_trace).unwrap();
+
+    assert_eq!(
+        evidence.conflicting_block.signed_header,
+        forged.signed_header
+    );
+    assert_eq!(evidence.conflicting_block.validator_set, forged.validators);
+    assert_eq!(evidence.common_height, blocks[2].height());
+    assert_eq!(evidence.timestamp, blocks[2].time());
+    assert_eq!(
+        evidence.total_voting_power,
+        blocks[2].validators.total_voting_power()
+    );
+
+    // Every validator of the common light block signed the forged light block
+    let mut byzantine_validators = blocks[2].validators.validators().clone();
+    byzantine_validators.sort_by_key(|v| (v.power, v.address));
+    assert_eq!(evidence.byzantine_validators, byzantine_validators);
+}
+
+#[test]
+fn fails_without_conflicting_block() {
+    let (blocks, _) = light_blocks();
+
+    let trace = [blocks[0].clone(), blocks[2].clone(), blocks[4].clone()];
+
+    let err = evidence_from_traces(&trace, &trace).unwrap_err();
+    assert!(matches!(err.detail(), ErrorDetail::NoConflictingBlock(_)));
+}
+
+#[test]
+fn fails_without_common_block() {
+    let (blocks, forged) = light_blocks();
+
+    let trace = [blocks[0].clone(), blocks[2].clone(), blocks[4].clone()];
+    let conflicting_trace = [blocks[0].clone(), blocks[3].clone(), forged];
+
+    let err = evidence_from_traces(&trace, &conflicting_trace).unwrap_err();
+    match err.detail() {
+        ErrorDetail::NoCommonBlock(e) => assert_eq!(e.height, blocks[4].height()),
+        e => panic!("unexpected error: {e}"),
+    }
+}
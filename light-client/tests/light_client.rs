@@ -6,7 +6,7 @@ use tendermint_light_client::{
         scheduler,
     },
     errors::Error,
-    light_client::LightClient,
+    light_client::{AsyncLightClient, LightClient},
     state::State,
     store::{memory::MemoryStore, LightStore},
     tests::*,
@@ -44,6 +44,8 @@ fn run_test(tc: LightClientTest<LightBlock>) -> BisectionTestResult {
         trust_threshold,
         trusting_period: trusting_period.into(),
         clock_drift,
+        verification_mode: Default::default(),
+        commit_signatures: Default::default(),
     };
 
     let provider = tc.primary;
@@ -85,6 +87,59 @@ fn run_test(tc: LightClientTest<LightBlock>) -> BisectionTestResult {
     }
 }
 
+/// Run the test case with an `AsyncLightClient`, which must reach the same states as
+/// the blocking one.
+fn async_forward_test(tc: LightClientTest<LightBlock>) {
+    let primary = default_peer_id();
+    let untrusted_height = tc.height_to_verify;
+
+    let options = Options {
+        trust_threshold: tc.trust_options.trust_level,
+        trusting_period: tc.trust_options.period.into(),
+        clock_drift: Duration::from_secs(10),
+        verification_mode: Default::default(),
+        commit_signatures: Default::default(),
+    };
+
+    let io = MockIo::new(tc.primary.lite_blocks.clone());
+    let trusted_state = io
+        .fetch_light_block(AtHeight::At(tc.trust_options.height))
+        .expect("could not 'request' light block");
+
+    let mut light_store = MemoryStore::new();
+    light_store.insert(trusted_state, Status::Trusted);
+
+    let mut state = State {
+        light_store: Box::new(light_store),
+        verification_trace: HashMap::new(),
+    };
+
+    let light_client = AsyncLightClient::new(
+        primary,
+        options,
+        MockClock { now: tc.now },
+        scheduler::basic_bisecting_schedule,
+        ProdVerifier::default(),
+        io,
+    );
+
+    let result = futures::executor::block_on(verify_bisection_async(
+        untrusted_height,
+        &light_client,
+        &mut state,
+    ));
+
+    match (result, run_test(tc).new_states) {
+        (Ok(async_states), Ok(states)) => assert_eq!(async_states, states),
+        (Err(_), Err(_)) => {},
+        (async_result, result) => panic!(
+            "async and blocking light clients disagree: {:?} vs {:?}",
+            async_result.map(|_| ()),
+            result.map(|_| ())
+        ),
+    }
+}
+
 fn forward_test(tc: LightClientTest<LightBlock>) {
     let expect_error = match &tc.expected_output {
         Some(eo) => eo.eq("error"),
@@ -113,6 +168,10 @@ fn forward_test(tc: LightClientTest<LightBlock>) {
 fn run_tests() {
     let mut tester = Tester::new("light client verification", TEST_FILES_PATH);
     tester.add_test("forward verification with bisection", forward_test);
+    tester.add_test(
+        "async forward verification with bisection",
+        async_forward_test,
+    );
     tester.run_foreach_in_dir("bisection/single_peer");
     tester.finalize();
 }
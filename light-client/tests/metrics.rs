@@ -0,0 +1,177 @@
+#![cfg(feature = "metrics")]
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use metrics::{
+    Counter, CounterFn, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+    SharedString, Unit,
+};
+use tendermint_light_client::{
+    components::{
+        io::{AtHeight, Io},
+        scheduler::BoundedStepSchedule,
+    },
+    metrics::{
+        BISECTION_DEPTH, FORKS_DETECTED, SIGNATURE_VERIFICATIONS, STORE_LOOKUPS,
+        VERIFICATION_DURATION, VERIFICATION_STEPS,
+    },
+    tests::{light_blocks, make_light_client, make_state, now, MockIo},
+    verifier::types::{Height, LightBlock},
+};
+use tendermint_testgen::light_block::default_peer_id;
+
+const LENGTH: u64 = 10;
+
+struct AtomicCounter(AtomicU64);
+
+impl CounterFn for AtomicCounter {
+    fn increment(&self, value: u64) {
+        self.0.fetch_add(value, Ordering::SeqCst);
+    }
+
+    fn absolute(&self, value: u64) {
+        self.0.store(value, Ordering::SeqCst);
+    }
+}
+
+struct Samples(Mutex<Vec<f64>>);
+
+impl HistogramFn for Samples {
+    fn record(&self, value: f64) {
+        self.0.lock().unwrap().push(value);
+    }
+}
+
+/// Records counters and histograms in memory, by name and labels.
+#[derive(Default)]
+struct MemoryRecorder {
+    counters: Mutex<HashMap<String, Arc<AtomicCounter>>>,
+    histograms: Mutex<HashMap<String, Arc<Samples>>>,
+}
+
+impl MemoryRecorder {
+    fn counter(&self, name: &str) -> u64 {
+        self.counters
+            .lock()
+            .unwrap()
+            .get(name)
+            .map_or(0, |counter| counter.0.load(Ordering::SeqCst))
+    }
+
+    fn histogram(&self, name: &str) -> Vec<f64> {
+        self.histograms
+            .lock()
+            .unwrap()
+            .get(name)
+            .map_or_else(Vec::new, |samples| samples.0.lock().unwrap().clone())
+    }
+}
+
+fn key_name(key: &Key) -> String {
+    let labels: Vec<_> = key
+        .labels()
+        .map(|label| format!("{}={}", label.key(), label.value()))
+        .collect();
+
+    if labels.is_empty() {
+        key.name().to_string()
+    } else {
+        format!("{}{{{}}}", key.name(), labels.join(","))
+    }
+}
+
+impl Recorder for MemoryRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        let mut counters = self.counters.lock().unwrap();
+        let counter = counters
+            .entry(key_name(key))
+            .or_insert_with(|| Arc::new(AtomicCounter(AtomicU64::new(0))));
+        Counter::from_arc(counter.clone())
+    }
+
+    fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::noop()
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        let mut histograms = self.histograms.lock().unwrap();
+        let samples = histograms
+            .entry(key_name(key))
+            .or_insert_with(|| Arc::new(Samples(Mutex::new(Vec::new()))));
+        Histogram::from_arc(samples.clone())
+    }
+}
+
+fn signatures(light_block: &LightBlock) -> u64 {
+    light_block
+        .signed_header
+        .commit
+        .signatures
+        .iter()
+        .filter(|signature| !signature.is_absent())
+        .count() as u64
+}
+
+#[test]
+fn records_metrics_of_verifications() {
+    let light_blocks = light_blocks(LENGTH);
+    let io = MockIo::new(light_blocks.clone());
+
+    let trusted_state = io
+        .fetch_light_block(AtHeight::At(1_u32.into()))
+        .expect("could not find trusted light block");
+
+    let mut state = make_state(trusted_state);
+    let light_client = make_light_client(io, BoundedStepSchedule::new(3), now(LENGTH));
+
+    let recorder = MemoryRecorder::default();
+
+    metrics::with_local_recorder(&recorder, || {
+        let target = Height::try_from(LENGTH).unwrap();
+        light_client.verify_to_target(target, &mut state).unwrap();
+
+        // Verified blocks are looked up in the light store
+        let target = Height::try_from(7_u64).unwrap();
+        light_client.verify_to_target(target, &mut state).unwrap();
+
+        light_client.notify_fork_detected(&light_blocks[1], default_peer_id(), &light_blocks[1]);
+    });
+
+    let success = format!("{VERIFICATION_DURATION}{{result=success}}");
+    assert_eq!(recorder.histogram(&success).len(), 1);
+    assert_eq!(recorder.histogram(BISECTION_DEPTH), [3.0]);
+
+    let steps = format!("{VERIFICATION_STEPS}{{verdict=success}}");
+    assert_eq!(recorder.counter(&steps), 3);
+
+    // Every step skips over two light blocks, so signatures are checked against both sets
+    let expected: u64 = [3, 6, 9]
+        .iter()
+        .map(|&i| 2 * signatures(&light_blocks[i]))
+        .sum();
+    assert_eq!(recorder.counter(SIGNATURE_VERIFICATIONS), expected);
+
+    // The target and the three light blocks to verify were missing from the light store
+    assert_eq!(
+        recorder.counter(&format!("{STORE_LOOKUPS}{{result=miss}}")),
+        4
+    );
+    assert_eq!(
+        recorder.counter(&format!("{STORE_LOOKUPS}{{result=hit}}")),
+        1
+    );
+
+    assert_eq!(recorder.counter(FORKS_DETECTED), 1);
+}
@@ -0,0 +1,27 @@
+use tendermint_light_client::{
+    store::{memory::MemoryStore, migrate, LightStore},
+    tests::light_blocks,
+    verifier::types::Status,
+};
+
+#[test]
+fn migrate_preserves_statuses() {
+    let blocks = light_blocks(8);
+
+    let mut from = MemoryStore::new();
+    for (lb, status) in blocks.iter().cloned().zip(Status::iter().iter().cycle()) {
+        from.insert(lb, *status);
+    }
+
+    let mut to = MemoryStore::new();
+    let count = migrate(&from, &mut to);
+
+    assert_eq!(count, blocks.len());
+
+    for status in Status::iter() {
+        assert_eq!(
+            from.all(*status).collect::<Vec<_>>(),
+            to.all(*status).collect::<Vec<_>>()
+        );
+    }
+}
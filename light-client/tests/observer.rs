@@ -0,0 +1,179 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tendermint_light_client::{
+    components::{
+        io::{AtHeight, Io},
+        observer::{ClockDrift, Observer},
+        scheduler::{self, BoundedStepSchedule, Scheduler},
+    },
+    instance::Instance,
+    tests::{light_blocks, make_light_client, make_state, now, MockIo},
+    verifier::{
+        types::{Height, LightBlock, PeerId, Time},
+        Verdict,
+    },
+};
+use tendermint_testgen::light_block::default_peer_id;
+
+const LENGTH: u64 = 10;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Event {
+    BlockVerified(u64),
+    BisectionStep(u64, u64, bool),
+    ForkDetected(u64, u64),
+    TrustExpired(u64),
+    ClockDrift(u64, u64),
+}
+
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<Event>>>);
+
+impl Recorder {
+    fn events(&self) -> Vec<Event> {
+        self.0.lock().unwrap().clone()
+    }
+
+    fn record(&self, event: Event) {
+        self.0.lock().unwrap().push(event);
+    }
+}
+
+impl Observer for Recorder {
+    fn on_block_verified(&self, light_block: &LightBlock) {
+        self.record(Event::BlockVerified(light_block.height().value()));
+    }
+
+    fn on_bisection_step(
+        &self,
+        trusted_block: &LightBlock,
+        current_block: &LightBlock,
+        verdict: &Verdict,
+    ) {
+        self.record(Event::BisectionStep(
+            trusted_block.height().value(),
+            current_block.height().value(),
+            *verdict == Verdict::Success,
+        ));
+    }
+
+    fn on_fork_detected(
+        &self,
+        verified_block: &LightBlock,
+        _witness: PeerId,
+        conflicting_block: &LightBlock,
+    ) {
+        self.record(Event::ForkDetected(
+            verified_block.height().value(),
+            conflicting_block.height().value(),
+        ));
+    }
+
+    fn on_trust_expired(&self, trusted_block: &LightBlock) {
+        self.record(Event::TrustExpired(trusted_block.height().value()));
+    }
+
+    fn on_clock_drift(&self, drift: &ClockDrift) {
+        self.record(Event::ClockDrift(
+            drift.height.value(),
+            drift.drift.as_secs(),
+        ));
+    }
+}
+
+fn make_instance(scheduler: impl Scheduler + 'static, now: Time) -> (Instance, Recorder) {
+    let io = MockIo::new(light_blocks(LENGTH));
+
+    let trusted_state = io
+        .fetch_light_block(AtHeight::At(1_u32.into()))
+        .expect("could not find trusted light block");
+
+    let light_client = make_light_client(io, scheduler, now);
+
+    let recorder = Recorder::default();
+    let mut instance = Instance::new(light_client, make_state(trusted_state));
+    instance.add_observer(recorder.clone());
+
+    (instance, recorder)
+}
+
+fn verify_to_target(instance: &mut Instance, height: u64) -> Result<LightBlock, String> {
+    instance
+        .light_client
+        .verify_to_target(Height::try_from(height).unwrap(), &mut instance.state)
+        .map_err(|e| e.to_string())
+}
+
+#[test]
+fn notifies_bisection_steps_and_verified_block() {
+    let (mut instance, recorder) = make_instance(BoundedStepSchedule::new(3), now(LENGTH));
+
+    verify_to_target(&mut instance, LENGTH).unwrap();
+
+    assert_eq!(
+        recorder.events(),
+        [
+            Event::BisectionStep(1, 4, true),
+            Event::BisectionStep(4, 7, true),
+            Event::BisectionStep(7, 10, true),
+            Event::BlockVerified(10),
+        ]
+    );
+
+    // Blocks already verified are not verified nor notified again
+    verify_to_target(&mut instance, 7).unwrap();
+    assert_eq!(recorder.events().len(), 4);
+}
+
+#[test]
+fn notifies_expired_trust() {
+    let expired = (now(LENGTH) + Duration::from_secs(60 * 60 * 24 * 30)).unwrap();
+    let (mut instance, recorder) = make_instance(scheduler::basic_bisecting_schedule, expired);
+
+    verify_to_target(&mut instance, LENGTH).unwrap_err();
+
+    assert_eq!(recorder.events(), [Event::TrustExpired(1)]);
+}
+
+#[test]
+fn notifies_clock_drift_beyond_margin() {
+    // The header at height `h` has time `h`, so that the one at height 10 is 3 seconds
+    // ahead of the local time, which is still within the maximum clock drift
+    let lagging = tendermint_testgen::helpers::get_time(7).unwrap();
+    let (mut instance, recorder) = make_instance(BoundedStepSchedule::new(3), lagging);
+    instance.light_client = instance
+        .light_client
+        .with_clock_drift_warning(Duration::from_secs(2));
+
+    verify_to_target(&mut instance, LENGTH).unwrap();
+
+    assert_eq!(
+        recorder.events(),
+        [
+            Event::BisectionStep(1, 4, true),
+            Event::BisectionStep(4, 7, true),
+            Event::ClockDrift(10, 3),
+            Event::BisectionStep(7, 10, true),
+            Event::BlockVerified(10),
+        ]
+    );
+}
+
+#[test]
+fn notifies_detected_forks_to_all_observers() {
+    let (mut instance, recorder) = make_instance(scheduler::basic_bisecting_schedule, now(LENGTH));
+
+    let other = Recorder::default();
+    instance.add_observer(other.clone());
+
+    let blocks = light_blocks(2);
+    instance
+        .light_client
+        .notify_fork_detected(&blocks[1], default_peer_id(), &blocks[1]);
+
+    assert_eq!(recorder.events(), [Event::ForkDetected(2, 2)]);
+    assert_eq!(other.events(), recorder.events());
+}
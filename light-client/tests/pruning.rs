@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use tendermint_light_client::{
+    components::pruner::PruningPolicy,
+    instance::Instance,
+    store::{memory::MemoryStore, LightStore},
+    tests::{self, light_blocks, now},
+    verifier::types::{Height, Status},
+};
+
+fn heights(light_store: &dyn LightStore, status: Status) -> Vec<u64> {
+    light_store.heights(status).map(|h| h.value()).collect()
+}
+
+fn make_instance(length: u64, pruning_policy: PruningPolicy) -> Instance {
+    let Instance {
+        light_client,
+        state,
+    } = tests::make_instance(length);
+
+    Instance::new(light_client.with_pruning_policy(pruning_policy), state)
+}
+
+#[test]
+fn default_policy_keeps_everything() {
+    let mut light_store = MemoryStore::new();
+    for lb in light_blocks(5) {
+        light_store.insert(lb, Status::Verified);
+    }
+
+    let pruned = PruningPolicy::default().prune(&mut light_store, now(5), 1_u32.into());
+
+    assert_eq!(pruned, 0);
+    assert_eq!(heights(&light_store, Status::Verified), vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn keep_last_keeps_highest_blocks() {
+    let mut light_store = MemoryStore::new();
+    for lb in light_blocks(10) {
+        let status = if lb.height().value() % 2 == 0 {
+            Status::Verified
+        } else {
+            Status::Unverified
+        };
+
+        light_store.insert(lb, status);
+    }
+
+    let pruned = PruningPolicy::keep_last(3).prune(&mut light_store, now(10), 10_u32.into());
+
+    assert_eq!(pruned, 5);
+    assert_eq!(heights(&light_store, Status::Verified), vec![6, 8, 10]);
+    assert_eq!(heights(&light_store, Status::Unverified), vec![7, 9]);
+}
+
+#[test]
+fn keep_for_removes_old_blocks() {
+    let mut light_store = MemoryStore::new();
+    for lb in light_blocks(10) {
+        light_store.insert(lb, Status::Verified);
+    }
+
+    // Blocks at height `h` are produced at time `h`, in seconds
+    let policy = PruningPolicy::keep_for(Duration::from_secs(4));
+    let pruned = policy.prune(&mut light_store, now(10), 10_u32.into());
+
+    assert_eq!(pruned, 6);
+    assert_eq!(heights(&light_store, Status::Verified), vec![7, 8, 9, 10]);
+}
+
+#[test]
+fn keep_checkpoints_only_removes_unverified_and_failed_blocks() {
+    let mut light_store = MemoryStore::new();
+    for lb in light_blocks(4) {
+        let status = match lb.height().value() {
+            1 => Status::Trusted,
+            2 => Status::Unverified,
+            3 => Status::Failed,
+            _ => Status::Verified,
+        };
+
+        light_store.insert(lb, status);
+    }
+
+    let pruned =
+        PruningPolicy::keep_checkpoints_only().prune(&mut light_store, now(4), 4_u32.into());
+
+    assert_eq!(pruned, 2);
+    assert_eq!(heights(&light_store, Status::Trusted), vec![1]);
+    assert_eq!(heights(&light_store, Status::Verified), vec![4]);
+    assert!(heights(&light_store, Status::Unverified).is_empty());
+    assert!(heights(&light_store, Status::Failed).is_empty());
+}
+
+#[test]
+fn light_client_prunes_after_verification() {
+    let mut instance = make_instance(20, PruningPolicy::keep_last(2));
+
+    let targets = [5_u32, 10, 15, 20].map(Height::from);
+    for (height, result) in instance.verify_to_heights(&targets) {
+        assert_eq!(result.unwrap().height(), height);
+    }
+
+    let light_store = instance.state.light_store.as_ref();
+    assert_eq!(heights(light_store, Status::Verified), vec![15, 20]);
+    assert!(heights(light_store, Status::Trusted).is_empty());
+}
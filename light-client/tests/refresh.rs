@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use tendermint_light_client::{
+    components::scheduler::BoundedStepSchedule,
+    instance::Instance,
+    state::State,
+    store::{memory::MemoryStore, LightStore},
+    tests::{light_blocks, make_light_client, options, MockIo},
+    verifier::types::{LightBlock, Status, Time},
+};
+
+const LENGTH: u64 = 10;
+
+const DAY: Duration = Duration::from_secs(60 * 60 * 24);
+
+/// An instance trusting the given light blocks, at 8 days after the first one.
+fn make_instance(light_blocks: Vec<LightBlock>, trusted_blocks: Vec<LightBlock>) -> Instance {
+    let mut light_store = MemoryStore::new();
+    for light_block in trusted_blocks {
+        light_store.insert(light_block, Status::Trusted);
+    }
+
+    let light_client = make_light_client(
+        MockIo::new(light_blocks),
+        BoundedStepSchedule::new(3),
+        now(),
+    );
+
+    Instance::new(light_client, State::new(light_store))
+}
+
+fn now() -> Time {
+    (tendermint_testgen::helpers::get_time(1).unwrap() + 8 * DAY).unwrap()
+}
+
+#[test]
+fn refresh_trusted_state_when_due() {
+    let light_blocks = light_blocks(LENGTH);
+    let trusted_block = light_blocks[0].clone();
+    let mut instance = make_instance(light_blocks.clone(), vec![trusted_block.clone()]);
+
+    let expires_at = (trusted_block.time() + options().trusting_period).unwrap();
+    assert_eq!(
+        instance.light_client.trust_expires_at(&instance.state),
+        Some(expires_at)
+    );
+
+    // The trusted state expires in 2 days
+    assert_eq!(instance.refresh_delay(DAY), Some(DAY));
+    assert_eq!(instance.refresh_trusted_state(DAY).unwrap(), None);
+    assert_eq!(instance.latest_trusted(), Some(trusted_block));
+
+    assert_eq!(instance.refresh_delay(3 * DAY), Some(Duration::ZERO));
+    let refreshed = instance.refresh_trusted_state(3 * DAY).unwrap().unwrap();
+    assert_eq!(refreshed.height().value(), LENGTH);
+
+    // The trusted state now expires 10 days after the highest block of the primary
+    assert_eq!(
+        instance.light_client.trust_expires_at(&instance.state),
+        Some((light_blocks[LENGTH as usize - 1].time() + options().trusting_period).unwrap())
+    );
+    assert_eq!(instance.refresh_trusted_state(DAY).unwrap(), None);
+}
+
+#[test]
+fn refresh_trusted_state_without_trusted_state() {
+    let mut instance = make_instance(light_blocks(LENGTH), vec![]);
+
+    assert_eq!(
+        instance.light_client.trust_expires_at(&instance.state),
+        None
+    );
+    assert_eq!(instance.refresh_delay(DAY), None);
+    assert!(instance.refresh_trusted_state(DAY).is_err());
+}
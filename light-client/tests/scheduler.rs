@@ -0,0 +1,282 @@
+use std::{
+    sync::{Arc, Condvar, Mutex},
+    thread,
+};
+
+use tendermint_light_client::{
+    builder::LightClientBuilder,
+    components::{
+        io::{AtHeight, Io},
+        prefetch::{self, PrefetchingIo, PrefetchingSchedule},
+        scheduler::{
+            self, check_scheduler, BoundedStepSchedule, Scheduler, ValidatorSetChangeSchedule,
+        },
+    },
+    instance::Instance,
+    state::State,
+    store::{memory::MemoryStore, LightStore},
+    tests::{light_blocks, make_light_client, make_state, now, options, MockClock, MockIo},
+    verifier::{options::VerificationMode, types::Height, ProdVerifier},
+};
+use tendermint_testgen::light_block::default_peer_id;
+
+const LENGTH: u64 = 10;
+
+fn make_instance(scheduler: impl Scheduler + 'static) -> Instance {
+    let io = MockIo::new(light_blocks(LENGTH));
+
+    let trusted_state = io
+        .fetch_light_block(AtHeight::At(1_u32.into()))
+        .expect("could not find trusted light block");
+
+    let light_client = make_light_client(io, scheduler, now(LENGTH));
+
+    Instance::new(light_client, make_state(trusted_state))
+}
+
+/// The heights of the light blocks verified on the way to the last height of the chain,
+/// besides the trusted one.
+fn verified_heights(scheduler: impl Scheduler + 'static) -> Vec<u64> {
+    let mut instance = make_instance(scheduler);
+
+    let target_height = Height::try_from(LENGTH).unwrap();
+    let verified = instance
+        .light_client
+        .verify_to_target(target_height, &mut instance.state)
+        .unwrap();
+
+    assert_eq!(verified.height(), target_height);
+
+    trace_heights(&instance.state, target_height)
+}
+
+fn trace_heights(state: &State, target_height: Height) -> Vec<u64> {
+    state
+        .get_trace(target_height)
+        .iter()
+        .map(|lb| lb.height().value())
+        .collect()
+}
+
+fn heights(heights: impl IntoIterator<Item = u64>) -> Vec<Height> {
+    heights
+        .into_iter()
+        .map(|h| Height::try_from(h).unwrap())
+        .collect()
+}
+
+#[test]
+fn schedulers_satisfy_the_specification() {
+    let light_block = &light_blocks(1)[0];
+
+    check_scheduler(&scheduler::basic_bisecting_schedule, light_block, 16).unwrap();
+    check_scheduler(&scheduler::exponential_probing_schedule, light_block, 16).unwrap();
+    check_scheduler(&BoundedStepSchedule::sequential(), light_block, 16).unwrap();
+    check_scheduler(&BoundedStepSchedule::new(3), light_block, 16).unwrap();
+
+    let schedule = ValidatorSetChangeSchedule::new(heights([2, 5, 6, 12]));
+    check_scheduler(&schedule, light_block, 16).unwrap();
+
+    let schedule = scheduler::trace_scheduler(heights([3, 6, 7, 12]));
+    check_scheduler(&schedule, light_block, 16).unwrap();
+
+    let io = PrefetchingIo::new(MockIo::new(light_blocks(16)));
+    let schedule = PrefetchingSchedule::new(scheduler::basic_bisecting_schedule, io);
+    check_scheduler(&schedule, light_block, 16).unwrap();
+}
+
+#[test]
+fn check_scheduler_reports_violations() {
+    let light_block = &light_blocks(1)[0];
+
+    // Never moves forward, which violates the postcondition of the scheduler
+    let stuck = |_: &dyn LightStore, current_height: Height, _: Height| current_height;
+
+    let violation = check_scheduler(&stuck, light_block, 4).unwrap_err();
+
+    assert_eq!(violation.trusted_height.value(), 1);
+    assert_eq!(violation.current_height.value(), 1);
+    assert_eq!(violation.target_height.value(), 2);
+    assert_eq!(violation.scheduled_height, None);
+}
+
+#[test]
+fn bounded_step_schedule() {
+    assert_eq!(verified_heights(BoundedStepSchedule::new(3)), [4, 7, 10]);
+    assert_eq!(
+        verified_heights(BoundedStepSchedule::sequential()),
+        (2..=LENGTH).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn exponential_probing_schedule() {
+    assert_eq!(
+        verified_heights(scheduler::exponential_probing_schedule),
+        [2, 3, 5, 9, 10]
+    );
+}
+
+#[test]
+fn validator_set_change_schedule() {
+    let schedule = ValidatorSetChangeSchedule::new(heights([5, 6]));
+    assert_eq!(verified_heights(schedule), [4, 5, 6, 10]);
+}
+
+#[test]
+fn trace_scheduler() {
+    let schedule = scheduler::trace_scheduler(heights([3, 6, 10]));
+    let instance = make_instance(scheduler::basic_bisecting_schedule);
+    let light_store = instance.state.light_store.as_ref();
+
+    let schedule = |current_height: u64| {
+        let target_height = Height::try_from(LENGTH).unwrap();
+        let current_height = Height::try_from(current_height).unwrap();
+        schedule
+            .schedule(light_store, current_height, target_height)
+            .value()
+    };
+
+    // Bisects over the heights of the trace below the one which could not be verified
+    assert_eq!(schedule(10), 6);
+    assert_eq!(schedule(6), 3);
+    assert_eq!(schedule(3), 2);
+
+    // Retries the target height once back at the trusted height
+    assert_eq!(schedule(1), LENGTH);
+}
+
+#[test]
+fn prefetching_bisecting_schedule() {
+    let mock_io = MockIo::new(light_blocks(LENGTH));
+    let fetched = Arc::new(Mutex::new(Vec::new()));
+
+    let counting_io = {
+        let fetched = fetched.clone();
+        move |height: AtHeight| {
+            if let AtHeight::At(height) = height {
+                fetched.lock().unwrap().push(height.value());
+            }
+            mock_io.fetch_light_block(height)
+        }
+    };
+
+    let (schedule, io) = prefetch::prefetching_bisecting_schedule(counting_io);
+
+    let trusted_state = io.fetch_light_block(AtHeight::At(1_u32.into())).unwrap();
+    let mut state = make_state(trusted_state);
+
+    let light_client = make_light_client(io.clone(), schedule, now(LENGTH));
+
+    let target_height = Height::try_from(LENGTH).unwrap();
+    light_client
+        .verify_to_target(target_height, &mut state)
+        .unwrap();
+
+    // Schedules the same heights as the basic bisecting scheduler
+    assert_eq!(trace_heights(&state, target_height), [1, LENGTH]);
+
+    // The target height was prefetched, and not fetched again by the light client, while the
+    // midpoint to fall back to if it could not be verified was discarded once it was
+    let fetched_target = fetched
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|&&h| h == LENGTH)
+        .count();
+    assert_eq!(fetched_target, 1);
+    assert!(io.prefetched_heights().is_empty());
+}
+
+#[test]
+fn prefetching_io_cancels_stale_prefetches() {
+    let mock_io = MockIo::new(light_blocks(LENGTH));
+    let fetched = Arc::new(Mutex::new(Vec::new()));
+    let released = Arc::new((Mutex::new(false), Condvar::new()));
+
+    // Blocks the fetches until released
+    let blocking_io = {
+        let (fetched, released) = (fetched.clone(), released.clone());
+        move |height: AtHeight| {
+            if let AtHeight::At(height) = height {
+                fetched.lock().unwrap().push(height.value());
+            }
+
+            let (lock, cvar) = &*released;
+            let _released = cvar
+                .wait_while(lock.lock().unwrap(), |released| !*released)
+                .unwrap();
+
+            mock_io.fetch_light_block(height)
+        }
+    };
+
+    let io = PrefetchingIo::with_max_threads(blocking_io, 1);
+    io.prefetch(&heights([5, 6, 7]));
+    while fetched.lock().unwrap().is_empty() {
+        thread::yield_now();
+    }
+
+    // The fetch at height 6 was queued behind the one at height 5, and never starts
+    io.prefetch(&heights([7, 8]));
+    assert_eq!(io.prefetched_heights(), heights([7, 8]));
+
+    let (lock, cvar) = &*released;
+    *lock.lock().unwrap() = true;
+    cvar.notify_all();
+
+    for height in [8_u32, 7] {
+        let light_block = io.fetch_light_block(AtHeight::At(height.into())).unwrap();
+        assert_eq!(light_block.height(), height.into());
+    }
+
+    let mut fetched = fetched.lock().unwrap().clone();
+    fetched.sort();
+    assert_eq!(fetched, [5, 7, 8]);
+    assert!(io.prefetched_heights().is_empty());
+}
+
+#[test]
+fn sequential_verification_mode_overrides_scheduler() {
+    let mut instance = make_instance(scheduler::basic_bisecting_schedule);
+    instance.light_client.options.verification_mode = VerificationMode::Sequential;
+
+    let target_height = Height::try_from(LENGTH).unwrap();
+    instance
+        .light_client
+        .verify_to_target(target_height, &mut instance.state)
+        .unwrap();
+
+    assert_eq!(
+        trace_heights(&instance.state, target_height),
+        (2..=LENGTH).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn builder_sets_scheduler() {
+    let io = MockIo::new(light_blocks(LENGTH));
+
+    let mut instance = LightClientBuilder::custom(
+        default_peer_id(),
+        options(),
+        Box::new(MemoryStore::new()),
+        Box::new(io),
+        Box::new(MockClock { now: now(LENGTH) }),
+        Box::<ProdVerifier>::default(),
+        Box::new(scheduler::basic_bisecting_schedule),
+        Box::<tendermint_light_client::verifier::predicates::ProdPredicates>::default(),
+    )
+    .scheduler(BoundedStepSchedule::new(4))
+    .trust_primary_at(1_u32.into(), light_blocks(1)[0].signed_header.header.hash())
+    .unwrap()
+    .build();
+
+    let target_height = Height::try_from(LENGTH).unwrap();
+    instance
+        .light_client
+        .verify_to_target(target_height, &mut instance.state)
+        .unwrap();
+
+    assert_eq!(trace_heights(&instance.state, target_height), [5, 9, 10]);
+}
@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use tendermint_light_client::{
+    components::{
+        io::{AtHeight, Io},
+        scheduler::BoundedStepSchedule,
+    },
+    instance::{Instance, Snapshot},
+    state::State,
+    store::{memory::MemoryStore, LightStore},
+    tests::{light_blocks, make_light_client, now, options, MockIo},
+    verifier::{
+        options::Options,
+        types::{Height, LightBlock, Status},
+    },
+};
+
+const LENGTH: u64 = 10;
+
+/// An instance verifying with steps of 3 blocks, whose primary only serves the given blocks,
+/// trusting the given blocks.
+fn make_instance(
+    options: Options,
+    light_blocks: Vec<LightBlock>,
+    trusted_blocks: Vec<LightBlock>,
+) -> Instance {
+    let mut light_store = MemoryStore::new();
+    for light_block in trusted_blocks {
+        light_store.insert(light_block, Status::Trusted);
+    }
+
+    let mut light_client = make_light_client(
+        MockIo::new(light_blocks),
+        BoundedStepSchedule::new(3),
+        now(LENGTH),
+    );
+    light_client.options = options;
+
+    Instance::new(light_client, State::new(light_store))
+}
+
+fn heights(snapshot: &Snapshot) -> Vec<(u64, Status)> {
+    snapshot
+        .light_blocks
+        .iter()
+        .map(|(lb, status)| (lb.height().value(), *status))
+        .collect()
+}
+
+#[test]
+fn export_and_import_state() {
+    let light_blocks = light_blocks(LENGTH);
+    let io = MockIo::new(light_blocks.clone());
+    let trusted_block = io.fetch_light_block(AtHeight::At(1_u32.into())).unwrap();
+
+    let mut instance = make_instance(options(), light_blocks.clone(), vec![trusted_block]);
+    instance
+        .light_client
+        .verify_to_target(Height::try_from(LENGTH).unwrap(), &mut instance.state)
+        .unwrap();
+
+    let snapshot = instance.export_state();
+    assert_eq!(snapshot.options, options());
+    assert_eq!(
+        heights(&snapshot),
+        [
+            (1, Status::Trusted),
+            (4, Status::Verified),
+            (7, Status::Verified),
+            (10, Status::Verified),
+        ]
+    );
+
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let snapshot: Snapshot = serde_json::from_str(&json).unwrap();
+
+    // A new instance whose primary only serves the first block verifies from the imported
+    // light blocks
+    let mut imported = make_instance(
+        Options {
+            clock_drift: Duration::from_secs(1),
+            ..options()
+        },
+        vec![light_blocks[0].clone()],
+        vec![],
+    );
+    assert_eq!(imported.import_state(snapshot.clone()), 4);
+    assert_eq!(imported.light_client.options, options());
+    assert_eq!(imported.export_state(), snapshot);
+
+    let verified = imported
+        .light_client
+        .verify_to_target(Height::try_from(LENGTH).unwrap(), &mut imported.state)
+        .unwrap();
+    assert_eq!(verified.height().value(), LENGTH);
+
+    // Light blocks already in the light store are not imported again
+    assert_eq!(imported.import_state(snapshot), 0);
+}
+
+#[test]
+fn import_state_does_not_downgrade_light_blocks() {
+    let light_blocks = light_blocks(LENGTH);
+
+    let snapshot = Snapshot {
+        options: options(),
+        light_blocks: vec![
+            (light_blocks[0].clone(), Status::Verified),
+            (light_blocks[1].clone(), Status::Verified),
+        ],
+    };
+
+    let mut instance = make_instance(
+        options(),
+        light_blocks.clone(),
+        vec![light_blocks[0].clone()],
+    );
+    assert_eq!(instance.import_state(snapshot), 1);
+
+    assert_eq!(
+        heights(&instance.export_state()),
+        [(1, Status::Trusted), (2, Status::Verified)]
+    );
+}
@@ -0,0 +1,92 @@
+use tendermint_light_client::{
+    instance::Instance,
+    store::{memory::MemoryStore, overlay::OverlayStore, LightStore},
+    tests::{light_blocks, make_instance},
+    verifier::types::{Height, Status},
+};
+
+const LENGTH: u64 = 10;
+
+fn highest_verified_height(instance: &Instance) -> Option<u64> {
+    instance
+        .state
+        .light_store
+        .highest_trusted_or_verified()
+        .map(|lb| lb.height().value())
+}
+
+fn verify_to_target(instance: &mut Instance, height: u64) {
+    instance
+        .light_client
+        .verify_to_target(Height::try_from(height).unwrap(), &mut instance.state)
+        .unwrap();
+}
+
+#[test]
+fn discarded_speculation_leaves_state_untouched() {
+    let mut instance = make_instance(LENGTH);
+
+    let mut speculation = instance.speculative();
+    verify_to_target(&mut speculation, LENGTH);
+    assert_eq!(highest_verified_height(&speculation), Some(LENGTH));
+    speculation.discard();
+
+    assert_eq!(highest_verified_height(&instance), Some(1));
+    assert!(instance.state.verification_trace.is_empty());
+    assert_eq!(instance.state.light_store.all(Status::Verified).count(), 0);
+
+    // Dropping the speculation discards it as well
+    {
+        let mut speculation = instance.speculative();
+        verify_to_target(&mut speculation, LENGTH);
+    }
+
+    assert_eq!(highest_verified_height(&instance), Some(1));
+}
+
+#[test]
+fn committed_speculation_is_applied_to_state() {
+    let mut instance = make_instance(LENGTH);
+
+    let mut speculation = instance.speculative();
+    verify_to_target(&mut speculation, LENGTH);
+    speculation.commit();
+
+    assert_eq!(highest_verified_height(&instance), Some(LENGTH));
+    assert!(!instance.state.verification_trace.is_empty());
+}
+
+#[test]
+fn overlay_store_shadows_underlying_store() {
+    let blocks = light_blocks(4);
+
+    let mut base = MemoryStore::new();
+    for lb in &blocks {
+        base.insert(lb.clone(), Status::Verified);
+    }
+
+    let mut overlay = OverlayStore::new(Box::new(base));
+    overlay.remove(blocks[3].height(), Status::Verified);
+    overlay.update(&blocks[1], Status::Trusted);
+
+    let heights = |store: &dyn LightStore, status| {
+        store
+            .all(status)
+            .map(|lb| lb.height().value())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(heights(&overlay, Status::Verified), [1, 3]);
+    assert_eq!(heights(&overlay, Status::Trusted), [2]);
+    assert_eq!(
+        overlay
+            .highest(Status::Verified)
+            .map(|lb| lb.height().value()),
+        Some(3)
+    );
+    assert!(overlay.has_changes());
+
+    let base = overlay.commit();
+    assert_eq!(heights(base.as_ref(), Status::Verified), [1, 3]);
+    assert_eq!(heights(base.as_ref(), Status::Trusted), [2]);
+}
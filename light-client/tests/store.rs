@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+use tendermint_light_client::{
+    store::{memory::MemoryStore, LightStore},
+    tests::light_blocks,
+    verifier::types::Status,
+};
+
+#[test]
+fn latest_trusted_at_returns_trusted_state_as_of_time() {
+    let blocks = light_blocks(8);
+
+    // Trust the blocks at odd heights, and only verify the other ones
+    let mut store = MemoryStore::new();
+    for lb in &blocks {
+        let status = if lb.height().value() % 2 == 1 {
+            Status::Trusted
+        } else {
+            Status::Verified
+        };
+        store.insert(lb.clone(), status);
+    }
+
+    let height_at = |time| store.latest_trusted_at(time).map(|lb| lb.height().value());
+
+    let before_first = (blocks[0].time() - Duration::from_secs(1)).unwrap();
+    let after_last = (blocks[7].time() + Duration::from_secs(1)).unwrap();
+
+    assert_eq!(height_at(before_first), None);
+    assert_eq!(height_at(blocks[0].time()), Some(1));
+    assert_eq!(height_at(blocks[3].time()), Some(3));
+    assert_eq!(height_at(blocks[4].time()), Some(5));
+    assert_eq!(height_at(after_last), Some(7));
+
+    assert_eq!(
+        store
+            .highest_at(blocks[6].time(), Status::Verified)
+            .map(|lb| lb.height().value()),
+        Some(6)
+    );
+}
@@ -0,0 +1,55 @@
+use tendermint_light_client::{
+    instance::Instance,
+    state::State,
+    tests::make_instance,
+    verifier::types::{Height, TrustThreshold},
+};
+
+fn trace_heights(state: &State, target_height: Height) -> Vec<u64> {
+    state
+        .get_trace(target_height)
+        .iter()
+        .map(|lb| lb.height().value())
+        .collect()
+}
+
+#[test]
+fn default_trust_threshold_skips_to_target() {
+    let Instance {
+        light_client,
+        mut state,
+    } = make_instance(10);
+    let target_height = Height::from(10_u32);
+
+    light_client
+        .verify_to_target_with_trust_threshold(target_height, None, &mut state)
+        .unwrap();
+
+    assert_eq!(trace_heights(&state, target_height), vec![1, 10]);
+}
+
+#[test]
+fn trust_threshold_override_applies_to_call() {
+    let Instance {
+        light_client,
+        mut state,
+    } = make_instance(10);
+    let target_height = Height::from(10_u32);
+
+    // No skip can ever gather more than all of the voting power, so the light client
+    // has to verify every header sequentially.
+    let trust_threshold = TrustThreshold::new(1, 1).unwrap();
+
+    light_client
+        .verify_to_target_with_trust_threshold(target_height, Some(trust_threshold), &mut state)
+        .unwrap();
+
+    assert_eq!(
+        trace_heights(&state, target_height),
+        (2..=10).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        light_client.options.trust_threshold,
+        TrustThreshold::default()
+    );
+}
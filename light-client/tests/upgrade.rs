@@ -0,0 +1,113 @@
+use tendermint::chain;
+use tendermint_light_client::{
+    components::scheduler,
+    errors::ErrorDetail,
+    instance::Instance,
+    tests::{make_light_client, make_state, testgen_to_lb, MockIo},
+    upgrade::ChainUpgrade,
+    verifier::types::{Height, LightBlock, Time},
+};
+use tendermint_testgen::{light_block::LightBlock as TestgenLightBlock, Generator, LightChain};
+
+const UPGRADE_HEIGHT: u64 = 5;
+
+/// The blocks of the chain up to the upgrade height, followed by the given blocks
+/// of the chain with the given chain id.
+fn light_blocks(successor_chain_id: &str, length: u64) -> Vec<LightBlock> {
+    let old_chain = LightChain::default_with_length(UPGRADE_HEIGHT).light_blocks;
+
+    let new_chain = (UPGRADE_HEIGHT + 1..=length).map(|height| {
+        TestgenLightBlock::new_default_with_time_and_chain_id(
+            successor_chain_id.to_string(),
+            Time::from_unix_timestamp(height as i64, 0).unwrap(),
+            height,
+        )
+    });
+
+    old_chain
+        .into_iter()
+        .chain(new_chain)
+        .map(|lb| lb.generate().unwrap())
+        .map(testgen_to_lb)
+        .collect()
+}
+
+fn upgrade() -> ChainUpgrade {
+    ChainUpgrade::new(
+        Height::try_from(UPGRADE_HEIGHT).unwrap(),
+        chain::Id::try_from("test-chain-2").unwrap(),
+    )
+}
+
+fn make_instance(light_blocks: Vec<LightBlock>) -> Instance {
+    let state = make_state(light_blocks[0].clone());
+
+    let light_client = make_light_client(
+        MockIo::new(light_blocks),
+        scheduler::basic_bisecting_schedule,
+        tendermint_testgen::helpers::get_time(20).unwrap(),
+    )
+    .with_chain_upgrade(upgrade());
+
+    Instance::new(light_client, state)
+}
+
+#[test]
+fn does_not_verify_across_the_upgrade() {
+    let mut instance = make_instance(light_blocks("test-chain-2", 8));
+
+    let err = instance
+        .light_client
+        .verify_to_target(Height::try_from(8_u64).unwrap(), &mut instance.state)
+        .unwrap_err();
+
+    match err.detail() {
+        ErrorDetail::TargetBeyondUpgradeHeight(e) => {
+            assert_eq!(e.target_height.value(), 8);
+            assert_eq!(e.upgrade_height.value(), UPGRADE_HEIGHT);
+        },
+        e => panic!("unexpected error: {e}"),
+    }
+
+    // The highest block of the primary is past the upgrade
+    let verified = instance
+        .light_client
+        .verify_to_highest(&mut instance.state)
+        .unwrap();
+    assert_eq!(verified.height().value(), UPGRADE_HEIGHT);
+}
+
+#[test]
+fn hands_off_to_the_successor_chain() {
+    let mut instance = make_instance(light_blocks("test-chain-2", 8));
+
+    let first_block = instance.hand_off_upgrade().unwrap();
+
+    assert_eq!(first_block.height().value(), UPGRADE_HEIGHT + 1);
+    assert_eq!(
+        first_block.signed_header.header.chain_id,
+        upgrade().successor_chain_id
+    );
+
+    // The last block before the upgrade was verified on the way
+    assert!(instance
+        .state
+        .light_store
+        .get_trusted_or_verified(upgrade().height)
+        .is_some());
+}
+
+#[test]
+fn rejects_hand_off_to_another_chain() {
+    let mut instance = make_instance(light_blocks("other-chain", 8));
+
+    let err = instance.hand_off_upgrade().unwrap_err();
+
+    match err.detail() {
+        ErrorDetail::InvalidUpgradeChainId(e) => {
+            assert_eq!(e.chain_id.as_str(), "other-chain");
+            assert_eq!(e.successor_chain_id, upgrade().successor_chain_id);
+        },
+        e => panic!("unexpected error: {e}"),
+    }
+}
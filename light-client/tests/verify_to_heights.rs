@@ -0,0 +1,80 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use tendermint_light_client::{
+    components::{
+        io::{AtHeight, Io, IoError},
+        scheduler,
+    },
+    instance::Instance,
+    tests::{light_blocks, make_light_client, make_state, now, MockIo},
+    verifier::types::{Height, LightBlock},
+};
+
+/// An `Io` component which counts the light blocks it fetched.
+#[derive(Clone)]
+struct CountingIo {
+    io: MockIo,
+    fetched: Arc<AtomicUsize>,
+}
+
+impl Io for CountingIo {
+    fn fetch_light_block(&self, height: AtHeight) -> Result<LightBlock, IoError> {
+        self.fetched.fetch_add(1, Ordering::SeqCst);
+        self.io.fetch_light_block(height)
+    }
+}
+
+fn make(length: u64, fetched: Arc<AtomicUsize>) -> Instance {
+    let io = MockIo::new(light_blocks(length));
+
+    let trusted_state = io
+        .fetch_light_block(AtHeight::At(1_u32.into()))
+        .expect("could not find trusted light block");
+
+    let light_client = make_light_client(
+        CountingIo { io, fetched },
+        scheduler::basic_bisecting_schedule,
+        now(length),
+    );
+
+    Instance::new(light_client, make_state(trusted_state))
+}
+
+#[test]
+fn verify_to_heights_sorts_and_dedups_targets() {
+    let fetched = Arc::new(AtomicUsize::new(0));
+    let mut instance = make(20, fetched.clone());
+
+    let heights = [15_u32, 5, 10, 5].map(Height::from);
+    let results = instance.verify_to_heights(&heights);
+
+    let verified = results
+        .into_iter()
+        .map(|(height, result)| (height, result.unwrap().height()))
+        .collect::<Vec<_>>();
+
+    let expected = [5_u32, 10, 15]
+        .map(|h| (Height::from(h), Height::from(h)))
+        .to_vec();
+
+    assert_eq!(verified, expected);
+
+    // Each target is verified from the previous one, so no light block is fetched twice
+    assert_eq!(fetched.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn verify_to_heights_reports_each_failure() {
+    let fetched = Arc::new(AtomicUsize::new(0));
+    let mut instance = make(10, fetched);
+
+    let heights = [5_u32, 42].map(Height::from);
+    let results = instance.verify_to_heights(&heights);
+
+    assert_eq!(results.len(), 2);
+    assert!(results[0].1.is_ok());
+    assert!(results[1].1.is_err());
+}
@@ -0,0 +1,120 @@
+use tendermint::vote::{self, ValidatorIndex, Vote};
+use tendermint_light_client::{
+    errors::ErrorDetail,
+    instance::Instance,
+    light_client::LightClient,
+    state::State,
+    tests::make_instance,
+    verifier::{
+        errors::VerificationErrorDetail,
+        options::VoteExtensionsOptions,
+        types::{Height, LightBlock, VoteExtension},
+    },
+};
+use tendermint_testgen::{Generator, LightChain, Validator};
+
+const LENGTH: u64 = 10;
+
+fn make_client() -> (LightClient, State, Vec<Validator>) {
+    let validators = LightChain::default_with_length(LENGTH).light_blocks[0]
+        .validators
+        .clone()
+        .unwrap();
+
+    let Instance {
+        light_client,
+        state,
+    } = make_instance(LENGTH);
+
+    (light_client, state, validators)
+}
+
+/// Sign the given extension of the precommit of the given validator for the given light block
+fn vote_extension(
+    validator: &Validator,
+    light_block: &LightBlock,
+    extension: &[u8],
+) -> VoteExtension {
+    let commit = &light_block.signed_header.commit;
+    let validator_address = validator.generate().unwrap().address;
+
+    let sign_bytes = Vote {
+        vote_type: vote::Type::Precommit,
+        height: commit.height,
+        round: commit.round,
+        block_id: Some(commit.block_id),
+        timestamp: None,
+        validator_address,
+        validator_index: ValidatorIndex::try_from(0_u32).unwrap(),
+        signature: None,
+        extension: extension.to_vec(),
+        extension_signature: None,
+    }
+    .into_extension_signable_vec(light_block.signed_header.header.chain_id.clone());
+
+    VoteExtension {
+        validator_address,
+        extension: extension.to_vec(),
+        extension_signature: Some(validator.sign(&sign_bytes).unwrap()),
+    }
+}
+
+fn height(height: u64) -> Height {
+    Height::try_from(height).unwrap()
+}
+
+#[test]
+fn verification_of_vote_extensions_is_disabled_by_default() {
+    let (light_client, mut state, _) = make_client();
+
+    let err = light_client
+        .verify_vote_extensions(height(LENGTH), &[], &mut state)
+        .unwrap_err();
+
+    assert!(matches!(
+        err.detail(),
+        ErrorDetail::VoteExtensionsDisabled(_)
+    ));
+}
+
+#[test]
+fn verifies_vote_extensions_from_enable_height() {
+    let (light_client, mut state, validators) = make_client();
+    let light_client = light_client.with_vote_extensions(VoteExtensionsOptions::new(height(5)));
+
+    // Vote extensions are not required below the enable height
+    light_client
+        .verify_vote_extensions(height(4), &[], &mut state)
+        .unwrap();
+
+    let err = light_client
+        .verify_vote_extensions(height(LENGTH), &[], &mut state)
+        .unwrap_err();
+
+    match err.detail() {
+        ErrorDetail::InvalidVoteExtensions(e) => {
+            assert!(matches!(
+                e.source,
+                VerificationErrorDetail::NotEnoughVoteExtensions(_)
+            ));
+        },
+        e => panic!("expected InvalidVoteExtensions error, got: {e:?}"),
+    }
+
+    // The light block has been verified nonetheless
+    let light_block = state
+        .light_store
+        .get_trusted_or_verified(height(LENGTH))
+        .unwrap();
+
+    let vote_extensions: Vec<_> = validators
+        .iter()
+        .map(|val| vote_extension(val, &light_block, b"price=42"))
+        .collect();
+
+    let verified = light_client
+        .verify_vote_extensions(height(LENGTH), &vote_extensions, &mut state)
+        .unwrap();
+
+    assert_eq!(verified, light_block);
+}
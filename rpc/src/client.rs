@@ -3,6 +3,11 @@
 mod compat;
 pub use compat::CompatMode;
 
+#[cfg(feature = "grpc-client")]
+pub mod grpc;
+#[cfg(feature = "grpc-client")]
+pub use grpc::GrpcClient;
+
 #[cfg(any(feature = "http-client", feature = "websocket-client"))]
 mod subscription;
 #[cfg(any(feature = "http-client", feature = "websocket-client"))]
@@ -11,18 +16,34 @@ pub use subscription::{Subscription, SubscriptionClient};
 #[cfg(any(feature = "http-client", feature = "websocket-client"))]
 pub mod sync;
 
+#[cfg(any(feature = "http-client", feature = "websocket-client"))]
+mod timeout;
+#[cfg(any(feature = "http-client", feature = "websocket-client"))]
+pub use timeout::{TimeoutExt, WithTimeout};
+
 #[cfg(any(feature = "http-client", feature = "websocket-client"))]
 mod transport;
 
+#[cfg(any(feature = "http-client", feature = "websocket-client"))]
+pub use transport::proxy::{Proxy, ProxyScheme};
+#[cfg(any(feature = "http-client", feature = "websocket-client"))]
+pub use transport::tls::TlsConfig;
+
 #[cfg(feature = "http-client")]
-pub use transport::http::{HttpClient, HttpClientUrl};
+pub use transport::http::{
+    Batch, BatchItem, BatchResponses, CacheConfig, HttpClient, HttpClientUrl, RetryPolicy,
+    DEFAULT_MAX_RESPONSE_SIZE,
+};
 #[cfg(feature = "websocket-client")]
 pub use transport::websocket::{
-    WebSocketClient, WebSocketClientDriver, WebSocketClientUrl, WebSocketConfig,
+    ReconnectPolicy, WebSocketClient, WebSocketClientDriver, WebSocketClientUrl, WebSocketConfig,
 };
 
 #[cfg(any(feature = "http-client", feature = "websocket-client"))]
-pub use transport::mock::{MockClient, MockRequestMatcher, MockRequestMethodMatcher};
+pub use transport::mock::{
+    MockClient, MockRequest, MockRequestMatcher, MockRequestMethodMatcher,
+    MockRequestScriptMatcher, MockResponse,
+};
 
 use core::fmt;
 
@@ -30,6 +51,8 @@ use async_trait::async_trait;
 use serde::{de::DeserializeOwned, Serialize};
 use tendermint::{abci, block::Height, evidence::Evidence, Genesis, Hash};
 
+#[cfg(any(feature = "http-client", feature = "websocket-client"))]
+use crate::paging::PageStreamOptions;
 use crate::{
     endpoint::{validators::DEFAULT_VALIDATORS_PER_PAGE, *},
     paging::Paging,
@@ -98,7 +121,7 @@ pub trait Client {
         self.perform(header::Request::new(height.into())).await
     }
 
-    /// `/header_by_hash`: get block by hash.
+    /// `/header_by_hash`: get block header by the hash of its block.
     async fn header_by_hash(
         &self,
         hash: tendermint::Hash,
@@ -132,6 +155,46 @@ pub trait Client {
             .await
     }
 
+    #[cfg(any(feature = "http-client", feature = "websocket-client"))]
+    /// `/block_search`: stream all the blocks matching the given query, walking
+    /// the pages of results with the default [`PageStreamOptions`].
+    ///
+    /// See [`Client::block_search_all_with`].
+    fn block_search_all(
+        &self,
+        query: Query,
+        order: Order,
+    ) -> futures::stream::BoxStream<'_, Result<block::Response, Error>>
+    where
+        Self: Sync,
+    {
+        self.block_search_all_with(query, order, PageStreamOptions::default())
+    }
+
+    #[cfg(any(feature = "http-client", feature = "websocket-client"))]
+    /// `/block_search`: stream all the blocks matching the given query, walking
+    /// the pages of results.
+    ///
+    /// The pages are walked as described by [`Client::tx_search_all_with`].
+    fn block_search_all_with(
+        &self,
+        query: Query,
+        order: Order,
+        options: PageStreamOptions,
+    ) -> futures::stream::BoxStream<'_, Result<block::Response, Error>>
+    where
+        Self: Sync,
+    {
+        stream_pages(
+            self,
+            options,
+            move |page| {
+                block_search::Request::new(query.clone(), page, options.per_page, order.clone())
+            },
+            |response| (response.total_count, response.blocks),
+        )
+    }
+
     /// `/blockchain`: get block headers for `min` <= `height` <= `max`.
     ///
     /// Block headers are returned in descending order (highest first).
@@ -172,6 +235,15 @@ pub trait Client {
         self.perform(broadcast::tx_commit::Request::new(tx)).await
     }
 
+    /// `/check_tx`: check a transaction against the mempool, returning the
+    /// response from `CheckTx` without broadcasting the transaction.
+    async fn check_tx<T>(&self, tx: T) -> Result<check_tx::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        self.perform(check_tx::Request::new(tx)).await
+    }
+
     /// `/commit`: get block commit at a given height.
     async fn commit<H>(&self, height: H) -> Result<commit::Response, Error>
     where
@@ -195,6 +267,12 @@ pub trait Client {
         self.perform(consensus_state::Request::new()).await
     }
 
+    /// `/dump_consensus_state`: get the full consensus state of the node, along
+    /// with that of its peers
+    async fn dump_consensus_state(&self) -> Result<dump_consensus_state::Response, Error> {
+        self.perform(dump_consensus_state::Request::new()).await
+    }
+
     // TODO(thane): Simplify once validators endpoint removes pagination.
     /// `/validators`: get validators a given height.
     async fn validators<H>(&self, height: H, paging: Paging) -> Result<validators::Response, Error>
@@ -270,11 +348,66 @@ pub trait Client {
         Ok(self.perform(genesis::Request::default()).await?.genesis)
     }
 
+    /// `/genesis_chunked`: get the chunk of the genesis file with the given
+    /// index.
+    async fn genesis_chunked(&self, chunk: u64) -> Result<genesis_chunked::Response, Error> {
+        self.perform(genesis_chunked::Request::new(chunk)).await
+    }
+
+    /// `/genesis_chunked`: get the genesis file chunk by chunk, reassemble it
+    /// and deserialize it.
+    ///
+    /// Nodes only serve genesis files too large for `/genesis` this way. The
+    /// given callback is called with the progress of the assembly once each
+    /// chunk is fetched.
+    async fn genesis_from_chunks<AppState, F>(
+        &self,
+        mut progress: F,
+    ) -> Result<Genesis<AppState>, Error>
+    where
+        AppState: fmt::Debug + Serialize + DeserializeOwned + Send,
+        F: FnMut(genesis_chunked::Progress) + Send,
+    {
+        let mut bytes = Vec::new();
+        let mut chunk = 0;
+
+        loop {
+            let response = self.genesis_chunked(chunk).await?;
+            if response.chunk != chunk {
+                return Err(Error::server(format!(
+                    "requested chunk {} of the genesis file, got chunk {}",
+                    chunk, response.chunk
+                )));
+            }
+
+            bytes.extend(response.data);
+            chunk += 1;
+
+            progress(genesis_chunked::Progress {
+                chunks: chunk,
+                total: response.total,
+                bytes: bytes.len(),
+            });
+
+            if chunk >= response.total {
+                break;
+            }
+        }
+
+        serde_json::from_slice(&bytes).map_err(Error::serde)
+    }
+
     /// `/net_info`: obtain information about P2P and other network connections.
     async fn net_info(&self) -> Result<net_info::Response, Error> {
         self.perform(net_info::Request).await
     }
 
+    /// `/num_unconfirmed_txs`: get the number and the total size of the
+    /// transactions in the mempool.
+    async fn num_unconfirmed_txs(&self) -> Result<num_unconfirmed_txs::Response, Error> {
+        self.perform(num_unconfirmed_txs::Request).await
+    }
+
     /// `/status`: get Tendermint status including node info, pubkey, latest
     /// block hash, app hash, block height and time.
     async fn status(&self) -> Result<status::Response, Error> {
@@ -304,6 +437,61 @@ pub trait Client {
             .await
     }
 
+    #[cfg(any(feature = "http-client", feature = "websocket-client"))]
+    /// `/tx_search`: stream all the transactions matching the given query, along
+    /// with their results, walking the pages of results with the default
+    /// [`PageStreamOptions`].
+    ///
+    /// See [`Client::tx_search_all_with`].
+    fn tx_search_all(
+        &self,
+        query: Query,
+        order: Order,
+    ) -> futures::stream::BoxStream<'_, Result<tx::Response, Error>>
+    where
+        Self: Sync,
+    {
+        self.tx_search_all_with(query, order, PageStreamOptions::default())
+    }
+
+    #[cfg(any(feature = "http-client", feature = "websocket-client"))]
+    /// `/tx_search`: stream all the transactions matching the given query, along
+    /// with their results, walking the pages of results.
+    ///
+    /// Up to `options.concurrency` pages are fetched at once, and the
+    /// transactions are yielded in order. A page which cannot be fetched is
+    /// fetched again up to `options.max_retries` times, after which the stream
+    /// yields the error and ends.
+    ///
+    /// The number of pages is that of the first page of results: transactions
+    /// indexed while the stream is walking the pages may be missed.
+    fn tx_search_all_with(
+        &self,
+        query: Query,
+        order: Order,
+        options: PageStreamOptions,
+    ) -> futures::stream::BoxStream<'_, Result<tx::Response, Error>>
+    where
+        Self: Sync,
+    {
+        stream_pages(
+            self,
+            options,
+            move |page| {
+                tx_search::Request::new(query.clone(), false, page, options.per_page, order.clone())
+            },
+            |response| (response.total_count, response.txs),
+        )
+    }
+
+    /// `/unconfirmed_txs`: list up to `limit` transactions in the mempool.
+    async fn unconfirmed_txs(
+        &self,
+        limit: Option<u32>,
+    ) -> Result<unconfirmed_txs::Response, Error> {
+        self.perform(unconfirmed_txs::Request::new(limit)).await
+    }
+
     #[cfg(any(feature = "http-client", feature = "websocket-client"))]
     /// Poll the `/health` endpoint until it returns a successful result or
     /// the given `timeout` has elapsed.
@@ -335,3 +523,84 @@ pub trait Client {
     where
         R: SimpleRequest;
 }
+
+/// Stream the items of all the pages of a paginated endpoint, as described by
+/// [`Client::tx_search_all_with`].
+///
+/// The request for each page, counting from 1, is built by `request`, and the
+/// response for each page is split into the total number of items and the
+/// items of the page by `items`.
+#[cfg(any(feature = "http-client", feature = "websocket-client"))]
+fn stream_pages<'a, C, R, T>(
+    client: &'a C,
+    options: PageStreamOptions,
+    request: impl Fn(u32) -> R + Clone + Send + 'a,
+    items: impl Fn(R::Output) -> (u32, Vec<T>) + Clone + Send + 'a,
+) -> futures::stream::BoxStream<'a, Result<T, Error>>
+where
+    C: Client + Sync + ?Sized,
+    R: SimpleRequest + 'a,
+    R::Output: Send,
+    T: Send + 'a,
+{
+    use futures::{future, stream, StreamExt};
+    use std::sync::{Arc, Mutex};
+    use tokio::time::{sleep, sleep_until, Instant};
+
+    // The earliest instant at which the next request may start.
+    let next_request = Arc::new(Mutex::new(Instant::now()));
+
+    let fetch_page = move |page: u32| {
+        let request = request.clone();
+        let items = items.clone();
+        let next_request = next_request.clone();
+        async move {
+            let mut retries = 0;
+            loop {
+                let start = {
+                    let mut next_request = next_request.lock().unwrap();
+                    let start = (*next_request).max(Instant::now());
+                    *next_request = start + options.min_interval;
+                    start
+                };
+                sleep_until(start).await;
+
+                match client.perform(request(page)).await {
+                    Ok(response) => return Ok(items(response)),
+                    Err(_) if retries < options.max_retries => {
+                        retries += 1;
+                        sleep(options.retry_delay).await;
+                    },
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+    };
+
+    stream::once(fetch_page(1))
+        .flat_map(move |first| {
+            let pages = match &first {
+                Ok((total, _)) => total.div_ceil(u32::from(options.per_page.max(1))),
+                Err(_) => 1,
+            };
+            stream::once(future::ready(first)).chain(
+                stream::iter(2..=pages)
+                    .map(fetch_page.clone())
+                    .buffered(options.concurrency.max(1)),
+            )
+        })
+        .scan(false, |failed, page| {
+            if *failed {
+                return future::ready(None);
+            }
+            *failed = page.is_err();
+            future::ready(Some(page))
+        })
+        .flat_map(|page| {
+            stream::iter(match page {
+                Ok((_, items)) => items.into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(e)],
+            })
+        })
+        .boxed()
+}
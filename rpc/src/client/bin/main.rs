@@ -1,6 +1,7 @@
 //! CLI for performing simple interactions against a Tendermint node's RPC.
 
 use core::str::FromStr;
+use std::path::PathBuf;
 
 use futures::StreamExt;
 use structopt::StructOpt;
@@ -17,7 +18,8 @@ use tracing::{debug, error, info, level_filters::LevelFilter, warn};
 
 /// CLI for performing simple interactions against a Tendermint node's RPC.
 ///
-/// Supports HTTP, HTTPS, WebSocket and secure WebSocket (wss://) URLs.
+/// Supports HTTP, HTTPS, WebSocket, secure WebSocket (wss://) and Unix domain
+/// socket (unix://) URLs.
 #[derive(Debug, StructOpt)]
 struct Opt {
     /// The URL of the Tendermint node's RPC endpoint.
@@ -107,6 +109,11 @@ enum ClientRequest {
         order: Order,
     },
     // TODO(thane): Implement evidence broadcast
+    /// Broadcast evidence of misbehavior.
+    BroadcastEvidence {
+        /// Path to a JSON file holding the evidence, encoded as in blocks.
+        path: PathBuf,
+    },
     /// Broadcast a transaction asynchronously (without waiting for the ABCI
     /// app to check it or for it to be committed).
     BroadcastTxAsync {
@@ -125,6 +132,11 @@ enum ClientRequest {
         /// The transaction to broadcast.
         tx: String,
     },
+    /// Check a transaction with the ABCI app, without broadcasting it.
+    CheckTx {
+        /// The transaction to check.
+        tx: String,
+    },
     /// Get the commit for the given height.
     Commit { height: u32 },
     /// Get consensus parameters for a specific height.
@@ -134,8 +146,17 @@ enum ClientRequest {
     },
     /// Get the current consensus state.
     ConsensusState,
+    /// Get the full consensus state, along with that of the node's peers.
+    DumpConsensusState,
     /// Get the node's genesis data.
     Genesis,
+    /// Get the node's genesis data chunk by chunk, for genesis files too large
+    /// to be fetched at once.
+    GenesisChunked,
+    /// Get a block header at a given height.
+    Header { height: u32 },
+    /// Get a block header by the hash of its block.
+    HeaderByHash { hash: String },
     /// Get the node's health.
     Health,
     /// Request the latest block.
@@ -148,6 +169,8 @@ enum ClientRequest {
     LatestCommit,
     /// Obtain information about the P2P stack and other network connections.
     NetInfo,
+    /// Get the number and the total size of the transactions in the mempool.
+    NumUnconfirmedTxs,
     /// Get Tendermint status (node info, public key, latest block hash, etc.).
     Status,
     /// Fetch a transaction by way of its hash.
@@ -173,6 +196,12 @@ enum ClientRequest {
         #[structopt(long)]
         prove: bool,
     },
+    /// List the transactions in the mempool.
+    UnconfirmedTxs {
+        /// The maximum number of transactions to list.
+        #[structopt(long)]
+        limit: Option<u32>,
+    },
     /// Get the validators at the given height.
     Validators {
         /// The height at which to query the validators.
@@ -211,7 +240,9 @@ async fn main() {
         },
     };
     let result = match opt.url.scheme() {
-        Scheme::Http | Scheme::Https => http_request(opt.url, proxy_url, opt.req).await,
+        Scheme::Http | Scheme::Https | Scheme::Unix => {
+            http_request(opt.url, proxy_url, opt.req).await
+        },
         Scheme::WebSocket | Scheme::SecureWebSocket => match opt.proxy_url {
             Some(_) => Err(Error::invalid_params(
                 "proxies are only supported for use with HTTP clients at present".to_string(),
@@ -371,6 +402,15 @@ where
             serde_json::to_string_pretty(&client.block_search(query, page, per_page, order).await?)
                 .map_err(Error::serde)?
         },
+        ClientRequest::BroadcastEvidence { path } => {
+            let json = std::fs::read_to_string(path).map_err(Error::io)?;
+            let evidence: tendermint_proto::v0_38::types::Evidence =
+                serde_json::from_str(&json).map_err(Error::serde)?;
+            let evidence =
+                tendermint::evidence::Evidence::try_from(evidence).map_err(Error::tendermint)?;
+            serde_json::to_string_pretty(&client.broadcast_evidence(evidence).await?)
+                .map_err(Error::serde)?
+        },
         ClientRequest::BroadcastTxAsync { tx } => {
             serde_json::to_string_pretty(&client.broadcast_tx_async(tx).await?)
                 .map_err(Error::serde)?
@@ -385,6 +425,9 @@ where
             serde_json::to_string_pretty(&client.broadcast_tx_sync(tx).await?)
                 .map_err(Error::serde)?
         },
+        ClientRequest::CheckTx { tx } => {
+            serde_json::to_string_pretty(&client.check_tx(tx).await?).map_err(Error::serde)?
+        },
         ClientRequest::ConsensusParams { height } => {
             serde_json::to_string_pretty(&client.consensus_params(height).await?)
                 .map_err(Error::serde)?
@@ -409,16 +452,46 @@ where
         ClientRequest::ConsensusState => {
             serde_json::to_string_pretty(&client.consensus_state().await?).map_err(Error::serde)?
         },
+        ClientRequest::DumpConsensusState => {
+            serde_json::to_string_pretty(&client.dump_consensus_state().await?)
+                .map_err(Error::serde)?
+        },
         ClientRequest::Genesis => {
             serde_json::to_string_pretty(&client.genesis::<serde_json::Value>().await?)
                 .map_err(Error::serde)?
         },
+        ClientRequest::GenesisChunked => serde_json::to_string_pretty(
+            &client
+                .genesis_from_chunks::<serde_json::Value, _>(|progress| {
+                    info!(
+                        "Fetched chunk {} of {} of the genesis file ({} bytes)",
+                        progress.chunks, progress.total, progress.bytes
+                    )
+                })
+                .await?,
+        )
+        .map_err(Error::serde)?,
+        ClientRequest::Header { height } => {
+            serde_json::to_string_pretty(&client.header(height).await?).map_err(Error::serde)?
+        },
+        ClientRequest::HeaderByHash { hash } => serde_json::to_string_pretty(
+            &client
+                .header_by_hash(
+                    tendermint::Hash::from_str(&hash).map_err(|e| Error::parse(e.to_string()))?,
+                )
+                .await?,
+        )
+        .map_err(Error::serde)?,
         ClientRequest::Health => {
             serde_json::to_string_pretty(&client.health().await?).map_err(Error::serde)?
         },
         ClientRequest::NetInfo => {
             serde_json::to_string_pretty(&client.net_info().await?).map_err(Error::serde)?
         },
+        ClientRequest::NumUnconfirmedTxs => {
+            serde_json::to_string_pretty(&client.num_unconfirmed_txs().await?)
+                .map_err(Error::serde)?
+        },
         ClientRequest::Status => {
             serde_json::to_string_pretty(&client.status().await?).map_err(Error::serde)?
         },
@@ -443,6 +516,10 @@ where
                 .await?,
         )
         .map_err(Error::serde)?,
+        ClientRequest::UnconfirmedTxs { limit } => {
+            serde_json::to_string_pretty(&client.unconfirmed_txs(limit).await?)
+                .map_err(Error::serde)?
+        },
         ClientRequest::Validators {
             height,
             all,
@@ -14,6 +14,8 @@ pub enum CompatMode {
     V0_34,
     /// Use version 0.37 of the protocol.
     V0_37,
+    /// Use version 0.38 of the protocol.
+    V0_38,
 }
 
 impl Default for CompatMode {
@@ -49,7 +51,7 @@ impl CompatMode {
         match (version.major, version.minor) {
             (0, 34) => Ok(CompatMode::V0_34),
             (0, 37) => Ok(CompatMode::V0_37),
-            (0, 38) => Ok(CompatMode::V0_37),
+            (0, 38) => Ok(CompatMode::V0_38),
             _ => Err(Error::unsupported_tendermint_version(version.to_string())),
         }
     }
@@ -60,6 +62,7 @@ impl fmt::Display for CompatMode {
         match self {
             CompatMode::V0_34 => f.write_str("v0.34"),
             CompatMode::V0_37 => f.write_str("v0.37"),
+            CompatMode::V0_38 => f.write_str("v0.38"),
         }
     }
 }
@@ -91,7 +94,7 @@ mod tests {
         );
         assert_eq!(
             CompatMode::from_version(parse_version("v0.38.0")).unwrap(),
-            CompatMode::V0_37
+            CompatMode::V0_38
         );
         let res = CompatMode::from_version(parse_version("v0.39.0"));
         assert!(res.is_err());
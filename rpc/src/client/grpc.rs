@@ -0,0 +1,411 @@
+//! gRPC-based client for the services of CometBFT 1.x nodes.
+//!
+//! Since version 1.0, CometBFT nodes can expose some of their data over gRPC,
+//! besides the JSON-RPC endpoints, through the `BlockService`,
+//! `BlockResultsService` and `VersionService` services. These services are
+//! disabled by default, and are enabled in the `[grpc]` section of the node's
+//! configuration.
+
+use core::convert::{TryFrom, TryInto};
+
+use futures::{stream::BoxStream, StreamExt};
+use tendermint::{block::Height, AppHash, Block};
+use tonic::{
+    client::Grpc,
+    codec::ProstCodec,
+    codegen::http::uri::PathAndQuery,
+    transport::{Channel, Endpoint},
+};
+
+use crate::{endpoint, prelude::*, Error, Scheme, Url};
+
+const GET_BY_HEIGHT: &str = "/cometbft.services.block.v1.BlockService/GetByHeight";
+const GET_LATEST_HEIGHT: &str = "/cometbft.services.block.v1.BlockService/GetLatestHeight";
+const GET_BLOCK_RESULTS: &str =
+    "/cometbft.services.block_results.v1.BlockResultsService/GetBlockResults";
+const GET_VERSION: &str = "/cometbft.services.version.v1.VersionService/GetVersion";
+
+/// A gRPC client for the services of CometBFT 1.x nodes.
+///
+/// Its methods return the same responses as the matching methods of
+/// [`crate::Client`], so that applications can fetch blocks and block results
+/// over gRPC where the node makes it available, and over JSON-RPC otherwise.
+/// Only plaintext `http://` connections are supported at present.
+///
+/// Clones of a client share its connection to the node.
+///
+/// ## Examples
+///
+/// ```rust,ignore
+/// use tendermint_rpc::GrpcClient;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = GrpcClient::connect("http://127.0.0.1:26670".parse().unwrap())
+///         .await
+///         .unwrap();
+///
+///     let height = client.latest_height().await.unwrap();
+///     let block = client.block(height).await.unwrap();
+///
+///     println!("Got block: {:?}", block);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct GrpcClient {
+    inner: Grpc<Channel>,
+}
+
+impl GrpcClient {
+    /// Connect to the gRPC server of the node with the given URL.
+    pub async fn connect(url: Url) -> Result<Self, Error> {
+        if url.scheme() != Scheme::Http {
+            return Err(Error::invalid_params(format!(
+                "cannot use URL {url} with gRPC clients, only http:// URLs are supported"
+            )));
+        }
+
+        let channel = Endpoint::from_shared(url.to_string())
+            .map_err(Error::grpc_transport)?
+            .connect()
+            .await
+            .map_err(Error::grpc_transport)?;
+
+        Ok(Self {
+            inner: Grpc::new(channel),
+        })
+    }
+
+    /// `BlockService/GetByHeight`: get the block at the given height.
+    pub async fn block<H>(&self, height: H) -> Result<endpoint::block::Response, Error>
+    where
+        H: Into<Height>,
+    {
+        let request = proto::GetByHeightRequest {
+            height: height.into().into(),
+        };
+        let response: proto::GetByHeightResponse = self.unary(GET_BY_HEIGHT, request).await?;
+
+        let block_id = response
+            .block_id
+            .ok_or_else(|| Error::server("missing block ID in response".to_string()))?;
+        let block = response
+            .block
+            .ok_or_else(|| Error::server("missing block in response".to_string()))?;
+
+        Ok(endpoint::block::Response {
+            block_id: block_id.try_into().map_err(Error::tendermint)?,
+            block: Block::try_from(block).map_err(Error::tendermint)?,
+        })
+    }
+
+    /// `BlockService/GetLatestHeight`: get the height of the next block
+    /// committed by the node.
+    ///
+    /// The node only reports a height once a new block is committed, so that
+    /// this waits for the next block.
+    pub async fn latest_height(&self) -> Result<Height, Error> {
+        self.latest_heights()
+            .await?
+            .next()
+            .await
+            .ok_or_else(|| Error::server("latest height stream ended".to_string()))?
+    }
+
+    /// `BlockService/GetLatestHeight`: get the heights of the blocks committed
+    /// by the node from now on, as they are committed.
+    pub async fn latest_heights(&self) -> Result<BoxStream<'static, Result<Height, Error>>, Error> {
+        let mut grpc = self.inner.clone();
+        grpc.ready().await.map_err(Error::grpc_transport)?;
+
+        let response = grpc
+            .server_streaming(
+                tonic::Request::new(proto::GetLatestHeightRequest {}),
+                PathAndQuery::from_static(GET_LATEST_HEIGHT),
+                ProstCodec::<_, proto::GetLatestHeightResponse>::default(),
+            )
+            .await
+            .map_err(Error::grpc)?;
+
+        let heights = response.into_inner().map(|res| {
+            let response = res.map_err(Error::grpc)?;
+            Height::try_from(response.height).map_err(Error::tendermint)
+        });
+
+        Ok(heights.boxed())
+    }
+
+    /// `BlockResultsService/GetBlockResults`: get the results of the execution
+    /// of the block at the given height.
+    pub async fn block_results<H>(
+        &self,
+        height: H,
+    ) -> Result<endpoint::block_results::Response, Error>
+    where
+        H: Into<Height>,
+    {
+        let request = proto::GetBlockResultsRequest {
+            height: height.into().into(),
+        };
+        let response: proto::GetBlockResultsResponse =
+            self.unary(GET_BLOCK_RESULTS, request).await?;
+
+        response.try_into()
+    }
+
+    /// `VersionService/GetVersion`: get the versions of the node software and
+    /// of the protocols it speaks.
+    pub async fn version(&self) -> Result<Version, Error> {
+        let response: proto::GetVersionResponse =
+            self.unary(GET_VERSION, proto::GetVersionRequest {}).await?;
+
+        Ok(Version {
+            node: response.node,
+            abci: response.abci,
+            p2p: response.p2p,
+            block: response.block,
+        })
+    }
+
+    async fn unary<Req, Res>(&self, path: &'static str, request: Req) -> Result<Res, Error>
+    where
+        Req: prost::Message + 'static,
+        Res: prost::Message + Default + 'static,
+    {
+        let mut grpc = self.inner.clone();
+        grpc.ready().await.map_err(Error::grpc_transport)?;
+
+        let response = grpc
+            .unary(
+                tonic::Request::new(request),
+                PathAndQuery::from_static(path),
+                ProstCodec::<Req, Res>::default(),
+            )
+            .await
+            .map_err(Error::grpc)?;
+
+        Ok(response.into_inner())
+    }
+}
+
+/// The versions reported by the `VersionService` of a node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Version {
+    /// Version of the CometBFT software the node runs
+    pub node: String,
+    /// Version of the ABCI protocol
+    pub abci: String,
+    /// Version of the P2P protocol
+    pub p2p: u64,
+    /// Version of the block protocol
+    pub block: u64,
+}
+
+impl TryFrom<proto::GetBlockResultsResponse> for endpoint::block_results::Response {
+    type Error = Error;
+
+    fn try_from(response: proto::GetBlockResultsResponse) -> Result<Self, Error> {
+        let txs_results = response
+            .tx_results
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()
+            .map_err(Error::tendermint)?;
+
+        let finalize_block_events = response
+            .finalize_block_events
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()
+            .map_err(Error::tendermint)?;
+
+        let validator_updates = response
+            .validator_updates
+            .into_iter()
+            .map(proto::ValidatorUpdate::into_raw)
+            .map(|update| update?.try_into().map_err(Error::tendermint))
+            .collect::<Result<_, _>>()?;
+
+        let consensus_param_updates = response
+            .consensus_param_updates
+            .map(TryInto::try_into)
+            .transpose()
+            .map_err(Error::tendermint)?;
+
+        Ok(Self {
+            height: Height::try_from(response.height).map_err(Error::tendermint)?,
+            txs_results: Some(txs_results),
+            finalize_block_events,
+            begin_block_events: None,
+            end_block_events: None,
+            validator_updates,
+            consensus_param_updates,
+            app_hash: AppHash::try_from(response.app_hash).map_err(Error::tendermint)?,
+        })
+    }
+}
+
+/// The messages of the CometBFT 1.x gRPC services.
+///
+/// Their encoding is the same as the one of the 0.38 messages they are made
+/// of, so that these are reused, except for the validator updates, whose
+/// public key is given as its type and raw bytes since CometBFT 1.0.
+mod proto {
+    use tendermint_proto::v0_38::{
+        abci,
+        crypto::{public_key::Sum, PublicKey},
+        types,
+    };
+
+    use crate::{prelude::*, Error};
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct GetByHeightRequest {
+        #[prost(int64, tag = "1")]
+        pub height: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct GetByHeightResponse {
+        #[prost(message, optional, tag = "1")]
+        pub block_id: Option<types::BlockId>,
+        #[prost(message, optional, tag = "2")]
+        pub block: Option<types::Block>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct GetLatestHeightRequest {}
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct GetLatestHeightResponse {
+        #[prost(int64, tag = "1")]
+        pub height: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct GetBlockResultsRequest {
+        #[prost(int64, tag = "1")]
+        pub height: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct GetBlockResultsResponse {
+        #[prost(int64, tag = "1")]
+        pub height: i64,
+        #[prost(message, repeated, tag = "2")]
+        pub tx_results: Vec<abci::ExecTxResult>,
+        #[prost(message, repeated, tag = "3")]
+        pub finalize_block_events: Vec<abci::Event>,
+        #[prost(message, repeated, tag = "4")]
+        pub validator_updates: Vec<ValidatorUpdate>,
+        #[prost(message, optional, tag = "5")]
+        pub consensus_param_updates: Option<types::ConsensusParams>,
+        #[prost(bytes = "vec", tag = "6")]
+        pub app_hash: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ValidatorUpdate {
+        /// The public key, as sent by the nodes prior to CometBFT 1.0
+        #[prost(message, optional, tag = "1")]
+        pub pub_key: Option<PublicKey>,
+        #[prost(int64, tag = "2")]
+        pub power: i64,
+        #[prost(bytes = "vec", tag = "3")]
+        pub pub_key_bytes: Vec<u8>,
+        #[prost(string, tag = "4")]
+        pub pub_key_type: String,
+    }
+
+    impl ValidatorUpdate {
+        /// The validator update with its public key as a 0.38 message.
+        pub fn into_raw(self) -> Result<abci::ValidatorUpdate, Error> {
+            let pub_key = match self.pub_key {
+                Some(pub_key) => pub_key,
+                None => {
+                    let sum = match self.pub_key_type.as_str() {
+                        "ed25519" => Sum::Ed25519(self.pub_key_bytes),
+                        "secp256k1" => Sum::Secp256k1(self.pub_key_bytes),
+                        other => {
+                            return Err(Error::server(format!(
+                                "unsupported validator public key type: {other}"
+                            )))
+                        },
+                    };
+                    PublicKey { sum: Some(sum) }
+                },
+            };
+
+            Ok(abci::ValidatorUpdate {
+                pub_key: Some(pub_key),
+                power: self.power,
+            })
+        }
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct GetVersionRequest {}
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct GetVersionResponse {
+        #[prost(string, tag = "1")]
+        pub node: String,
+        #[prost(string, tag = "2")]
+        pub abci: String,
+        #[prost(uint64, tag = "3")]
+        pub p2p: u64,
+        #[prost(uint64, tag = "4")]
+        pub block: u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+    use tendermint_proto::v0_38::abci;
+
+    use super::proto::{GetBlockResultsResponse, ValidatorUpdate};
+    use crate::{endpoint::block_results, prelude::*};
+
+    #[test]
+    fn converts_block_results() {
+        let update = ValidatorUpdate {
+            pub_key: None,
+            power: 10,
+            pub_key_bytes: vec![1; 32],
+            pub_key_type: "ed25519".to_string(),
+        };
+
+        let response = GetBlockResultsResponse {
+            height: 42,
+            tx_results: vec![abci::ExecTxResult {
+                code: 1,
+                ..Default::default()
+            }],
+            finalize_block_events: vec![],
+            validator_updates: vec![update.clone()],
+            consensus_param_updates: None,
+            app_hash: vec![2; 32],
+        };
+
+        // The message survives a round trip through its encoding
+        let response =
+            GetBlockResultsResponse::decode(response.encode_to_vec().as_slice()).unwrap();
+
+        let results = block_results::Response::try_from(response.clone()).unwrap();
+        assert_eq!(results.height.value(), 42);
+        assert_eq!(results.txs_results.unwrap()[0].code.value(), 1);
+        assert_eq!(results.validator_updates[0].power.value(), 10);
+        assert_eq!(results.app_hash.as_bytes(), &[2; 32]);
+
+        let unsupported = ValidatorUpdate {
+            pub_key_type: "bls12_381".to_string(),
+            ..update
+        };
+        let response = GetBlockResultsResponse {
+            validator_updates: vec![unsupported],
+            ..response
+        };
+        assert!(block_results::Response::try_from(response).is_err());
+    }
+}
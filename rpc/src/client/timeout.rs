@@ -0,0 +1,118 @@
+//! Time limits of single calls to the methods of a [`Client`](super::Client).
+
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use pin_project::pin_project;
+use tokio::time::Instant;
+
+use crate::Error;
+
+/// Limits the time a call to a [`Client`](super::Client) takes, whatever its
+/// transport, so that a call can wait for longer or shorter than the others.
+///
+/// ```rust,ignore
+/// use core::time::Duration;
+/// use tendermint_rpc::{client::TimeoutExt, Client, HttpClient};
+///
+/// let client = HttpClient::builder(url).timeout(Duration::from_secs(5)).build()?;
+/// let status = client.status().await?;
+/// // Also fails once the timeout of the client is over
+/// let results = client
+///     .block_results(height)
+///     .timeout(Duration::from_secs(1))
+///     .await?;
+/// ```
+///
+/// The call fails with [`Error::request_timeout`] once the time is over. Such
+/// a time limit cannot extend the ones the client has of its own, such as the
+/// timeout of an `HttpClient`, whose builder is the way to give the calls to
+/// given methods more time than the others.
+pub trait TimeoutExt: Future + Sized {
+    /// Fail the call if it does not complete within the given time.
+    fn timeout(self, duration: Duration) -> WithTimeout<Self> {
+        WithTimeout {
+            inner: tokio::time::timeout(duration, self),
+            duration,
+        }
+    }
+
+    /// Fail the call if it does not complete by the given instant.
+    fn deadline(self, deadline: Instant) -> WithTimeout<Self> {
+        WithTimeout {
+            inner: tokio::time::timeout_at(deadline, self),
+            duration: deadline.saturating_duration_since(Instant::now()),
+        }
+    }
+}
+
+impl<F, T> TimeoutExt for F where F: Future<Output = Result<T, Error>> {}
+
+/// A call to a [`Client`](super::Client) with a time limit, see
+/// [`TimeoutExt`].
+#[pin_project]
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct WithTimeout<F> {
+    #[pin]
+    inner: tokio::time::Timeout<F>,
+    duration: Duration,
+}
+
+impl<F, T> Future for WithTimeout<F>
+where
+    F: Future<Output = Result<T, Error>>,
+{
+    type Output = Result<T, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        match this.inner.poll(cx) {
+            Poll::Ready(Ok(result)) => Poll::Ready(result),
+            Poll::Ready(Err(_)) => Poll::Ready(Err(Error::request_timeout(*this.duration))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorDetail;
+
+    async fn respond_after(delay: Duration) -> Result<(), Error> {
+        tokio::time::sleep(delay).await;
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn times_out_calls() {
+        let timeout = Duration::from_secs(1);
+        assert!(respond_after(timeout / 2).timeout(timeout).await.is_ok());
+
+        let err = respond_after(timeout * 2)
+            .timeout(timeout)
+            .await
+            .unwrap_err();
+        match err.detail() {
+            ErrorDetail::RequestTimeout(e) => assert_eq!(e.duration, timeout),
+            _ => panic!("unexpected error: {err}"),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn times_out_calls_at_deadline() {
+        let deadline = Instant::now() + Duration::from_secs(2);
+        let err = respond_after(Duration::from_secs(3))
+            .deadline(deadline)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err.detail(), ErrorDetail::RequestTimeout(_)));
+        assert_eq!(Instant::now(), deadline);
+    }
+}
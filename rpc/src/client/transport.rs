@@ -2,13 +2,18 @@
 
 mod auth;
 pub mod mock;
+pub mod proxy;
 mod router;
+pub mod tls;
 
 macro_rules! perform_with_compat {
-    ($self:expr, $request:expr) => {{
+    ($self:expr, $request:expr) => {
+        perform_with_compat!($self, $self.compat, $request)
+    };
+    ($self:expr, $compat:expr, $request:expr) => {{
         let request = $request;
-        match $self.compat {
-            CompatMode::V0_37 => {
+        match $compat {
+            CompatMode::V0_37 | CompatMode::V0_38 => {
                 $self
                     .perform_with_dialect(request, crate::dialect::v0_37::Dialect)
                     .await
@@ -0,0 +1,284 @@
+//! Batches of JSON-RPC requests, sent by an [`HttpClient`] to the node in a
+//! single HTTP request.
+
+use core::{fmt, marker::PhantomData, time::Duration};
+
+use super::HttpClient;
+use crate::{prelude::*, request, response, Error, Id, Method, Response, SimpleRequest};
+
+/// A batch of requests, sent to the node in a single round trip with
+/// [`Batch::send`], see [`HttpClient::batch`].
+///
+/// Every request added to the batch gives a [`BatchItem`], which takes its
+/// typed response out of the [`BatchResponses`]. The requests of the batch
+/// fail or succeed on their own: an error of the node for one of them, or a
+/// response which fails to decode, only fails the items concerned.
+///
+/// ```rust,ignore
+/// let mut batch = client.batch();
+/// let commits = heights
+///     .iter()
+///     .map(|&height| batch.add(commit::Request::new(height)))
+///     .collect::<Vec<_>>();
+/// let validators = batch.add(validators::Request::new(Some(height), None, None));
+///
+/// let responses = batch.send().await?;
+/// for commit in commits {
+///     let commit = responses.get(commit)?;
+/// }
+/// let validators = responses.get(validators)?;
+/// ```
+///
+/// The requests are performed in the latest dialect of the protocol, like
+/// those of [`Client::perform`](crate::Client::perform), and their responses
+/// are not cached.
+pub struct Batch<'a> {
+    client: &'a HttpClient,
+    requests: Vec<(Method, String)>,
+}
+
+impl<'a> Batch<'a> {
+    pub(super) fn new(client: &'a HttpClient) -> Self {
+        Self {
+            client,
+            requests: Vec::new(),
+        }
+    }
+
+    /// Add the given request to the batch.
+    pub fn add<R>(&mut self, request: R) -> BatchItem<R>
+    where
+        R: SimpleRequest,
+    {
+        let index = self.requests.len();
+        let method = request.method();
+        let wrapper = request::Wrapper::new_with_id(Id::Num(index as i64), request);
+        self.requests.push((method, wrapper.into_json()));
+
+        BatchItem {
+            index,
+            request: PhantomData,
+        }
+    }
+
+    /// The number of requests in the batch.
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Whether there is no request in the batch.
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// Send the requests of the batch to the node, returning their responses.
+    ///
+    /// Only fails if the batch as a whole fails, such as when the node cannot
+    /// be reached or rejects the batch. The batch is retried as the requests
+    /// of the client are, if all its requests are idempotent, and waits for as
+    /// long as the longest timeout of their methods, if they all have one.
+    pub async fn send(self) -> Result<BatchResponses, Error> {
+        let len = self.requests.len();
+        if len == 0 {
+            return Ok(BatchResponses { items: Vec::new() });
+        }
+
+        let client = self.client;
+        let methods = self.requests.iter().map(|(method, _)| *method);
+        let retry = client
+            .retry
+            .as_ref()
+            .filter(|_| methods.clone().all(Method::is_idempotent));
+        let timeout = methods
+            .map(|method| client.timeouts.get(method))
+            .try_fold(Duration::ZERO, |longest, timeout| {
+                Some(longest.max(timeout?))
+            });
+
+        let requests = self.requests.into_iter().map(|(_, request)| request);
+        let body = format!("[{}]", requests.collect::<Vec<_>>().join(","));
+        let request = client.build_request(body)?;
+        let response_body = client.send(request, "batch", retry, timeout).await?;
+
+        BatchResponses::parse(&response_body, len)
+    }
+}
+
+impl fmt::Debug for Batch<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Batch")
+            .field(
+                "methods",
+                &self
+                    .requests
+                    .iter()
+                    .map(|(method, _)| method)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// A request in a [`Batch`], which takes its response out of the
+/// [`BatchResponses`] of the batch.
+pub struct BatchItem<R> {
+    index: usize,
+    request: PhantomData<fn() -> R>,
+}
+
+impl<R> BatchItem<R> {
+    /// The position of the request in the batch, from 0.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<R> Clone for BatchItem<R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R> Copy for BatchItem<R> {}
+
+impl<R> fmt::Debug for BatchItem<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchItem")
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+/// The responses of the node to the requests of a [`Batch`].
+#[derive(Debug, Clone)]
+pub struct BatchResponses {
+    // The responses, by position of their request in the batch.
+    items: Vec<Option<serde_json::Value>>,
+}
+
+impl BatchResponses {
+    /// Parse the given body of the response of the node to a batch of the
+    /// given number of requests.
+    fn parse(body: &[u8], len: usize) -> Result<Self, Error> {
+        let responses: Vec<serde_json::Value> = match serde_json::from_slice(body) {
+            Ok(responses) => responses,
+            // The node answers the batches it rejects as a whole with a single
+            // error
+            Err(e) => {
+                let wrapper: response::Wrapper<serde_json::Value> =
+                    serde_json::from_slice(body).map_err(|_| Error::serde(e))?;
+                return Err(wrapper.into_error().unwrap_or_else(Error::malformed_json));
+            },
+        };
+
+        let mut items = vec![None; len];
+        for response in responses {
+            let index = match response.get("id").cloned().map(serde_json::from_value) {
+                Some(Ok(Id::Num(index))) => usize::try_from(index).ok(),
+                _ => None,
+            };
+            // Ignore the responses which match none of the requests
+            if let Some(item) = index.and_then(|index| items.get_mut(index)) {
+                *item = Some(response);
+            }
+        }
+
+        Ok(Self { items })
+    }
+
+    /// The response to the request of the given item, or its error.
+    pub fn get<R>(&self, item: BatchItem<R>) -> Result<R::Output, Error>
+    where
+        R: SimpleRequest,
+    {
+        let response = self
+            .items
+            .get(item.index)
+            .and_then(Option::as_ref)
+            .ok_or_else(|| Error::missing_batch_response(item.index))?;
+
+        // Parsing from the text supports all the deserializers of the responses
+        let response = serde_json::to_vec(response).map_err(Error::serde)?;
+        R::Response::from_string(response).map(Into::into)
+    }
+
+    /// The number of requests in the batch.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether there was no request in the batch.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tendermint::block::Height;
+
+    use super::*;
+    use crate::{
+        endpoint::{commit, health},
+        error::ErrorDetail,
+    };
+
+    const COMMIT: &str =
+        include_str!("../../../../tests/kvstore_fixtures/v0_37/incoming/commit_at_height_10.json");
+
+    fn items() -> (BatchItem<commit::Request>, BatchItem<health::Request>) {
+        let client = HttpClient::new("http://127.0.0.1:26657").unwrap();
+        let mut batch = client.batch();
+        let commit = batch.add(commit::Request::new(Height::from(10_u32)));
+        let health = batch.add(health::Request);
+        (commit, health)
+    }
+
+    /// The given response to a request of a batch, at the given position.
+    fn with_id(response: &str, index: i64) -> serde_json::Value {
+        let mut response: serde_json::Value = serde_json::from_str(response).unwrap();
+        response["id"] = index.into();
+        response
+    }
+
+    #[test]
+    fn parses_responses_in_any_order() {
+        let (commit, health) = items();
+        let body = serde_json::to_vec(&[
+            with_id(r#"{"jsonrpc":"2.0","id":"","result":{}}"#, 1),
+            with_id(COMMIT, 0),
+        ])
+        .unwrap();
+
+        let responses = BatchResponses::parse(&body, 2).unwrap();
+        let commit = responses.get(commit).unwrap();
+        assert_eq!(commit.signed_header.header.height.value(), 10);
+        responses.get(health).unwrap();
+    }
+
+    #[test]
+    fn fails_items_on_their_own() {
+        let (commit, health) = items();
+        let error = r#"{"jsonrpc":"2.0","id":"","error":{"code":-32603,"message":"Internal error","data":"height 10 is not available"}}"#;
+        let body = serde_json::to_vec(&[with_id(error, 0)]).unwrap();
+
+        let responses = BatchResponses::parse(&body, 2).unwrap();
+        let err = responses.get(commit).unwrap_err();
+        assert!(matches!(err.detail(), ErrorDetail::Response(_)));
+        let err = responses.get(health).unwrap_err();
+        assert!(matches!(
+            err.detail(),
+            ErrorDetail::MissingBatchResponse(e) if e.index == 1
+        ));
+    }
+
+    #[test]
+    fn fails_rejected_batches() {
+        let error = br#"{"jsonrpc":"2.0","id":null,"error":{"code":-32600,"message":"Invalid Request","data":"batches are not supported"}}"#;
+        let err = BatchResponses::parse(error, 2).unwrap_err();
+        assert!(matches!(err.detail(), ErrorDetail::Response(_)));
+
+        let err = BatchResponses::parse(b"not json", 2).unwrap_err();
+        assert!(matches!(err.detail(), ErrorDetail::Serde(_)));
+    }
+}
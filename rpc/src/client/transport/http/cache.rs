@@ -0,0 +1,259 @@
+//! Cache of the responses of the node to the requests which always get the
+//! same answer, for an [`HttpClient`](super::HttpClient).
+
+use alloc::collections::BTreeMap;
+use core::time::Duration;
+use std::sync::Mutex;
+
+use bytes::Bytes;
+use tokio::time::Instant;
+
+use crate::{prelude::*, request::RequestMessage, Method};
+
+/// Which responses an [`HttpClient`](super::HttpClient) caches, so that it
+/// answers the same requests again without asking the node, see
+/// [`Builder::cache`](super::Builder::cache).
+///
+/// The responses for a given height or hash, such as those of `/block`,
+/// `/commit` or `/validators` at a past height, never change, which is why
+/// light clients verifying overlapping ranges of blocks spare a lot of requests
+/// with a cache. Only the responses of the configured methods are cached, but
+/// never those for the latest height, ie. the requests with a `null` height.
+///
+/// The cache holds up to `capacity` responses, evicting the least recently
+/// used ones first, each for the time to live of its method, if any. Only the
+/// successful responses are cached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheConfig {
+    capacity: usize,
+    methods: BTreeMap<Method, Option<Duration>>,
+}
+
+impl CacheConfig {
+    /// A cache of up to `capacity` responses, for none of the methods yet.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            methods: BTreeMap::new(),
+        }
+    }
+
+    /// A cache of up to `capacity` responses to the requests for blocks,
+    /// headers, commits and validator sets at given heights or hashes, which
+    /// are kept until they are evicted.
+    pub fn historical(capacity: usize) -> Self {
+        [
+            Method::Block,
+            Method::BlockByHash,
+            Method::BlockResults,
+            Method::Commit,
+            Method::Header,
+            Method::HeaderByHash,
+            Method::Validators,
+        ]
+        .into_iter()
+        .fold(Self::new(capacity), |config, method| {
+            config.method(method, None)
+        })
+    }
+
+    /// Cache the responses of the given method, for the given time, or until
+    /// they are evicted if `None`.
+    pub fn method(mut self, method: Method, ttl: Option<Duration>) -> Self {
+        self.methods.insert(method, ttl);
+        self
+    }
+
+    /// The capacity of the cache, in responses.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Whether the responses of the given method are cached, and for how long.
+    pub fn ttl(&self, method: Method) -> Option<Option<Duration>> {
+        self.methods.get(&method).copied()
+    }
+}
+
+/// A request, by method and parameters.
+type Key = (Method, String);
+
+#[derive(Debug)]
+struct Entry {
+    body: Bytes,
+    expires: Option<Instant>,
+    // When the entry was last used, as a key of `Entries::by_use`.
+    used: u64,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    by_key: BTreeMap<Key, Entry>,
+    // The keys of the entries, from the least to the most recently used.
+    by_use: BTreeMap<u64, Key>,
+    uses: u64,
+}
+
+impl Entries {
+    fn next_use(&mut self) -> u64 {
+        self.uses += 1;
+        self.uses
+    }
+
+    fn remove(&mut self, key: &Key) {
+        if let Some(entry) = self.by_key.remove(key) {
+            self.by_use.remove(&entry.used);
+        }
+    }
+}
+
+/// A bounded cache of the bodies of the responses, shared by an `HttpClient`
+/// and its clones.
+#[derive(Debug)]
+pub(super) struct Cache {
+    config: CacheConfig,
+    entries: Mutex<Entries>,
+}
+
+impl Cache {
+    pub fn new(config: CacheConfig) -> Self {
+        Self {
+            config,
+            entries: Mutex::new(Entries::default()),
+        }
+    }
+
+    /// The key of the given request, if its response may be cached, along
+    /// with its time to live.
+    pub fn key<R>(&self, request: &R) -> Option<(Key, Option<Duration>)>
+    where
+        R: RequestMessage,
+    {
+        let method = request.method();
+        let ttl = self.config.ttl(method)?;
+        if self.config.capacity == 0 {
+            return None;
+        }
+
+        let params = serde_json::to_value(request).ok()?;
+        if params.get("height").is_some_and(|height| height.is_null()) {
+            return None;
+        }
+
+        // The maps of `serde_json` are sorted, so that equal parameters always
+        // serialize the same.
+        let params = serde_json::to_string(&params).ok()?;
+        Some(((method, params), ttl))
+    }
+
+    /// The cached response to the request with the given key, if any.
+    pub fn get(&self, key: &Key) -> Option<Bytes> {
+        let mut entries = self.entries.lock().unwrap();
+        let now = Instant::now();
+
+        match entries.by_key.get(key) {
+            None => return None,
+            Some(entry) if entry.expires.is_some_and(|expires| expires <= now) => {
+                entries.remove(key);
+                return None;
+            },
+            Some(_) => {},
+        }
+
+        let used = entries.next_use();
+        let entry = entries.by_key.get_mut(key)?;
+        let previous = core::mem::replace(&mut entry.used, used);
+        let body = entry.body.clone();
+        entries.by_use.remove(&previous);
+        entries.by_use.insert(used, key.clone());
+
+        Some(body)
+    }
+
+    /// Cache the given response to the request with the given key, evicting
+    /// the least recently used response if the cache is full.
+    pub fn insert(&self, key: Key, ttl: Option<Duration>, body: Bytes) {
+        let mut entries = self.entries.lock().unwrap();
+
+        entries.remove(&key);
+        while entries.by_key.len() >= self.config.capacity {
+            match entries.by_use.pop_first() {
+                Some((_, evicted)) => {
+                    entries.by_key.remove(&evicted);
+                },
+                None => break,
+            }
+        }
+
+        let used = entries.next_use();
+        let expires = ttl.map(|ttl| Instant::now() + ttl);
+        entries.by_use.insert(used, key.clone());
+        entries.by_key.insert(
+            key,
+            Entry {
+                body,
+                expires,
+                used,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tendermint::block::Height;
+
+    use super::*;
+    use crate::endpoint::{block, commit, status};
+
+    #[test]
+    fn caches_configured_requests() {
+        let cache = Cache::new(CacheConfig::historical(10));
+
+        let height = Height::from(10_u32);
+        assert!(cache.key(&block::Request::new(height)).is_some());
+        assert!(cache.key(&commit::Request::new(height)).is_some());
+
+        // The latest block changes as the chain grows
+        assert!(cache.key(&block::Request::default()).is_none());
+        // As does the status of the node
+        assert!(cache.key(&status::Request).is_none());
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = Cache::new(CacheConfig::historical(2));
+        let key = |height: u32| {
+            cache
+                .key(&block::Request::new(Height::from(height)))
+                .unwrap()
+                .0
+        };
+
+        cache.insert(key(1), None, Bytes::from_static(b"1"));
+        cache.insert(key(2), None, Bytes::from_static(b"2"));
+        assert_eq!(cache.get(&key(1)).unwrap(), "1");
+
+        // The response at height 2 is the least recently used one
+        cache.insert(key(3), None, Bytes::from_static(b"3"));
+        assert!(cache.get(&key(2)).is_none());
+        assert_eq!(cache.get(&key(1)).unwrap(), "1");
+        assert_eq!(cache.get(&key(3)).unwrap(), "3");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn expires_responses() {
+        let ttl = Duration::from_secs(5);
+        let cache = Cache::new(CacheConfig::new(10).method(Method::Block, Some(ttl)));
+        let (key, ttl) = cache
+            .key(&block::Request::new(Height::from(1_u32)))
+            .unwrap();
+
+        cache.insert(key.clone(), ttl, Bytes::from_static(b"1"));
+        tokio::time::advance(Duration::from_secs(4)).await;
+        assert!(cache.get(&key).is_some());
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert!(cache.get(&key).is_none());
+    }
+}
@@ -0,0 +1,198 @@
+//! Decompression of the bodies of the responses of the nodes, for the
+//! encodings the [`HttpClient`](super::HttpClient) accepts.
+
+use bytes::Bytes;
+use miniz_oxide::inflate::{self, DecompressError, TINFLStatus};
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_ENCODING};
+
+use crate::{prelude::*, Error};
+
+/// The value of the `Accept-Encoding` header of the requests.
+pub(super) const ACCEPT_ENCODING: HeaderValue = HeaderValue::from_static("gzip, deflate");
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const GZIP_DEFLATE: u8 = 8;
+// The flags of the header of a gzip member, see RFC 1952.
+const GZIP_FHCRC: u8 = 0x02;
+const GZIP_FEXTRA: u8 = 0x04;
+const GZIP_FNAME: u8 = 0x08;
+const GZIP_FCOMMENT: u8 = 0x10;
+// The size of the fixed part of the header, and of the trailer.
+const GZIP_HEADER_LEN: usize = 10;
+const GZIP_TRAILER_LEN: usize = 8;
+
+/// Decode the given body of a response according to its `Content-Encoding`
+/// header, if any, failing if the decoded body is larger than `max_size`
+/// bytes.
+///
+/// The limit guards against small compressed bodies which decompress to huge
+/// ones, as a node is not trusted to send reasonable responses.
+pub(super) fn decode(headers: &HeaderMap, body: Bytes, max_size: usize) -> Result<Bytes, Error> {
+    let encoding = match headers.get(CONTENT_ENCODING) {
+        Some(encoding) => encoding
+            .to_str()
+            .map_err(|_| Error::decompression("invalid Content-Encoding header".to_string()))?
+            .trim()
+            .to_ascii_lowercase(),
+        None => "identity".to_string(),
+    };
+
+    match encoding.as_str() {
+        "" | "identity" if body.len() > max_size => Err(too_large(max_size)),
+        "" | "identity" => Ok(body),
+        "gzip" | "x-gzip" => gunzip(&body, max_size).map(Bytes::from),
+        // Some servers send raw DEFLATE data rather than the zlib format the
+        // specification calls for.
+        "deflate" => match inflate::decompress_to_vec_zlib_with_limit(&body, max_size) {
+            Ok(decompressed) => Ok(decompressed),
+            Err(e) if e.status == TINFLStatus::HasMoreOutput => Err(too_large(max_size)),
+            Err(_) => inflate::decompress_to_vec_with_limit(&body, max_size)
+                .map_err(|e| inflate_error("deflate", e, max_size)),
+        }
+        .map(Bytes::from),
+        _ => Err(Error::decompression(format!(
+            "unsupported content encoding: {encoding}"
+        ))),
+    }
+}
+
+/// Decompress the given gzip data, made of a single member, to at most
+/// `max_size` bytes.
+fn gunzip(data: &[u8], max_size: usize) -> Result<Vec<u8>, Error> {
+    let invalid = |reason: &str| Error::decompression(format!("invalid gzip data: {reason}"));
+
+    if data.len() < GZIP_HEADER_LEN + GZIP_TRAILER_LEN || data[..2] != GZIP_MAGIC {
+        return Err(invalid("missing header"));
+    }
+    if data[2] != GZIP_DEFLATE {
+        return Err(invalid("unsupported compression method"));
+    }
+
+    let flags = data[3];
+    let (mut rest, trailer) =
+        data[GZIP_HEADER_LEN..].split_at(data.len() - GZIP_HEADER_LEN - GZIP_TRAILER_LEN);
+    if flags & GZIP_FEXTRA != 0 {
+        if rest.len() < 2 {
+            return Err(invalid("truncated header"));
+        }
+        let len = usize::from(u16::from_le_bytes([rest[0], rest[1]]));
+        rest = rest
+            .get(2 + len..)
+            .ok_or_else(|| invalid("truncated header"))?;
+    }
+    for flag in [GZIP_FNAME, GZIP_FCOMMENT] {
+        if flags & flag != 0 {
+            // Zero-terminated strings
+            let end = rest
+                .iter()
+                .position(|&b| b == 0)
+                .ok_or_else(|| invalid("truncated header"))?;
+            rest = &rest[end + 1..];
+        }
+    }
+    if flags & GZIP_FHCRC != 0 {
+        rest = rest.get(2..).ok_or_else(|| invalid("truncated header"))?;
+    }
+
+    let decompressed = inflate::decompress_to_vec_with_limit(rest, max_size)
+        .map_err(|e| inflate_error("gzip", e, max_size))?;
+
+    let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+    let len = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+    if crc32fast::hash(&decompressed) != crc || decompressed.len() as u32 != len {
+        return Err(invalid("checksum mismatch"));
+    }
+
+    Ok(decompressed)
+}
+
+fn inflate_error(format: &str, e: DecompressError, max_size: usize) -> Error {
+    match e.status {
+        TINFLStatus::HasMoreOutput => too_large(max_size),
+        _ => Error::decompression(format!("invalid {format} data: {e}")),
+    }
+}
+
+fn too_large(max_size: usize) -> Error {
+    Error::decompression(format!(
+        "the response is larger than the maximum size of {max_size} bytes"
+    ))
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use miniz_oxide::deflate::{compress_to_vec, compress_to_vec_zlib};
+
+    use super::*;
+
+    /// The given data compressed in the gzip format, with a file name.
+    pub fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut gzip = vec![0x1f, 0x8b, GZIP_DEFLATE, GZIP_FNAME, 0, 0, 0, 0, 0, 0xff];
+        gzip.extend_from_slice(b"response.json\0");
+        gzip.extend(compress_to_vec(data, 6));
+        gzip.extend(crc32fast::hash(data).to_le_bytes());
+        gzip.extend((data.len() as u32).to_le_bytes());
+        gzip
+    }
+
+    fn encoded(encoding: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_ENCODING, HeaderValue::from_static(encoding));
+        headers
+    }
+
+    const BODY: &[u8] = br#"{"jsonrpc":"2.0","id":"","result":{"validators":[]}}"#;
+    const MAX_SIZE: usize = 1024;
+
+    #[test]
+    fn decodes_supported_encodings() {
+        let decode =
+            |headers: &HeaderMap, body: Vec<u8>| decode(headers, body.into(), MAX_SIZE).unwrap();
+
+        assert_eq!(decode(&HeaderMap::new(), BODY.to_vec()), BODY);
+        assert_eq!(decode(&encoded("identity"), BODY.to_vec()), BODY);
+        assert_eq!(decode(&encoded("gzip"), gzip(BODY)), BODY);
+        assert_eq!(
+            decode(&encoded("deflate"), compress_to_vec_zlib(BODY, 6)),
+            BODY
+        );
+        assert_eq!(decode(&encoded("deflate"), compress_to_vec(BODY, 6)), BODY);
+    }
+
+    #[test]
+    fn rejects_invalid_data() {
+        let mut corrupted = gzip(BODY);
+        let len = corrupted.len();
+        corrupted[len - 8] ^= 0xff;
+        assert!(decode(&encoded("gzip"), corrupted.into(), MAX_SIZE).is_err());
+
+        assert!(decode(&encoded("gzip"), BODY.to_vec().into(), MAX_SIZE).is_err());
+        assert!(decode(&encoded("br"), BODY.to_vec().into(), MAX_SIZE).is_err());
+    }
+
+    #[test]
+    fn rejects_oversized_responses() {
+        // Compressed zeros, which decompress way past the limit
+        let zeros = vec![0; 1024 * MAX_SIZE];
+        let bodies = [
+            ("gzip", gzip(&zeros)),
+            ("deflate", compress_to_vec_zlib(&zeros, 6)),
+            ("deflate", compress_to_vec(&zeros, 6)),
+            ("identity", vec![0; MAX_SIZE + 1]),
+        ];
+
+        for (encoding, body) in bodies {
+            let err = decode(&encoded(encoding), body.into(), MAX_SIZE).unwrap_err();
+            assert!(
+                err.to_string().contains("maximum size"),
+                "{encoding}: {err}"
+            );
+        }
+
+        let body = vec![b' '; MAX_SIZE];
+        assert_eq!(
+            decode(&encoded("gzip"), gzip(&body).into(), MAX_SIZE).unwrap(),
+            body
+        );
+    }
+}
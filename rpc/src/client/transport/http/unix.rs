@@ -0,0 +1,146 @@
+//! Transport of the requests of the [`HttpClient`](super::HttpClient) over a
+//! Unix domain socket, for the nodes running on the same host.
+
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use std::{io, path::PathBuf, sync::Arc};
+
+use hyper::{
+    client::connect::{Connected, Connection},
+    service::Service,
+    Body, HeaderMap, Uri,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::UnixStream,
+};
+
+use crate::{prelude::*, Error};
+
+/// A `hyper` client sending the requests to the node over a Unix domain
+/// socket, keeping its connections in a pool like `reqwest` does.
+#[derive(Debug, Clone)]
+pub(super) struct UnixClient {
+    inner: hyper::Client<UnixConnector, Body>,
+}
+
+impl UnixClient {
+    pub fn new(
+        path: impl Into<PathBuf>,
+        pool_max_idle_per_host: usize,
+        pool_idle_timeout: Option<Duration>,
+    ) -> Self {
+        let connector = UnixConnector {
+            path: Arc::new(path.into()),
+        };
+        let inner = hyper::Client::builder()
+            .pool_max_idle_per_host(pool_max_idle_per_host)
+            .pool_idle_timeout(pool_idle_timeout)
+            .build(connector);
+
+        Self { inner }
+    }
+
+    /// Send the given request to the node, returning the status, the headers
+    /// and the body of the response.
+    pub async fn execute(
+        &self,
+        request: reqwest::Request,
+    ) -> Result<(reqwest::StatusCode, HeaderMap, bytes::Bytes), Error> {
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|body| Body::from(body.to_vec()))
+            .unwrap_or_default();
+
+        // The connector ignores the host, only for which `hyper` requires an
+        // absolute URI.
+        let mut builder = hyper::Request::builder()
+            .method(request.method().clone())
+            .uri("http://localhost/");
+        if let Some(headers) = builder.headers_mut() {
+            headers.extend(request.headers().clone());
+        }
+        let request = builder
+            .body(body)
+            .expect("the request is built from a valid one");
+
+        let response = self
+            .inner
+            .request(request)
+            .await
+            .map_err(Error::unix_socket)?;
+        let (parts, body) = response.into_parts();
+        let body = hyper::body::to_bytes(body)
+            .await
+            .map_err(Error::unix_socket)?;
+
+        Ok((parts.status, parts.headers, body))
+    }
+}
+
+/// Connects to the socket at the given path, whatever the URI of the request.
+#[derive(Debug, Clone)]
+struct UnixConnector {
+    path: Arc<PathBuf>,
+}
+
+impl Service<Uri> for UnixConnector {
+    type Response = UnixConnection;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<UnixConnection>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: Uri) -> Self::Future {
+        let path = self.path.clone();
+        Box::pin(async move {
+            UnixStream::connect(path.as_path())
+                .await
+                .map(UnixConnection)
+        })
+    }
+}
+
+/// A connection to the socket, as `hyper` expects it.
+struct UnixConnection(UnixStream);
+
+impl Connection for UnixConnection {
+    fn connected(&self) -> Connected {
+        Connected::new()
+    }
+}
+
+impl AsyncRead for UnixConnection {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for UnixConnection {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+}
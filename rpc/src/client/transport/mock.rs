@@ -1,6 +1,8 @@
 //! Mock client implementation for use in testing.
 
-use alloc::collections::BTreeMap as HashMap;
+use alloc::collections::{BTreeMap as HashMap, VecDeque};
+use core::time::Duration;
+use std::sync::Mutex;
 
 use async_trait::async_trait;
 
@@ -67,6 +69,10 @@ impl<M: MockRequestMatcher> Client for MockClient<M> {
     where
         R: SimpleRequest<v0_37::Dialect>,
     {
+        let delay = self.matcher.delay_for(&request);
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
         self.matcher
             .response_for(request)
             .ok_or_else(Error::mismatch_response)?
@@ -84,6 +90,12 @@ impl<M: MockRequestMatcher> MockClient<M> {
         )
     }
 
+    /// The request matcher of the client, e.g. to inspect the requests it
+    /// received.
+    pub fn matcher(&self) -> &M {
+        &self.matcher
+    }
+
     /// Publishes the given event to all subscribers whose query exactly
     /// matches that of the event.
     pub fn publish(&self, ev: &Event) {
@@ -204,6 +216,17 @@ pub trait MockRequestMatcher: Send + Sync {
     where
         R: Request<S>,
         S: Dialect;
+
+    /// Provide the delay to wait for before responding to the given request.
+    ///
+    /// No delay by default.
+    fn delay_for<R, S>(&self, _request: &R) -> Duration
+    where
+        R: Request<S>,
+        S: Dialect,
+    {
+        Duration::ZERO
+    }
 }
 
 /// Provides a simple [`MockRequestMatcher`] implementation that simply maps
@@ -240,16 +263,185 @@ impl MockRequestMethodMatcher {
     }
 }
 
+/// A response scripted for a [`MockRequestScriptMatcher`].
+#[derive(Debug, Clone)]
+pub enum MockResponse {
+    /// Respond with the given JSON-encoded response.
+    Json(String),
+    /// Respond with malformed JSON, which fails to parse as a response.
+    Malformed,
+    /// Fail with the given error.
+    Error(Error),
+    /// Fail as if the connection to the node were dropped.
+    ConnectionDropped,
+    /// Fail as if the request timed out, after the given duration.
+    Timeout(Duration),
+    /// Respond with the given response, after the given duration.
+    Delayed(Duration, Box<MockResponse>),
+}
+
+impl MockResponse {
+    /// Respond with the given JSON-encoded response.
+    pub fn json(response: impl Into<String>) -> Self {
+        Self::Json(response.into())
+    }
+
+    /// Respond with this response, after the given duration.
+    pub fn delayed(self, delay: Duration) -> Self {
+        Self::Delayed(delay, Box::new(self))
+    }
+
+    fn delay(&self) -> Duration {
+        match self {
+            Self::Timeout(timeout) => *timeout,
+            Self::Delayed(delay, response) => delay.saturating_add(response.delay()),
+            _ => Duration::ZERO,
+        }
+    }
+
+    fn into_response<R: Response>(self) -> Result<R, Error> {
+        match self {
+            Self::Json(json) => R::from_string(json),
+            Self::Malformed => R::from_string(r#"{"jsonrpc": "2.0", "id": "", "result": "#),
+            Self::Error(e) => Err(e),
+            Self::ConnectionDropped => Err(Error::io(std::io::Error::new(
+                std::io::ErrorKind::ConnectionReset,
+                "connection dropped by the mock node",
+            ))),
+            Self::Timeout(_) => Err(Error::io(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "request to the mock node timed out",
+            ))),
+            Self::Delayed(_, response) => response.into_response(),
+        }
+    }
+}
+
+/// A request received by a [`MockRequestScriptMatcher`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockRequest {
+    /// The method of the request
+    pub method: Method,
+    /// The parameters of the request
+    pub params: serde_json::Value,
+}
+
+/// Provides a [`MockRequestMatcher`] implementation which responds to the
+/// requests with each method with a script of responses, in order, and
+/// records the requests it receives.
+///
+/// The delay of a response is waited for before the response is taken off
+/// the script, so that concurrent requests with the same method all wait for
+/// the delay of the next response. Once all the responses scripted for a
+/// method have been used, requests with that method get no response.
+///
+/// ## Examples
+///
+/// ```rust
+/// use core::time::Duration;
+/// use tendermint_rpc::{Client, Method, MockClient, MockRequestScriptMatcher, MockResponse};
+///
+/// const HEALTH_RESPONSE: &str = r#"{"jsonrpc": "2.0", "id": "", "result": {}}"#;
+///
+/// tokio_test::block_on(async {
+///     let matcher = MockRequestScriptMatcher::default()
+///         .respond(Method::Health, MockResponse::ConnectionDropped)
+///         .respond(Method::Health, MockResponse::Malformed)
+///         .respond(
+///             Method::Health,
+///             MockResponse::json(HEALTH_RESPONSE).delayed(Duration::from_millis(10)),
+///         );
+///     let (client, driver) = MockClient::new(matcher);
+///     let driver_hdl = tokio::spawn(async move { driver.run().await });
+///
+///     assert!(client.health().await.is_err());
+///     assert!(client.health().await.is_err());
+///     assert!(client.health().await.is_ok());
+///     // The script is over.
+///     assert!(client.health().await.is_err());
+///
+///     assert_eq!(client.matcher().requests().len(), 4);
+///
+///     client.close();
+///     driver_hdl.await.unwrap();
+/// });
+/// ```
+#[derive(Debug, Default)]
+pub struct MockRequestScriptMatcher {
+    scripts: Mutex<HashMap<Method, VecDeque<MockResponse>>>,
+    requests: Mutex<Vec<MockRequest>>,
+}
+
+impl MockRequestMatcher for MockRequestScriptMatcher {
+    fn response_for<R, S>(&self, request: R) -> Option<Result<R::Response, Error>>
+    where
+        R: Request<S>,
+        S: Dialect,
+    {
+        let method = request.method();
+        let json: serde_json::Value = serde_json::from_str(&request.into_json()).unwrap();
+        self.requests.lock().unwrap().push(MockRequest {
+            method,
+            params: json["params"].clone(),
+        });
+
+        let response = self.scripts.lock().unwrap().get_mut(&method)?.pop_front()?;
+        Some(response.into_response())
+    }
+
+    fn delay_for<R, S>(&self, request: &R) -> Duration
+    where
+        R: Request<S>,
+        S: Dialect,
+    {
+        self.scripts
+            .lock()
+            .unwrap()
+            .get(&request.method())
+            .and_then(VecDeque::front)
+            .map_or(Duration::ZERO, MockResponse::delay)
+    }
+}
+
+impl MockRequestScriptMatcher {
+    /// Adds `response` to the script of responses to the requests with the
+    /// given method.
+    pub fn respond(self, method: Method, response: MockResponse) -> Self {
+        self.scripts
+            .lock()
+            .unwrap()
+            .entry(method)
+            .or_default()
+            .push_back(response);
+        self
+    }
+
+    /// The requests received so far, in order.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// The number of responses scripted for the given method which have not
+    /// been used yet.
+    pub fn remaining(&self, method: Method) -> usize {
+        self.scripts
+            .lock()
+            .unwrap()
+            .get(&method)
+            .map_or(0, VecDeque::len)
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use std::path::PathBuf;
+    use std::{collections::BTreeMap, path::PathBuf, sync::Mutex};
 
     use futures::StreamExt;
     use tendermint::{block::Height, chain::Id};
     use tokio::fs;
 
     use super::*;
-    use crate::query::EventType;
+    use crate::{query::EventType, Order, PageStreamOptions};
 
     async fn read_json_fixture(version: &str, name: &str) -> String {
         fs::read_to_string(
@@ -262,6 +454,277 @@ mod test {
         .unwrap()
     }
 
+    /// Serves the given chunks of a genesis file, by their index.
+    struct GenesisChunkMatcher {
+        chunks: Vec<Vec<u8>>,
+    }
+
+    impl MockRequestMatcher for GenesisChunkMatcher {
+        fn response_for<R, S>(&self, request: R) -> Option<Result<R::Response, Error>>
+        where
+            R: Request<S>,
+            S: Dialect,
+        {
+            let request: serde_json::Value = serde_json::from_str(&request.into_json()).unwrap();
+            let chunk: usize = request["params"]["chunk"].as_str()?.parse().unwrap();
+            let data = subtle_encoding::base64::encode(self.chunks.get(chunk)?);
+
+            let response = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": "",
+                "result": {
+                    "chunk": chunk.to_string(),
+                    "total": self.chunks.len().to_string(),
+                    "data": String::from_utf8(data).unwrap(),
+                },
+            });
+            Some(R::Response::from_string(response.to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn mock_client_genesis_from_chunks() {
+        let fixture: serde_json::Value =
+            serde_json::from_str(&read_json_fixture("v0_37", "genesis").await).unwrap();
+        let bytes = serde_json::to_vec(&fixture["result"]["genesis"]).unwrap();
+        let chunks: Vec<Vec<u8>> = bytes.chunks(100).map(<[u8]>::to_vec).collect();
+        let total = chunks.len() as u64;
+        assert!(total > 1);
+
+        let (client, driver) = MockClient::new(GenesisChunkMatcher { chunks });
+        let driver_hdl = tokio::spawn(async move { driver.run().await });
+
+        let mut progress = Vec::new();
+        let genesis = client
+            .genesis_from_chunks::<Option<serde_json::Value>, _>(|p| progress.push(p))
+            .await
+            .unwrap();
+        assert_eq!("dockerchain".parse::<Id>().unwrap(), genesis.chain_id);
+
+        assert_eq!(progress.len() as u64, total);
+        assert!(progress.iter().all(|p| p.total == total));
+        assert_eq!(progress.last().unwrap().chunks, total);
+        assert_eq!(progress.last().unwrap().bytes, bytes.len());
+
+        client.close();
+        driver_hdl.await.unwrap().unwrap();
+    }
+
+    /// Serves pages of the given items, under the given field of the results,
+    /// failing to serve each page as many times as given by `failures`.
+    struct PageMatcher {
+        field: &'static str,
+        items: Vec<serde_json::Value>,
+        failures: Mutex<BTreeMap<usize, u32>>,
+    }
+
+    impl PageMatcher {
+        /// Serves the items of the given fixture, returning the matcher along
+        /// with the hashes of the items.
+        async fn from_fixture(
+            name: &str,
+            field: &'static str,
+            hash: fn(&serde_json::Value) -> &serde_json::Value,
+            failures: &[(usize, u32)],
+        ) -> (Self, Vec<String>) {
+            let fixture: serde_json::Value =
+                serde_json::from_str(&read_json_fixture("v0_37", name).await).unwrap();
+            let items = fixture["result"][field].as_array().unwrap().clone();
+            let hashes = items
+                .iter()
+                .map(|item| hash(item).as_str().unwrap().to_owned())
+                .collect();
+            let matcher = Self {
+                field,
+                items,
+                failures: Mutex::new(failures.iter().copied().collect()),
+            };
+            (matcher, hashes)
+        }
+    }
+
+    impl MockRequestMatcher for PageMatcher {
+        fn response_for<R, S>(&self, request: R) -> Option<Result<R::Response, Error>>
+        where
+            R: Request<S>,
+            S: Dialect,
+        {
+            let request: serde_json::Value = serde_json::from_str(&request.into_json()).unwrap();
+            let page: usize = request["params"]["page"].as_str()?.parse().unwrap();
+            let per_page: usize = request["params"]["per_page"].as_str()?.parse().unwrap();
+
+            if let Some(failures) = self.failures.lock().unwrap().get_mut(&page) {
+                if *failures > 0 {
+                    *failures -= 1;
+                    return Some(Err(Error::server(format!("failed to serve page {page}"))));
+                }
+            }
+
+            let items: Vec<_> = self
+                .items
+                .iter()
+                .skip((page - 1) * per_page)
+                .take(per_page)
+                .collect();
+            let response = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": "",
+                "result": {
+                    self.field: items,
+                    "total_count": self.items.len().to_string(),
+                },
+            });
+            Some(R::Response::from_string(response.to_string()))
+        }
+    }
+
+    async fn tx_search_matcher(failures: &[(usize, u32)]) -> (PageMatcher, Vec<String>) {
+        PageMatcher::from_fixture("tx_search_no_prove", "txs", |tx| &tx["hash"], failures).await
+    }
+
+    fn page_stream_options() -> PageStreamOptions {
+        PageStreamOptions {
+            per_page: 2,
+            concurrency: 2,
+            retry_delay: core::time::Duration::ZERO,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn mock_client_tx_search_all() {
+        let (matcher, hashes) = tx_search_matcher(&[(3, 2)]).await;
+        assert!(hashes.len() > 4);
+
+        let (client, driver) = MockClient::new(matcher);
+        let driver_hdl = tokio::spawn(async move { driver.run().await });
+
+        let txs: Vec<_> = client
+            .tx_search_all_with(
+                Query::from(EventType::Tx),
+                Order::Ascending,
+                page_stream_options(),
+            )
+            .collect()
+            .await;
+        let txs: Vec<String> = txs
+            .into_iter()
+            .map(|tx| tx.unwrap().hash.to_string())
+            .collect();
+        assert_eq!(txs, hashes);
+
+        client.close();
+        driver_hdl.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn mock_client_tx_search_all_ends_on_error() {
+        let (matcher, hashes) = tx_search_matcher(&[(2, u32::MAX)]).await;
+
+        let (client, driver) = MockClient::new(matcher);
+        let driver_hdl = tokio::spawn(async move { driver.run().await });
+
+        let txs: Vec<_> = client
+            .tx_search_all_with(
+                Query::from(EventType::Tx),
+                Order::Ascending,
+                page_stream_options(),
+            )
+            .collect()
+            .await;
+        assert_eq!(txs.len(), 3);
+        assert_eq!(txs[0].as_ref().unwrap().hash.to_string(), hashes[0]);
+        assert_eq!(txs[1].as_ref().unwrap().hash.to_string(), hashes[1]);
+        assert!(txs[2].is_err());
+
+        client.close();
+        driver_hdl.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn mock_client_block_search_all() {
+        let (matcher, hashes) = PageMatcher::from_fixture(
+            "block_search",
+            "blocks",
+            |block| &block["block_id"]["hash"],
+            &[(4, 1)],
+        )
+        .await;
+
+        let (client, driver) = MockClient::new(matcher);
+        let driver_hdl = tokio::spawn(async move { driver.run().await });
+
+        let blocks: Vec<_> = client
+            .block_search_all_with(
+                Query::from(EventType::NewBlock),
+                Order::Ascending,
+                page_stream_options(),
+            )
+            .collect()
+            .await;
+        let blocks: Vec<String> = blocks
+            .into_iter()
+            .map(|block| block.unwrap().block_id.hash.to_string())
+            .collect();
+        assert_eq!(blocks, hashes);
+
+        client.close();
+        driver_hdl.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn mock_client_scripted_responses() {
+        let status_fixture = read_json_fixture("v0_37", "status").await;
+        let block_fixture = read_json_fixture("v0_37", "block_at_height_10").await;
+        let matcher = MockRequestScriptMatcher::default()
+            .respond(Method::Status, MockResponse::Malformed)
+            .respond(
+                Method::Status,
+                MockResponse::Timeout(core::time::Duration::from_millis(20)),
+            )
+            .respond(Method::Status, MockResponse::ConnectionDropped)
+            .respond(
+                Method::Status,
+                MockResponse::json(status_fixture).delayed(core::time::Duration::from_millis(20)),
+            )
+            .respond(Method::Block, MockResponse::json(block_fixture))
+            .respond(
+                Method::Block,
+                MockResponse::Error(Error::server("unavailable".to_owned())),
+            );
+        let (client, driver) = MockClient::new(matcher);
+        let driver_hdl = tokio::spawn(async move { driver.run().await });
+
+        assert!(client.status().await.is_err());
+
+        let start = tokio::time::Instant::now();
+        assert!(client.status().await.is_err());
+        assert!(start.elapsed() >= core::time::Duration::from_millis(20));
+
+        assert!(client.status().await.is_err());
+
+        let start = tokio::time::Instant::now();
+        assert!(client.status().await.is_ok());
+        assert!(start.elapsed() >= core::time::Duration::from_millis(20));
+
+        // The script of the method is over.
+        assert!(client.status().await.is_err());
+        assert_eq!(client.matcher().remaining(Method::Status), 0);
+
+        assert!(client.block(Height::from(10_u32)).await.is_ok());
+        assert!(client.block(Height::from(11_u32)).await.is_err());
+
+        let requests = client.matcher().requests();
+        assert_eq!(requests.len(), 7);
+        assert!(requests[..5].iter().all(|r| r.method == Method::Status));
+        assert_eq!(requests[5].method, Method::Block);
+        assert_eq!(requests[5].params["height"], "10");
+        assert_eq!(requests[6].params["height"], "11");
+
+        client.close();
+        driver_hdl.await.unwrap().unwrap();
+    }
+
     mod v0_34 {
         use super::*;
         use crate::event::v0_34::DeEvent;
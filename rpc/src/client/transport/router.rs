@@ -115,6 +115,18 @@ impl SubscriptionRouter {
 
 #[cfg(feature = "websocket-client")]
 impl SubscriptionRouter {
+    /// Returns the queries with active subscriptions, each along with the ID
+    /// of one of its subscriptions.
+    pub fn queries(&self) -> Vec<(SubscriptionQuery, SubscriptionId)> {
+        self.subscriptions
+            .iter()
+            .filter_map(|(query, subs_for_query)| {
+                let id = subs_for_query.keys().next()?;
+                Some((query.clone(), id.clone()))
+            })
+            .collect()
+    }
+
     /// Returns the number of active subscriptions for the given query.
     pub fn num_subscriptions_for_query(&self, query: impl ToString) -> usize {
         self.subscriptions
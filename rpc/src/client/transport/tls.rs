@@ -0,0 +1,366 @@
+//! TLS configuration of the secure connections of the RPC clients to the
+//! nodes.
+
+use core::fmt;
+use std::{sync::Arc, time::SystemTime};
+
+use rustls::{
+    client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier},
+    Certificate, ClientConfig, PrivateKey, RootCertStore, ServerName,
+};
+use sha2::{Digest, Sha256};
+
+use crate::{prelude::*, Error};
+
+/// How the [`HttpClient`] and the [`WebSocketClient`] secure their connections
+/// to the nodes, for the nodes whose RPC endpoints sit behind gateways with
+/// their own certificate authorities, or which require the clients to present
+/// certificates of their own (mutual TLS).
+///
+/// By default, the certificates of the nodes are verified against the root
+/// certificates of the platform, to which [`TlsConfig::root_certificate_pem`]
+/// adds more. The certificates may also be pinned with
+/// [`TlsConfig::pin_certificate`], in which case the certificate of a node is
+/// accepted only if it is both valid and pinned.
+///
+/// The certificates and keys are only parsed once the client is built, which
+/// fails if any of them is invalid.
+///
+/// ## Examples
+///
+/// ```rust,ignore
+/// use tendermint_rpc::{HttpClient, TlsConfig};
+///
+/// let tls = TlsConfig::new()
+///     .native_roots(false)
+///     .root_certificate_pem(std::fs::read("gateway-ca.pem").unwrap())
+///     .client_identity_pem(
+///         std::fs::read("client.pem").unwrap(),
+///         std::fs::read("client-key.pem").unwrap(),
+///     );
+///
+/// let client = HttpClient::builder("https://rpc.example.com".parse().unwrap())
+///     .tls(tls)
+///     .build()
+///     .unwrap();
+/// ```
+///
+/// [`HttpClient`]: crate::HttpClient
+/// [`WebSocketClient`]: crate::WebSocketClient
+#[derive(Clone, PartialEq, Eq)]
+pub struct TlsConfig {
+    native_roots: bool,
+    roots: Vec<Vec<u8>>,
+    pins: Vec<[u8; 32]>,
+    identity: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TlsConfig {
+    /// Verify the certificates of the nodes against the root certificates of
+    /// the platform only, without presenting any certificate to them.
+    pub fn new() -> Self {
+        Self {
+            native_roots: true,
+            roots: Vec::new(),
+            pins: Vec::new(),
+            identity: None,
+        }
+    }
+
+    /// Whether to trust the root certificates of the platform, which is the
+    /// default. Without them, only the certificates added with
+    /// [`TlsConfig::root_certificate_pem`] are trusted.
+    pub fn native_roots(mut self, enabled: bool) -> Self {
+        self.native_roots = enabled;
+        self
+    }
+
+    /// Trust the root certificates found in the given PEM data, such as the
+    /// certificate of the authority of a private network.
+    pub fn root_certificate_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.roots.push(pem.into());
+        self
+    }
+
+    /// Only accept the certificates of the nodes whose SHA-256 fingerprint is
+    /// among the pinned ones, such as the one printed by
+    /// `openssl x509 -noout -fingerprint -sha256`.
+    ///
+    /// The fingerprint is that of the DER encoding of the certificate of the
+    /// node itself, not of its issuers.
+    pub fn pin_certificate(mut self, fingerprint: [u8; 32]) -> Self {
+        self.pins.push(fingerprint);
+        self
+    }
+
+    /// Present the certificate chain found in the given PEM data to the nodes
+    /// which require one, starting with the certificate of the client, along
+    /// with the private key of the client in PEM format (PKCS#8, PKCS#1 or
+    /// SEC1).
+    pub fn client_identity_pem(
+        mut self,
+        cert_chain: impl Into<Vec<u8>>,
+        key: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.identity = Some((cert_chain.into(), key.into()));
+        self
+    }
+
+    /// Build the `rustls` configuration shared by the transports.
+    pub(crate) fn client_config(&self) -> Result<ClientConfig, Error> {
+        let mut roots = RootCertStore::empty();
+        if self.native_roots {
+            let certs = rustls_native_certs::load_native_certs()
+                .map_err(|e| Error::tls(format!("failed to load the root certificates: {e}")))?;
+            let certs: Vec<Vec<u8>> = certs.into_iter().map(|cert| cert.0).collect();
+            roots.add_parsable_certificates(&certs);
+        }
+        for pem in &self.roots {
+            for cert in parse_certificates(pem)? {
+                roots
+                    .add(&cert)
+                    .map_err(|e| Error::tls(format!("invalid root certificate: {e}")))?;
+            }
+        }
+
+        let verifier = WebPkiVerifier::new(roots, None);
+        let builder = ClientConfig::builder().with_safe_defaults();
+        let builder = if self.pins.is_empty() {
+            builder.with_custom_certificate_verifier(Arc::new(verifier))
+        } else {
+            builder.with_custom_certificate_verifier(Arc::new(PinningVerifier {
+                inner: verifier,
+                pins: self.pins.clone(),
+            }))
+        };
+
+        match &self.identity {
+            None => Ok(builder.with_no_client_auth()),
+            Some((cert_chain, key)) => {
+                let cert_chain = parse_certificates(cert_chain)?;
+                if cert_chain.is_empty() {
+                    return Err(Error::tls(
+                        "no certificate found for the client".to_string(),
+                    ));
+                }
+                builder
+                    .with_client_auth_cert(cert_chain, parse_private_key(key)?)
+                    .map_err(|e| Error::tls(format!("invalid client identity: {e}")))
+            },
+        }
+    }
+}
+
+impl fmt::Debug for TlsConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsConfig")
+            .field("native_roots", &self.native_roots)
+            .field("roots", &self.roots.len())
+            .field("pins", &self.pins.len())
+            .field("identity", &self.identity.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+/// All the certificates found in the given PEM data.
+fn parse_certificates(pem: &[u8]) -> Result<Vec<Certificate>, Error> {
+    let certs = rustls_pemfile::certs(&mut &pem[..])
+        .map_err(|e| Error::tls(format!("invalid PEM certificates: {e}")))?;
+
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+/// The first private key found in the given PEM data.
+fn parse_private_key(pem: &[u8]) -> Result<PrivateKey, Error> {
+    let mut reader = pem;
+    loop {
+        match rustls_pemfile::read_one(&mut reader)
+            .map_err(|e| Error::tls(format!("invalid PEM private key: {e}")))?
+        {
+            Some(
+                rustls_pemfile::Item::PKCS8Key(key)
+                | rustls_pemfile::Item::RSAKey(key)
+                | rustls_pemfile::Item::ECKey(key),
+            ) => return Ok(PrivateKey(key)),
+            Some(_) => continue,
+            None => return Err(Error::tls("no private key found".to_string())),
+        }
+    }
+}
+
+/// Verifies the certificates of the nodes as usual, then checks that they are
+/// pinned.
+struct PinningVerifier {
+    inner: WebPkiVerifier,
+    pins: Vec<[u8; 32]>,
+}
+
+impl ServerCertVerifier for PinningVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        intermediates: &[Certificate],
+        server_name: &ServerName,
+        scts: &mut dyn Iterator<Item = &[u8]>,
+        ocsp_response: &[u8],
+        now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let verified = self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            scts,
+            ocsp_response,
+            now,
+        )?;
+
+        let fingerprint: [u8; 32] = Sha256::digest(&end_entity.0).into();
+        if self.pins.contains(&fingerprint) {
+            Ok(verified)
+        } else {
+            Err(rustls::Error::General(
+                "the certificate of the node is not pinned".to_string(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CA: &[u8] = include_bytes!("../../../tests/tls_fixtures/ca.pem");
+    const CLIENT: &[u8] = include_bytes!("../../../tests/tls_fixtures/client.pem");
+    const CLIENT_KEY: &[u8] = include_bytes!("../../../tests/tls_fixtures/client-key.pem");
+
+    #[test]
+    fn invalid_configurations() {
+        let no_key = TlsConfig::new()
+            .native_roots(false)
+            .client_identity_pem(CLIENT, CLIENT);
+        assert!(no_key
+            .client_config()
+            .unwrap_err()
+            .to_string()
+            .contains("no private key found"));
+
+        let no_certificate = TlsConfig::new()
+            .native_roots(false)
+            .client_identity_pem(CLIENT_KEY, CLIENT_KEY);
+        assert!(no_certificate
+            .client_config()
+            .unwrap_err()
+            .to_string()
+            .contains("no certificate found"));
+
+        let valid = TlsConfig::new()
+            .native_roots(false)
+            .root_certificate_pem(CA)
+            .client_identity_pem(CLIENT, CLIENT_KEY);
+        assert!(valid.client_config().is_ok());
+        assert!(!format!("{valid:?}").contains("BEGIN"));
+    }
+
+    #[cfg(feature = "websocket-client")]
+    mod handshake {
+        use rustls::{server::AllowAnyAuthenticatedClient, ServerConfig};
+        use tokio::io::{duplex, AsyncReadExt, AsyncWriteExt};
+        use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+        use super::*;
+
+        const SERVER: &[u8] = include_bytes!("../../../tests/tls_fixtures/server.pem");
+        const SERVER_KEY: &[u8] = include_bytes!("../../../tests/tls_fixtures/server-key.pem");
+
+        fn server_config(client_auth: bool) -> ServerConfig {
+            let builder = ServerConfig::builder().with_safe_defaults();
+            let builder = if client_auth {
+                let mut roots = RootCertStore::empty();
+                roots.add(&parse_certificates(CA).unwrap()[0]).unwrap();
+                builder.with_client_cert_verifier(Arc::new(AllowAnyAuthenticatedClient::new(roots)))
+            } else {
+                builder.with_no_client_auth()
+            };
+            builder
+                .with_single_cert(
+                    parse_certificates(SERVER).unwrap(),
+                    parse_private_key(SERVER_KEY).unwrap(),
+                )
+                .unwrap()
+        }
+
+        /// Perform a handshake between a client with the given configuration
+        /// and a server for `node.test`, then exchange a message.
+        async fn handshake(config: TlsConfig, client_auth: bool) -> Result<(), String> {
+            let (client, server) = duplex(16 * 1024);
+            let acceptor = TlsAcceptor::from(Arc::new(server_config(client_auth)));
+            let server = tokio::spawn(async move {
+                let mut stream = acceptor.accept(server).await.ok()?;
+                stream.write_all(b"hello").await.ok()?;
+                stream.flush().await.ok()
+            });
+
+            let connector = TlsConnector::from(Arc::new(config.client_config().unwrap()));
+            let domain = ServerName::try_from("node.test").unwrap();
+            let result = async {
+                let mut stream = connector.connect(domain, client).await?;
+                let mut message = [0; 5];
+                stream.read_exact(&mut message).await?;
+                assert_eq!(&message, b"hello");
+                Ok::<_, std::io::Error>(())
+            }
+            .await
+            .map_err(|e| e.to_string());
+
+            drop(server);
+            result
+        }
+
+        fn trusting_ca() -> TlsConfig {
+            TlsConfig::new()
+                .native_roots(false)
+                .root_certificate_pem(CA)
+        }
+
+        fn server_fingerprint() -> [u8; 32] {
+            Sha256::digest(&parse_certificates(SERVER).unwrap()[0].0).into()
+        }
+
+        #[tokio::test]
+        async fn custom_roots() {
+            handshake(trusting_ca(), false).await.unwrap();
+
+            let err = handshake(TlsConfig::new().native_roots(false), false)
+                .await
+                .unwrap_err();
+            assert!(err.contains("UnknownIssuer"), "{err}");
+        }
+
+        #[tokio::test]
+        async fn pinned_certificates() {
+            handshake(trusting_ca().pin_certificate(server_fingerprint()), false)
+                .await
+                .unwrap();
+
+            let err = handshake(trusting_ca().pin_certificate([0; 32]), false)
+                .await
+                .unwrap_err();
+            assert!(err.contains("not pinned"), "{err}");
+        }
+
+        #[tokio::test]
+        async fn client_certificates() {
+            handshake(trusting_ca().client_identity_pem(CLIENT, CLIENT_KEY), true)
+                .await
+                .unwrap();
+
+            assert!(handshake(trusting_ca(), true).await.is_err());
+        }
+    }
+}
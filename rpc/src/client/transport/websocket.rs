@@ -6,6 +6,7 @@ use core::{
     ops::Add,
     str::FromStr,
 };
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use async_tungstenite::{
@@ -21,7 +22,7 @@ use serde::{Deserialize, Serialize};
 use tokio::time::{Duration, Instant};
 use tracing::{debug, error};
 
-use tendermint::{block::Height, Hash};
+use tendermint::{block::Height, evidence::Evidence, Hash};
 use tendermint_config::net;
 
 use super::router::{SubscriptionId, SubscriptionIdRef};
@@ -32,15 +33,15 @@ use crate::{
         transport::router::{PublishResult, SubscriptionRouter},
         Client, CompatMode,
     },
-    dialect::{v0_34, Dialect, LatestDialect},
+    dialect::{v0_34, v0_38, Dialect, LatestDialect},
     endpoint::{self, subscribe, unsubscribe},
-    error::Error,
+    error::{Error, ErrorDetail},
     event::{self, Event},
     prelude::*,
     query::Query,
     request::Wrapper,
-    response, Id, Order, Request, Response, Scheme, SimpleRequest, Subscription,
-    SubscriptionClient, Url,
+    response, Id, Order, Proxy, Request, Response, Scheme, SimpleRequest, Subscription,
+    SubscriptionClient, TlsConfig, Url,
 };
 
 // WebSocket connection times out if we haven't heard anything at all from the
@@ -59,6 +60,41 @@ const PING_INTERVAL: Duration = Duration::from_secs((RECV_TIMEOUT_SECONDS * 9) /
 /// Low-level WebSocket configuration
 pub use async_tungstenite::tungstenite::protocol::WebSocketConfig;
 
+/// How the [`WebSocketClientDriver`] re-establishes its connection once it is
+/// lost, see [`Builder::reconnect`].
+///
+/// The driver waits `initial_delay` before its first attempt to reconnect, and
+/// doubles the delay after every failed attempt, up to `max_delay`. It gives
+/// up after `max_attempts` failed attempts, returning the last error.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    /// Delay before the first attempt to reconnect
+    pub initial_delay: Duration,
+    /// Maximum delay between two attempts to reconnect
+    pub max_delay: Duration,
+    /// Maximum number of attempts to reconnect in a row
+    pub max_attempts: u32,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            max_attempts: 10,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Delay before the given attempt to reconnect, counting from 0.
+    fn delay(&self, attempt: u32) -> Duration {
+        self.initial_delay
+            .saturating_mul(2_u32.saturating_pow(attempt))
+            .min(self.max_delay)
+    }
+}
+
 /// Tendermint RPC client that provides access to all RPC functionality
 /// (including [`Event`] subscription) over a WebSocket connection.
 ///
@@ -85,6 +121,18 @@ pub use async_tungstenite::tungstenite::protocol::WebSocketConfig;
 ///
 /// This is not configurable at present.
 ///
+/// ### Reconnection
+///
+/// By default, the driver terminates as soon as the connection is lost, along
+/// with all the subscriptions. With a [`ReconnectPolicy`] set through
+/// [`Builder::reconnect`], it instead re-establishes the connection and issues
+/// the active subscriptions again. Each subscription then receives an error of
+/// kind [`ErrorDetail::WebSocketReconnected`] before any new event, telling
+/// that events may have been missed while disconnected, and keeps going. The
+/// requests awaiting a response when the connection was lost fail.
+///
+/// [`ErrorDetail::WebSocketReconnected`]: crate::error::ErrorDetail::WebSocketReconnected
+///
 /// ### Keep-Alive
 ///
 /// The WebSocket client implements a keep-alive mechanism whereby it sends a
@@ -149,6 +197,9 @@ pub struct Builder {
     url: WebSocketClientUrl,
     compat: CompatMode,
     transport_config: Option<WebSocketConfig>,
+    reconnect: Option<ReconnectPolicy>,
+    proxy: Option<Proxy>,
+    tls: Option<TlsConfig>,
 }
 
 impl Builder {
@@ -166,16 +217,69 @@ impl Builder {
         self
     }
 
+    /// Re-establish the connection when it is lost, according to the given
+    /// policy, instead of terminating the driver.
+    ///
+    /// See the [`WebSocketClient`] documentation for how subscriptions are
+    /// carried over.
+    pub fn reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect = Some(policy);
+        self
+    }
+
+    /// Connect through the given HTTP or SOCKS5 proxy, authenticating to it
+    /// with its credentials, if any.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Secure the `wss://` connections to the node with the given TLS
+    /// configuration, instead of just verifying the certificate of the node
+    /// against the root certificates of the platform.
+    pub fn tls(mut self, config: TlsConfig) -> Self {
+        self.tls = Some(config);
+        self
+    }
+
     /// Try to create a client with the options specified for this builder.
     pub async fn build(self) -> Result<(WebSocketClient, WebSocketClientDriver), Error> {
         let url = self.url.0;
         let compat = self.compat;
-        let (inner, driver) = if url.is_secure() {
-            sealed::WebSocketClient::new_secure(url, compat, self.transport_config).await?
+        let options = sealed::ConnectOptions {
+            proxy: self.proxy,
+            tls: match &self.tls {
+                Some(tls) => Some(Arc::new(tls.client_config()?)),
+                None => None,
+            },
+        };
+        let (inner, mut driver) = if url.is_secure() {
+            sealed::WebSocketClient::new_secure(
+                url.clone(),
+                compat,
+                self.transport_config,
+                &options,
+            )
+            .await?
         } else {
-            sealed::WebSocketClient::new_unsecure(url, compat, self.transport_config).await?
+            sealed::WebSocketClient::new_unsecure(
+                url.clone(),
+                compat,
+                self.transport_config,
+                &options,
+            )
+            .await?
         };
 
+        if let Some(policy) = self.reconnect {
+            driver.reconnect = Some(Reconnect {
+                url,
+                config: self.transport_config,
+                options,
+                policy,
+            });
+        }
+
         Ok((WebSocketClient { inner, compat }, driver))
     }
 }
@@ -217,6 +321,9 @@ impl WebSocketClient {
             url,
             compat: Default::default(),
             transport_config: Default::default(),
+            reconnect: None,
+            proxy: None,
+            tls: None,
         }
     }
 
@@ -255,7 +362,9 @@ impl Client for WebSocketClient {
     {
         let height = height.into();
         match self.compat {
-            CompatMode::V0_37 => self.perform(endpoint::header::Request::new(height)).await,
+            CompatMode::V0_37 | CompatMode::V0_38 => {
+                self.perform(endpoint::header::Request::new(height)).await
+            },
             CompatMode::V0_34 => {
                 // Back-fill with a request to /block endpoint and
                 // taking just the header from the response.
@@ -272,7 +381,7 @@ impl Client for WebSocketClient {
         hash: Hash,
     ) -> Result<endpoint::header_by_hash::Response, Error> {
         match self.compat {
-            CompatMode::V0_37 => {
+            CompatMode::V0_37 | CompatMode::V0_38 => {
                 self.perform(endpoint::header_by_hash::Request::new(hash))
                     .await
             },
@@ -290,6 +399,21 @@ impl Client for WebSocketClient {
         }
     }
 
+    /// `/broadcast_evidence`: broadcast an evidence.
+    async fn broadcast_evidence(&self, e: Evidence) -> Result<endpoint::evidence::Response, Error> {
+        match self.compat {
+            CompatMode::V0_38 => {
+                self.perform_with_dialect(endpoint::evidence::Request::new(e), v0_38::Dialect)
+                    .await
+            },
+            CompatMode::V0_37 => self.perform(endpoint::evidence::Request::new(e)).await,
+            CompatMode::V0_34 => {
+                self.perform_with_dialect(endpoint::evidence::Request::new(e), v0_34::Dialect)
+                    .await
+            },
+        }
+    }
+
     async fn tx(&self, hash: Hash, prove: bool) -> Result<endpoint::tx::Response, Error> {
         perform_with_compat!(self, endpoint::tx::Request::new(hash, prove))
     }
@@ -317,6 +441,13 @@ impl Client for WebSocketClient {
     {
         perform_with_compat!(self, endpoint::broadcast::tx_commit::Request::new(tx))
     }
+
+    async fn check_tx<T>(&self, tx: T) -> Result<endpoint::check_tx::Response, Error>
+    where
+        T: Into<Vec<u8>> + Send,
+    {
+        perform_with_compat!(self, endpoint::check_tx::Request::new(tx))
+    }
 }
 
 #[async_trait]
@@ -402,19 +533,25 @@ impl From<WebSocketClientUrl> for Url {
 
 mod sealed {
     use async_tungstenite::{
-        tokio::{connect_async_with_config, connect_async_with_tls_connector_and_config},
+        tokio::{
+            client_async_tls_with_connector_and_config, connect_async_with_config,
+            connect_async_with_tls_connector_and_config, ConnectStream,
+        },
         tungstenite::client::IntoClientRequest,
+        WebSocketStream,
     };
+    use rustls::ClientConfig;
+    use tokio_rustls::TlsConnector;
     use tracing::debug;
 
     use super::{
-        DriverCommand, SimpleRequestCommand, SubscribeCommand, UnsubscribeCommand,
+        Arc, DriverCommand, SimpleRequestCommand, SubscribeCommand, UnsubscribeCommand,
         WebSocketClientDriver, WebSocketConfig,
     };
     use crate::{
         client::{
             sync::{unbounded, ChannelTx},
-            transport::auth::authorize,
+            transport::{auth::authorize, proxy},
             CompatMode,
         },
         dialect::Dialect,
@@ -422,9 +559,52 @@ mod sealed {
         query::Query,
         request::Wrapper,
         utils::uuid_str,
-        Error, Response, SimpleRequest, Subscription, Url,
+        Error, Proxy, Response, SimpleRequest, Subscription, Url,
     };
 
+    /// How the connections to the node are established, besides their URL and
+    /// WebSocket configuration.
+    #[derive(Debug, Clone, Default)]
+    pub struct ConnectOptions {
+        /// The proxy to connect through, if any
+        pub proxy: Option<Proxy>,
+        /// The TLS configuration of secure connections, if not the default one
+        pub tls: Option<Arc<ClientConfig>>,
+    }
+
+    /// Open a WebSocket connection to the node with the given address, over a
+    /// secure connection if its scheme calls for one, and through the given
+    /// proxy, if any.
+    pub async fn connect(
+        url: Url,
+        config: Option<WebSocketConfig>,
+        options: &ConnectOptions,
+    ) -> Result<WebSocketStream<ConnectStream>, Error> {
+        // Not supplying a connector means async_tungstenite will create the
+        // connector for us.
+        let connector = options.tls.clone().map(TlsConnector::from);
+        let (stream, _response) = if let Some(proxy) = &options.proxy {
+            debug!(
+                "Connecting to WebSocket endpoint {} through proxy {}",
+                url, proxy
+            );
+
+            let tunnel = proxy::connect(proxy, url.host(), url.port()).await?;
+            client_async_tls_with_connector_and_config(url, tunnel, connector, config).await
+        } else if url.is_secure() {
+            debug!("Connecting to secure WebSocket endpoint: {}", url);
+
+            connect_async_with_tls_connector_and_config(url, connector, config).await
+        } else {
+            debug!("Connecting to unsecure WebSocket endpoint: {}", url);
+
+            connect_async_with_config(url, config).await
+        }
+        .map_err(Error::tungstenite)?;
+
+        Ok(stream)
+    }
+
     /// Marker for the [`AsyncTungsteniteClient`] for clients operating over
     /// unsecure connections.
     #[derive(Debug, Clone)]
@@ -460,12 +640,9 @@ mod sealed {
             url: Url,
             compat: CompatMode,
             config: Option<WebSocketConfig>,
+            options: &ConnectOptions,
         ) -> Result<(Self, WebSocketClientDriver), Error> {
-            debug!("Connecting to unsecure WebSocket endpoint: {}", url);
-
-            let (stream, _response) = connect_async_with_config(url, config)
-                .await
-                .map_err(Error::tungstenite)?;
+            let stream = connect(url, config, options).await?;
 
             let (cmd_tx, cmd_rx) = unbounded();
             let driver = WebSocketClientDriver::new(stream, cmd_rx, compat);
@@ -492,15 +669,9 @@ mod sealed {
             url: Url,
             compat: CompatMode,
             config: Option<WebSocketConfig>,
+            options: &ConnectOptions,
         ) -> Result<(Self, WebSocketClientDriver), Error> {
-            debug!("Connecting to secure WebSocket endpoint: {}", url);
-
-            // Not supplying a connector means async_tungstenite will create the
-            // connector for us.
-            let (stream, _response) =
-                connect_async_with_tls_connector_and_config(url, None, config)
-                    .await
-                    .map_err(Error::tungstenite)?;
+            let stream = connect(url, config, options).await?;
 
             let (cmd_tx, cmd_rx) = unbounded();
             let driver = WebSocketClientDriver::new(stream, cmd_rx, compat);
@@ -597,9 +768,10 @@ mod sealed {
             url: Url,
             compat: CompatMode,
             config: Option<WebSocketConfig>,
+            options: &ConnectOptions,
         ) -> Result<(Self, WebSocketClientDriver), Error> {
             let (client, driver) =
-                AsyncTungsteniteClient::<Unsecure>::new(url, compat, config).await?;
+                AsyncTungsteniteClient::<Unsecure>::new(url, compat, config, options).await?;
             Ok((Self::Unsecure(client), driver))
         }
 
@@ -607,9 +779,10 @@ mod sealed {
             url: Url,
             compat: CompatMode,
             config: Option<WebSocketConfig>,
+            options: &ConnectOptions,
         ) -> Result<(Self, WebSocketClientDriver), Error> {
             let (client, driver) =
-                AsyncTungsteniteClient::<Secure>::new(url, compat, config).await?;
+                AsyncTungsteniteClient::<Secure>::new(url, compat, config, options).await?;
             Ok((Self::Secure(client), driver))
         }
 
@@ -745,6 +918,17 @@ pub struct WebSocketClientDriver {
     pending_commands: HashMap<SubscriptionId, DriverCommand>,
     // The compatibility mode directing how to parse subscription events.
     compat: CompatMode,
+    // How to re-establish the connection once it is lost, if at all.
+    reconnect: Option<Reconnect>,
+}
+
+// What the driver needs to re-establish its connection.
+#[derive(Debug, Clone)]
+struct Reconnect {
+    url: Url,
+    config: Option<WebSocketConfig>,
+    options: sealed::ConnectOptions,
+    policy: ReconnectPolicy,
 }
 
 impl WebSocketClientDriver {
@@ -759,6 +943,7 @@ impl WebSocketClientDriver {
             cmd_rx,
             pending_commands: HashMap::new(),
             compat,
+            reconnect: None,
         }
     }
 
@@ -784,6 +969,22 @@ impl WebSocketClientDriver {
     /// Executes the WebSocket driver, which manages the underlying WebSocket
     /// transport.
     pub async fn run(mut self) -> Result<(), Error> {
+        loop {
+            let e = match self.run_connection().await {
+                Ok(()) => return Ok(()),
+                Err(e) => e,
+            };
+
+            match self.reconnect.clone() {
+                Some(reconnect) if is_connection_lost(&e) => self.reconnect(&reconnect, e).await?,
+                _ => return Err(e),
+            }
+        }
+    }
+
+    /// Manages the current WebSocket connection until the driver is told to
+    /// terminate, or the connection fails.
+    async fn run_connection(&mut self) -> Result<(), Error> {
         let mut ping_interval =
             tokio::time::interval_at(Instant::now().add(PING_INTERVAL), PING_INTERVAL);
 
@@ -792,19 +993,20 @@ impl WebSocketClientDriver {
 
         loop {
             tokio::select! {
-                Some(res) = self.stream.next() => match res {
-                    Ok(msg) => {
+                res = self.stream.next() => match res {
+                    Some(Ok(msg)) => {
                         // Reset the receive timeout every time we successfully
                         // receive a message from the remote endpoint.
                         recv_timeout.as_mut().reset(Instant::now().add(RECV_TIMEOUT));
                         self.handle_incoming_msg(msg).await?
                     },
-                    Err(e) => return Err(
+                    Some(Err(e)) => return Err(
                         Error::web_socket(
                             "failed to read from WebSocket connection".to_string(),
                             e
                         ),
                     ),
+                    None => return Err(Error::web_socket_closed()),
                 },
                 Some(cmd) = self.cmd_rx.recv() => match cmd {
                     DriverCommand::Subscribe(subs_cmd) => self.subscribe(subs_cmd).await?,
@@ -820,6 +1022,76 @@ impl WebSocketClientDriver {
         }
     }
 
+    /// Re-establishes the connection lost with the given error, and issues
+    /// the active subscriptions again over the new connection.
+    async fn reconnect(&mut self, reconnect: &Reconnect, mut e: Error) -> Result<(), Error> {
+        // The responses to the pending commands will never come over the new
+        // connection.
+        for (_, cmd) in core::mem::take(&mut self.pending_commands) {
+            // Unsubscribing is already done as far as the subscribers are
+            // concerned, and the server forgets the subscriptions of a lost
+            // connection anyway.
+            let _ = match cmd {
+                DriverCommand::Subscribe(cmd) => cmd.response_tx.send(Err(e.clone())),
+                DriverCommand::Unsubscribe(cmd) => cmd.response_tx.send(Ok(())),
+                DriverCommand::SimpleRequest(cmd) => cmd.response_tx.send(Err(e.clone())),
+                DriverCommand::Terminate => Ok(()),
+            };
+        }
+
+        let policy = reconnect.policy;
+        for attempt in 1..=policy.max_attempts {
+            let delay = policy.delay(attempt - 1);
+            debug!(
+                "WebSocket connection lost: {}. Reconnecting in {:?} (attempt {}/{})...",
+                e, delay, attempt, policy.max_attempts
+            );
+            tokio::time::sleep(delay).await;
+
+            let res =
+                match sealed::connect(reconnect.url.clone(), reconnect.config, &reconnect.options)
+                    .await
+                {
+                    Ok(stream) => {
+                        self.stream = stream;
+                        self.resubscribe(attempt).await
+                    },
+                    Err(e) => Err(e),
+                };
+
+            match res {
+                Ok(()) => return Ok(()),
+                Err(err) => e = err,
+            }
+        }
+
+        error!(
+            "Failed to re-establish the WebSocket connection after {} attempts",
+            policy.max_attempts
+        );
+        Err(e)
+    }
+
+    /// Tells the active subscriptions that events may have been missed, and
+    /// issues them again over the new connection, after the given number of
+    /// attempts to reconnect.
+    async fn resubscribe(&mut self, attempts: u32) -> Result<(), Error> {
+        for (query, id) in self.router.queries() {
+            let gap = Err(Error::web_socket_reconnected(attempts));
+            if let PublishResult::AllDisconnected(query) = self.router.publish(query.clone(), gap) {
+                self.router.remove_by_query(query);
+                continue;
+            }
+
+            // Reusing the ID of one of the subscriptions to the query routes
+            // any error in response back to its subscribers.
+            let wrapper = Wrapper::new_with_id(Id::Str(id), subscribe::Request::new(query));
+            self.send_request(wrapper).await?;
+        }
+
+        Ok(())
+    }
+
     async fn send_request<R>(&mut self, wrapper: Wrapper<R>) -> Result<(), Error>
     where
         R: Request,
@@ -889,6 +1161,7 @@ impl WebSocketClientDriver {
 
     async fn handle_text_msg(&mut self, msg: String) -> Result<(), Error> {
         let parse_res = match self.compat {
+            CompatMode::V0_38 => event::v0_38::DeEvent::from_string(&msg).map(Into::into),
             CompatMode::V0_37 => event::v0_37::DeEvent::from_string(&msg).map(Into::into),
             CompatMode::V0_34 => event::v0_34::DeEvent::from_string(&msg).map(Into::into),
         };
@@ -994,7 +1267,7 @@ impl WebSocketClientDriver {
         self.send_msg(Message::Ping(Vec::new())).await
     }
 
-    async fn close(mut self) -> Result<(), Error> {
+    async fn close(&mut self) -> Result<(), Error> {
         self.send_msg(Message::Close(Some(CloseFrame {
             code: CloseCode::Normal,
             reason: Cow::from("client closed WebSocket connection"),
@@ -1010,6 +1283,16 @@ impl WebSocketClientDriver {
     }
 }
 
+// Whether the given error means that the WebSocket connection is lost.
+fn is_connection_lost(e: &Error) -> bool {
+    matches!(
+        e.detail(),
+        ErrorDetail::WebSocket(_)
+            | ErrorDetail::WebSocketTimeout(_)
+            | ErrorDetail::WebSocketClosed(_)
+    )
+}
+
 #[cfg(test)]
 mod test {
     use alloc::collections::BTreeMap as HashMap;
@@ -1038,6 +1321,7 @@ mod test {
         driver_hdl: JoinHandle<Result<(), Error>>,
         terminate_tx: ChannelTx<Result<(), Error>>,
         event_tx: ChannelTx<Event>,
+        disconnect_tx: ChannelTx<()>,
     }
 
     // A setting telling which of the CometBFT server versions to emulate
@@ -1060,13 +1344,16 @@ mod test {
             };
             let (terminate_tx, terminate_rx) = unbounded();
             let (event_tx, event_rx) = unbounded();
-            let driver = TestServerDriver::new(listener, version, event_rx, terminate_rx);
+            let (disconnect_tx, disconnect_rx) = unbounded();
+            let driver =
+                TestServerDriver::new(listener, version, event_rx, terminate_rx, disconnect_rx);
             let driver_hdl = tokio::spawn(async move { driver.run().await });
             Self {
                 node_addr,
                 driver_hdl,
                 terminate_tx,
                 event_tx,
+                disconnect_tx,
             }
         }
 
@@ -1074,6 +1361,11 @@ mod test {
             self.event_tx.send(ev)
         }
 
+        // Closes all the current connections, while still accepting new ones.
+        fn disconnect_all(&mut self) -> Result<(), Error> {
+            self.disconnect_tx.send(())
+        }
+
         async fn terminate(self) -> Result<(), Error> {
             self.terminate_tx.send(Ok(())).unwrap();
             self.driver_hdl.await.unwrap()
@@ -1086,6 +1378,7 @@ mod test {
         version: TestRpcVersion,
         event_rx: ChannelRx<Event>,
         terminate_rx: ChannelRx<Result<(), Error>>,
+        disconnect_rx: ChannelRx<()>,
         handlers: Vec<TestServerHandler>,
     }
 
@@ -1095,12 +1388,14 @@ mod test {
             version: TestRpcVersion,
             event_rx: ChannelRx<Event>,
             terminate_rx: ChannelRx<Result<(), Error>>,
+            disconnect_rx: ChannelRx<()>,
         ) -> Self {
             Self {
                 listener,
                 version,
                 event_rx,
                 terminate_rx,
+                disconnect_rx,
                 handlers: Vec::new(),
             }
         }
@@ -1113,6 +1408,7 @@ mod test {
                         let (stream, _) = res.unwrap();
                         self.handle_incoming(stream).await
                     }
+                    Some(()) = self.disconnect_rx.recv() => self.terminate().await,
                     Some(res) = self.terminate_rx.recv() => {
                         self.terminate().await;
                         return res;
@@ -1482,6 +1778,50 @@ mod test {
                 );
             }
         }
+
+        #[tokio::test]
+        async fn websocket_client_reconnects() {
+            let event1 = read_event("subscribe_newblock_0").await;
+            let event2 = read_event("subscribe_newblock_1").await;
+
+            let mut server = TestServer::new("127.0.0.1:0", TestRpcVersion::V0_37).await;
+            let url = server.node_addr.clone().try_into().unwrap();
+            let (client, driver) = WebSocketClient::builder(url)
+                .compat_mode(CompatMode::V0_37)
+                .reconnect(ReconnectPolicy {
+                    initial_delay: Duration::from_millis(10),
+                    max_delay: Duration::from_millis(100),
+                    max_attempts: 5,
+                })
+                .build()
+                .await
+                .unwrap();
+            let driver_handle = tokio::spawn(async move { driver.run().await });
+
+            let mut subs = client.subscribe(EventType::NewBlock.into()).await.unwrap();
+            server.publish_event(event1.clone()).unwrap();
+            assert_eq!(event1, subs.next().await.unwrap().unwrap());
+
+            println!("Closing the connection from the server...");
+            server.disconnect_all().unwrap();
+
+            // The subscription is told that events may have been missed
+            let gap = subs.next().await.unwrap().unwrap_err();
+            assert!(matches!(
+                gap.detail(),
+                ErrorDetail::WebSocketReconnected(e) if e.attempts == 1
+            ));
+
+            // Once the server has answered another subscription request over the
+            // new connection, it knows about the subscription issued again
+            client.subscribe(EventType::Tx.into()).await.unwrap();
+            server.publish_event(event2.clone()).unwrap();
+            assert_eq!(event2, subs.next().await.unwrap().unwrap());
+
+            client.close().unwrap();
+            server.terminate().await.unwrap();
+            let _ = driver_handle.await.unwrap();
+        }
     }
 
     mod v0_38 {
@@ -1506,7 +1846,7 @@ mod test {
             println!("Creating client RPC WebSocket connection...");
             let url = server.node_addr.clone().try_into().unwrap();
             let (client, driver) = WebSocketClient::builder(url)
-                .compat_mode(CompatMode::V0_37)
+                .compat_mode(CompatMode::V0_38)
                 .build()
                 .await
                 .unwrap();
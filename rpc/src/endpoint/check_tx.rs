@@ -0,0 +1,165 @@
+//! `/check_tx`: checks a transaction against the mempool of the node, without
+//! adding it to the mempool nor broadcasting it.
+
+use serde::{Deserialize, Serialize};
+
+use tendermint::abci;
+
+use crate::dialect::{self, Dialect};
+use crate::{prelude::*, request::RequestMessage, serializers};
+
+/// `/check_tx`: checks a transaction against the mempool of the node, without
+/// adding it to the mempool nor broadcasting it.
+///
+/// If `CheckTx` fails, no error will be returned, but the returned result will
+/// contain a non-OK ABCI code.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Request {
+    /// Transaction to check
+    #[serde(with = "serializers::bytes::base64string")]
+    pub tx: Vec<u8>,
+}
+
+impl Request {
+    /// Create a new request to check a transaction
+    pub fn new(tx: impl Into<Vec<u8>>) -> Request {
+        Request { tx: tx.into() }
+    }
+}
+
+impl RequestMessage for Request {
+    fn method(&self) -> crate::Method {
+        crate::Method::CheckTx
+    }
+}
+
+impl crate::Request<dialect::v0_34::Dialect> for Request {
+    type Response = self::v0_34::DialectResponse;
+}
+
+impl crate::Request<dialect::v0_37::Dialect> for Request {
+    type Response = Response;
+}
+
+impl<S: Dialect> crate::SimpleRequest<S> for Request
+where
+    Self: crate::Request<S>,
+    Response: From<Self::Response>,
+{
+    type Output = Response;
+}
+
+/// Response from `/check_tx`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Response {
+    /// `CheckTx` result
+    #[serde(flatten)]
+    pub check_tx: abci::response::CheckTx,
+}
+
+impl crate::Response for Response {}
+
+/// Serialization for /check_tx endpoint format in Tendermint 0.34
+pub mod v0_34 {
+    use super::Response;
+    use crate::dialect;
+    use crate::dialect::v0_34::Event;
+    use serde::{Deserialize, Serialize};
+
+    /// RPC dialect helper for serialization of the response.
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct DialectResponse {
+        /// `CheckTx` result
+        #[serde(flatten)]
+        pub check_tx: dialect::CheckTx<Event>,
+    }
+
+    impl crate::Response for DialectResponse {}
+
+    impl From<DialectResponse> for Response {
+        fn from(msg: DialectResponse) -> Self {
+            Self {
+                check_tx: msg.check_tx.into(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Response as _;
+
+    #[test]
+    fn decodes_v0_34_response() {
+        let json = r#"{
+          "jsonrpc": "2.0",
+          "id": "",
+          "result": {
+            "code": 5,
+            "codespace": "sdk",
+            "data": null,
+            "events": [
+              {
+                "type": "app",
+                "attributes": [
+                  { "key": "Y3JlYXRvcg==", "value": "Q29zbW9zaGkgTmV0b3dva28=", "index": true }
+                ]
+              }
+            ],
+            "gas_used": "3",
+            "gas_wanted": "10",
+            "info": "",
+            "log": "insufficient fee",
+            "mempoolError": "",
+            "priority": "0",
+            "sender": ""
+          }
+        }"#;
+
+        let response: Response = v0_34::DialectResponse::from_string(json).unwrap().into();
+        check_response(response.check_tx);
+    }
+
+    #[test]
+    fn decodes_v0_37_response() {
+        let json = r#"{
+          "jsonrpc": "2.0",
+          "id": "",
+          "result": {
+            "code": 5,
+            "codespace": "sdk",
+            "data": null,
+            "events": [
+              {
+                "type": "app",
+                "attributes": [
+                  { "key": "creator", "value": "Cosmoshi Netowoko", "index": true }
+                ]
+              }
+            ],
+            "gas_used": "3",
+            "gas_wanted": "10",
+            "info": "",
+            "log": "insufficient fee"
+          }
+        }"#;
+
+        let response = Response::from_string(json).unwrap();
+        check_response(response.check_tx);
+    }
+
+    fn check_response(check_tx: abci::response::CheckTx) {
+        assert_eq!(check_tx.code.value(), 5);
+        assert_eq!(check_tx.codespace, "sdk");
+        assert_eq!(check_tx.log, "insufficient fee");
+        assert_eq!(check_tx.gas_wanted, 10);
+        assert_eq!(check_tx.gas_used, 3);
+
+        let event = &check_tx.events[0];
+        assert_eq!(event.kind, "app");
+        assert_eq!(event.attributes[0].key, "creator");
+        assert_eq!(event.attributes[0].value, "Cosmoshi Netowoko");
+        assert!(event.attributes[0].index);
+    }
+}
@@ -130,9 +130,176 @@ pub struct RoundVotes {
     // type).
     pub round: u32,
     pub prevotes: Vec<RoundVote>,
-    pub prevotes_bit_array: String,
+    pub prevotes_bit_array: VoteBitArray,
     pub precommits: Vec<RoundVote>,
-    pub precommits_bit_array: String,
+    pub precommits_bit_array: VoteBitArray,
+}
+
+/// An array of bits, one per validator, as rendered by a Tendermint node: `x`
+/// for a set bit, and `_` for an unset one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BitArray(Vec<bool>);
+
+impl BitArray {
+    /// Create a bit array from the given bits.
+    pub fn new(bits: Vec<bool>) -> Self {
+        Self(bits)
+    }
+
+    /// The number of bits of the array.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether the array holds no bits.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The bit at the given index, if any.
+    pub fn get(&self, index: usize) -> Option<bool> {
+        self.0.get(index).copied()
+    }
+
+    /// The number of set bits of the array.
+    pub fn count_ones(&self) -> usize {
+        self.0.iter().filter(|bit| **bit).count()
+    }
+
+    /// The bits of the array.
+    pub fn bits(&self) -> &[bool] {
+        &self.0
+    }
+}
+
+impl FromStr for BitArray {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.chars()
+            .map(|c| match c {
+                'x' => Ok(true),
+                '_' => Ok(false),
+                _ => Err(Error::client_internal(format!(
+                    "invalid character in bit array: {c}"
+                ))),
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl fmt::Display for BitArray {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for bit in &self.0 {
+            f.write_str(if *bit { "x" } else { "_" })?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for BitArray {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for BitArray {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// The validators which cast a vote of a particular type in a consensus
+/// round, along with the voting power they hold, as summarized by a
+/// Tendermint node, e.g. `BA{4:xx_x} 30/40 = 0.75`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoteBitArray {
+    /// Whether each validator voted, by validator index
+    pub bits: BitArray,
+    /// Voting power of the validators which voted
+    pub voted_power: i64,
+    /// Total voting power of the validators
+    pub total_power: i64,
+}
+
+impl VoteBitArray {
+    /// The ratio of the total voting power held by the validators which
+    /// voted.
+    pub fn voted_ratio(&self) -> f64 {
+        self.voted_power as f64 / self.total_power as f64
+    }
+}
+
+impl FromStr for VoteBitArray {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            Error::client_internal(format!(
+                "invalid format for consensus state vote bit array: {s}"
+            ))
+        };
+
+        let (bit_array, power) = s
+            .strip_prefix("BA{")
+            .and_then(|s| s.split_once("} "))
+            .ok_or_else(invalid)?;
+        let (len, bits) = bit_array.split_once(':').ok_or_else(invalid)?;
+        let (power, _ratio) = power.split_once(" = ").ok_or_else(invalid)?;
+        let (voted_power, total_power) = power.split_once('/').ok_or_else(invalid)?;
+
+        let bits = BitArray::from_str(bits)?;
+        if usize::from_str(len).map_err(|_| invalid())? != bits.len() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            bits,
+            voted_power: i64::from_str(voted_power).map_err(|_| invalid())?,
+            total_power: i64::from_str(total_power).map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl fmt::Display for VoteBitArray {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "BA{{{}:{}}} {}/{} = {:.2}",
+            self.bits.len(),
+            self.bits,
+            self.voted_power,
+            self.total_power,
+            self.voted_ratio(),
+        )
+    }
+}
+
+impl Serialize for VoteBitArray {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for VoteBitArray {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(serde::de::Error::custom)
+    }
 }
 
 /// Details of a single vote from a particular consensus round.
@@ -397,4 +564,34 @@ mod test {
             assert_eq!(expected.clone(), actual);
         }
     }
+
+    #[test]
+    fn vote_bit_array_round_trip() {
+        for s in [
+            "BA{4:xx_x} 30/40 = 0.75",
+            "BA{1:_} 0/10 = 0.00",
+            "BA{0:} 0/10 = 0.00",
+        ] {
+            let bit_array = VoteBitArray::from_str(s).unwrap();
+            assert_eq!(bit_array.to_string(), s);
+        }
+
+        let bit_array = VoteBitArray::from_str("BA{4:xx_x} 30/40 = 0.75").unwrap();
+        assert_eq!(bit_array.bits, BitArray::new(vec![true, true, false, true]));
+        assert_eq!(bit_array.bits.count_ones(), 3);
+        assert_eq!(bit_array.voted_power, 30);
+        assert_eq!(bit_array.total_power, 40);
+    }
+
+    #[test]
+    fn invalid_vote_bit_arrays() {
+        for s in [
+            "",
+            "BA{4:xx_x}",
+            "BA{3:xx_x} 30/40 = 0.75",
+            "BA{4:xxox} 30/40 = 0.75",
+        ] {
+            assert!(VoteBitArray::from_str(s).is_err(), "{}", s);
+        }
+    }
 }
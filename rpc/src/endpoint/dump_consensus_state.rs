@@ -0,0 +1,296 @@
+//! `/dump_consensus_state` endpoint JSON-RPC wrapper
+
+use alloc::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use tendermint::{block, validator, Time};
+
+use super::consensus_state::{BitArray, RoundVote, RoundVotes, VoteBitArray};
+use crate::{dialect::Dialect, prelude::*, request::RequestMessage, serializers, Method};
+
+/// Get the full consensus state of the node, along with the consensus state of
+/// each of its peers, as far as the node knows it.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Request;
+
+impl Request {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl RequestMessage for Request {
+    fn method(&self) -> Method {
+        Method::DumpConsensusState
+    }
+}
+
+impl<S: Dialect> crate::Request<S> for Request {
+    type Response = Response;
+}
+
+impl<S: Dialect> crate::SimpleRequest<S> for Request {
+    type Output = Response;
+}
+
+/// The full consensus state of the node, and that of its peers (UNSTABLE).
+///
+/// Currently based on <https://github.com/cometbft/cometbft/blob/v0.38.0/rpc/core/types/responses.go#L192>
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Response {
+    pub round_state: RoundState,
+    pub peers: Vec<PeerState>,
+}
+
+impl crate::Response for Response {}
+
+/// The full state of the current consensus round of the node.
+///
+/// The proposal, and the proposed, locked and valid blocks along with their
+/// parts, are not exposed.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RoundState {
+    pub height: block::Height,
+
+    // Rounds and steps are serialized as integers and not strings (unlike that
+    // which is expected from the `Round` type), and rounds may be -1.
+    pub round: i32,
+    pub step: u8,
+
+    #[serde(with = "tendermint::serializers::time")]
+    pub start_time: Time,
+
+    #[serde(with = "tendermint::serializers::time")]
+    pub commit_time: Time,
+
+    pub validators: validator::Set,
+
+    pub locked_round: i32,
+
+    pub valid_round: i32,
+
+    pub votes: Vec<RoundVotes>,
+
+    pub commit_round: i32,
+
+    pub last_commit: Option<VoteSet>,
+
+    pub last_validators: validator::Set,
+
+    pub triggered_timeout_precommit: bool,
+}
+
+/// The votes of a particular type received for a consensus round.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct VoteSet {
+    pub votes: Vec<RoundVote>,
+    pub votes_bit_array: VoteBitArray,
+    /// The block for which each peer claims to have seen a +2/3 majority, by
+    /// peer ID
+    pub peer_maj_23s: BTreeMap<String, block::Id>,
+}
+
+/// The consensus state of a peer of the node.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PeerState {
+    pub node_address: String,
+    pub peer_state: PeerStateInfo,
+}
+
+/// The consensus state of a peer, as known to the node.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PeerStateInfo {
+    pub round_state: PeerRoundState,
+    pub stats: PeerStats,
+}
+
+/// The state of the consensus round of a peer, as known to the node.
+///
+/// The bit arrays hold a bit for each block part or validator, set if the
+/// peer is known to have the corresponding part or vote.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PeerRoundState {
+    pub height: block::Height,
+
+    pub round: i32,
+    pub step: u8,
+
+    #[serde(with = "tendermint::serializers::time")]
+    pub start_time: Time,
+
+    /// Whether the peer has the proposal of the round
+    pub proposal: bool,
+    pub proposal_block_part_set_header: block::parts::Header,
+    pub proposal_block_parts: Option<BitArray>,
+
+    /// The proof-of-lock round of the proposal
+    pub proposal_pol_round: i32,
+    pub proposal_pol: Option<BitArray>,
+
+    pub prevotes: Option<BitArray>,
+    pub precommits: Option<BitArray>,
+
+    pub last_commit_round: i32,
+    pub last_commit: Option<BitArray>,
+
+    pub catchup_commit_round: i32,
+    pub catchup_commit: Option<BitArray>,
+}
+
+/// The number of useful messages the node received from a peer.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PeerStats {
+    #[serde(with = "serializers::from_str")]
+    pub votes: u64,
+    #[serde(with = "serializers::from_str")]
+    pub block_parts: u64,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Response as _;
+
+    #[test]
+    fn decodes_response() {
+        let json = r#"{
+          "jsonrpc": "2.0",
+          "id": "",
+          "result": {
+            "round_state": {
+              "height": "12",
+              "round": 0,
+              "step": 3,
+              "start_time": "2023-06-26T13:05:41.146254109Z",
+              "commit_time": "2023-06-26T13:05:40.146254109Z",
+              "validators": {
+                "validators": [
+                  {
+                    "address": "2DD9F44FD9067555C322243C3C913BA7B51D2BE0",
+                    "pub_key": {
+                      "type": "tendermint/PubKeyEd25519",
+                      "value": "bNNlGls5R25wC3Sd8720F/3+7IZBhXcD22MNFtPk/v0="
+                    },
+                    "voting_power": "10",
+                    "proposer_priority": "0"
+                  }
+                ],
+                "proposer": {
+                  "address": "2DD9F44FD9067555C322243C3C913BA7B51D2BE0",
+                  "pub_key": {
+                    "type": "tendermint/PubKeyEd25519",
+                    "value": "bNNlGls5R25wC3Sd8720F/3+7IZBhXcD22MNFtPk/v0="
+                  },
+                  "voting_power": "10",
+                  "proposer_priority": "0"
+                }
+              },
+              "proposal": null,
+              "proposal_block": null,
+              "proposal_block_parts": null,
+              "locked_round": -1,
+              "locked_block": null,
+              "locked_block_parts": null,
+              "valid_round": -1,
+              "valid_block": null,
+              "valid_block_parts": null,
+              "votes": [
+                {
+                  "round": 0,
+                  "prevotes": ["nil-Vote"],
+                  "prevotes_bit_array": "BA{1:_} 0/10 = 0.00",
+                  "precommits": ["nil-Vote"],
+                  "precommits_bit_array": "BA{1:_} 0/10 = 0.00"
+                }
+              ],
+              "commit_round": -1,
+              "last_commit": {
+                "votes": [
+                  "Vote{0:2DD9F44FD906 11/00/2(Precommit) 1D4C55789D1A 8A0A2E4DB9FF @ 2023-06-26T13:05:40.146254109Z}"
+                ],
+                "votes_bit_array": "BA{1:x} 10/10 = 1.00",
+                "peer_maj_23s": {
+                  "f8b0c790d4e6d7a5d7dd3ad906dca771e6c1f0fb": {
+                    "hash": "1D4C55789D1A088E66C84ADF163B7A0B7C5B0861A738F88EE3C01BD9FE9A8E3B",
+                    "parts": {
+                      "total": 1,
+                      "hash": "62A8F1FACA1A8C7DC18A4B8FE1ACD0E9929E5D0DAB1EF85DE5F7FB7FC4079D8E"
+                    }
+                  }
+                }
+              },
+              "last_validators": {
+                "validators": [
+                  {
+                    "address": "2DD9F44FD9067555C322243C3C913BA7B51D2BE0",
+                    "pub_key": {
+                      "type": "tendermint/PubKeyEd25519",
+                      "value": "bNNlGls5R25wC3Sd8720F/3+7IZBhXcD22MNFtPk/v0="
+                    },
+                    "voting_power": "10",
+                    "proposer_priority": "0"
+                  }
+                ],
+                "proposer": null
+              },
+              "triggered_timeout_precommit": false
+            },
+            "peers": [
+              {
+                "node_address": "f8b0c790d4e6d7a5d7dd3ad906dca771e6c1f0fb@172.19.0.3:26656",
+                "peer_state": {
+                  "round_state": {
+                    "height": "12",
+                    "round": 0,
+                    "step": 3,
+                    "start_time": "2023-06-26T13:05:41.14658507Z",
+                    "proposal": true,
+                    "proposal_block_part_set_header": {
+                      "total": 1,
+                      "hash": "62A8F1FACA1A8C7DC18A4B8FE1ACD0E9929E5D0DAB1EF85DE5F7FB7FC4079D8E"
+                    },
+                    "proposal_block_parts": "x",
+                    "proposal_pol_round": -1,
+                    "proposal_pol": "_",
+                    "prevotes": "_",
+                    "precommits": null,
+                    "last_commit_round": 0,
+                    "last_commit": "x",
+                    "catchup_commit_round": -1,
+                    "catchup_commit": null
+                  },
+                  "stats": {
+                    "votes": "23",
+                    "block_parts": "11"
+                  }
+                }
+              }
+            ]
+          }
+        }"#;
+
+        let response = Response::from_string(json).unwrap();
+
+        let round_state = response.round_state;
+        assert_eq!(round_state.height.value(), 12);
+        assert_eq!(round_state.step, 3);
+        assert_eq!(round_state.locked_round, -1);
+        assert_eq!(round_state.validators.validators().len(), 1);
+        assert_eq!(round_state.votes[0].prevotes_bit_array.total_power, 10);
+
+        let last_commit = round_state.last_commit.unwrap();
+        assert_eq!(last_commit.votes_bit_array.bits.count_ones(), 1);
+        assert_eq!(last_commit.peer_maj_23s.len(), 1);
+
+        let peer = &response.peers[0].peer_state;
+        assert!(peer.round_state.proposal);
+        assert_eq!(
+            peer.round_state.proposal_block_parts,
+            Some(BitArray::new(vec![true]))
+        );
+        assert_eq!(peer.round_state.prevotes, Some(BitArray::new(vec![false])));
+        assert_eq!(peer.round_state.precommits, None);
+        assert_eq!(peer.stats.votes, 23);
+        assert_eq!(peer.stats.block_parts, 11);
+    }
+}
@@ -44,3 +44,76 @@ pub struct Response {
 }
 
 impl crate::Response for Response {}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, path::PathBuf};
+
+    use tendermint::evidence::Evidence;
+
+    use super::*;
+    use crate::{
+        dialect::{v0_34, v0_37, v0_38},
+        endpoint::block_search,
+        prelude::*,
+        Response as _,
+    };
+
+    fn duplicate_vote_evidence() -> Evidence {
+        let content = fs::read_to_string(PathBuf::from(
+            "./tests/kvstore_fixtures/v0_34/incoming/block_search_evidence.json",
+        ))
+        .unwrap();
+        let result = block_search::Response::from_string(content).unwrap();
+        result.blocks[0]
+            .block
+            .evidence
+            .iter()
+            .next()
+            .unwrap()
+            .clone()
+    }
+
+    fn check_request<S: Dialect>(evidence: Evidence) {
+        let request = Request::<S>::new(evidence);
+        let json: serde_json::Value =
+            serde_json::from_str(&RequestMessage::into_json(request)).unwrap();
+
+        assert_eq!(json["method"], "broadcast_evidence");
+        assert_eq!(
+            json["params"]["evidence"]["type"],
+            "tendermint/DuplicateVoteEvidence"
+        );
+        assert_eq!(
+            json["params"]["evidence"]["value"]["vote_a"]["height"],
+            "8009"
+        );
+    }
+
+    #[test]
+    fn serializes_evidence_of_all_dialects() {
+        let evidence = duplicate_vote_evidence();
+
+        check_request::<v0_34::Dialect>(evidence.clone());
+        check_request::<v0_37::Dialect>(evidence.clone());
+        check_request::<v0_38::Dialect>(evidence);
+    }
+
+    #[test]
+    fn deserializes_response() {
+        let response = Response::from_string(
+            r#"{
+              "jsonrpc": "2.0",
+              "id": "",
+              "result": {
+                "hash": "RbNhM3nA1qEFvRisJ2Dh9nEKnLwiRANHYwtKQyaPpWI="
+              }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            response.hash.to_string(),
+            "45B3613379C0D6A105BD18AC2760E1F6710A9CBC22440347630B4A43268FA562"
+        );
+    }
+}
@@ -0,0 +1,70 @@
+//! `/genesis_chunked` endpoint JSON-RPC wrapper
+
+use serde::{Deserialize, Serialize};
+
+use crate::{dialect::Dialect, prelude::*, request::RequestMessage, serializers, Method};
+
+/// Get a chunk of the genesis file of the current chain.
+///
+/// Nodes only serve the genesis file in chunks when it is too large to be
+/// served at once by `/genesis`.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Request {
+    /// Index of the chunk to request, starting at 0.
+    #[serde(with = "serializers::from_str")]
+    pub chunk: u64,
+}
+
+impl Request {
+    /// Create a new request for the chunk of the genesis file with the given
+    /// index.
+    pub fn new(chunk: u64) -> Self {
+        Self { chunk }
+    }
+}
+
+impl RequestMessage for Request {
+    fn method(&self) -> Method {
+        Method::GenesisChunked
+    }
+}
+
+impl<S: Dialect> crate::Request<S> for Request {
+    type Response = Response;
+}
+
+impl<S: Dialect> crate::SimpleRequest<S> for Request {
+    type Output = Response;
+}
+
+/// Genesis chunk response
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Response {
+    /// Index of the chunk
+    #[serde(with = "serializers::from_str")]
+    pub chunk: u64,
+
+    /// Total number of chunks of the genesis file
+    #[serde(with = "serializers::from_str")]
+    pub total: u64,
+
+    /// Bytes of the genesis file held by the chunk
+    #[serde(with = "serializers::bytes::base64string")]
+    pub data: Vec<u8>,
+}
+
+impl crate::Response for Response {}
+
+/// The progress of the assembly of a genesis file from its chunks, as
+/// reported by [`Client::genesis_from_chunks`].
+///
+/// [`Client::genesis_from_chunks`]: crate::Client::genesis_from_chunks
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Progress {
+    /// Number of chunks fetched so far
+    pub chunks: u64,
+    /// Total number of chunks of the genesis file
+    pub total: u64,
+    /// Number of bytes of the genesis file fetched so far
+    pub bytes: usize,
+}
@@ -0,0 +1,42 @@
+//! `/num_unconfirmed_txs` endpoint JSON-RPC wrapper
+
+use serde::{Deserialize, Serialize};
+
+use crate::{dialect::Dialect, request::RequestMessage, serializers, Method};
+
+/// Get the number and the total size of the transactions in the mempool of
+/// the node
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Request;
+
+impl RequestMessage for Request {
+    fn method(&self) -> Method {
+        Method::NumUnconfirmedTxs
+    }
+}
+
+impl<S: Dialect> crate::Request<S> for Request {
+    type Response = Response;
+}
+
+impl<S: Dialect> crate::SimpleRequest<S> for Request {
+    type Output = Response;
+}
+
+/// Number of unconfirmed transactions response
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Response {
+    /// Number of transactions in the mempool
+    #[serde(with = "serializers::from_str")]
+    pub n_txs: u64,
+
+    /// Total number of transactions in the mempool
+    #[serde(with = "serializers::from_str")]
+    pub total: u64,
+
+    /// Total size of the transactions in the mempool, in bytes
+    #[serde(with = "serializers::from_str")]
+    pub total_bytes: u64,
+}
+
+impl crate::Response for Response {}
@@ -12,6 +12,12 @@ type ReqwestError = flex_error::DisplayOnly<reqwest::Error>;
 #[cfg(not(feature = "reqwest"))]
 type ReqwestError = flex_error::NoSource;
 
+#[cfg(feature = "hyper")]
+type HyperError = flex_error::DisplayOnly<hyper::Error>;
+
+#[cfg(not(feature = "hyper"))]
+type HyperError = flex_error::NoSource;
+
 #[cfg(feature = "reqwest")]
 type HttpStatusCode = reqwest::StatusCode;
 
@@ -30,6 +36,18 @@ type TungsteniteError = flex_error::DisplayOnly<async_tungstenite::tungstenite::
 #[cfg(not(feature = "async-tungstenite"))]
 type TungsteniteError = flex_error::NoSource;
 
+#[cfg(feature = "tonic")]
+type GrpcTransportError = flex_error::DisplayOnly<tonic::transport::Error>;
+
+#[cfg(not(feature = "tonic"))]
+type GrpcTransportError = flex_error::NoSource;
+
+#[cfg(feature = "tonic")]
+type GrpcStatus = flex_error::DisplayOnly<tonic::Status>;
+
+#[cfg(not(feature = "tonic"))]
+type GrpcStatus = flex_error::NoSource;
+
 define_error! {
     #[derive(Debug, Clone, PartialEq, Eq)]
     Error {
@@ -45,10 +63,38 @@ define_error! {
             [ ReqwestError ]
             | _ | { "HTTP error" },
 
+        UnixSocket
+            [ HyperError ]
+            | _ | { "HTTP error over a Unix domain socket" },
+
+        Decompression
+            {
+                reason: String
+            }
+            | e | {
+                format_args!("failed to decompress the response: {}", e.reason)
+            },
+
         InvalidProxy
             [ ReqwestError ]
             | _ | { "Invalid proxy configuration" },
 
+        Proxy
+            {
+                reason: String
+            }
+            | e | {
+                format_args!("proxy error: {}", e.reason)
+            },
+
+        Tls
+            {
+                reason: String
+            }
+            | e | {
+                format_args!("TLS configuration error: {}", e.reason)
+            },
+
         InvalidParams
             {
                 message: String
@@ -66,6 +112,14 @@ define_error! {
                 format_args!("web socket error: {}", e.message)
             },
 
+        RequestTimeout
+            {
+                duration: Duration
+            }
+            | e | {
+                format_args!("request timed out after {}ms", e.duration.as_millis())
+            },
+
         WebSocketTimeout
             {
                 timeout: Duration
@@ -75,6 +129,18 @@ define_error! {
                     e.timeout.as_secs())
             },
 
+        WebSocketClosed
+            | _ | { "WebSocket connection closed by the server" },
+
+        WebSocketReconnected
+            {
+                attempts: u32
+            }
+            | e | {
+                format_args!("WebSocket connection re-established after {} attempts, events may have been missed",
+                    e.attempts)
+            },
+
         MethodNotFound
             {
                 method: String
@@ -176,6 +242,14 @@ define_error! {
             [ TungsteniteError ]
             | _ | { "tungstenite error" },
 
+        GrpcTransport
+            [ GrpcTransportError ]
+            | _ | { "gRPC transport error" },
+
+        Grpc
+            [ GrpcStatus ]
+            | _ | { "gRPC request failed" },
+
         Join
             [ JoinError ]
             | _ | { "join error" },
@@ -183,6 +257,14 @@ define_error! {
         MalformedJson
             | _ | { "server returned malformatted JSON (no 'result' or 'error')" },
 
+        MissingBatchResponse
+            {
+                index: usize
+            }
+            | e | {
+                format_args!("server returned no response to request #{} of the batch", e.index)
+            },
+
         UnsupportedScheme
             {
                 scheme: String
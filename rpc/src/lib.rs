@@ -14,6 +14,8 @@
 //! * `websocket-client` - Provides [`WebSocketClient`], which provides full client functionality,
 //!   including general RPC functionality as well as [`event::Event`] subscription functionality.
 //!   Can be used over secure (`wss://`) and unsecure (`ws://`) connections.
+//! * `grpc-client` - Provides [`GrpcClient`], which fetches blocks, block results and the
+//!   version of remote CometBFT 1.x nodes from their **gRPC services**, where they are enabled.
 //!
 //! ### Mock Clients
 //!
@@ -34,15 +36,22 @@ mod prelude;
 
 pub mod client;
 
+#[cfg(feature = "grpc-client")]
+pub use client::GrpcClient;
+#[cfg(feature = "http-client")]
+pub use client::{
+    Batch, BatchItem, BatchResponses, CacheConfig, HttpClient, HttpClientUrl, RetryPolicy,
+};
 #[cfg(any(feature = "http-client", feature = "websocket-client"))]
 pub use client::{
-    Client, MockClient, MockRequestMatcher, MockRequestMethodMatcher, Subscription,
-    SubscriptionClient,
+    Client, MockClient, MockRequest, MockRequestMatcher, MockRequestMethodMatcher,
+    MockRequestScriptMatcher, MockResponse, Proxy, ProxyScheme, Subscription, SubscriptionClient,
+    TimeoutExt, TlsConfig, WithTimeout,
 };
-#[cfg(feature = "http-client")]
-pub use client::{HttpClient, HttpClientUrl};
 #[cfg(feature = "websocket-client")]
-pub use client::{WebSocketClient, WebSocketClientDriver, WebSocketClientUrl, WebSocketConfig};
+pub use client::{
+    ReconnectPolicy, WebSocketClient, WebSocketClientDriver, WebSocketClientUrl, WebSocketConfig,
+};
 
 pub mod dialect;
 pub mod endpoint;
@@ -65,7 +74,7 @@ pub use error::Error;
 pub use id::Id;
 pub use method::Method;
 pub use order::Order;
-pub use paging::{PageNumber, Paging, PerPage};
+pub use paging::{PageNumber, PageStreamOptions, Paging, PerPage};
 pub use request::{Request, SimpleRequest};
 pub use response::Response;
 pub use response_error::{Code, ResponseError};
@@ -44,6 +44,9 @@ pub enum Method {
     /// Broadcast transaction commit
     BroadcastTxCommit,
 
+    /// Check a transaction without broadcasting it
+    CheckTx,
+
     /// Get commit info for a block
     Commit,
 
@@ -53,9 +56,15 @@ pub enum Method {
     /// Get consensus state
     ConsensusState,
 
+    /// Get the full consensus state, along with that of the peers
+    DumpConsensusState,
+
     /// Get genesis file
     Genesis,
 
+    /// Get a chunk of the genesis file
+    GenesisChunked,
+
     /// Get block header
     Header,
 
@@ -68,6 +77,9 @@ pub enum Method {
     /// Get network info
     NetInfo,
 
+    /// Get the number of unconfirmed transactions
+    NumUnconfirmedTxs,
+
     /// Get node status
     Status,
 
@@ -77,6 +89,9 @@ pub enum Method {
     /// Search for transactions with their results
     TxSearch,
 
+    /// List unconfirmed transactions
+    UnconfirmedTxs,
+
     /// Get validator info for a block
     Validators,
 
@@ -105,22 +120,44 @@ impl Method {
             Method::BroadcastTxAsync => "broadcast_tx_async",
             Method::BroadcastTxSync => "broadcast_tx_sync",
             Method::BroadcastTxCommit => "broadcast_tx_commit",
+            Method::CheckTx => "check_tx",
             Method::Commit => "commit",
             Method::ConsensusParams => "consensus_params",
             Method::ConsensusState => "consensus_state",
+            Method::DumpConsensusState => "dump_consensus_state",
             Method::Genesis => "genesis",
+            Method::GenesisChunked => "genesis_chunked",
             Method::Header => "header",
             Method::HeaderByHash => "header_by_hash",
             Method::Health => "health",
             Method::NetInfo => "net_info",
+            Method::NumUnconfirmedTxs => "num_unconfirmed_txs",
             Method::Status => "status",
             Method::Subscribe => "subscribe",
             Method::Tx => "tx",
             Method::TxSearch => "tx_search",
+            Method::UnconfirmedTxs => "unconfirmed_txs",
             Method::Unsubscribe => "unsubscribe",
             Method::Validators => "validators",
         }
     }
+
+    /// Whether performing a request with this method more than once has the
+    /// same effect as performing it once, so that it can safely be retried.
+    ///
+    /// Only the methods which broadcast something to the network, or which
+    /// manage subscriptions, are not.
+    pub fn is_idempotent(self) -> bool {
+        !matches!(
+            self,
+            Method::BroadcastEvidence
+                | Method::BroadcastTxAsync
+                | Method::BroadcastTxSync
+                | Method::BroadcastTxCommit
+                | Method::Subscribe
+                | Method::Unsubscribe
+        )
+    }
 }
 
 impl FromStr for Method {
@@ -141,16 +178,21 @@ impl FromStr for Method {
             "broadcast_tx_async" => Method::BroadcastTxAsync,
             "broadcast_tx_sync" => Method::BroadcastTxSync,
             "broadcast_tx_commit" => Method::BroadcastTxCommit,
+            "check_tx" => Method::CheckTx,
             "commit" => Method::Commit,
             "consensus_params" => Method::ConsensusParams,
             "consensus_state" => Method::ConsensusState,
+            "dump_consensus_state" => Method::DumpConsensusState,
             "genesis" => Method::Genesis,
+            "genesis_chunked" => Method::GenesisChunked,
             "health" => Method::Health,
             "net_info" => Method::NetInfo,
+            "num_unconfirmed_txs" => Method::NumUnconfirmedTxs,
             "status" => Method::Status,
             "subscribe" => Method::Subscribe,
             "tx" => Method::Tx,
             "tx_search" => Method::TxSearch,
+            "unconfirmed_txs" => Method::UnconfirmedTxs,
             "unsubscribe" => Method::Unsubscribe,
             "validators" => Method::Validators,
             other => return Err(Error::method_not_found(other.to_string())),
@@ -1,6 +1,6 @@
 //! Pagination-related data structures for the Tendermint RPC.
 
-use core::{convert::TryInto, str::FromStr};
+use core::{convert::TryInto, str::FromStr, time::Duration};
 
 use serde::{Deserialize, Serialize};
 
@@ -22,6 +22,36 @@ pub enum Paging {
     },
 }
 
+/// Options for the streams which walk all the pages of a paginated endpoint,
+/// such as the one returned by `Client::tx_search_all_with`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PageStreamOptions {
+    /// The number of items to fetch per page.
+    pub per_page: u8,
+    /// Maximum number of pages to fetch at once.
+    pub concurrency: usize,
+    /// Minimum delay between the starts of two requests, to keep from
+    /// exceeding the rate limit of a node.
+    pub min_interval: Duration,
+    /// Number of times to fetch a page again after failing to fetch it,
+    /// before the stream yields the error and ends.
+    pub max_retries: u32,
+    /// Delay before fetching a page again after failing to fetch it.
+    pub retry_delay: Duration,
+}
+
+impl Default for PageStreamOptions {
+    fn default() -> Self {
+        Self {
+            per_page: 100,
+            concurrency: 1,
+            min_interval: Duration::ZERO,
+            max_retries: 3,
+            retry_delay: Duration::from_millis(500),
+        }
+    }
+}
+
 /// A page number in paginated RPC responses.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord)]
 pub struct PageNumber(usize);
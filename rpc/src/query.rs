@@ -169,6 +169,11 @@ impl Query {
         self.conditions.push(Condition::exists(key.to_string()));
         self
     }
+
+    /// Start building a query whose keys are checked, with a [`QueryBuilder`].
+    pub fn builder() -> QueryBuilder {
+        QueryBuilder::default()
+    }
 }
 
 impl Default for Query {
@@ -208,6 +213,146 @@ impl fmt::Display for Query {
     }
 }
 
+/// The key of a [`Condition`], checked to be a valid key: an ASCII letter,
+/// followed by ASCII letters, digits, underscores and dots, such as
+/// `transfer.recipient`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Key(String);
+
+impl Key {
+    /// The key of the given attribute of the events of the given type, such as
+    /// `transfer.recipient` for the `recipient` attribute of `transfer` events.
+    pub fn new(event_type: &str, attribute: &str) -> Result<Self, Error> {
+        format!("{event_type}.{attribute}").parse()
+    }
+
+    /// The key as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for Key {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        query_parser::key(s)
+            .map(|key| Self(key.to_owned()))
+            .map_err(|e| Error::invalid_params(format!("invalid query key {s:?}: {e}")))
+    }
+}
+
+impl AsRef<str> for Key {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A builder of [`Query`], checking the key of every condition.
+///
+/// Unlike the constructors of [`Query`], which take keys as they are, the
+/// builder makes sure that the query it builds can be parsed by a node: the
+/// first invalid key is reported by [`QueryBuilder::build`]. Operands are
+/// escaped as they are when displaying any query.
+///
+/// ## Examples
+///
+/// ```rust
+/// use tendermint_rpc::query::{EventType, Query};
+///
+/// let query = Query::builder()
+///     .event_type(EventType::Tx)
+///     .eq("transfer.recipient", "cosmos1 'quoted'")
+///     .gte("tx.height", 100_u64)
+///     .exists("message.action")
+///     .build()
+///     .unwrap();
+/// assert_eq!(
+///     "tm.event = 'Tx' AND transfer.recipient = 'cosmos1 \\'quoted\\'' AND \
+///      tx.height >= 100 AND message.action EXISTS",
+///     query.to_string(),
+/// );
+/// assert_eq!(query, query.to_string().parse().unwrap());
+///
+/// assert!(Query::builder().eq("transfer recipient", "x").build().is_err());
+/// ```
+#[derive(Debug, Default)]
+pub struct QueryBuilder {
+    event_type: Option<EventType>,
+    conditions: Vec<Condition>,
+    error: Option<Error>,
+}
+
+impl QueryBuilder {
+    /// Only match events of the given type.
+    pub fn event_type(mut self, event_type: EventType) -> Self {
+        self.event_type = Some(event_type);
+        self
+    }
+
+    /// Add the condition `<key> = <value>` to the query.
+    pub fn eq(self, key: impl AsRef<str>, value: impl Into<Operand>) -> Self {
+        self.condition(key, Operation::Eq(value.into()))
+    }
+
+    /// Add the condition `<key> < <value>` to the query.
+    pub fn lt(self, key: impl AsRef<str>, value: impl Into<Operand>) -> Self {
+        self.condition(key, Operation::Lt(value.into()))
+    }
+
+    /// Add the condition `<key> <= <value>` to the query.
+    pub fn lte(self, key: impl AsRef<str>, value: impl Into<Operand>) -> Self {
+        self.condition(key, Operation::Lte(value.into()))
+    }
+
+    /// Add the condition `<key> > <value>` to the query.
+    pub fn gt(self, key: impl AsRef<str>, value: impl Into<Operand>) -> Self {
+        self.condition(key, Operation::Gt(value.into()))
+    }
+
+    /// Add the condition `<key> >= <value>` to the query.
+    pub fn gte(self, key: impl AsRef<str>, value: impl Into<Operand>) -> Self {
+        self.condition(key, Operation::Gte(value.into()))
+    }
+
+    /// Add the condition `<key> CONTAINS <value>` to the query.
+    pub fn contains(self, key: impl AsRef<str>, value: impl ToString) -> Self {
+        self.condition(key, Operation::Contains(value.to_string()))
+    }
+
+    /// Add the condition `<key> EXISTS` to the query.
+    pub fn exists(self, key: impl AsRef<str>) -> Self {
+        self.condition(key, Operation::Exists)
+    }
+
+    fn condition(mut self, key: impl AsRef<str>, operation: Operation) -> Self {
+        if self.error.is_none() {
+            match Key::from_str(key.as_ref()) {
+                Ok(key) => self.conditions.push(Condition::new(key.0, operation)),
+                Err(e) => self.error = Some(e),
+            }
+        }
+        self
+    }
+
+    /// Build the query, or fail with the error of the first invalid key.
+    pub fn build(self) -> Result<Query, Error> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(Query {
+                event_type: self.event_type,
+                conditions: self.conditions,
+            }),
+        }
+    }
+}
+
 peg::parser! {
     grammar query_parser() for str {
         // Some or no whitespace.
@@ -216,8 +361,14 @@ peg::parser! {
         // At least some whitespace.
         rule __() = quiet!{[' ']+}
 
-        rule string() -> &'input str
-            = "'" s:$([^'\'']*) "'" { s }
+        // A backslash escapes a single quote or another backslash, as done when
+        // displaying a query, and is taken as is otherwise.
+        rule string_char() -> &'input str
+            = "\\" c:$(['\'' | '\\']) { c }
+            / $([^'\''])
+
+        rule string() -> String
+            = "'" s:string_char()* "'" { s.concat() }
 
         rule unsigned() -> u64
             = s:$(['0'..='9']+) {?
@@ -265,7 +416,7 @@ peg::parser! {
             }
 
         rule string_op() -> Operand
-            = s:string() { Operand::String(s.to_owned()) }
+            = s:string() { Operand::String(s) }
 
         rule unsigned_op() -> Operand
             = u:unsigned() { Operand::Unsigned(u) }
@@ -312,7 +463,7 @@ peg::parser! {
             = t:tag() _ ">" _ op:operand() { Condition::gt(t.to_owned(), op) }
 
         rule contains() -> Condition
-            = t:tag() __ "CONTAINS" __ op:string() { Condition::contains(t.to_owned(), op.to_owned()) }
+            = t:tag() __ "CONTAINS" __ op:string() { Condition::contains(t.to_owned(), op) }
 
         rule exists() -> Condition
             = t:tag() __ "EXISTS" { Condition::exists(t.to_owned()) }
@@ -330,6 +481,9 @@ peg::parser! {
 
         pub rule query() -> Vec<Term>
             = t:term() ** ( __ "AND" __ ) { t }
+
+        pub rule key() -> &'input str
+            = tag()
     }
 }
 
@@ -997,4 +1151,83 @@ mod test {
             }
         );
     }
+
+    #[test]
+    fn escaped_strings_round_trip() {
+        for value in [
+            "'quoted'",
+            "back\\slash",
+            "\\'",
+            "ünïcödé ✓",
+            "C:\\path",
+            "",
+        ] {
+            let query = Query::eq("some.field", value).and_contains("other.field", value);
+            assert_eq!(query, Query::from_str(&query.to_string()).unwrap());
+        }
+
+        // A backslash which escapes nothing is taken as is.
+        let query = Query::from_str("some.field = 'C:\\path'").unwrap();
+        assert_eq!(query, Query::eq("some.field", "C:\\path"));
+    }
+
+    #[test]
+    fn keys() {
+        for key in [
+            "tx.height",
+            "transfer.recipient",
+            "a",
+            "message.module_name2",
+        ] {
+            assert_eq!(Key::from_str(key).unwrap().as_str(), key);
+        }
+        for key in [
+            "",
+            "1tx",
+            ".tx",
+            "tx height",
+            "tx.height'",
+            "tx-height",
+            "tx.héight",
+        ] {
+            assert!(Key::from_str(key).is_err(), "{}", key);
+        }
+
+        assert_eq!(
+            Key::new("transfer", "recipient").unwrap().as_str(),
+            "transfer.recipient"
+        );
+        assert!(Key::new("transfer", "recipient = 'x' OR").is_err());
+    }
+
+    #[test]
+    fn query_builder() {
+        let query = Query::builder()
+            .event_type(EventType::NewBlock)
+            .lt("block.height", 10_u64)
+            .gt("block.height", -10_i64)
+            .gt("block.time", datetime!(2020-09-24 10:17:23.1234 -04:00))
+            .gte("block.date", date!(2020 - 09 - 24))
+            .contains("app.name", "it's")
+            .exists(Key::new("app", "version").unwrap())
+            .build()
+            .unwrap();
+        assert_eq!(
+            "tm.event = 'NewBlock' AND block.height < 10 AND block.height > -10 \
+             AND block.time > TIME 2020-09-24T14:17:23.1234Z \
+             AND block.date >= DATE 2020-09-24 AND app.name CONTAINS 'it\\'s' \
+             AND app.version EXISTS",
+            query.to_string()
+        );
+        assert_eq!(query, Query::from_str(&query.to_string()).unwrap());
+
+        // The first invalid key is reported.
+        let err = Query::builder()
+            .eq("tx.height", 1_u64)
+            .eq("tx height", 2_u64)
+            .eq("tx'height", 3_u64)
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("tx height"));
+    }
 }
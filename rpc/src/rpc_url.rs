@@ -13,6 +13,7 @@ pub enum Scheme {
     Https,
     WebSocket,
     SecureWebSocket,
+    Unix,
 }
 
 impl fmt::Display for Scheme {
@@ -22,6 +23,7 @@ impl fmt::Display for Scheme {
             Scheme::Https => write!(f, "https"),
             Scheme::WebSocket => write!(f, "ws"),
             Scheme::SecureWebSocket => write!(f, "wss"),
+            Scheme::Unix => write!(f, "unix"),
         }
     }
 }
@@ -35,6 +37,7 @@ impl FromStr for Scheme {
             "https" => Scheme::Https,
             "ws" => Scheme::WebSocket,
             "wss" => Scheme::SecureWebSocket,
+            "unix" => Scheme::Unix,
             _ => return Err(Error::unsupported_scheme(s.to_string())),
         })
     }
@@ -69,6 +72,7 @@ impl Url {
             Scheme::Https => true,
             Scheme::WebSocket => false,
             Scheme::SecureWebSocket => true,
+            Scheme::Unix => false,
         }
     }
 
@@ -94,17 +98,20 @@ impl Url {
             .map(|user| format!("{}:{}", user, self.password().unwrap_or_default()))
     }
 
-    /// Get the host associated with this URL.
+    /// Get the host associated with this URL, which is empty for Unix domain
+    /// sockets.
     pub fn host(&self) -> &str {
-        self.inner.host_str().unwrap()
+        self.inner.host_str().unwrap_or_default()
     }
 
-    /// Get the port associated with this URL.
+    /// Get the port associated with this URL, which is 0 for Unix domain
+    /// sockets.
     pub fn port(&self) -> u16 {
-        self.inner.port_or_known_default().unwrap()
+        self.inner.port_or_known_default().unwrap_or_default()
     }
 
-    /// Get this URL's path.
+    /// Get this URL's path, which is the path of the socket for Unix domain
+    /// sockets.
     pub fn path(&self) -> &str {
         self.inner.path()
     }
@@ -134,6 +141,16 @@ impl TryFrom<url::Url> for Url {
     fn try_from(url: url::Url) -> Result<Self, Self::Error> {
         let scheme: Scheme = url.scheme().parse()?;
 
+        if scheme == Scheme::Unix {
+            if url.path().is_empty() || url.path() == "/" {
+                return Err(Error::invalid_params(format!(
+                    "URL is missing the path of its socket: {url}"
+                )));
+            }
+
+            return Ok(Self { inner: url, scheme });
+        }
+
         if url.host_str().is_none() {
             return Err(Error::invalid_params(format!(
                 "URL is missing its host: {url}"
@@ -350,6 +367,17 @@ mod test {
                     username: Some("foo".to_string()),
                     password: Some("bar".to_string()),
                 }
+            ),
+            (
+                "unix:///var/run/cometbft/rpc.sock".to_owned(),
+                ExpectedUrl {
+                    scheme: Scheme::Unix,
+                    host: "".to_string(),
+                    port: 0,
+                    path: "/var/run/cometbft/rpc.sock".to_string(),
+                    username: None,
+                    password: None,
+                }
             )
         ];
     }
@@ -374,4 +402,10 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn unix_socket_without_path() {
+        assert!(Url::from_str("unix://").is_err());
+        assert!(Url::from_str("unix:///").is_err());
+    }
 }
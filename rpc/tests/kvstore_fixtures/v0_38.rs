@@ -120,6 +120,15 @@ fn outgoing_fixtures() {
                     base64::decode("c3luYy1rZXk9dmFsdWU=").unwrap()
                 );
             },
+            "check_tx" => {
+                let wrapped =
+                    serde_json::from_str::<RequestWrapper<endpoint::check_tx::Request>>(&content)
+                        .unwrap();
+                assert_eq!(
+                    wrapped.params().tx,
+                    base64::decode("Y2hlY2sta2V5PXZhbHVl").unwrap()
+                );
+            },
             "commit_at_height_10" => {
                 let wrapped =
                     serde_json::from_str::<RequestWrapper<endpoint::commit::Request>>(&content)
@@ -142,10 +151,30 @@ fn outgoing_fixtures() {
                 RequestWrapper<endpoint::genesis::Request::<serde_json::Value>>,
             >(&content)
             .is_ok()),
+            "header_at_height_10" => {
+                let wrapped =
+                    serde_json::from_str::<RequestWrapper<endpoint::header::Request>>(&content)
+                        .unwrap();
+                assert_eq!(wrapped.params().height.unwrap().value(), 10);
+            },
+            "header_by_hash" => {
+                let wrapped = serde_json::from_str::<
+                    RequestWrapper<endpoint::header_by_hash::Request>,
+                >(&content)
+                .unwrap();
+                assert_eq!(
+                    wrapped.params().hash.unwrap().to_string(),
+                    "47493B51E102705F6DCCE5981E05B7C025BB5BF19CF5E4B54FE28CAFE9D20C8A"
+                );
+            },
             "net_info" => assert!(serde_json::from_str::<
                 RequestWrapper<endpoint::net_info::Request>,
             >(&content)
             .is_ok()),
+            "num_unconfirmed_txs" => assert!(serde_json::from_str::<
+                RequestWrapper<endpoint::num_unconfirmed_txs::Request>,
+            >(&content)
+            .is_ok()),
             "status" => assert!(
                 serde_json::from_str::<RequestWrapper<endpoint::status::Request>>(&content).is_ok()
             ),
@@ -227,6 +256,13 @@ fn outgoing_fixtures() {
                 );
                 assert!(!wrapped.params().prove);
             },
+            "unconfirmed_txs" => {
+                let wrapped = serde_json::from_str::<
+                    RequestWrapper<endpoint::unconfirmed_txs::Request>,
+                >(&content)
+                .unwrap();
+                assert_eq!(wrapped.params().limit, Some(10));
+            },
             "tx_search_no_prove" => {
                 let wrapped =
                     serde_json::from_str::<RequestWrapper<endpoint::tx_search::Request>>(&content)
@@ -540,6 +576,15 @@ fn incoming_fixtures() {
                 );
                 assert!(result.log.is_empty());
             },
+            "check_tx" => {
+                let result = endpoint::check_tx::Response::from_string(content).unwrap();
+                assert_eq!(result.check_tx.code, abci::Code::Ok);
+                assert!(result.check_tx.codespace.is_empty());
+                assert!(result.check_tx.data.is_empty());
+                assert!(result.check_tx.events.is_empty());
+                assert_eq!(result.check_tx.gas_wanted, 1);
+                assert_eq!(result.check_tx.gas_used, 0);
+            },
             "commit_at_height_10" => {
                 let result = endpoint::commit::Response::from_string(content).unwrap();
                 assert!(!result.signed_header.commit.block_id.hash.is_empty());
@@ -665,6 +710,19 @@ fn incoming_fixtures() {
                 assert_eq!(result.genesis.validators[0].proposer_priority.value(), 0);
                 assert_eq!(result.genesis.consensus_params.block.time_iota_ms, 1000);
             },
+            "header_at_height_10" => {
+                let result = endpoint::header::Response::from_string(content).unwrap();
+                assert_eq!(result.header.chain_id.as_str(), CHAIN_ID);
+                assert_eq!(result.header.height.value(), 10);
+                assert!(result.header.last_block_id.is_some());
+            },
+            "header_by_hash" => {
+                let result = endpoint::header_by_hash::Response::from_string(content).unwrap();
+                assert_eq!(
+                    result.header.unwrap().hash().to_string(),
+                    "47493B51E102705F6DCCE5981E05B7C025BB5BF19CF5E4B54FE28CAFE9D20C8A"
+                );
+            },
             "net_info" => {
                 let result = endpoint::net_info::Response::from_string(content).unwrap();
                 assert_eq!(result.listeners.len(), 1);
@@ -673,6 +731,12 @@ fn incoming_fixtures() {
                 assert_eq!(result.n_peers, 0);
                 assert!(result.peers.is_empty());
             },
+            "num_unconfirmed_txs" => {
+                let result = endpoint::num_unconfirmed_txs::Response::from_string(content).unwrap();
+                assert_eq!(result.n_txs, 0);
+                assert_eq!(result.total, 0);
+                assert_eq!(result.total_bytes, 0);
+            },
             "status" => {
                 let result = endpoint::status::Response::from_string(content).unwrap();
                 assert_eq!(
@@ -1327,6 +1391,13 @@ fn incoming_fixtures() {
                 );
                 assert_eq!(u64::from(result.height), 12u64);
             },
+            "unconfirmed_txs" => {
+                let result = endpoint::unconfirmed_txs::Response::from_string(content).unwrap();
+                assert_eq!(result.n_txs, 0);
+                assert_eq!(result.total, 0);
+                assert_eq!(result.total_bytes, 0);
+                assert!(result.txs.is_empty());
+            },
             "tx_search_no_prove" => {
                 let result = endpoint::tx_search::Response::from_string(content).unwrap();
                 assert_eq!(result.total_count as usize, result.txs.len());
@@ -30,13 +30,54 @@ impl crate::crypto::signature::Verifier for Verifier {
             _ => Err(Error::UnsupportedKeyType),
         }
     }
+
+    /// Verify the Ed25519 signatures with the batch verification of `ed25519-consensus`,
+    /// which accepts exactly the signatures accepted by their individual verification,
+    /// and the others one at a time.
+    ///
+    /// The random coefficients of the batch verification are drawn from a generator seeded
+    /// with the hash of the whole batch, so that they cannot be known before all the
+    /// signatures of the batch are.
+    ///
+    /// Without the `std` feature, all the signatures are verified one at a time.
+    #[cfg(feature = "std")]
+    fn verify_batch(items: &[(PublicKey, &[u8], &Signature)]) -> Result<(), Error> {
+        use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+        use sha2::{Digest, Sha256};
+
+        let mut batch = ed25519_consensus::batch::Verifier::new();
+        let mut seed = Sha256::new();
+
+        for (pubkey, msg, signature) in items {
+            #[allow(unreachable_patterns)]
+            match pubkey {
+                PublicKey::Ed25519(pk) => {
+                    let vk = ed25519_consensus::VerificationKey::try_from(*pk)
+                        .map_err(|_| Error::MalformedPublicKey)?;
+                    let sig = ed25519_consensus::Signature::try_from(signature.as_bytes())
+                        .map_err(|_| Error::MalformedSignature)?;
+
+                    seed.update(vk.as_bytes());
+                    seed.update(sig.to_bytes());
+                    seed.update((msg.len() as u64).to_be_bytes());
+                    seed.update(msg);
+
+                    batch.queue((vk.into(), sig, msg));
+                },
+                _ => Self::verify(*pubkey, msg, signature)?,
+            }
+        }
+
+        let rng = ChaCha20Rng::from_seed(seed.finalize().into());
+        batch.verify(rng).map_err(|_| Error::VerificationFailed)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::crypto::default::signature::Verifier;
-    use crate::crypto::signature::Verifier as _;
-    use crate::{PublicKey, Signature};
+    use crate::crypto::signature::{Error, Verifier as _};
+    use crate::{prelude::*, PublicKey, Signature};
 
     // From https://datatracker.ietf.org/doc/html/rfc8032#section-7.1
     // Each test vector consists of: [public_key, message, signature].
@@ -201,6 +242,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn ed25519_test_vectors_batch() {
+        let vectors: Vec<_> = ED25519_TEST_VECTORS
+            .iter()
+            .map(|v| {
+                let public_key = PublicKey::from_raw_ed25519(v[0]).unwrap();
+                let sig = Signature::try_from(v[2]).unwrap();
+                (public_key, v[1], sig)
+            })
+            .collect();
+
+        let items: Vec<_> = vectors
+            .iter()
+            .map(|(public_key, msg, sig)| (*public_key, *msg, sig))
+            .collect();
+        Verifier::verify_batch(&items).expect("batch of signatures should be valid");
+
+        // Mismatch the messages of the first two signatures
+        let mut items = items;
+        let msg = items[0].1;
+        items[0].1 = items[1].1;
+        items[1].1 = msg;
+        assert!(matches!(
+            Verifier::verify_batch(&items),
+            Err(Error::VerificationFailed)
+        ));
+    }
+
     // Arbitrary "valid" tests taken from
     // https://github.com/google/wycheproof/blob/2196000605e45d91097147c9c71f26b72af58003/testvectors/ecdsa_secp256k1_sha256_test.json
     //
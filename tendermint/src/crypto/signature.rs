@@ -32,4 +32,15 @@ impl std::error::Error for Error {}
 
 pub trait Verifier {
     fn verify(pubkey: PublicKey, msg: &[u8], signature: &Signature) -> Result<(), Error>;
+
+    /// Verify all the given signatures of the given messages, which succeeds only if all of
+    /// them are valid, and fails without telling which one is invalid otherwise.
+    ///
+    /// Implementations may verify them at once faster than one at a time, eg. with batch
+    /// verification. By default, they are verified one at a time.
+    fn verify_batch(items: &[(PublicKey, &[u8], &Signature)]) -> Result<(), Error> {
+        items
+            .iter()
+            .try_for_each(|(pubkey, msg, signature)| Self::verify(*pubkey, msg, signature))
+    }
 }
@@ -1,6 +1,7 @@
 //! Votes from validators
 
 mod canonical_vote;
+mod canonical_vote_extension;
 mod power;
 mod sign_vote;
 mod validator_index;
@@ -9,11 +10,18 @@ use core::{fmt, str::FromStr};
 
 use bytes::BufMut;
 use serde::{Deserialize, Serialize};
-use tendermint_proto::v0_38::types::{CanonicalVote as RawCanonicalVote, Vote as RawVote};
+use tendermint_proto::v0_38::types::{
+    CanonicalVote as RawCanonicalVote, CanonicalVoteExtension as RawCanonicalVoteExtension,
+    Vote as RawVote,
+};
 use tendermint_proto::{Error as ProtobufError, Protobuf};
 
 pub use self::{
-    canonical_vote::CanonicalVote, power::Power, sign_vote::*, validator_index::ValidatorIndex,
+    canonical_vote::{CanonicalVote, CanonicalVoteSignBytes},
+    canonical_vote_extension::CanonicalVoteExtension,
+    power::Power,
+    sign_vote::*,
+    validator_index::ValidatorIndex,
 };
 use crate::{
     account, block, chain::Id as ChainId, consensus::State, error::Error, hash, prelude::*,
@@ -270,6 +278,13 @@ impl Vote {
         Protobuf::<RawCanonicalVote>::encode_length_delimited_vec(canonical)
     }
 
+    /// Create signable vector from the extension of a Vote, whose signature is
+    /// the `extension_signature` of the vote.
+    pub fn into_extension_signable_vec(self, chain_id: ChainId) -> Vec<u8> {
+        let canonical = CanonicalVoteExtension::new(self, chain_id);
+        Protobuf::<RawCanonicalVoteExtension>::encode_length_delimited_vec(canonical)
+    }
+
     /// Consensus state from this vote - This doesn't seem to be used anywhere.
     #[deprecated(
         since = "0.17.0",
@@ -1,5 +1,8 @@
+use prost::{encoding, Message};
 use serde::{Deserialize, Serialize};
-use tendermint_proto::v0_37::types::CanonicalVote as RawCanonicalVote;
+use tendermint_proto::{
+    google::protobuf::Timestamp, v0_37::types::CanonicalVote as RawCanonicalVote,
+};
 
 use crate::{block, chain::Id as ChainId, prelude::*, Time};
 
@@ -89,6 +92,110 @@ impl CanonicalVote {
     }
 }
 
+/// Sign bytes of the canonical votes which share their type, height, round and chain id, such
+/// as the votes of a commit, whose encoding of these fields is computed only once.
+///
+/// The sign bytes are the same as the ones of [`SignedVote::sign_bytes`](super::SignedVote::sign_bytes).
+#[derive(Clone, Debug)]
+pub struct CanonicalVoteSignBytes {
+    vote_type: super::Type,
+    height: block::Height,
+    round: block::Round,
+    chain_id: ChainId,
+    /// Block ID of most of the votes, eg. the one of the commit
+    block_id: Option<block::Id>,
+    /// Encoding of the type, height and round
+    prefix: Vec<u8>,
+    /// Encoding of the block ID of most of the votes
+    encoded_block_id: Vec<u8>,
+    /// Encoding of the chain ID
+    suffix: Vec<u8>,
+}
+
+impl CanonicalVoteSignBytes {
+    /// Create the sign bytes of the votes of the given type, height and round on the given
+    /// chain, most of which are for the given block ID.
+    pub fn new(
+        vote_type: super::Type,
+        height: block::Height,
+        round: block::Round,
+        block_id: Option<block::Id>,
+        chain_id: ChainId,
+    ) -> Self {
+        let prefix = RawCanonicalVote {
+            r#type: vote_type.into(),
+            height: height.into(),
+            round: round.value().into(),
+            ..Default::default()
+        }
+        .encode_to_vec();
+
+        let suffix = RawCanonicalVote {
+            chain_id: chain_id.to_string(),
+            ..Default::default()
+        }
+        .encode_to_vec();
+
+        Self {
+            vote_type,
+            height,
+            round,
+            encoded_block_id: encode_block_id(block_id),
+            block_id,
+            chain_id,
+            prefix,
+            suffix,
+        }
+    }
+
+    /// Return the bytes of the canonicalized vote to be signed.
+    pub fn sign_bytes(&self, vote: &super::Vote) -> Vec<u8> {
+        if (vote.vote_type, vote.height, vote.round) != (self.vote_type, self.height, self.round) {
+            let canonical_vote = CanonicalVote::new(vote.clone(), self.chain_id.clone());
+            return RawCanonicalVote::from(canonical_vote).encode_length_delimited_to_vec();
+        }
+
+        let encoded_block_id = if vote.block_id == self.block_id {
+            None
+        } else {
+            Some(encode_block_id(vote.block_id))
+        };
+        let encoded_block_id = encoded_block_id.as_ref().unwrap_or(&self.encoded_block_id);
+
+        let timestamp = vote.timestamp.map(Timestamp::from);
+        let timestamp_len = timestamp
+            .as_ref()
+            .map_or(0, |timestamp| encoding::message::encoded_len(5, timestamp));
+
+        let len = self.prefix.len() + encoded_block_id.len() + timestamp_len + self.suffix.len();
+        let mut bytes = Vec::with_capacity(encoding::encoded_len_varint(len as u64) + len);
+
+        // The fields are encoded in the order of their tags, as prost does
+        encoding::encode_varint(len as u64, &mut bytes);
+        bytes.extend_from_slice(&self.prefix);
+        bytes.extend_from_slice(encoded_block_id);
+        if let Some(timestamp) = &timestamp {
+            encoding::message::encode(5, timestamp, &mut bytes);
+        }
+        bytes.extend_from_slice(&self.suffix);
+
+        bytes
+    }
+}
+
+/// Encode the block ID field of a canonical vote.
+fn encode_block_id(block_id: Option<block::Id>) -> Vec<u8> {
+    // If the Hash is empty in BlockId, the BlockId should be empty.
+    // See: https://github.com/informalsystems/tendermint-rs/issues/663
+    RawCanonicalVote {
+        block_id: block_id
+            .filter(|id| id != &block::Id::default())
+            .map(Into::into),
+        ..Default::default()
+    }
+    .encode_to_vec()
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -138,4 +245,82 @@ mod tests {
             assert!(CanonicalVote::try_from(proto_cp).is_err());
         }
     }
+
+    mod sign_bytes {
+        use time::macros::datetime;
+
+        use crate::{
+            account::Id as AccountId,
+            block::{parts::Header, Height, Id as BlockId, Round},
+            chain::Id as ChainId,
+            prelude::*,
+            signature::Signature,
+            vote::{CanonicalVoteSignBytes, SignedVote, Type, ValidatorIndex},
+            Hash, Time, Vote,
+        };
+
+        fn block_id(hash: u8) -> BlockId {
+            BlockId {
+                hash: Hash::Sha256([hash; 32]),
+                part_set_header: Header::new(1, Hash::Sha256([hash + 1; 32])).unwrap(),
+            }
+        }
+
+        fn vote(round: u16, block_id: Option<BlockId>, timestamp: Option<Time>) -> Vote {
+            Vote {
+                vote_type: Type::Precommit,
+                height: Height::from(12345_u32),
+                round: Round::from(round),
+                block_id,
+                timestamp,
+                validator_address: AccountId::new([0xa3; 20]),
+                validator_index: ValidatorIndex::try_from(3).unwrap(),
+                signature: Signature::new(vec![1; 64]).unwrap(),
+                extension: vec![],
+                extension_signature: None,
+            }
+        }
+
+        #[test]
+        fn same_as_signed_vote() {
+            let chain_id = ChainId::try_from("testchain").unwrap();
+            let sign_bytes = CanonicalVoteSignBytes::new(
+                Type::Precommit,
+                Height::from(12345_u32),
+                Round::from(2_u16),
+                Some(block_id(1)),
+                chain_id.clone(),
+            );
+
+            let timestamps = [
+                None,
+                Some(Time::unix_epoch()),
+                Some(datetime!(2017-12-25 03:00:01.234 UTC).try_into().unwrap()),
+                Some(
+                    datetime!(2262-04-11 23:47:16.854775807 UTC)
+                        .try_into()
+                        .unwrap(),
+                ),
+            ];
+            let block_ids = [
+                Some(block_id(1)),
+                Some(block_id(7)),
+                Some(BlockId::default()),
+                None,
+            ];
+
+            for timestamp in timestamps {
+                for block_id in block_ids {
+                    // The last votes do not share the round of the other votes
+                    for round in [2, 3] {
+                        let vote = vote(round, block_id, timestamp);
+                        let signed_vote =
+                            SignedVote::from_vote(vote.clone(), chain_id.clone()).unwrap();
+
+                        assert_eq!(sign_bytes.sign_bytes(&vote), signed_vote.sign_bytes());
+                    }
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,92 @@
+use tendermint_proto::v0_38::types::CanonicalVoteExtension as RawCanonicalVoteExtension;
+use tendermint_proto::Protobuf;
+
+use crate::{block, chain::Id as ChainId, error::Error, prelude::*};
+
+/// CanonicalVoteExtension is used for protobuf encoding the vote extension of a precommit,
+/// which validators sign separately from the vote itself.
+///
+/// This structure has been added in CometBFT 0.38.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CanonicalVoteExtension {
+    /// Vote extension provided by the application
+    pub extension: Vec<u8>,
+
+    /// Block height
+    pub height: block::Height,
+
+    /// Round
+    pub round: block::Round,
+
+    /// Chain ID
+    pub chain_id: ChainId,
+}
+
+impl Protobuf<RawCanonicalVoteExtension> for CanonicalVoteExtension {}
+
+impl TryFrom<RawCanonicalVoteExtension> for CanonicalVoteExtension {
+    type Error = Error;
+
+    fn try_from(value: RawCanonicalVoteExtension) -> Result<Self, Self::Error> {
+        let round: i32 = value.round.try_into().map_err(Error::integer_overflow)?;
+
+        Ok(CanonicalVoteExtension {
+            extension: value.extension,
+            height: value.height.try_into()?,
+            round: round.try_into()?,
+            chain_id: ChainId::try_from(value.chain_id)?,
+        })
+    }
+}
+
+impl From<CanonicalVoteExtension> for RawCanonicalVoteExtension {
+    fn from(value: CanonicalVoteExtension) -> Self {
+        RawCanonicalVoteExtension {
+            extension: value.extension,
+            height: value.height.into(),
+            round: value.round.value().into(),
+            chain_id: value.chain_id.to_string(),
+        }
+    }
+}
+
+impl CanonicalVoteExtension {
+    /// Create CanonicalVoteExtension from the extension of a Vote
+    pub fn new(vote: super::Vote, chain_id: ChainId) -> CanonicalVoteExtension {
+        CanonicalVoteExtension {
+            extension: vote.extension,
+            height: vote.height,
+            round: vote.round,
+            chain_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tendermint_proto::v0_38::types::CanonicalVoteExtension as RawCanonicalVoteExtension;
+
+    use super::CanonicalVoteExtension;
+    use crate::prelude::*;
+
+    #[test]
+    fn canonical_vote_extension_round_trip() {
+        let raw = RawCanonicalVoteExtension {
+            extension: vec![1, 2, 3],
+            height: 2,
+            round: 4,
+            chain_id: "testchain".to_string(),
+        };
+        let domain = CanonicalVoteExtension::try_from(raw.clone()).unwrap();
+        assert_eq!(domain.height.value(), 2);
+        assert_eq!(domain.round.value(), 4);
+        assert_eq!(RawCanonicalVoteExtension::from(domain), raw);
+
+        // The round must fit in an i32
+        let raw = RawCanonicalVoteExtension {
+            round: i64::MAX,
+            ..raw
+        };
+        assert!(CanonicalVoteExtension::try_from(raw).is_err());
+    }
+}
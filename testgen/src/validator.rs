@@ -2,8 +2,8 @@ use gumdrop::Options;
 use serde::{Deserialize, Serialize};
 use simple_error::*;
 use tendermint::{
-    account, consensus::state::Ordering, private_key, public_key, public_key::PublicKey, validator,
-    vote,
+    account, consensus::state::Ordering, private_key, public_key, public_key::PublicKey,
+    signature::Signature, validator, vote,
 };
 
 use crate::{helpers::*, Generator};
@@ -60,6 +60,14 @@ impl Validator {
         self.get_private_key()
             .map(|secret_key| secret_key.verification_key())
     }
+
+    /// Sign the given message with the private key of this validator companion,
+    /// eg. to sign vote extensions.
+    pub fn sign(&self, msg: &[u8]) -> Result<Signature, SimpleError> {
+        let signing_key = self.get_private_key()?;
+        let signing_key = ed25519_consensus::SigningKey::try_from(signing_key).unwrap();
+        Ok(signing_key.sign(msg).into())
+    }
 }
 
 impl std::str::FromStr for Validator {
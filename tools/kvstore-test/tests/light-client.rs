@@ -61,6 +61,8 @@ fn make_primary() -> Instance {
         trust_threshold: TrustThreshold::new(1, 3).unwrap(),
         trusting_period: Duration::from_secs(60 * 60), // 60 minutes
         clock_drift: Duration::from_secs(5 * 60),      // 5 minutes
+        verification_mode: Default::default(),
+        commit_signatures: Default::default(),
     };
 
     make_instance(primary, options, node_address)
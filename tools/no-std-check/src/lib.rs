@@ -24,6 +24,37 @@ use sp_std;
 
 use core::panic::PanicInfo;
 
+use tendermint_light_client_verifier::{
+    crypto::CryptoProvider,
+    options::Options,
+    types::{Time, TrustedBlockState, UntrustedBlockState},
+    ProvidedVerifier, Verdict, Verifier,
+};
+
+/// Verify a header with the cryptographic primitives of the given provider and the given
+/// current time, as done by environments without `std` such as zkVMs or smart contracts,
+/// to check that the whole verification path is available without `std` nor `rust-crypto`.
+pub fn verify_update_header<C: CryptoProvider>(
+    untrusted: UntrustedBlockState<'_>,
+    trusted: TrustedBlockState<'_>,
+    options: &Options,
+    now: Time,
+) -> Verdict {
+    ProvidedVerifier::<C>::default().verify_update_header(untrusted, trusted, options, now)
+}
+
+/// Verify a header with the default cryptographic primitives.
+#[cfg(feature = "rust-crypto")]
+pub fn verify_update_header_with_rust_crypto(
+    untrusted: UntrustedBlockState<'_>,
+    trusted: TrustedBlockState<'_>,
+    options: &Options,
+    now: Time,
+) -> Verdict {
+    tendermint_light_client_verifier::ProdVerifier::default()
+        .verify_update_header(untrusted, trusted, options, now)
+}
+
 /*
 
 This function definition checks for the compliance of no-std in
@@ -44,7 +75,6 @@ error[E0152]: found duplicate lang item `panic_impl`
  */
 #[cfg(feature = "panic-handler")]
 #[panic_handler]
-#[no_mangle]
 fn panic(_info: &PanicInfo) -> ! {
     loop {}
 }
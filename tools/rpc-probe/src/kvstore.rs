@@ -37,9 +37,15 @@ pub fn quick_probe_plan(output_path: &Path, request_wait: Duration) -> Result<Pl
                 broadcast_tx("async", "async-key", "value"),
                 broadcast_tx("sync", "sync-key", "value"),
                 broadcast_tx("commit", "commit-key", "value"),
+                check_tx("check-key", "value"),
                 genesis(),
+                header(10).with_name("header_at_height_10"),
+                header_by_hash("R0k7UeECcF9tzOWYHgW3wCW7W/Gc9eS1T+KMr+nSDIo=")
+                    .with_name("header_by_hash"),
                 net_info(),
+                num_unconfirmed_txs(),
                 status(),
+                unconfirmed_txs(10).with_name("unconfirmed_txs"),
                 subscribe("tm.event = 'NewBlock'").with_name("subscribe_newblock"),
                 subscribe("malformed query")
                     .with_name("subscribe_malformed")